@@ -0,0 +1,227 @@
+//! Keyboard adjust mode: nudge / resize the shown tracked window with the arrows
+//!
+//! Entered from the chord (leader, then A) while the window is visible. Like
+//! chord follow-ups, the keys are bare global hotkeys registered only while
+//! the mode is active. Arrows move, Shift+arrows resize by `AdjustStepPx`;
+//! Enter keeps the new bounds, Esc puts the window back. The mode also ends
+//! (keeping the bounds) after ADJUST_TIMEOUT without a key press.
+
+use global_hotkey::GlobalHotKeyManager;
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use std::time::{Duration, Instant};
+
+use crate::settings;
+use crate::tracking::WindowBounds;
+
+/// Idle time after which the mode ends (arrow keys are given back)
+const ADJUST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pixels per key press (registry DWORD)
+const ADJUST_STEP_PX: &str = "AdjustStepPx";
+const DEFAULT_STEP_PX: u32 = 20;
+const MAX_STEP_PX: u32 = 500;
+
+/// Smallest size reachable by shrinking
+const MIN_WIDTH: i32 = 120;
+const MIN_HEIGHT: i32 = 80;
+
+/// Key action in unit steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    /// Arrows: move by (dx, dy) steps
+    Move(i32, i32),
+    /// Shift+arrows: resize by (dw, dh) steps (right / bottom edge)
+    Resize(i32, i32),
+    /// Enter: keep the bounds
+    Commit,
+    /// Esc: restore the bounds from before the mode
+    Cancel,
+}
+
+/// Mode keys, in registration order
+const KEYS: &[(Option<Modifiers>, Code, Key)] = &[
+    (None, Code::ArrowLeft, Key::Move(-1, 0)),
+    (None, Code::ArrowRight, Key::Move(1, 0)),
+    (None, Code::ArrowUp, Key::Move(0, -1)),
+    (None, Code::ArrowDown, Key::Move(0, 1)),
+    (Some(Modifiers::SHIFT), Code::ArrowLeft, Key::Resize(-1, 0)),
+    (Some(Modifiers::SHIFT), Code::ArrowRight, Key::Resize(1, 0)),
+    (Some(Modifiers::SHIFT), Code::ArrowUp, Key::Resize(0, -1)),
+    (Some(Modifiers::SHIFT), Code::ArrowDown, Key::Resize(0, 1)),
+    (None, Code::Enter, Key::Commit),
+    (None, Code::Escape, Key::Cancel),
+];
+
+/// Result of a key press (or the timeout)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdjustStep {
+    /// Move the window to these bounds (mode continues)
+    Moved(WindowBounds),
+    /// Mode ended: keep the window where it is
+    Done(WindowBounds),
+    /// Mode ended: move the window back to these bounds
+    Cancelled(WindowBounds),
+}
+
+/// Configured step in pixels
+pub fn step_px() -> i32 {
+    settings::get_u32(ADJUST_STEP_PX, DEFAULT_STEP_PX).clamp(1, MAX_STEP_PX) as i32
+}
+
+/// Bounds after one key press of `step` pixels
+fn adjusted(bounds: WindowBounds, key: Key, step: i32) -> WindowBounds {
+    match key {
+        Key::Move(dx, dy) => WindowBounds {
+            x: bounds.x + dx * step,
+            y: bounds.y + dy * step,
+            ..bounds
+        },
+        Key::Resize(dw, dh) => WindowBounds {
+            width: (bounds.width + dw * step).max(MIN_WIDTH),
+            height: (bounds.height + dh * step).max(MIN_HEIGHT),
+            ..bounds
+        },
+        Key::Commit | Key::Cancel => bounds,
+    }
+}
+
+/// Active mode
+#[derive(Debug)]
+struct Active {
+    last_key: Instant,
+    original: WindowBounds,
+    current: WindowBounds,
+    step: i32,
+    /// Keys that registered (others are taken by another app)
+    registered: Vec<(HotKey, Key)>,
+}
+
+/// Adjust mode state (owned by the event loop)
+#[derive(Debug, Default)]
+pub struct AdjustState {
+    active: Option<Active>,
+}
+
+impl AdjustState {
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Enter the mode for a window shown at `bounds`; false if no key could be registered
+    pub fn begin(&mut self, manager: &GlobalHotKeyManager, bounds: WindowBounds) -> bool {
+        self.end(manager);
+        let registered: Vec<(HotKey, Key)> = KEYS
+            .iter()
+            .map(|&(mods, code, key)| (HotKey::new(mods, code), key))
+            .filter(|(hotkey, _)| {
+                manager
+                    .register(*hotkey)
+                    .inspect_err(
+                        |e| tracing::debug!(key = ?hotkey.key, "Adjust key register failed: {e}"),
+                    )
+                    .is_ok()
+            })
+            .collect();
+        if registered.is_empty() {
+            return false;
+        }
+        self.active = Some(Active {
+            last_key: Instant::now(),
+            original: bounds,
+            current: bounds,
+            step: step_px(),
+            registered,
+        });
+        tracing::debug!("Adjust mode started");
+        true
+    }
+
+    /// Resolve a hotkey event; Some when it was a mode key
+    pub fn take(&mut self, manager: &GlobalHotKeyManager, id: u32) -> Option<AdjustStep> {
+        let active = self.active.as_mut()?;
+        let key = active
+            .registered
+            .iter()
+            .find(|(hotkey, _)| hotkey.id() == id)
+            .map(|(_, key)| *key)?;
+        active.last_key = Instant::now();
+        active.current = adjusted(active.current, key, active.step);
+        let step = match key {
+            Key::Move(..) | Key::Resize(..) => return Some(AdjustStep::Moved(active.current)),
+            Key::Commit => AdjustStep::Done(active.current),
+            Key::Cancel => AdjustStep::Cancelled(active.original),
+        };
+        self.end(manager);
+        Some(step)
+    }
+
+    /// End the mode after ADJUST_TIMEOUT idle (bounds kept)
+    pub fn poll(&mut self, manager: &GlobalHotKeyManager) -> Option<AdjustStep> {
+        let active = self.active.as_ref()?;
+        if active.last_key.elapsed() < ADJUST_TIMEOUT {
+            return None;
+        }
+        let step = AdjustStep::Done(active.current);
+        self.end(manager);
+        tracing::debug!("Adjust mode timed out");
+        Some(step)
+    }
+
+    /// Release the mode keys (bounds left as they are)
+    pub fn end(&mut self, manager: &GlobalHotKeyManager) {
+        if let Some(active) = self.active.take() {
+            let hotkeys: Vec<HotKey> = active.registered.iter().map(|(h, _)| *h).collect();
+            let _ = manager.unregister_all(&hotkeys);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: WindowBounds = WindowBounds {
+        x: 100,
+        y: 50,
+        width: 800,
+        height: 400,
+    };
+
+    #[test]
+    fn test_adjusted_move() {
+        let moved = adjusted(BOUNDS, Key::Move(-1, 0), 20);
+        assert_eq!((moved.x, moved.y), (80, 50));
+        assert_eq!((moved.width, moved.height), (800, 400));
+        let moved = adjusted(BOUNDS, Key::Move(0, 1), 5);
+        assert_eq!(moved.y, 55);
+    }
+
+    #[test]
+    fn test_adjusted_resize_keeps_origin() {
+        let resized = adjusted(BOUNDS, Key::Resize(1, -1), 20);
+        assert_eq!((resized.x, resized.y), (100, 50));
+        assert_eq!((resized.width, resized.height), (820, 380));
+    }
+
+    #[test]
+    fn test_adjusted_resize_minimum() {
+        let resized = adjusted(BOUNDS, Key::Resize(-1, -1), 1000);
+        assert_eq!((resized.width, resized.height), (MIN_WIDTH, MIN_HEIGHT));
+    }
+
+    #[test]
+    fn test_keys_distinct() {
+        let ids: Vec<u32> = KEYS
+            .iter()
+            .map(|&(mods, code, _)| HotKey::new(mods, code).id())
+            .collect();
+        for (i, id) in ids.iter().enumerate() {
+            assert!(!ids[i + 1..].contains(id));
+        }
+    }
+
+    #[test]
+    fn test_default_state_inactive() {
+        assert!(!AdjustState::default().is_active());
+    }
+}
@@ -1,15 +1,61 @@
 //! Animation module: easing, position calculation, animation loop
+//!
+//! Slide-in and slide-out have their own easing curve (`ShowEasing` /
+//! `HideEasing`, registry strings such as "Expo", default "Cubic").
+//! `AnimationStyle` "SlideFade" also fades the layered-window alpha from 0 to
+//! the window's opacity on show (reverse on hide) and restores it afterward;
+//! "Zoom" grows the window in place from `ZOOM_START` of its size instead of
+//! sliding it from off-screen. Per-app rules may pick their own style.
+//! `AnimationFps` (DWORD, 0 = compositor-synced) switches to a fixed timestep
+//! at that rate, paced by sleeping instead of DwmFlush (VMs / RDP).
+//! `AnimationSegments` turns slide-in into several phases, "settle", "bounce"
+//! or `time:position:easing` items separated by `;` (e.g.
+//! "0.4:0.95:Quad;1:1:Cubic": 95% of the way in 40% of the time, then settle).
+//! `HideShadowDuringSlide` = 1 turns off the DWM frame shadow and the
+//! CS_DROPSHADOW popup shadow while sliding (they smear on some GPUs).
 
+use std::cell::Cell;
 use std::time::{Duration, Instant};
 use windows::Win32::Foundation::{HWND, RECT};
-use windows::Win32::Graphics::Dwm::DwmFlush;
-use windows::Win32::Graphics::Gdi::InvalidateRect;
+use windows::Win32::Graphics::Dwm::{DWM_TIMING_INFO, DwmFlush, DwmGetCompositionTimingInfo};
+use windows::Win32::System::Performance::{QueryPerformanceCounter, QueryPerformanceFrequency};
 use windows::Win32::UI::WindowsAndMessaging::{
-    GWL_EXSTYLE, GetWindowLongPtrW, HWND_TOPMOST, SWP_HIDEWINDOW, SWP_NOACTIVATE, SWP_NOZORDER,
-    SWP_SHOWWINDOW, SetWindowLongPtrW, SetWindowPos, WS_EX_COMPOSITED,
+    SET_WINDOW_POS_FLAGS, SWP_HIDEWINDOW, SWP_NOACTIVATE, SWP_NOZORDER, SWP_SHOWWINDOW,
+    WS_EX_COMPOSITED, WS_EX_LAYERED,
 };
 
+use crate::compat::Compat;
+use crate::settings::{self, SettingsError};
+use crate::sysprefs;
 use crate::tracking::WindowBounds;
+use crate::win32::{self, Move, Shadow};
+
+/// Delay between group members' slides (registry DWORD, ms)
+const GROUP_STAGGER_MS: &str = "GroupStaggerMs";
+const DEFAULT_GROUP_STAGGER_MS: u32 = 40;
+const MAX_GROUP_STAGGER_MS: u32 = 500;
+
+const SHOW_EASING: &str = "ShowEasing";
+const HIDE_EASING: &str = "HideEasing";
+const ANIMATION_STYLE: &str = "AnimationStyle";
+
+/// Fixed-timestep rate (registry DWORD, 0 = compositor-synced)
+const ANIMATION_FPS: &str = "AnimationFps";
+const MIN_FPS: u32 = 15;
+const MAX_FPS: u32 = 240;
+
+const ANIMATION_SEGMENTS: &str = "AnimationSegments";
+
+const HIDE_SHADOW: &str = "HideShadowDuringSlide";
+
+/// Furthest a segment may overshoot the final position
+const MAX_OVERSHOOT: f64 = 1.2;
+
+/// Longest slide while battery saver is on
+const BATTERY_SAVER_DURATION_MS: u32 = 80;
+
+/// Size fraction a zoom starts from (slide-in) or ends at (slide-out)
+const ZOOM_START: f64 = 0.5;
 
 /// Slide direction
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,20 +66,183 @@ pub enum Direction {
     Bottom,
 }
 
-/// Easing function type
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Easing function type (ease-out curves, gentlest first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Easing {
+    Linear,
+    Quad,
+    #[default]
     Cubic,
+    Quart,
+    Expo,
 }
 
 impl Easing {
+    pub const ALL: [Easing; 5] = [
+        Easing::Linear,
+        Easing::Quad,
+        Easing::Cubic,
+        Easing::Quart,
+        Easing::Expo,
+    ];
+
     /// Apply easing function: t ∈ [0,1] → [0,1]
     /// ease-out variant: fast start, slow end
     pub fn apply(&self, t: f64) -> f64 {
         match self {
+            Easing::Linear => t,
+            Easing::Quad => 1.0 - (1.0 - t).powi(2),
             Easing::Cubic => 1.0 - (1.0 - t).powi(3),
+            Easing::Quart => 1.0 - (1.0 - t).powi(4),
+            Easing::Expo if t >= 1.0 => 1.0,
+            Easing::Expo => 1.0 - 2f64.powf(-10.0 * t),
+        }
+    }
+
+    /// Registry / menu name
+    pub fn name(self) -> &'static str {
+        match self {
+            Easing::Linear => "Linear",
+            Easing::Quad => "Quad",
+            Easing::Cubic => "Cubic",
+            Easing::Quart => "Quart",
+            Easing::Expo => "Expo",
         }
     }
+
+    /// Parse a name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|easing| easing.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// How a window enters and leaves the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnimStyle {
+    #[default]
+    Slide,
+    /// Slide while fading alpha in / out
+    SlideFade,
+    /// Grow / shrink in place around the window's center
+    Zoom,
+}
+
+impl AnimStyle {
+    pub const ALL: [AnimStyle; 3] = [AnimStyle::Slide, AnimStyle::SlideFade, AnimStyle::Zoom];
+
+    /// Registry name
+    pub fn name(self) -> &'static str {
+        match self {
+            AnimStyle::Slide => "Slide",
+            AnimStyle::SlideFade => "SlideFade",
+            AnimStyle::Zoom => "Zoom",
+        }
+    }
+
+    /// Parse a name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|style| style.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn fades(self) -> bool {
+        self == AnimStyle::SlideFade
+    }
+}
+
+/// One phase of a multi-stage slide: reach `position` at time `until`
+/// (both fractions of the whole slide), moving along `easing`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub until: f64,
+    pub position: f64,
+    pub easing: Easing,
+}
+
+impl Segment {
+    const fn new(until: f64, position: f64, easing: Easing) -> Self {
+        Self {
+            until,
+            position,
+            easing,
+        }
+    }
+}
+
+/// Fast slide to 95%, then a slow settle to the final pixel
+pub const SETTLE: [Segment; 2] = [
+    Segment::new(0.4, 0.95, Easing::Quad),
+    Segment::new(1.0, 1.0, Easing::Cubic),
+];
+
+/// Slight overshoot, then back
+pub const BOUNCE: [Segment; 2] = [
+    Segment::new(0.7, 1.04, Easing::Cubic),
+    Segment::new(1.0, 1.0, Easing::Quad),
+];
+
+/// Parse "settle", "bounce" or `until:position:easing` items separated by `;`
+///
+/// Times must increase and the last segment must end at 1:1 (the window lands
+/// exactly on its bounds).
+pub fn parse_segments(text: &str) -> Option<Vec<Segment>> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("settle") {
+        return Some(SETTLE.to_vec());
+    }
+    if text.eq_ignore_ascii_case("bounce") {
+        return Some(BOUNCE.to_vec());
+    }
+
+    let mut segments: Vec<Segment> = Vec::new();
+    for item in text.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+        let mut parts = item.split(':').map(str::trim);
+        let until: f64 = parts.next()?.parse().ok()?;
+        let position: f64 = parts.next()?.parse().ok()?;
+        let easing = parts
+            .next()
+            .map_or(Some(Easing::default()), Easing::from_name)?;
+        let previous = segments.last().map_or(0.0, |s| s.until);
+        if parts.next().is_some()
+            || until <= previous
+            || until > 1.0
+            || !(0.0..=MAX_OVERSHOOT).contains(&position)
+        {
+            return None;
+        }
+        segments.push(Segment::new(until, position, easing));
+    }
+    let last = segments.last()?;
+    (last.until == 1.0 && last.position == 1.0).then_some(segments)
+}
+
+/// Progress of a multi-stage slide at raw time `t` ∈ [0,1]
+pub fn apply_segments(segments: &[Segment], t: f64) -> f64 {
+    let (mut start, mut from) = (0.0, 0.0);
+    for segment in segments {
+        if t < segment.until {
+            let local = (t - start) / (segment.until - start);
+            return from + (segment.position - from) * segment.easing.apply(local);
+        }
+        start = segment.until;
+        from = segment.position;
+    }
+    from
+}
+
+/// Configured slide-in segments (empty: a single `ShowEasing` curve)
+pub fn segments() -> Vec<Segment> {
+    let Some(text) = settings::get_string(ANIMATION_SEGMENTS).filter(|t| !t.trim().is_empty())
+    else {
+        return Vec::new();
+    };
+    parse_segments(&text).unwrap_or_else(|| {
+        tracing::warn!(segments = %text, "Invalid AnimationSegments ignored");
+        Vec::new()
+    })
 }
 
 /// Linear interpolation: lerp(a, b, t) = a + (b - a) * t
@@ -45,16 +254,328 @@ pub fn lerp(a: i32, b: i32, t: f64) -> i32 {
 #[derive(Debug, Clone)]
 pub struct AnimConfig {
     pub duration_ms: u32,
-    pub easing: Easing,
+    /// Slide-in curve
+    pub show_easing: Easing,
+    /// Slide-out curve
+    pub hide_easing: Easing,
+    pub style: AnimStyle,
+    /// Fixed-timestep rate (None: compositor-synced, measured frames)
+    pub fps: Option<u32>,
+    /// Slide-in phases (empty: `show_easing` alone)
+    pub segments: Vec<Segment>,
+    /// Double-buffer with WS_EX_COMPOSITED while sliding
+    pub composited: bool,
+    /// Turn window shadows off while sliding
+    pub hide_shadow: bool,
 }
 
 impl Default for AnimConfig {
     fn default() -> Self {
         Self {
             duration_ms: 200,
-            easing: Easing::Cubic,
+            show_easing: Easing::Cubic,
+            hide_easing: Easing::Cubic,
+            style: AnimStyle::Slide,
+            fps: None,
+            segments: Vec::new(),
+            composited: true,
+            hide_shadow: false,
+        }
+    }
+}
+
+impl AnimConfig {
+    /// Configured curves and style with the default duration
+    pub fn load() -> Self {
+        Self {
+            show_easing: easing(true),
+            hide_easing: easing(false),
+            style: style(),
+            fps: fixed_fps(),
+            segments: segments(),
+            hide_shadow: settings::get_bool(HIDE_SHADOW, false),
+            ..Self::default()
+        }
+    }
+
+    /// Downgraded to what the OS visual-effect settings allow: instant plain
+    /// slides without animation effects (or in a remote / locked session), no
+    /// fade without transparency effects, short plain slides on battery saver
+    pub fn follow_system(mut self) -> Self {
+        if sysprefs::is_constrained_session() {
+            self.duration_ms = 0;
+            self.style = AnimStyle::Slide;
+            self.composited = false;
+        } else if !sysprefs::animations_enabled() {
+            self.duration_ms = 0;
+            self.style = AnimStyle::Slide;
+        } else if sysprefs::is_battery_saver() {
+            self.duration_ms = self.duration_ms.min(BATTERY_SAVER_DURATION_MS);
+            self.style = AnimStyle::Slide;
+        } else if self.style.fades() && !sysprefs::transparency_enabled() {
+            self.style = AnimStyle::Slide;
+        }
+        self
+    }
+
+    /// Eased progress of a slide at raw time `t` (segments on slide-in, else the curve)
+    pub fn progress(&self, slide_in: bool, t: f64) -> f64 {
+        if slide_in && !self.segments.is_empty() {
+            apply_segments(&self.segments, t)
+        } else {
+            self.easing(slide_in).apply(t)
+        }
+    }
+
+    /// Curve of a slide in or out
+    pub fn easing(&self, slide_in: bool) -> Easing {
+        if slide_in {
+            self.show_easing
+        } else {
+            self.hide_easing
+        }
+    }
+}
+
+fn easing_key(slide_in: bool) -> &'static str {
+    if slide_in { SHOW_EASING } else { HIDE_EASING }
+}
+
+/// Configured curve of slide-in / slide-out (unknown or missing → Cubic)
+pub fn easing(slide_in: bool) -> Easing {
+    settings::get_string(easing_key(slide_in))
+        .and_then(|name| Easing::from_name(&name))
+        .unwrap_or_default()
+}
+
+/// Persist the curve of slide-in / slide-out
+pub fn set_easing(slide_in: bool, easing: Easing) -> Result<(), SettingsError> {
+    settings::set_string(easing_key(slide_in), easing.name())
+}
+
+/// Configured animation style (unknown or missing → Slide)
+pub fn style() -> AnimStyle {
+    settings::get_string(ANIMATION_STYLE)
+        .and_then(|name| AnimStyle::from_name(&name))
+        .unwrap_or_default()
+}
+
+/// Persist the animation style
+pub fn set_style(style: AnimStyle) -> Result<(), SettingsError> {
+    settings::set_string(ANIMATION_STYLE, style.name())
+}
+
+/// Configured fixed-timestep rate (None: compositor-synced)
+pub fn fixed_fps() -> Option<u32> {
+    match settings::get_u32(ANIMATION_FPS, 0) {
+        0 => None,
+        fps => Some(fps.clamp(MIN_FPS, MAX_FPS)),
+    }
+}
+
+/// Layered alpha at eased progress `t`: 0 → `target` on slide-in, reverse on slide-out
+pub fn fade_alpha(target: u8, t: f64, slide_in: bool) -> u8 {
+    let visible = if slide_in { t } else { 1.0 - t };
+    (target as f64 * visible.clamp(0.0, 1.0)).round() as u8
+}
+
+/// Longest animation time a single frame may consume (a stall advances at most this)
+const MAX_FRAME_DELTA: Duration = Duration::from_millis(50);
+
+/// Fraction of the accumulated lag recovered per frame after a stall
+const CATCH_UP_RATE: f64 = 0.5;
+
+/// Frames longer than this count as dropped (two 60 Hz frames)
+const DROPPED_FRAME: Duration = Duration::from_millis(33);
+
+/// Animation clock: wall time → progress with a bounded per-frame step
+///
+/// After a stall (e.g. DwmFlush blocked under GPU load) progress does not jump
+/// to where wall time landed; it advances by at most `MAX_FRAME_DELTA` and
+/// recovers the lag over the following frames.
+#[derive(Debug, Clone)]
+pub struct FrameClock {
+    duration: Duration,
+    /// Wall time since start
+    wall: Duration,
+    /// Animation time consumed (≤ wall)
+    anim: Duration,
+    /// Frames longer than `DROPPED_FRAME`
+    dropped: u32,
+}
+
+impl FrameClock {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            wall: Duration::ZERO,
+            anim: Duration::ZERO,
+            dropped: 0,
         }
     }
+
+    /// Account a frame that took `dt`, returns raw progress ∈ [0,1]
+    pub fn advance(&mut self, dt: Duration) -> f64 {
+        let lag = self.wall.saturating_sub(self.anim);
+        self.wall += dt;
+        if dt > DROPPED_FRAME {
+            self.dropped += 1;
+        }
+
+        let step = (dt + lag.mul_f64(CATCH_UP_RATE)).min(MAX_FRAME_DELTA);
+        self.anim = (self.anim + step).min(self.wall);
+        self.progress()
+    }
+
+    /// Raw progress ∈ [0,1] (before easing)
+    pub fn progress(&self) -> f64 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (self.anim.as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+    }
+
+    /// Frames longer than two 60 Hz frames so far
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+}
+
+/// Frame period assumed when the compositor reports none (60 Hz)
+const FALLBACK_FRAME: Duration = Duration::from_micros(16_667);
+
+/// A DwmFlush returning faster than this did not wait for a frame (e.g. RDP)
+const MIN_FLUSH: Duration = Duration::from_millis(1);
+
+/// Frame pacing from compositor timing
+///
+/// Waits for the next vblank reported by DwmGetCompositionTimingInfo, so slides
+/// run at the display's refresh rate (60/120/144/240 Hz). Without timing info
+/// (composition off, some RDP sessions) falls back to DwmFlush, and to sleeping
+/// one frame when DwmFlush returns without waiting. A fixed pacer never
+/// touches DWM: it sleeps out the rest of each period (FPS cap).
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    /// Refresh period in QPC ticks (None: no compositor timing)
+    qpc_period: Option<i64>,
+    /// Refresh period (sleep length when DwmFlush does not wait)
+    period: Duration,
+    /// Fixed pacing: end of the previous wait (Some once started)
+    fixed: Option<Cell<Option<Instant>>>,
+}
+
+impl FramePacer {
+    pub fn new() -> Self {
+        let pacer = match composition_timing() {
+            Some(info) if info.qpcRefreshPeriod > 0 => {
+                let qpc_period = info.qpcRefreshPeriod as i64;
+                let period = qpc_to_duration(qpc_period).unwrap_or(FALLBACK_FRAME);
+                Self {
+                    qpc_period: Some(qpc_period),
+                    period,
+                    fixed: None,
+                }
+            }
+            _ => Self {
+                qpc_period: None,
+                period: FALLBACK_FRAME,
+                fixed: None,
+            },
+        };
+        tracing::trace!(
+            period_us = pacer.period.as_micros() as u64,
+            timed = pacer.qpc_period.is_some(),
+            "Frame pacing"
+        );
+        pacer
+    }
+
+    /// Fixed pacing at `fps` frames per second, without DWM
+    pub fn fixed(fps: u32) -> Self {
+        Self {
+            qpc_period: None,
+            period: frame_period(fps),
+            fixed: Some(Cell::new(None)),
+        }
+    }
+
+    /// Block until the next frame
+    pub fn wait(&self) {
+        if let Some(last) = &self.fixed {
+            let since = last.get().map(|last| last.elapsed());
+            std::thread::sleep(cap_delay(self.period, since));
+            last.set(Some(Instant::now()));
+            return;
+        }
+        if let Some(delay) = self.qpc_period.and_then(next_vblank_delay) {
+            std::thread::sleep(delay);
+            return;
+        }
+        let start = Instant::now();
+        let flushed = unsafe { DwmFlush() }.is_ok();
+        let elapsed = start.elapsed();
+        if !flushed || elapsed < MIN_FLUSH {
+            std::thread::sleep(self.period.saturating_sub(elapsed));
+        }
+    }
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Length of one frame at `fps`
+fn frame_period(fps: u32) -> Duration {
+    Duration::from_secs(1) / fps.max(1)
+}
+
+/// Sleep that keeps frames `period` apart, given the time since the last one
+/// (first frame: no wait)
+fn cap_delay(period: Duration, since_last: Option<Duration>) -> Duration {
+    since_last.map_or(Duration::ZERO, |since| period.saturating_sub(since))
+}
+
+/// Current compositor timing (None if DWM reports none)
+fn composition_timing() -> Option<DWM_TIMING_INFO> {
+    let mut info = DWM_TIMING_INFO {
+        cbSize: std::mem::size_of::<DWM_TIMING_INFO>() as u32,
+        ..Default::default()
+    };
+    // hwnd must be null since Windows 8.1 (timing of the whole desktop)
+    unsafe { DwmGetCompositionTimingInfo(HWND::default(), &mut info) }.ok()?;
+    Some(info)
+}
+
+/// Time until the vblank after now (re-queried each frame: the phase drifts)
+fn next_vblank_delay(qpc_period: i64) -> Option<Duration> {
+    let info = composition_timing()?;
+    let mut now = 0;
+    unsafe { QueryPerformanceCounter(&mut now) }.ok()?;
+    qpc_to_duration(ticks_to_next_vblank(
+        now,
+        info.qpcVBlank as i64,
+        qpc_period,
+    )?)
+}
+
+/// QPC ticks from `now` to the first vblank after it (vblank may lie before or after now)
+fn ticks_to_next_vblank(now: i64, vblank: i64, period: i64) -> Option<i64> {
+    if period <= 0 {
+        return None;
+    }
+    let phase = (now - vblank).rem_euclid(period);
+    Some(period - phase)
+}
+
+fn qpc_to_duration(ticks: i64) -> Option<Duration> {
+    let mut frequency = 0;
+    unsafe { QueryPerformanceFrequency(&mut frequency) }.ok()?;
+    if frequency <= 0 || ticks < 0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(ticks as f64 / frequency as f64))
 }
 
 /// Calculate window position based on direction and progress
@@ -95,93 +616,350 @@ pub fn calc_position(
     }
 }
 
-/// Run slide animation
-/// slide_in=true: off-screen → original position (show window, animate in)
-/// slide_in=false: original position → off-screen (animate out, hide window)
-pub fn run_animation(
-    hwnd: HWND,
-    config: &AnimConfig,
+/// Window rect of a zoom at `progress`, scaled around the center of `original`
+///
+/// slide_in=true:  progress 0→1 grows from `ZOOM_START` of the size → original
+/// slide_in=false: progress 0→1 shrinks from original → `ZOOM_START` of the size
+pub fn calc_zoom(original: &WindowBounds, progress: f64, slide_in: bool) -> WindowBounds {
+    let t = if slide_in { progress } else { 1.0 - progress };
+    let scale = ZOOM_START + (1.0 - ZOOM_START) * t;
+    let width = (original.width as f64 * scale).round() as i32;
+    let height = (original.height as f64 * scale).round() as i32;
+    WindowBounds {
+        x: original.x + (original.width - width) / 2,
+        y: original.y + (original.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Window rect of a slide in `style` at eased progress `t`
+fn calc_frame(
+    style: AnimStyle,
     direction: Direction,
-    bounds: &WindowBounds,
     work_area: &RECT,
+    original: &WindowBounds,
+    t: f64,
     slide_in: bool,
-) {
-    let duration = Duration::from_millis(config.duration_ms as u64);
-    let start = Instant::now();
+) -> ((i32, i32), (i32, i32)) {
+    match style {
+        AnimStyle::Zoom => {
+            let rect = calc_zoom(original, t, slide_in);
+            ((rect.x, rect.y), (rect.width, rect.height))
+        }
+        AnimStyle::Slide | AnimStyle::SlideFade => (
+            calc_position(direction, work_area, original, t, slide_in),
+            (original.width, original.height),
+        ),
+    }
+}
 
-    // Frame sync: wait for VSync before rendering
-    fn frame_sync() {
-        unsafe {
-            if DwmFlush().is_err() {
-                std::thread::sleep(Duration::from_millis(16));
-            }
+/// Frame rect after the window adopted `current` DPI mid-slide while its bounds
+/// are for `home`: size scaled to match (what the app picked on WM_DPICHANGED),
+/// anchored at the edge leading into the work area so the visible part holds still
+pub fn dpi_adjusted(
+    ((x, y), (width, height)): ((i32, i32), (i32, i32)),
+    home: u32,
+    current: u32,
+    direction: Direction,
+) -> ((i32, i32), (i32, i32)) {
+    if home == 0 || home == current {
+        return ((x, y), (width, height));
+    }
+    let scale = |v: i32| (v as f64 * current as f64 / home as f64).round() as i32;
+    let (new_width, new_height) = (scale(width), scale(height));
+    let position = match direction {
+        Direction::Left => (x + width - new_width, y),
+        Direction::Top => (x, y + height - new_height),
+        Direction::Right | Direction::Bottom => (x, y),
+    };
+    (position, (new_width, new_height))
+}
+
+/// Samples of a slide path checked for a DPI boundary
+const DPI_SAMPLES: usize = 5;
+
+/// Target DPI of a per-monitor-aware window whose path crosses a monitor with
+/// another DPI (None: no adjustment needed)
+fn path_dpi(
+    slide: &Slide,
+    style: AnimStyle,
+    direction: Direction,
+    work_area: &RECT,
+) -> Option<u32> {
+    let api = win32::api();
+    api.window_dpi(slide.hwnd)?;
+    let rect_at = |t: f64| {
+        let ((x, y), (width, height)) =
+            calc_frame(style, direction, work_area, &slide.bounds, t, true);
+        RECT {
+            left: x,
+            top: y,
+            right: x + width,
+            bottom: y + height,
+        }
+    };
+    let home = api.dpi_at(&rect_at(1.0))?;
+    let crosses = (0..DPI_SAMPLES)
+        .map(|i| i as f64 / DPI_SAMPLES as f64)
+        .any(|t| api.dpi_at(&rect_at(t)).is_some_and(|dpi| dpi != home));
+    crosses.then_some(home)
+}
+
+/// Measured frames of one slide
+#[derive(Debug, Clone, Default)]
+pub struct AnimReport {
+    /// Wall time of each animation frame (frame sync to frame sync)
+    pub frames: Vec<Duration>,
+    /// Frames longer than two 60 Hz frames
+    pub dropped: u32,
+    /// First frame sync to the final frame
+    pub total: Duration,
+}
+
+impl AnimReport {
+    pub fn longest(&self) -> Duration {
+        self.frames.iter().copied().max().unwrap_or_default()
+    }
+}
+
+/// Window moved by a slide (its on-screen bounds)
+#[derive(Debug, Clone, Copy)]
+pub struct Slide {
+    pub hwnd: HWND,
+    pub bounds: WindowBounds,
+    /// Start offset within a group animation (stagger)
+    pub delay: Duration,
+    /// Per-app compositing treatment
+    pub compat: Compat,
+}
+
+/// One frame's window moves, applied together
+///
+/// A single move is a plain SetWindowPos; several go through one
+/// DeferWindowPos batch so the windows land in the same composition (no
+/// tearing between them). Falls back to per-window moves if batching fails.
+#[derive(Debug, Default)]
+pub struct FrameBatch {
+    moves: Vec<Move>,
+}
+
+impl FrameBatch {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            moves: Vec::with_capacity(capacity),
         }
     }
 
-    // Apply WS_EX_COMPOSITED for double-buffered rendering (anti-flicker)
-    let original_exstyle = unsafe { GetWindowLongPtrW(hwnd, GWL_EXSTYLE) };
-    unsafe {
-        SetWindowLongPtrW(
+    /// Queue a topmost move of `hwnd` to (x, y) with size (width, height)
+    pub fn push(
+        &mut self,
+        hwnd: HWND,
+        (x, y): (i32, i32),
+        (width, height): (i32, i32),
+        flags: SET_WINDOW_POS_FLAGS,
+    ) {
+        self.moves.push(Move {
             hwnd,
-            GWL_EXSTYLE,
-            original_exstyle | WS_EX_COMPOSITED.0 as isize,
-        );
-        // Force repaint after style change to refresh DWM buffer
-        let _ = InvalidateRect(Some(hwnd), None, true);
+            x,
+            y,
+            width,
+            height,
+            flags,
+        });
     }
 
-    // Show window at start position if sliding in
+    /// Apply and clear the queued moves
+    pub fn apply(&mut self) {
+        win32::api().move_windows(&self.moves);
+        self.moves.clear();
+    }
+}
+
+/// Configured stagger between group members (0 = slide together)
+pub fn group_stagger() -> Duration {
+    let ms = settings::get_u32(GROUP_STAGGER_MS, DEFAULT_GROUP_STAGGER_MS);
+    Duration::from_millis(ms.min(MAX_GROUP_STAGGER_MS) as u64)
+}
+
+/// Start delays for `count` windows in order: cascade forward on slide-in,
+/// backward on slide-out (the first window leaves last)
+pub fn stagger_delays(count: usize, stagger: Duration, slide_in: bool) -> Vec<Duration> {
+    (0..count)
+        .map(|i| {
+            let step = if slide_in { i } else { count - 1 - i };
+            stagger * step as u32
+        })
+        .collect()
+}
+
+/// Progress of a slide that starts `delay` into a group animation
+/// progress: overall progress (0.0-1.0) of an animation lasting `total`
+fn staggered_progress(progress: f64, total: Duration, delay: Duration, duration: Duration) -> f64 {
+    if duration.is_zero() {
+        return 1.0;
+    }
+    let elapsed = progress * total.as_secs_f64();
+    ((elapsed - delay.as_secs_f64()) / duration.as_secs_f64()).clamp(0.0, 1.0)
+}
+
+/// Run slide animation for several windows, each starting at its `delay`
+/// (all zero: lockstep, same progress every frame)
+/// slide_in=true: off-screen → original position (show windows, animate in)
+/// slide_in=false: original position → off-screen (animate out, hide windows)
+pub fn run_group_animation(
+    slides: &[Slide],
+    config: &AnimConfig,
+    direction: Direction,
+    work_area: &RECT,
+    slide_in: bool,
+) -> AnimReport {
+    let duration = Duration::from_millis(config.duration_ms as u64);
+    let total = duration + slides.iter().map(|s| s.delay).max().unwrap_or_default();
+    let mut clock = FrameClock::new(total);
+    let mut finished = vec![false; slides.len()];
+    let mut batch = FrameBatch::with_capacity(slides.len());
+
+    // Frame sync: wait for the next vblank (or fixed period) before rendering
+    let pacer = config.fps.map_or_else(FramePacer::new, FramePacer::fixed);
+    let frame_sync = || pacer.wait();
+    // Fixed timestep: every frame advances the same amount, however long it took
+    let fixed_step = config.fps.map(frame_period);
+
+    // Apply WS_EX_COMPOSITED for double-buffered rendering (anti-flicker)
+    let api = win32::api();
+    let original_exstyles: Vec<isize> = slides
+        .iter()
+        .map(|slide| {
+            let original = api.ex_style(slide.hwnd);
+            if config.composited && slide.compat.composited {
+                api.set_ex_style(slide.hwnd, original | WS_EX_COMPOSITED.0 as isize);
+            }
+            if slide.compat.invalidate {
+                // Force repaint after style change to refresh DWM buffer
+                api.invalidate(slide.hwnd);
+            }
+            original
+        })
+        .collect();
+
+    // Shadows turned off for the slide (None: left alone)
+    let shadows: Vec<Option<Shadow>> = slides
+        .iter()
+        .map(|slide| {
+            if !config.hide_shadow {
+                return None;
+            }
+            let shadow = api.shadow(slide.hwnd);
+            shadow.any().then(|| {
+                api.set_shadow(slide.hwnd, Shadow::default());
+                shadow
+            })
+        })
+        .collect();
+
+    // Fade target per window: its current alpha (opaque if not layered).
+    // Windows layered without an alpha (UpdateLayeredWindow) only slide.
+    let layered = WS_EX_LAYERED.0 as isize;
+    let fade_targets: Vec<Option<u8>> = slides
+        .iter()
+        .zip(&original_exstyles)
+        .map(|(slide, &exstyle)| {
+            if !config.style.fades() {
+                return None;
+            }
+            if exstyle & layered == 0 {
+                api.set_ex_style(slide.hwnd, api.ex_style(slide.hwnd) | layered);
+                api.set_alpha(slide.hwnd, u8::MAX);
+                return Some(u8::MAX);
+            }
+            api.alpha(slide.hwnd)
+        })
+        .collect();
+
+    // Windows whose path crosses a DPI boundary (target DPI)
+    let home_dpis: Vec<Option<u32>> = slides
+        .iter()
+        .map(|slide| path_dpi(slide, config.style, direction, work_area))
+        .collect();
+
+    // Show windows at start position if sliding in
     if slide_in {
+        for (slide, target) in slides.iter().zip(&fade_targets) {
+            if target.is_some() {
+                api.set_alpha(slide.hwnd, 0);
+            }
+        }
         frame_sync(); // sync BEFORE window becomes visible
-        let (x, y) = calc_position(direction, work_area, bounds, 0.0, true);
-        unsafe {
-            let _ = SetWindowPos(
-                hwnd,
-                Some(HWND_TOPMOST),
-                x,
-                y,
-                bounds.width,
-                bounds.height,
-                SWP_SHOWWINDOW,
-            );
+        for slide in slides {
+            let (position, size) =
+                calc_frame(config.style, direction, work_area, &slide.bounds, 0.0, true);
+            batch.push(slide.hwnd, position, size, SWP_SHOWWINDOW);
         }
+        batch.apply();
     }
 
     // Animation loop
+    let start = Instant::now();
+    let mut last_frame = start;
+    let mut frames = Vec::new();
     loop {
         frame_sync(); // sync BEFORE position update
 
-        let elapsed = start.elapsed();
-        let raw_t = (elapsed.as_secs_f64() / duration.as_secs_f64()).min(1.0);
-        let t = config.easing.apply(raw_t);
-        let is_final = raw_t >= 1.0;
-
-        let (x, y) = calc_position(direction, work_area, bounds, t, slide_in);
-
-        // Atomic hide: combine final position with SWP_HIDEWINDOW
-        // slide_in: allow activation (no SWP_NOACTIVATE)
-        // slide_out: prevent activation + hide at final frame
-        let flags = if is_final && !slide_in {
-            SWP_NOACTIVATE | SWP_HIDEWINDOW
-        } else if slide_in {
-            SWP_NOZORDER // allow activation during slide_in
-        } else {
-            SWP_NOACTIVATE
-        };
+        // Measure the frame; the clock bounds the step after a stall
+        let now = Instant::now();
+        let dt = now - last_frame;
+        frames.push(dt);
+        let raw_t = clock.advance(fixed_step.unwrap_or(dt));
+        last_frame = now;
+
+        for (((slide, finished), target), home_dpi) in slides
+            .iter()
+            .zip(finished.iter_mut())
+            .zip(&fade_targets)
+            .zip(&home_dpis)
+        {
+            if *finished {
+                continue;
+            }
+            let slide_t = staggered_progress(raw_t, total, slide.delay, duration);
+            let t = config.progress(slide_in, slide_t);
+            *finished = slide_t >= 1.0;
 
-        unsafe {
-            let _ = SetWindowPos(
-                hwnd,
-                Some(HWND_TOPMOST),
-                x,
-                y,
-                bounds.width,
-                bounds.height,
-                flags,
+            // Atomic hide: combine final position with SWP_HIDEWINDOW
+            // slide_in: allow activation (no SWP_NOACTIVATE)
+            // slide_out: prevent activation + hide at final frame
+            let flags = if *finished && !slide_in {
+                SWP_NOACTIVATE | SWP_HIDEWINDOW
+            } else if slide_in {
+                SWP_NOZORDER // allow activation during slide_in
+            } else {
+                SWP_NOACTIVATE
+            };
+
+            let mut frame = calc_frame(
+                config.style,
+                direction,
+                work_area,
+                &slide.bounds,
+                t,
+                slide_in,
             );
+            // Past a DPI boundary: keep the size the app chose instead of fighting it
+            if let Some(home) = *home_dpi
+                && let Some(current) = api.window_dpi(slide.hwnd)
+            {
+                frame = dpi_adjusted(frame, home, current, direction);
+            }
+            let (position, size) = frame;
+            batch.push(slide.hwnd, position, size, flags);
+            if let Some(target) = *target {
+                api.set_alpha(slide.hwnd, fade_alpha(target, t, slide_in));
+            }
         }
+        batch.apply();
 
-        if is_final {
+        if raw_t >= 1.0 {
             break;
         }
     }
@@ -191,12 +969,40 @@ pub fn run_animation(
         frame_sync();
     }
 
-    // Restore original extended style
-    unsafe {
+    let report = AnimReport {
+        frames,
+        dropped: clock.dropped(),
+        total: last_frame - start,
+    };
+    if report.dropped > 0 {
+        tracing::debug!(
+            dropped = report.dropped,
+            longest_ms = report.longest().as_millis() as u64,
+            total_ms = report.total.as_millis() as u64,
+            "Animation frames dropped"
+        );
+    }
+
+    // Restore original alpha and extended style
+    for ((slide, original_exstyle), target) in
+        slides.iter().zip(original_exstyles).zip(fade_targets)
+    {
+        if let Some(target) = target {
+            api.set_alpha(slide.hwnd, target);
+        }
         // Invalidate before style restoration to prevent black artifacts
-        let _ = InvalidateRect(Some(hwnd), None, true);
-        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, original_exstyle);
+        if slide.compat.invalidate {
+            api.invalidate(slide.hwnd);
+        }
+        api.set_ex_style(slide.hwnd, original_exstyle);
+    }
+    for (slide, shadow) in slides.iter().zip(shadows) {
+        if let Some(shadow) = shadow {
+            api.set_shadow(slide.hwnd, shadow);
+        }
     }
+
+    report
 }
 
 #[cfg(test)]
@@ -217,6 +1023,162 @@ mod tests {
         assert!((Easing::Cubic.apply(0.5) - 0.875).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_easings_monotonic_from_zero_to_one() {
+        for easing in Easing::ALL {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+            let samples: Vec<f64> = (0..=20).map(|i| easing.apply(i as f64 / 20.0)).collect();
+            assert!(samples.windows(2).all(|w| w[0] <= w[1]), "{easing:?}");
+        }
+        // Snappier curves cover more ground early
+        assert!(Easing::Expo.apply(0.25) > Easing::Quad.apply(0.25));
+    }
+
+    #[test]
+    fn test_easing_names() {
+        for easing in Easing::ALL {
+            assert_eq!(Easing::from_name(easing.name()), Some(easing));
+        }
+        assert_eq!(Easing::from_name(" expo "), Some(Easing::Expo));
+        assert_eq!(Easing::from_name("bounce"), None);
+    }
+
+    #[test]
+    fn test_anim_config_easing_per_direction() {
+        let config = AnimConfig {
+            show_easing: Easing::Expo,
+            hide_easing: Easing::Quad,
+            ..AnimConfig::default()
+        };
+        assert_eq!(config.easing(true), Easing::Expo);
+        assert_eq!(config.easing(false), Easing::Quad);
+    }
+
+    #[test]
+    fn test_parse_segments() {
+        assert_eq!(parse_segments("Settle"), Some(SETTLE.to_vec()));
+        assert_eq!(parse_segments(" bounce "), Some(BOUNCE.to_vec()));
+        assert_eq!(
+            parse_segments("0.5:0.9:expo; 1:1"),
+            Some(vec![
+                Segment::new(0.5, 0.9, Easing::Expo),
+                Segment::new(1.0, 1.0, Easing::Cubic),
+            ])
+        );
+        // Must end exactly on the bounds, times increasing
+        assert_eq!(parse_segments("1:0.9:Quad"), None);
+        assert_eq!(parse_segments("0.6:0.9;0.4:0.95;1:1"), None);
+        assert_eq!(parse_segments("0.5:0.9:wobble;1:1"), None);
+        assert_eq!(parse_segments("0.5:2;1:1"), None);
+        assert_eq!(parse_segments(""), None);
+    }
+
+    #[test]
+    fn test_apply_segments() {
+        assert_eq!(apply_segments(&SETTLE, 0.0), 0.0);
+        assert!((apply_segments(&SETTLE, 0.4) - 0.95).abs() < 1e-9);
+        assert_eq!(apply_segments(&SETTLE, 1.0), 1.0);
+        // Bounce passes the target before landing on it
+        assert!(apply_segments(&BOUNCE, 0.7) > 1.0);
+        assert_eq!(apply_segments(&BOUNCE, 1.0), 1.0);
+    }
+
+    #[test]
+    fn test_anim_config_segments_apply_to_slide_in() {
+        let config = AnimConfig {
+            segments: SETTLE.to_vec(),
+            ..AnimConfig::default()
+        };
+        assert!((config.progress(true, 0.4) - 0.95).abs() < 1e-9);
+        assert_eq!(config.progress(false, 0.5), Easing::Cubic.apply(0.5));
+    }
+
+    #[test]
+    fn test_anim_style_names() {
+        for style in AnimStyle::ALL {
+            assert_eq!(AnimStyle::from_name(style.name()), Some(style));
+        }
+        assert_eq!(
+            AnimStyle::from_name("slidefade"),
+            Some(AnimStyle::SlideFade)
+        );
+        assert_eq!(AnimStyle::from_name("zoom"), None);
+        assert!(!AnimStyle::default().fades());
+    }
+
+    #[test]
+    fn test_fade_alpha() {
+        assert_eq!(fade_alpha(255, 0.0, true), 0);
+        assert_eq!(fade_alpha(255, 1.0, true), 255);
+        assert_eq!(fade_alpha(204, 0.5, true), 102);
+        // Slide-out runs the reverse
+        assert_eq!(fade_alpha(255, 0.0, false), 255);
+        assert_eq!(fade_alpha(255, 1.0, false), 0);
+        // Overshoot stays in range
+        assert_eq!(fade_alpha(200, 1.2, true), 200);
+    }
+
+    #[test]
+    fn test_dpi_adjusted() {
+        let frame = ((-400, 100), (800, 600));
+        // Same DPI: untouched
+        assert_eq!(dpi_adjusted(frame, 96, 96, Direction::Left), frame);
+        // Neighbor at 150%: grown, right edge (leading into the screen) fixed
+        assert_eq!(
+            dpi_adjusted(frame, 96, 144, Direction::Left),
+            ((-800, 100), (1200, 900))
+        );
+        assert_eq!(
+            dpi_adjusted(frame, 144, 96, Direction::Right),
+            ((-400, 100), (533, 400))
+        );
+        // Top slide keeps the bottom edge
+        assert_eq!(
+            dpi_adjusted(((0, -600), (800, 600)), 96, 192, Direction::Top),
+            ((0, -1800), (1600, 1200))
+        );
+    }
+
+    #[test]
+    fn test_calc_zoom() {
+        let bounds = make_bounds(100, 100, 800, 600);
+        // Starts at half size around the center
+        let start = calc_zoom(&bounds, 0.0, true);
+        assert_eq!(start, make_bounds(300, 250, 400, 300));
+        assert_eq!(calc_zoom(&bounds, 1.0, true), bounds);
+        // Slide-out runs the reverse
+        assert_eq!(calc_zoom(&bounds, 0.0, false), bounds);
+        assert_eq!(calc_zoom(&bounds, 1.0, false), start);
+    }
+
+    #[test]
+    fn test_calc_frame_zoom_keeps_position_on_screen() {
+        let work_area = make_work_area(0, 0, 1920, 1080);
+        let bounds = make_bounds(100, 100, 800, 600);
+        let (position, size) = calc_frame(
+            AnimStyle::Zoom,
+            Direction::Left,
+            &work_area,
+            &bounds,
+            0.0,
+            true,
+        );
+        assert_eq!(position, (300, 250));
+        assert_eq!(size, (400, 300));
+        // Slide styles move at full size
+        let (position, size) = calc_frame(
+            AnimStyle::Slide,
+            Direction::Left,
+            &work_area,
+            &bounds,
+            0.0,
+            true,
+        );
+        assert_eq!(position, (-800, 100));
+        assert_eq!(size, (800, 600));
+    }
+
     // ========== Lerp Tests ==========
 
     #[test]
@@ -237,6 +1199,163 @@ mod tests {
         assert_eq!(lerp(-100, 0, 0.5), -50);
     }
 
+    // ========== FramePacer Tests ==========
+
+    #[test]
+    fn test_next_vblank_after_last_vblank() {
+        // Last vblank 100 ticks ago, period 1000: next one in 900
+        assert_eq!(ticks_to_next_vblank(10_100, 10_000, 1_000), Some(900));
+    }
+
+    #[test]
+    fn test_next_vblank_skips_whole_periods() {
+        assert_eq!(ticks_to_next_vblank(12_100, 10_000, 1_000), Some(900));
+    }
+
+    #[test]
+    fn test_next_vblank_reported_ahead() {
+        // Reported vblank still in the future
+        assert_eq!(ticks_to_next_vblank(9_700, 10_000, 1_000), Some(300));
+    }
+
+    #[test]
+    fn test_next_vblank_on_boundary_waits_full_period() {
+        assert_eq!(ticks_to_next_vblank(11_000, 10_000, 1_000), Some(1_000));
+    }
+
+    #[test]
+    fn test_next_vblank_invalid_period() {
+        assert_eq!(ticks_to_next_vblank(10_100, 10_000, 0), None);
+    }
+
+    #[test]
+    fn test_frame_period() {
+        assert_eq!(frame_period(60), Duration::from_nanos(16_666_666));
+        assert_eq!(frame_period(0), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_cap_delay() {
+        let period = Duration::from_millis(16);
+        assert_eq!(cap_delay(period, None), Duration::ZERO);
+        assert_eq!(
+            cap_delay(period, Some(Duration::from_millis(4))),
+            Duration::from_millis(12)
+        );
+        // Slow frame: no extra wait
+        assert_eq!(
+            cap_delay(period, Some(Duration::from_millis(30))),
+            Duration::ZERO
+        );
+    }
+
+    // ========== FrameBatch Tests ==========
+
+    #[test]
+    fn test_frame_batch_queues_moves() {
+        let mut batch = FrameBatch::default();
+        batch.push(HWND(0x1000 as *mut _), (0, 0), (100, 100), SWP_NOACTIVATE);
+        batch.push(HWND(0x2000 as *mut _), (100, 0), (100, 100), SWP_NOACTIVATE);
+        assert_eq!(batch.moves.len(), 2);
+        assert_eq!(batch.moves[1].x, 100);
+    }
+
+    #[test]
+    fn test_anim_report_longest() {
+        let report = AnimReport {
+            frames: vec![FRAME, Duration::from_millis(40), FRAME],
+            dropped: 1,
+            total: Duration::from_millis(72),
+        };
+        assert_eq!(report.longest(), Duration::from_millis(40));
+        assert_eq!(AnimReport::default().longest(), Duration::ZERO);
+    }
+
+    // ========== FrameClock Tests ==========
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    #[test]
+    fn test_frame_clock_steady_frames_follow_wall_time() {
+        let mut clock = FrameClock::new(Duration::from_millis(160));
+        for _ in 0..5 {
+            clock.advance(FRAME);
+        }
+        assert!((clock.progress() - 0.5).abs() < 1e-9);
+        assert_eq!(clock.dropped(), 0);
+    }
+
+    #[test]
+    fn test_frame_clock_stall_is_capped() {
+        let mut clock = FrameClock::new(Duration::from_millis(200));
+        clock.advance(FRAME);
+        // 150 ms stall: progress may only move by MAX_FRAME_DELTA
+        let before = clock.progress();
+        let after = clock.advance(Duration::from_millis(150));
+        assert!((after - before - 0.25).abs() < 1e-9);
+        assert_eq!(clock.dropped(), 1);
+    }
+
+    #[test]
+    fn test_frame_clock_catches_up_and_finishes() {
+        let mut clock = FrameClock::new(Duration::from_millis(200));
+        clock.advance(Duration::from_millis(150));
+        // Lagging: next regular frame advances more than its own duration
+        let before = clock.progress();
+        let after = clock.advance(FRAME);
+        assert!(after - before > 0.08);
+
+        let mut frames = 0;
+        while clock.progress() < 1.0 {
+            clock.advance(FRAME);
+            frames += 1;
+            assert!(frames < 20, "animation did not finish");
+        }
+    }
+
+    #[test]
+    fn test_frame_clock_zero_duration() {
+        let mut clock = FrameClock::new(Duration::ZERO);
+        assert_eq!(clock.advance(FRAME), 1.0);
+    }
+
+    // ========== Stagger Tests ==========
+
+    #[test]
+    fn test_stagger_delays_order() {
+        let stagger = Duration::from_millis(40);
+        let ms = |d: Vec<Duration>| d.iter().map(|d| d.as_millis()).collect::<Vec<_>>();
+        assert_eq!(ms(stagger_delays(3, stagger, true)), [0, 40, 80]);
+        assert_eq!(ms(stagger_delays(3, stagger, false)), [80, 40, 0]);
+        assert!(stagger_delays(0, stagger, true).is_empty());
+    }
+
+    #[test]
+    fn test_staggered_progress() {
+        let duration = Duration::from_millis(200);
+        let delay = Duration::from_millis(100);
+        let total = duration + delay;
+        // Not started yet
+        assert_eq!(staggered_progress(0.2, total, delay, duration), 0.0);
+        // Halfway through its own slide
+        let half = staggered_progress(200.0 / 300.0, total, delay, duration);
+        assert!((half - 0.5).abs() < 1e-9);
+        assert_eq!(staggered_progress(1.0, total, delay, duration), 1.0);
+        // Undelayed slide finishes before the group does
+        assert_eq!(
+            staggered_progress(0.7, total, Duration::ZERO, duration),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_staggered_progress_zero_duration() {
+        assert_eq!(
+            staggered_progress(0.0, Duration::ZERO, Duration::ZERO, Duration::ZERO),
+            1.0
+        );
+    }
+
     // ========== Position Tests ==========
 
     fn make_work_area(left: i32, top: i32, right: i32, bottom: i32) -> RECT {
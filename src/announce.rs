@@ -0,0 +1,124 @@
+//! Screen reader announcements for silent state changes (opt-in)
+//!
+//! With a screen reader running (SPI_GETSCREENREADER) the text is raised as a
+//! UI Automation notification on the foreground window; otherwise, or if UIA
+//! fails, it is spoken through SAPI.
+
+use std::cell::RefCell;
+use windows::Win32::Media::Speech::{ISpVoice, SPF_ASYNC, SPF_PURGEBEFORESPEAK, SpVoice};
+use windows::Win32::System::Com::{
+    CLSCTX_ALL, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+};
+use windows::Win32::UI::Accessibility::{
+    NotificationKind_Other, NotificationProcessing_ImportantMostRecent, UiaHostProviderFromHwnd,
+    UiaRaiseNotificationEvent,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetForegroundWindow, SPI_GETSCREENREADER, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    SystemParametersInfoW,
+};
+use windows::core::{BSTR, HSTRING};
+
+use crate::i18n::{Text, t, tf};
+use crate::settings::{self, SettingsError};
+
+const ANNOUNCE: &str = "ScreenReaderAnnounce";
+
+/// UIA activity id (lets screen readers group / replace our notifications)
+const ACTIVITY_ID: &str = "QuakeModoki.State";
+
+thread_local! {
+    /// SAPI voice kept alive so async speech is not cut off
+    static VOICE: RefCell<Option<ISpVoice>> = const { RefCell::new(None) };
+}
+
+/// Check if announcements enabled in registry (default: off)
+pub fn is_enabled() -> bool {
+    settings::get_bool(ANNOUNCE, false)
+}
+
+/// Check if announcements is fixed by policy
+pub fn is_locked() -> bool {
+    settings::is_locked(ANNOUNCE)
+}
+
+/// Toggle announcements, returns new state
+pub fn toggle() -> Result<bool, SettingsError> {
+    settings::toggle_bool(ANNOUNCE, false)
+}
+
+/// Window slid in
+pub fn shown() {
+    announce(t(Text::AnnounceShown));
+}
+
+/// Window slid out
+pub fn hidden() {
+    announce(t(Text::AnnounceHidden));
+}
+
+/// Window registered
+pub fn tracked(title: &str) {
+    announce(&tf(Text::Tracking, &[title]));
+}
+
+/// Announce text (no-op when disabled)
+fn announce(text: &str) {
+    if !is_enabled() {
+        return;
+    }
+
+    if screen_reader_running() {
+        match raise_uia_notification(text) {
+            Ok(()) => return,
+            Err(e) => tracing::debug!("UIA notification failed, using SAPI: {e}"),
+        }
+    }
+    if let Err(e) = speak(text) {
+        tracing::warn!("Announcement failed: {e}");
+    }
+}
+
+fn screen_reader_running() -> bool {
+    let mut running = windows::core::BOOL(0);
+    unsafe {
+        SystemParametersInfoW(
+            SPI_GETSCREENREADER,
+            0,
+            Some(&mut running as *mut _ as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    }
+    .is_ok()
+        && running.as_bool()
+}
+
+fn raise_uia_notification(text: &str) -> windows::core::Result<()> {
+    unsafe {
+        let provider = UiaHostProviderFromHwnd(GetForegroundWindow())?;
+        UiaRaiseNotificationEvent(
+            &provider,
+            NotificationKind_Other,
+            NotificationProcessing_ImportantMostRecent,
+            &BSTR::from(text),
+            &BSTR::from(ACTIVITY_ID),
+        )
+    }
+}
+
+/// Speak asynchronously, interrupting any previous announcement
+fn speak(text: &str) -> windows::core::Result<()> {
+    VOICE.with(|voice| {
+        let mut voice = voice.borrow_mut();
+        let voice = match voice.as_ref() {
+            Some(v) => v,
+            None => unsafe {
+                // S_FALSE / RPC_E_CHANGED_MODE: COM already initialized on this thread
+                let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+                voice.insert(CoCreateInstance(&SpVoice, None, CLSCTX_ALL)?)
+            },
+        };
+        let flags = (SPF_ASYNC.0 | SPF_PURGEBEFORESPEAK.0) as u32;
+        unsafe { voice.Speak(&HSTRING::from(text), flags, None) }
+    })
+}
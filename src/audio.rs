@@ -0,0 +1,113 @@
+//! Audio muting: silence the tracked process's audio sessions while hidden
+//!
+//! Uses Core Audio session enumeration on the default render endpoint.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Media::Audio::{
+    IAudioSessionControl2, IAudioSessionManager2, IMMDeviceEnumerator, ISimpleAudioVolume,
+    MMDeviceEnumerator, eMultimedia, eRender,
+};
+use windows::Win32::System::Com::{
+    CLSCTX_ALL, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+};
+use windows::core::Interface;
+
+use crate::settings::{self, SettingsError};
+
+const MUTE_WHEN_HIDDEN: &str = "MuteWhenHidden";
+
+/// Process muted by us, by hidden window; unmuted on show/untrack
+static MUTED_PIDS: Mutex<BTreeMap<isize, u32>> = Mutex::new(BTreeMap::new());
+
+/// Check if mute-while-hidden enabled in registry (default: off)
+pub fn is_enabled() -> bool {
+    settings::get_bool(MUTE_WHEN_HIDDEN, false)
+}
+
+/// Check if mute-while-hidden is fixed by policy
+pub fn is_locked() -> bool {
+    settings::is_locked(MUTE_WHEN_HIDDEN)
+}
+
+/// Toggle mute-while-hidden, returns new state
+pub fn toggle() -> Result<bool, SettingsError> {
+    settings::toggle_bool(MUTE_WHEN_HIDDEN, false)
+}
+
+/// Mute tracked process after slide-out (no-op when disabled)
+pub fn mute(hwnd: HWND) {
+    if !is_enabled() {
+        return;
+    }
+
+    let pid = crate::tracking::get_process_id(hwnd);
+    if pid == 0 {
+        return;
+    }
+
+    match set_process_mute(pid, true) {
+        Ok(0) => {}
+        Ok(count) => {
+            MUTED_PIDS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(hwnd.0 as isize, pid);
+            tracing::debug!(pid, count, "Audio sessions muted");
+        }
+        Err(e) => tracing::warn!("Audio mute failed: {e}"),
+    }
+}
+
+/// Unmute sessions previously muted by `mute` for `hwnd`
+pub fn unmute(hwnd: HWND) {
+    let Some(pid) = MUTED_PIDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&(hwnd.0 as isize))
+    else {
+        return;
+    };
+
+    if let Err(e) = set_process_mute(pid, false) {
+        tracing::warn!("Audio unmute failed: {e}");
+    }
+}
+
+/// Set mute state of all sessions owned by pid
+/// Muting skips sessions the user already muted; returns sessions changed
+fn set_process_mute(pid: u32, mute: bool) -> windows::core::Result<u32> {
+    unsafe {
+        // Per-thread COM init; S_FALSE / RPC_E_CHANGED_MODE are fine here
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+        let device = enumerator.GetDefaultAudioEndpoint(eRender, eMultimedia)?;
+        let manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)?;
+        let sessions = manager.GetSessionEnumerator()?;
+
+        let mut changed = 0;
+        for i in 0..sessions.GetCount()? {
+            let Ok(control) = sessions.GetSession(i) else {
+                continue;
+            };
+            let Ok(control) = control.cast::<IAudioSessionControl2>() else {
+                continue;
+            };
+            if control.GetProcessId().ok() != Some(pid) {
+                continue;
+            }
+            let Ok(volume) = control.cast::<ISimpleAudioVolume>() else {
+                continue;
+            };
+            if volume.GetMute()?.as_bool() != mute {
+                volume.SetMute(mute, std::ptr::null())?;
+                changed += 1;
+            }
+        }
+
+        Ok(changed)
+    }
+}
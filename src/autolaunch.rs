@@ -1,13 +1,40 @@
 //! Auto-launch via Windows Registry (HKCU\Software\Microsoft\Windows\CurrentVersion\Run)
+//! or a Task Scheduler logon task
+//!
+//! The Run key needs no rights but can't elevate or delay. The task backend
+//! (`AutoLaunchBackend` = "task") runs with highest privileges after a short
+//! logon delay; creating it needs an elevated process.
+//! Both start the exe with `--autostart`, which applies `AutoLaunchDelaySec`
+//! before hotkeys and the edge poller are set up.
 
 use std::env;
+use std::os::windows::process::CommandExt;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
 use thiserror::Error;
+use windows::Win32::System::Threading::CREATE_NO_WINDOW;
 use winreg::RegKey;
 use winreg::enums::{HKEY_CURRENT_USER, KEY_READ, KEY_WRITE};
 
+use crate::settings::{self, SettingsError};
+
 const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
 const APP_NAME: &str = "Quake Modoki";
 
+/// Registry value selecting the backend
+const AUTO_LAUNCH_BACKEND: &str = "AutoLaunchBackend";
+
+/// Registry value: extra seconds to wait when started by auto-launch
+const AUTO_LAUNCH_DELAY_SEC: &str = "AutoLaunchDelaySec";
+const MAX_DELAY_SEC: u32 = 600;
+
+/// Argument marking a start by auto-launch
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+/// Logon trigger delay of the scheduled task (schtasks mmmm:ss)
+const TASK_DELAY: &str = "0000:30";
+
 #[derive(Debug, Error)]
 pub enum AutoLaunchError {
     #[error("Registry access failed: {0}")]
@@ -15,33 +42,81 @@ pub enum AutoLaunchError {
 
     #[error("Executable path not found")]
     ExePath,
+
+    #[error("Task Scheduler failed: {0}")]
+    TaskScheduler(String),
+
+    #[error("Settings write failed: {0}")]
+    Settings(#[from] SettingsError),
+}
+
+/// Where the auto-launch entry lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// HKCU Run key (default)
+    RunKey,
+    /// Elevated, delayed Task Scheduler logon task
+    TaskScheduler,
+}
+
+impl Backend {
+    fn as_str(self) -> &'static str {
+        match self {
+            Backend::RunKey => "run",
+            Backend::TaskScheduler => "task",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        if value.eq_ignore_ascii_case("task") {
+            Backend::TaskScheduler
+        } else {
+            Backend::RunKey
+        }
+    }
+}
+
+/// Selected backend
+pub fn backend() -> Backend {
+    settings::get_string(AUTO_LAUNCH_BACKEND)
+        .map(|v| Backend::parse(&v))
+        .unwrap_or(Backend::RunKey)
+}
+
+/// Switch backend; an enabled entry moves to the new backend
+pub fn set_backend(new: Backend) -> Result<(), AutoLaunchError> {
+    let old = backend();
+    if old == new {
+        return Ok(());
+    }
+    let was_enabled = is_enabled();
+    if was_enabled {
+        // Create first so a failed (non-elevated) task creation keeps the old entry
+        enable_with(new)?;
+        disable_with(old)?;
+    }
+    settings::set_string(AUTO_LAUNCH_BACKEND, new.as_str())?;
+    Ok(())
 }
 
-/// Check if auto-launch enabled in registry
+/// Startup delay for auto-launched starts (0 = none, capped at 10 min)
+pub fn startup_delay() -> Duration {
+    Duration::from_secs(settings::get_u32(AUTO_LAUNCH_DELAY_SEC, 0).min(MAX_DELAY_SEC) as u64)
+}
+
+/// Check if auto-launch enabled (selected backend)
 pub fn is_enabled() -> bool {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    hkcu.open_subkey_with_flags(RUN_KEY, KEY_READ)
-        .ok()
-        .and_then(|key| key.get_value::<String, _>(APP_NAME).ok())
-        .is_some()
+    is_enabled_with(backend())
 }
 
-/// Enable auto-launch (write exe path to registry)
+/// Enable auto-launch (selected backend)
 pub fn enable() -> Result<(), AutoLaunchError> {
-    let exe_path = env::current_exe().map_err(|_| AutoLaunchError::ExePath)?;
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let (key, _) = hkcu.create_subkey(RUN_KEY)?;
-    key.set_value(APP_NAME, &format!("\"{}\"", exe_path.display()))?;
-    Ok(())
+    enable_with(backend())
 }
 
-/// Disable auto-launch (remove registry key)
+/// Disable auto-launch (selected backend)
 pub fn disable() -> Result<(), AutoLaunchError> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let key = hkcu.open_subkey_with_flags(RUN_KEY, KEY_WRITE)?;
-    // Ignore error if key doesn't exist
-    let _ = key.delete_value(APP_NAME);
-    Ok(())
+    disable_with(backend())
 }
 
 /// Toggle auto-launch state, returns new state
@@ -55,6 +130,84 @@ pub fn toggle() -> Result<bool, AutoLaunchError> {
     }
 }
 
+fn is_enabled_with(backend: Backend) -> bool {
+    match backend {
+        Backend::RunKey => {
+            let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+            hkcu.open_subkey_with_flags(RUN_KEY, KEY_READ)
+                .ok()
+                .and_then(|key| key.get_value::<String, _>(APP_NAME).ok())
+                .is_some()
+        }
+        Backend::TaskScheduler => schtasks(&["/Query", "/TN", APP_NAME]).is_ok(),
+    }
+}
+
+fn enable_with(backend: Backend) -> Result<(), AutoLaunchError> {
+    let exe_path = env::current_exe().map_err(|_| AutoLaunchError::ExePath)?;
+    match backend {
+        Backend::RunKey => {
+            let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+            let (key, _) = hkcu.create_subkey(RUN_KEY)?;
+            key.set_value(
+                APP_NAME,
+                &format!("\"{}\" {AUTOSTART_ARG}", exe_path.display()),
+            )?;
+            Ok(())
+        }
+        Backend::TaskScheduler => {
+            let args = create_task_args(&exe_path);
+            schtasks(&args.iter().map(String::as_str).collect::<Vec<_>>())
+        }
+    }
+}
+
+fn disable_with(backend: Backend) -> Result<(), AutoLaunchError> {
+    match backend {
+        Backend::RunKey => {
+            let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+            let key = hkcu.open_subkey_with_flags(RUN_KEY, KEY_WRITE)?;
+            // Ignore error if key doesn't exist
+            let _ = key.delete_value(APP_NAME);
+            Ok(())
+        }
+        // Missing task is not an error
+        Backend::TaskScheduler if !is_enabled_with(Backend::TaskScheduler) => Ok(()),
+        Backend::TaskScheduler => schtasks(&["/Delete", "/F", "/TN", APP_NAME]),
+    }
+}
+
+/// schtasks arguments creating the logon task
+fn create_task_args(exe: &Path) -> Vec<String> {
+    [
+        "/Create", "/F", "/TN", APP_NAME, "/SC", "ONLOGON", "/DELAY", TASK_DELAY, "/RL", "HIGHEST",
+        "/IT", "/TR",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .chain(std::iter::once(format!(
+        "\"{}\" {AUTOSTART_ARG}",
+        exe.display()
+    )))
+    .collect()
+}
+
+/// Run schtasks.exe without a console window
+fn schtasks(args: &[&str]) -> Result<(), AutoLaunchError> {
+    let output = Command::new("schtasks")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW.0)
+        .output()
+        .map_err(|e| AutoLaunchError::TaskScheduler(e.to_string()))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(AutoLaunchError::TaskScheduler(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,4 +253,27 @@ mod tests {
         assert!(!new_state);
         assert!(!is_enabled());
     }
+
+    #[test]
+    fn test_backend_parse() {
+        assert_eq!(Backend::parse("task"), Backend::TaskScheduler);
+        assert_eq!(Backend::parse("TASK"), Backend::TaskScheduler);
+        assert_eq!(Backend::parse("run"), Backend::RunKey);
+        assert_eq!(Backend::parse(""), Backend::RunKey);
+        for backend in [Backend::RunKey, Backend::TaskScheduler] {
+            assert_eq!(Backend::parse(backend.as_str()), backend);
+        }
+    }
+
+    #[test]
+    fn test_create_task_args() {
+        let args = create_task_args(Path::new(r"C:\Apps\quake-modoki.exe"));
+        assert_eq!(
+            args.last().unwrap(),
+            r#""C:\Apps\quake-modoki.exe" --autostart"#
+        );
+        assert!(args.windows(2).any(|w| w == ["/RL", "HIGHEST"]));
+        assert!(args.windows(2).any(|w| w == ["/SC", "ONLOGON"]));
+        assert!(args.windows(2).any(|w| w == ["/DELAY", TASK_DELAY]));
+    }
 }
@@ -0,0 +1,301 @@
+//! Settings import / export as JSON (backup, copy between machines)
+//!
+//! Exports the whole HKCU\Software\QuakeModoki tree: DWORDs become numbers,
+//! strings stay strings, subkeys become nested objects. Usage statistics
+//! (`Stats*`) and other value types are left out.
+//!
+//! Import never writes values that run something (hook commands, the default
+//! target's launch command, the terminal profile pasted into `wt.exe`'s command
+//! line, the switch that loads plugin DLLs): a settings file from someone else
+//! must not be able to execute programs. Those are counted and have to be set by hand.
+
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use windows::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx, CoTaskMemFree,
+};
+use windows::Win32::UI::Shell::Common::COMDLG_FILTERSPEC;
+use windows::Win32::UI::Shell::{
+    FileOpenDialog, FileSaveDialog, IFileDialog, IFileOpenDialog, IFileSaveDialog,
+    SIGDN_FILESYSPATH,
+};
+use windows::core::w;
+use winreg::RegKey;
+use winreg::enums::{HKEY_CURRENT_USER, KEY_READ, REG_DWORD, REG_SZ};
+
+use crate::settings;
+
+/// Format version written to / required in the file
+const FORMAT_VERSION: u64 = 1;
+
+/// Top-level keys of the export file
+const VERSION_FIELD: &str = "version";
+const SETTINGS_FIELD: &str = "settings";
+
+/// Values not worth carrying over (counters, not configuration)
+const EXCLUDED_PREFIX: &str = "Stats";
+
+/// Values exported but never imported (run via `cmd /C` / launched / loaded)
+const IMPORT_BLOCKED: [&str; 8] = [
+    "OnShowCommand",
+    "OnHideCommand",
+    "OnTrackCommand",
+    "OnUntrackCommand",
+    "DefaultTarget",
+    "DefaultTargetExe",
+    "TerminalProfile",
+    "EnablePlugins",
+];
+
+const DEFAULT_FILE_NAME: &str = "quake-modoki-settings.json";
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("File access failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid settings file: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unsupported settings file (version {0})")]
+    Version(u64),
+}
+
+/// Result of an import
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Imported {
+    /// Values written to the registry
+    pub written: usize,
+    /// Command / launch values left out (see [`IMPORT_BLOCKED`])
+    pub blocked: usize,
+}
+
+/// Write all settings to `path`
+pub fn export(path: &Path) -> Result<(), BackupError> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let tree = hkcu
+        .open_subkey_with_flags(settings::settings_key(), KEY_READ)
+        .map(|key| read_key(&key))
+        .unwrap_or_default();
+    let document = wrap(tree);
+    std::fs::write(path, serde_json::to_string_pretty(&document)?)?;
+    Ok(())
+}
+
+/// Apply settings from `path`
+pub fn import(path: &Path) -> Result<Imported, BackupError> {
+    let document: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let tree = unwrap(&document)?;
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (key, _) = hkcu.create_subkey(settings::settings_key())?;
+    let mut imported = Imported::default();
+    write_key(&key, tree, &mut imported)?;
+    Ok(imported)
+}
+
+/// Ask for a file to export to (None if cancelled)
+pub fn pick_export_path() -> Option<PathBuf> {
+    pick_file(true)
+}
+
+/// Ask for a file to import from (None if cancelled)
+pub fn pick_import_path() -> Option<PathBuf> {
+    pick_file(false)
+}
+
+/// Settings tree -> export document
+fn wrap(tree: Map<String, Value>) -> Value {
+    let mut document = Map::new();
+    document.insert(VERSION_FIELD.to_string(), Value::from(FORMAT_VERSION));
+    document.insert(SETTINGS_FIELD.to_string(), Value::Object(tree));
+    Value::Object(document)
+}
+
+/// Export document -> settings tree
+fn unwrap(document: &Value) -> Result<&Map<String, Value>, BackupError> {
+    let version = document
+        .get(VERSION_FIELD)
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    if version != FORMAT_VERSION {
+        return Err(BackupError::Version(version));
+    }
+    document
+        .get(SETTINGS_FIELD)
+        .and_then(Value::as_object)
+        .ok_or(BackupError::Version(version))
+}
+
+fn is_excluded(name: &str) -> bool {
+    name.starts_with(EXCLUDED_PREFIX)
+}
+
+/// Registry names are case-insensitive, so is the check
+fn is_import_blocked(name: &str) -> bool {
+    IMPORT_BLOCKED.iter().any(|b| b.eq_ignore_ascii_case(name))
+}
+
+/// Registry key -> JSON object (recursive)
+fn read_key(key: &RegKey) -> Map<String, Value> {
+    let mut map = Map::new();
+    for (name, raw) in key.enum_values().filter_map(Result::ok) {
+        if is_excluded(&name) {
+            continue;
+        }
+        let value = match raw.vtype {
+            REG_DWORD => key.get_value::<u32, _>(&name).ok().map(Value::from),
+            REG_SZ => key.get_value::<String, _>(&name).ok().map(Value::from),
+            _ => None,
+        };
+        if let Some(value) = value {
+            map.insert(name, value);
+        }
+    }
+    for name in key.enum_keys().filter_map(Result::ok) {
+        if let Ok(subkey) = key.open_subkey_with_flags(&name, KEY_READ) {
+            map.insert(name, Value::Object(read_key(&subkey)));
+        }
+    }
+    map
+}
+
+/// JSON object -> registry key (recursive); unsupported JSON types are skipped
+fn write_key(
+    key: &RegKey,
+    map: &Map<String, Value>,
+    imported: &mut Imported,
+) -> Result<(), BackupError> {
+    for (name, value) in map {
+        if is_excluded(name) {
+            continue;
+        }
+        if is_import_blocked(name) && !value.is_object() {
+            tracing::warn!(name, "Skipped setting: commands are not imported");
+            imported.blocked += 1;
+            continue;
+        }
+        match value {
+            Value::Number(n) => {
+                let Some(n) = n.as_u64().and_then(|n| u32::try_from(n).ok()) else {
+                    tracing::warn!(name, "Skipped setting: not a DWORD");
+                    continue;
+                };
+                key.set_value(name, &n)?;
+                imported.written += 1;
+            }
+            Value::Bool(b) => {
+                key.set_value(name, &(*b as u32))?;
+                imported.written += 1;
+            }
+            Value::String(s) => {
+                key.set_value(name, s)?;
+                imported.written += 1;
+            }
+            Value::Object(child) => {
+                let (subkey, _) = key.create_subkey(name)?;
+                write_key(&subkey, child, imported)?;
+            }
+            Value::Null | Value::Array(_) => {
+                tracing::warn!(name, "Skipped setting: unsupported type");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Common file dialog filtered to *.json
+fn pick_file(save: bool) -> Option<PathBuf> {
+    let filter = [COMDLG_FILTERSPEC {
+        pszName: w!("JSON (*.json)"),
+        pszSpec: w!("*.json"),
+    }];
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let dialog: IFileDialog = if save {
+            let dialog: IFileSaveDialog =
+                CoCreateInstance(&FileSaveDialog, None, CLSCTX_INPROC_SERVER).ok()?;
+            dialog.into()
+        } else {
+            let dialog: IFileOpenDialog =
+                CoCreateInstance(&FileOpenDialog, None, CLSCTX_INPROC_SERVER).ok()?;
+            dialog.into()
+        };
+        dialog.SetFileTypes(&filter).ok()?;
+        dialog.SetDefaultExtension(w!("json")).ok()?;
+        if save {
+            let name = windows::core::HSTRING::from(DEFAULT_FILE_NAME);
+            dialog.SetFileName(&name).ok()?;
+        }
+        // Cancel returns an error
+        dialog.Show(None).ok()?;
+        let item = dialog.GetResult().ok()?;
+        let raw = item.GetDisplayName(SIGDN_FILESYSPATH).ok()?;
+        let path = raw.to_string().ok().map(PathBuf::from);
+        CoTaskMemFree(Some(raw.0 as *const _));
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    fn test_wrap_unwrap_roundtrip() {
+        let mut tree = Map::new();
+        tree.insert("EdgeEnabled".to_string(), Value::from(1u32));
+        tree.insert("ToggleHotkey".to_string(), Value::from("Ctrl+F8"));
+        let document = wrap(tree.clone());
+        assert_eq!(unwrap(&document).unwrap(), &tree);
+    }
+
+    #[test]
+    fn test_unwrap_rejects_other_versions() {
+        let document = serde_json::json!({ "version": 99, "settings": {} });
+        assert!(matches!(unwrap(&document), Err(BackupError::Version(99))));
+        let document = serde_json::json!({ "settings": {} });
+        assert!(matches!(unwrap(&document), Err(BackupError::Version(0))));
+    }
+
+    #[test]
+    fn test_stats_excluded() {
+        assert!(is_excluded("StatsTogglesToday"));
+        assert!(!is_excluded("EdgeEnabled"));
+    }
+
+    #[test]
+    fn test_commands_not_imported() {
+        assert!(is_import_blocked("OnHideCommand"));
+        assert!(is_import_blocked("onshowcommand"));
+        assert!(is_import_blocked("DefaultTarget"));
+        assert!(is_import_blocked("TerminalProfile"));
+        assert!(is_import_blocked("EnablePlugins"));
+        assert!(!is_import_blocked("ToggleHotkey"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_import_skips_terminal_profile() {
+        settings::clear();
+        let path = std::env::temp_dir().join("quake-modoki-import-test.json");
+        let document = serde_json::json!({
+            "version": FORMAT_VERSION,
+            "settings": {
+                "TerminalProfile": "x\" ; calc ; \"",
+                "TerminalHeightPct": 50,
+            },
+        });
+        std::fs::write(&path, document.to_string()).expect("write failed");
+
+        let imported = import(&path).expect("import failed");
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(imported.written, 1);
+        assert_eq!(imported.blocked, 1);
+        assert!(settings::get_string("TerminalProfile").is_none());
+        assert_eq!(settings::get_u32("TerminalHeightPct", 40), 50);
+
+        settings::clear();
+    }
+}
@@ -0,0 +1,209 @@
+//! Animation benchmark (feature `bench`, run with `--bench`)
+//!
+//! Slides a self-created test window out and in through the real animation
+//! path and reports per-frame latency, dropped frames and the error of the
+//! total duration, so easing / frame-sync changes can be compared.
+
+use std::fmt::Write as _;
+use std::time::Duration;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CW_USEDEFAULT, CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, MSG,
+    PM_REMOVE, PeekMessageW, RegisterClassW, SW_SHOW, ShowWindow, TranslateMessage,
+    WINDOW_EX_STYLE, WNDCLASSW, WS_OVERLAPPEDWINDOW,
+};
+use windows::core::{PCWSTR, w};
+
+use crate::animation::{AnimConfig, AnimReport, Slide, run_group_animation};
+use crate::compat::Compat;
+use crate::tracking;
+
+/// Slide-out + slide-in pairs per run
+const ROUNDS: usize = 10;
+
+/// Test window size
+const WIDTH: i32 = 800;
+const HEIGHT: i32 = 600;
+
+const CLASS_NAME: PCWSTR = w!("QuakeModokiBench");
+
+/// Run the benchmark, returns the printable report
+pub fn run() -> windows::core::Result<String> {
+    let hwnd = create_window()?;
+    let config = AnimConfig::default();
+    let mut reports = Vec::with_capacity(ROUNDS * 2);
+
+    let setup = tracking::get_work_area(hwnd).zip(tracking::window_bounds(hwnd));
+    if let Some((work_area, bounds)) = setup {
+        let direction = tracking::calc_direction(&bounds, &work_area);
+        let slide = Slide {
+            hwnd,
+            bounds,
+            delay: Duration::ZERO,
+            compat: Compat::default(),
+        };
+        for _ in 0..ROUNDS {
+            for slide_in in [false, true] {
+                reports.push(run_group_animation(
+                    &[slide],
+                    &config,
+                    direction,
+                    &work_area,
+                    slide_in,
+                ));
+                pump_messages();
+            }
+        }
+    }
+
+    unsafe {
+        let _ = DestroyWindow(hwnd);
+    }
+    Ok(format_report(&config, &reports))
+}
+
+/// Visible top-level test window at the default position
+fn create_window() -> windows::core::Result<HWND> {
+    unsafe {
+        let instance = GetModuleHandleW(None)?;
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(wnd_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        RegisterClassW(&class);
+
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            CLASS_NAME,
+            w!("Quake Modoki Bench"),
+            WS_OVERLAPPEDWINDOW,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            WIDTH,
+            HEIGHT,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )?;
+        let _ = ShowWindow(hwnd, SW_SHOW);
+        pump_messages();
+        Ok(hwnd)
+    }
+}
+
+/// Let the test window paint between slides
+fn pump_messages() {
+    let mut msg = MSG::default();
+    unsafe {
+        while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+}
+
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+/// Value at percentile `p` (0-100) of `sorted`
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let index = (sorted.len() - 1) * p.min(100) / 100;
+    sorted[index]
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+fn format_report(config: &AnimConfig, reports: &[AnimReport]) -> String {
+    let mut frames: Vec<Duration> = reports.iter().flat_map(|r| r.frames.clone()).collect();
+    frames.sort();
+    let dropped: u32 = reports.iter().map(|r| r.dropped).sum();
+    let expected = Duration::from_millis(config.duration_ms as u64);
+    let errors: Vec<f64> = reports.iter().map(|r| ms(r.total) - ms(expected)).collect();
+    let mean_error = if errors.is_empty() {
+        0.0
+    } else {
+        errors.iter().sum::<f64>() / errors.len() as f64
+    };
+    let worst_error = errors.iter().fold(0.0_f64, |a, e| a.max(e.abs()));
+    let mean_frame = if frames.is_empty() {
+        Duration::ZERO
+    } else {
+        frames.iter().sum::<Duration>() / frames.len() as u32
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "slides: {} ({} ms each)",
+        reports.len(),
+        config.duration_ms
+    );
+    let _ = writeln!(out, "frames: {}", frames.len());
+    let _ = writeln!(
+        out,
+        "frame ms: mean {:.2} / p50 {:.2} / p95 {:.2} / max {:.2}",
+        ms(mean_frame),
+        ms(percentile(&frames, 50)),
+        ms(percentile(&frames, 95)),
+        ms(percentile(&frames, 100)),
+    );
+    let _ = writeln!(out, "dropped frames: {dropped}");
+    let _ = write!(
+        out,
+        "duration error ms: mean {mean_error:+.2} / worst {worst_error:.2}"
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(list: &[u64]) -> Vec<Duration> {
+        list.iter().map(|&m| Duration::from_millis(m)).collect()
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted = millis(&[10, 16, 17, 17, 50]);
+        assert_eq!(percentile(&sorted, 0), Duration::from_millis(10));
+        assert_eq!(percentile(&sorted, 50), Duration::from_millis(17));
+        assert_eq!(percentile(&sorted, 100), Duration::from_millis(50));
+        assert_eq!(percentile(&[], 95), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_format_report() {
+        let report = AnimReport {
+            frames: millis(&[16, 17, 40]),
+            dropped: 1,
+            total: Duration::from_millis(210),
+        };
+        let text = format_report(&AnimConfig::default(), &[report]);
+        assert!(text.contains("slides: 1 (200 ms each)"));
+        assert!(text.contains("frames: 3"));
+        assert!(text.contains("dropped frames: 1"));
+        assert!(text.contains("mean +10.00"));
+    }
+
+    #[test]
+    fn test_format_report_empty() {
+        let text = format_report(&AnimConfig::default(), &[]);
+        assert!(text.contains("frames: 0"));
+    }
+}
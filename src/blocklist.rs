@@ -0,0 +1,147 @@
+//! Untrackable windows: targets that would break the desktop or ourselves
+//!
+//! Screened before a window is taken under control (no hooks, no animation).
+//! Sliding the shell (GetShellWindow), the taskbar or the desktop (Progman /
+//! WorkerW) off screen leaves the desktop unusable, our own windows (tray
+//! menu, popups) would hide the way back, secure desktop surfaces (UAC
+//! consent, lock screen, logon UI) and protected processes reject our window
+//! calls, and cloaked windows (other virtual desktop, suspended UWP app)
+//! cannot be shown by us.
+
+use windows::Win32::Foundation::{CloseHandle, HWND};
+use windows::Win32::System::Threading::{
+    GetCurrentProcessId, GetProcessInformation, OpenProcess, PROCESS_PROTECTION_LEVEL_INFORMATION,
+    PROCESS_QUERY_LIMITED_INFORMATION, PROTECTION_LEVEL_NONE, ProcessProtectionLevelInfo,
+};
+use windows::Win32::UI::WindowsAndMessaging::{GA_ROOTOWNER, GetAncestor, GetShellWindow};
+
+use crate::context;
+use crate::i18n::{Text, t};
+use crate::tracking;
+
+/// Processes drawing secure desktop / system credential surfaces (lowercase)
+const SECURE_SURFACES: [&str; 4] = [
+    "consent.exe",
+    "logonui.exe",
+    "lockapp.exe",
+    "credentialuibroker.exe",
+];
+
+/// Why a window cannot be tracked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blocked {
+    Shell,
+    Taskbar,
+    Desktop,
+    OwnWindow,
+    SecureSurface,
+    Protected,
+    Cloaked,
+}
+
+impl Blocked {
+    /// Localized reason (toast text)
+    pub fn reason(self) -> &'static str {
+        t(match self {
+            Blocked::Shell => Text::BlockedShell,
+            Blocked::Taskbar => Text::BlockedTaskbar,
+            Blocked::Desktop => Text::BlockedDesktop,
+            Blocked::OwnWindow => Text::BlockedOwnWindow,
+            Blocked::SecureSurface => Text::BlockedSecureSurface,
+            Blocked::Protected => Text::BlockedProtected,
+            Blocked::Cloaked => Text::BlockedCloaked,
+        })
+    }
+}
+
+/// Shell windows by class name
+fn classify_class(class: &str) -> Option<Blocked> {
+    match class {
+        "Shell_TrayWnd" | "Shell_SecondaryTrayWnd" => Some(Blocked::Taskbar),
+        "Progman" | "WorkerW" => Some(Blocked::Desktop),
+        _ => None,
+    }
+}
+
+/// Secure desktop surfaces by exe path
+fn is_secure_surface(exe: &str) -> bool {
+    SECURE_SURFACES.contains(&context::exe_name(exe).as_str())
+}
+
+/// Check if the shell window (explorer's desktop) or a window it owns
+fn is_shell(hwnd: HWND) -> bool {
+    let shell = unsafe { GetShellWindow() };
+    shell != HWND::default()
+        && (hwnd == shell || unsafe { GetAncestor(hwnd, GA_ROOTOWNER) } == shell)
+}
+
+/// Check if the process runs protected (PP / PPL)
+fn is_protected(pid: u32) -> bool {
+    let Ok(process) = (unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) })
+    else {
+        return false;
+    };
+    let mut info = PROCESS_PROTECTION_LEVEL_INFORMATION::default();
+    let queried = unsafe {
+        GetProcessInformation(
+            process,
+            ProcessProtectionLevelInfo,
+            &mut info as *mut _ as *mut _,
+            size_of::<PROCESS_PROTECTION_LEVEL_INFORMATION>() as u32,
+        )
+    };
+    unsafe {
+        let _ = CloseHandle(process);
+    }
+    queried.is_ok() && info.ProtectionLevel != PROTECTION_LEVEL_NONE
+}
+
+/// Check `hwnd` against the blocklist (None: trackable)
+pub fn check(hwnd: HWND) -> Option<Blocked> {
+    if is_shell(hwnd) {
+        return Some(Blocked::Shell);
+    }
+    if let Some(blocked) = classify_class(&tracking::get_window_class(hwnd)) {
+        return Some(blocked);
+    }
+    let pid = tracking::get_process_id(hwnd);
+    if pid == unsafe { GetCurrentProcessId() } {
+        return Some(Blocked::OwnWindow);
+    }
+    if tracking::get_process_path(hwnd).is_some_and(|exe| is_secure_surface(&exe)) {
+        return Some(Blocked::SecureSurface);
+    }
+    if is_protected(pid) {
+        return Some(Blocked::Protected);
+    }
+    tracking::is_cloaked(hwnd).then_some(Blocked::Cloaked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_class() {
+        assert_eq!(classify_class("Shell_TrayWnd"), Some(Blocked::Taskbar));
+        assert_eq!(
+            classify_class("Shell_SecondaryTrayWnd"),
+            Some(Blocked::Taskbar)
+        );
+        assert_eq!(classify_class("Progman"), Some(Blocked::Desktop));
+        assert_eq!(classify_class("WorkerW"), Some(Blocked::Desktop));
+        assert_eq!(classify_class("CASCADIA_HOSTING_WINDOW_CLASS"), None);
+    }
+
+    #[test]
+    fn test_is_secure_surface() {
+        assert!(is_secure_surface(r"C:\Windows\System32\consent.exe"));
+        assert!(is_secure_surface(r"C:\Windows\SystemApps\LockApp.exe"));
+        assert!(!is_secure_surface(r"C:\Windows\explorer.exe"));
+    }
+
+    #[test]
+    fn test_protected_missing_process() {
+        assert!(!is_protected(0));
+    }
+}
@@ -0,0 +1,462 @@
+//! Interactive hotkey capture: "press new shortcut" popup + temporary keyboard hook
+//!
+//! While capturing, a low-level keyboard hook swallows every key. The first
+//! non-modifier key (with the modifiers held at that moment) is posted to the
+//! event loop as WM_HOTKEY_CAPTURED; Esc alone cancels.
+
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use std::ptr::null_mut;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, Ordering};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BeginPaint, COLOR_WINDOW, DT_CENTER, DT_SINGLELINE, DT_VCENTER, DrawTextW, EndPaint, HBRUSH,
+    PAINTSTRUCT, SetBkMode, TRANSPARENT,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, CreateWindowExW, DefWindowProcW, DestroyWindow, GetClientRect,
+    GetSystemMetrics, HHOOK, KBDLLHOOKSTRUCT, RegisterClassW, SM_CXSCREEN, SM_CYSCREEN,
+    SetWindowsHookExW, UnhookWindowsHookEx, WH_KEYBOARD_LL, WM_KEYDOWN, WM_KEYUP, WM_PAINT,
+    WM_SYSKEYDOWN, WM_SYSKEYUP, WM_USER, WNDCLASSW, WS_BORDER, WS_EX_TOOLWINDOW, WS_EX_TOPMOST,
+    WS_POPUP, WS_VISIBLE,
+};
+use windows::core::{PCWSTR, w};
+
+use crate::error::{CaptureError, HotkeyError};
+use crate::i18n::{Text, t};
+
+/// Posted to the event loop when capture ends (WPARAM: packed capture, 0 = cancelled)
+pub const WM_HOTKEY_CAPTURED: u32 = WM_USER + 5;
+
+const CLASS_NAME: PCWSTR = w!("QuakeModokiCapture");
+const POPUP_WIDTH: i32 = 420;
+const POPUP_HEIGHT: i32 = 80;
+
+// Win32 virtual-key codes (not exported by enabled windows-rs features)
+const VK_ESCAPE: u32 = 0x1B;
+
+/// Installed WH_KEYBOARD_LL hook
+static HOOK: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(null_mut());
+
+/// "Press new shortcut" popup
+static WINDOW: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(null_mut());
+
+/// Modifiers currently held (Modifiers bits; tracked here since keys are swallowed)
+static HELD_MODS: AtomicU32 = AtomicU32::new(0);
+
+/// Result already posted (ignore further keys until `stop`)
+static POSTED: AtomicBool = AtomicBool::new(false);
+
+/// Popup text (which binding is being changed)
+static PROMPT: Mutex<&str> = Mutex::new("");
+
+/// Check if a capture is in progress
+pub fn is_active() -> bool {
+    !HOOK.load(Ordering::SeqCst).is_null()
+}
+
+/// Show popup and start capturing (call on the main thread)
+/// prompt: popup text naming the binding being changed
+pub fn start(prompt: Text) -> Result<(), CaptureError> {
+    if is_active() {
+        return Ok(());
+    }
+    *PROMPT.lock().unwrap_or_else(|e| e.into_inner()) = t(prompt);
+    HELD_MODS.store(0, Ordering::SeqCst);
+    POSTED.store(false, Ordering::SeqCst);
+
+    unsafe {
+        let instance = GetModuleHandleW(None).map_err(|_| CaptureError::WindowCreate)?;
+
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(wnd_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            hbrBackground: HBRUSH((COLOR_WINDOW.0 + 1) as usize as *mut _),
+            ..Default::default()
+        };
+        RegisterClassW(&class);
+
+        let x = (GetSystemMetrics(SM_CXSCREEN) - POPUP_WIDTH) / 2;
+        let y = (GetSystemMetrics(SM_CYSCREEN) - POPUP_HEIGHT) / 2;
+        let hwnd = CreateWindowExW(
+            WS_EX_TOPMOST | WS_EX_TOOLWINDOW,
+            CLASS_NAME,
+            CLASS_NAME,
+            WS_POPUP | WS_BORDER | WS_VISIBLE,
+            x,
+            y,
+            POPUP_WIDTH,
+            POPUP_HEIGHT,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )
+        .map_err(|_| CaptureError::WindowCreate)?;
+        WINDOW.store(hwnd.0, Ordering::SeqCst);
+
+        match SetWindowsHookExW(WH_KEYBOARD_LL, Some(hook_proc), Some(instance.into()), 0) {
+            Ok(hook) => HOOK.store(hook.0, Ordering::SeqCst),
+            Err(_) => {
+                stop();
+                return Err(CaptureError::HookInstall);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove hook and popup
+pub fn stop() {
+    let hook = HOOK.swap(null_mut(), Ordering::SeqCst);
+    if !hook.is_null() {
+        unsafe {
+            let _ = UnhookWindowsHookEx(HHOOK(hook));
+        }
+    }
+    let hwnd = WINDOW.swap(null_mut(), Ordering::SeqCst);
+    if !hwnd.is_null() {
+        unsafe {
+            let _ = DestroyWindow(HWND(hwnd));
+        }
+    }
+}
+
+fn encode(mods: Modifiers, vk: u32) -> usize {
+    ((mods.bits() as usize) << 16) | (vk as usize & 0xFFFF)
+}
+
+/// Decode WM_HOTKEY_CAPTURED wParam (None = cancelled)
+pub fn decode(wparam: usize) -> Option<(Modifiers, u32)> {
+    let vk = (wparam & 0xFFFF) as u32;
+    (vk != 0).then(|| (Modifiers::from_bits_truncate((wparam >> 16) as u32), vk))
+}
+
+/// Validate captured keys as a toggle hotkey
+/// Bare keys are only accepted where they don't interfere with typing (F-keys, Pause, media keys...)
+pub fn to_hotkey(mods: Modifiers, vk: u32) -> Result<HotKey, HotkeyError> {
+    let code = vk_to_code(vk).ok_or(HotkeyError::UnsupportedKey(vk))?;
+    let hotkey = HotKey::new(Some(mods), code);
+    if mods.is_empty() && !allowed_without_modifier(code) {
+        return Err(HotkeyError::NeedsModifier(crate::hotkey::label(&hotkey)));
+    }
+    Ok(hotkey)
+}
+
+fn allowed_without_modifier(code: Code) -> bool {
+    matches!(
+        code,
+        Code::F1
+            | Code::F2
+            | Code::F3
+            | Code::F4
+            | Code::F5
+            | Code::F6
+            | Code::F7
+            | Code::F8
+            | Code::F9
+            | Code::F10
+            | Code::F11
+            | Code::F12
+            | Code::F13
+            | Code::F14
+            | Code::F15
+            | Code::F16
+            | Code::F17
+            | Code::F18
+            | Code::F19
+            | Code::F20
+            | Code::F21
+            | Code::F22
+            | Code::F23
+            | Code::F24
+            | Code::Pause
+            | Code::ScrollLock
+            | Code::AudioVolumeDown
+            | Code::AudioVolumeUp
+            | Code::AudioVolumeMute
+            | Code::MediaPlay
+            | Code::MediaPlayPause
+            | Code::MediaStop
+            | Code::MediaTrackNext
+            | Code::MediaTrackPrevious
+    )
+}
+
+/// Modifier flag for a modifier virtual key (generic + left/right variants)
+fn modifier_of(vk: u32) -> Option<Modifiers> {
+    match vk {
+        0x10 | 0xA0 | 0xA1 => Some(Modifiers::SHIFT),
+        0x11 | 0xA2 | 0xA3 => Some(Modifiers::CONTROL),
+        0x12 | 0xA4 | 0xA5 => Some(Modifiers::ALT),
+        0x5B | 0x5C => Some(Modifiers::SUPER),
+        _ => None,
+    }
+}
+
+/// Map Win32 virtual-key code to global-hotkey Code
+pub fn vk_to_code(vk: u32) -> Option<Code> {
+    const LETTERS: [Code; 26] = [
+        Code::KeyA,
+        Code::KeyB,
+        Code::KeyC,
+        Code::KeyD,
+        Code::KeyE,
+        Code::KeyF,
+        Code::KeyG,
+        Code::KeyH,
+        Code::KeyI,
+        Code::KeyJ,
+        Code::KeyK,
+        Code::KeyL,
+        Code::KeyM,
+        Code::KeyN,
+        Code::KeyO,
+        Code::KeyP,
+        Code::KeyQ,
+        Code::KeyR,
+        Code::KeyS,
+        Code::KeyT,
+        Code::KeyU,
+        Code::KeyV,
+        Code::KeyW,
+        Code::KeyX,
+        Code::KeyY,
+        Code::KeyZ,
+    ];
+    const DIGITS: [Code; 10] = [
+        Code::Digit0,
+        Code::Digit1,
+        Code::Digit2,
+        Code::Digit3,
+        Code::Digit4,
+        Code::Digit5,
+        Code::Digit6,
+        Code::Digit7,
+        Code::Digit8,
+        Code::Digit9,
+    ];
+    const NUMPAD: [Code; 10] = [
+        Code::Numpad0,
+        Code::Numpad1,
+        Code::Numpad2,
+        Code::Numpad3,
+        Code::Numpad4,
+        Code::Numpad5,
+        Code::Numpad6,
+        Code::Numpad7,
+        Code::Numpad8,
+        Code::Numpad9,
+    ];
+    const FUNCTION: [Code; 24] = [
+        Code::F1,
+        Code::F2,
+        Code::F3,
+        Code::F4,
+        Code::F5,
+        Code::F6,
+        Code::F7,
+        Code::F8,
+        Code::F9,
+        Code::F10,
+        Code::F11,
+        Code::F12,
+        Code::F13,
+        Code::F14,
+        Code::F15,
+        Code::F16,
+        Code::F17,
+        Code::F18,
+        Code::F19,
+        Code::F20,
+        Code::F21,
+        Code::F22,
+        Code::F23,
+        Code::F24,
+    ];
+
+    let code = match vk {
+        0x41..=0x5A => LETTERS[(vk - 0x41) as usize],
+        0x30..=0x39 => DIGITS[(vk - 0x30) as usize],
+        0x60..=0x69 => NUMPAD[(vk - 0x60) as usize],
+        0x70..=0x87 => FUNCTION[(vk - 0x70) as usize],
+        0x08 => Code::Backspace,
+        0x09 => Code::Tab,
+        0x0D => Code::Enter,
+        0x13 => Code::Pause,
+        0x1B => Code::Escape,
+        0x20 => Code::Space,
+        0x21 => Code::PageUp,
+        0x22 => Code::PageDown,
+        0x23 => Code::End,
+        0x24 => Code::Home,
+        0x25 => Code::ArrowLeft,
+        0x26 => Code::ArrowUp,
+        0x27 => Code::ArrowRight,
+        0x28 => Code::ArrowDown,
+        0x2C => Code::PrintScreen,
+        0x2D => Code::Insert,
+        0x2E => Code::Delete,
+        0x6A => Code::NumpadMultiply,
+        0x6B => Code::NumpadAdd,
+        0x6D => Code::NumpadSubtract,
+        0x6E => Code::NumpadDecimal,
+        0x6F => Code::NumpadDivide,
+        0x90 => Code::NumLock,
+        0x91 => Code::ScrollLock,
+        0xAD => Code::AudioVolumeMute,
+        0xAE => Code::AudioVolumeDown,
+        0xAF => Code::AudioVolumeUp,
+        0xB0 => Code::MediaTrackNext,
+        0xB1 => Code::MediaTrackPrevious,
+        0xB2 => Code::MediaStop,
+        0xB3 => Code::MediaPlayPause,
+        0xFA => Code::MediaPlay,
+        0xBA => Code::Semicolon,
+        0xBB => Code::Equal,
+        0xBC => Code::Comma,
+        0xBD => Code::Minus,
+        0xBE => Code::Period,
+        0xBF => Code::Slash,
+        0xC0 => Code::Backquote,
+        0xDB => Code::BracketLeft,
+        0xDC => Code::Backslash,
+        0xDD => Code::BracketRight,
+        0xDE => Code::Quote,
+        _ => return None,
+    };
+    Some(code)
+}
+
+fn post_result(wparam: usize) {
+    if POSTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    crate::events::post(WM_HOTKEY_CAPTURED, wparam);
+}
+
+/// Low-level keyboard hook: record modifiers, report first real key, swallow all
+unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code < 0 {
+        return unsafe { CallNextHookEx(None, code, wparam, lparam) };
+    }
+
+    let info = unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) };
+    let vk = info.vkCode;
+    let msg = wparam.0 as u32;
+    let held = Modifiers::from_bits_truncate(HELD_MODS.load(Ordering::SeqCst));
+
+    match (msg, modifier_of(vk)) {
+        (WM_KEYDOWN | WM_SYSKEYDOWN, Some(modifier)) => {
+            HELD_MODS.store((held | modifier).bits(), Ordering::SeqCst);
+        }
+        (WM_KEYUP | WM_SYSKEYUP, Some(modifier)) => {
+            HELD_MODS.store((held - modifier).bits(), Ordering::SeqCst);
+        }
+        (WM_KEYDOWN | WM_SYSKEYDOWN, None) if vk == VK_ESCAPE && held.is_empty() => {
+            post_result(0);
+        }
+        (WM_KEYDOWN | WM_SYSKEYDOWN, None) => post_result(encode(held, vk)),
+        _ => {}
+    }
+
+    // Swallow: keep the combination from reaching the foreground app
+    LRESULT(1)
+}
+
+/// Popup window procedure: draw prompt
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_PAINT {
+        unsafe {
+            let mut paint = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut paint);
+            let mut rect = RECT::default();
+            let _ = GetClientRect(hwnd, &mut rect);
+            SetBkMode(hdc, TRANSPARENT);
+            let prompt = *PROMPT.lock().unwrap_or_else(|e| e.into_inner());
+            let mut text: Vec<u16> = prompt.encode_utf16().collect();
+            DrawTextW(
+                hdc,
+                &mut text,
+                &mut rect,
+                DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+            );
+            let _ = EndPaint(hwnd, &paint);
+        }
+        return LRESULT(0);
+    }
+
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let mods = Modifiers::CONTROL | Modifiers::SHIFT;
+        assert_eq!(decode(encode(mods, 0x54)), Some((mods, 0x54)));
+        assert_eq!(decode(0), None);
+    }
+
+    #[test]
+    fn test_vk_to_code() {
+        assert_eq!(vk_to_code(0x41), Some(Code::KeyA));
+        assert_eq!(vk_to_code(0x5A), Some(Code::KeyZ));
+        assert_eq!(vk_to_code(0x39), Some(Code::Digit9));
+        assert_eq!(vk_to_code(0x77), Some(Code::F8));
+        assert_eq!(vk_to_code(0xC0), Some(Code::Backquote));
+        assert_eq!(vk_to_code(0xDC), Some(Code::Backslash));
+        assert_eq!(vk_to_code(0x6B), Some(Code::NumpadAdd));
+        assert_eq!(vk_to_code(0xB3), Some(Code::MediaPlayPause));
+        assert_eq!(vk_to_code(0xFF), None);
+    }
+
+    #[test]
+    fn test_modifier_of() {
+        assert_eq!(modifier_of(0xA2), Some(Modifiers::CONTROL));
+        assert_eq!(modifier_of(0x5C), Some(Modifiers::SUPER));
+        assert_eq!(modifier_of(0x41), None);
+    }
+
+    #[test]
+    fn test_to_hotkey_requires_modifier_for_typing_keys() {
+        assert!(matches!(
+            to_hotkey(Modifiers::empty(), 0x41),
+            Err(HotkeyError::NeedsModifier(_))
+        ));
+        assert_eq!(
+            to_hotkey(Modifiers::empty(), 0x78).ok(),
+            Some(HotKey::new(None, Code::F9))
+        );
+        assert_eq!(
+            to_hotkey(Modifiers::empty(), 0xB0).ok(),
+            Some(HotKey::new(None, Code::MediaTrackNext))
+        );
+        assert!(matches!(
+            to_hotkey(Modifiers::empty(), 0x65),
+            Err(HotkeyError::NeedsModifier(_))
+        ));
+        assert_eq!(
+            to_hotkey(Modifiers::ALT, 0x41).ok(),
+            Some(HotKey::new(Some(Modifiers::ALT), Code::KeyA))
+        );
+    }
+
+    #[test]
+    fn test_to_hotkey_unsupported_key() {
+        assert!(matches!(
+            to_hotkey(Modifiers::CONTROL, 0xFF),
+            Err(HotkeyError::UnsupportedKey(0xFF))
+        ));
+    }
+}
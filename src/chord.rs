@@ -0,0 +1,180 @@
+//! Leader-key chords: press the leader hotkey, then one follow-up key
+//!
+//! Follow-up keys are bare keys (T, U, G, A, L, Space, 1-4, arrows, C), so they are only
+//! registered as global hotkeys while a chord is pending and released again
+//! after one fires, Esc is pressed, or CHORD_TIMEOUT elapses.
+
+use global_hotkey::GlobalHotKeyManager;
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use std::time::{Duration, Instant};
+
+use crate::snap::Snap;
+
+/// Follow-up window after the leader
+const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Action selected by a follow-up key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChordAction {
+    /// T: track foreground window
+    Track,
+    /// U: untrack
+    Untrack,
+    /// G: add foreground window to the tracked window's group
+    Group,
+    /// A: keyboard adjust mode (move / resize the shown window)
+    Adjust,
+    /// L: put the shown window back into its layout
+    Relayout,
+    /// Space: toggle visibility
+    Toggle,
+    /// 1-4: toggle slot N
+    Slot(u8),
+    /// Arrows / C: snap the tracked window to a preset
+    Snap(Snap),
+    /// Esc: leave chord mode
+    Cancel,
+}
+
+/// Follow-up keys, in registration order
+const FOLLOW_UPS: &[(Code, ChordAction)] = &[
+    (Code::KeyT, ChordAction::Track),
+    (Code::KeyU, ChordAction::Untrack),
+    (Code::KeyG, ChordAction::Group),
+    (Code::KeyA, ChordAction::Adjust),
+    (Code::KeyL, ChordAction::Relayout),
+    (Code::Space, ChordAction::Toggle),
+    (Code::Digit1, ChordAction::Slot(1)),
+    (Code::Digit2, ChordAction::Slot(2)),
+    (Code::Digit3, ChordAction::Slot(3)),
+    (Code::Digit4, ChordAction::Slot(4)),
+    (Code::ArrowLeft, ChordAction::Snap(Snap::Left)),
+    (Code::ArrowRight, ChordAction::Snap(Snap::Right)),
+    (Code::ArrowUp, ChordAction::Snap(Snap::Top)),
+    (Code::ArrowDown, ChordAction::Snap(Snap::Bottom)),
+    (Code::KeyC, ChordAction::Snap(Snap::Center)),
+    (Code::Escape, ChordAction::Cancel),
+];
+
+/// Leader hotkey: Ctrl+Alt+Space
+pub fn leader_hotkey() -> HotKey {
+    HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::Space)
+}
+
+/// Action bound to a follow-up key
+fn follow_up_action(code: Code) -> Option<ChordAction> {
+    FOLLOW_UPS
+        .iter()
+        .find(|(key, _)| *key == code)
+        .map(|(_, action)| *action)
+}
+
+/// Pending chord
+#[derive(Debug)]
+struct Pending {
+    since: Instant,
+    /// Follow-ups that registered (others are taken by another app)
+    registered: Vec<HotKey>,
+}
+
+/// Chord mode state (owned by the event loop)
+#[derive(Debug, Default)]
+pub struct ChordState {
+    pending: Option<Pending>,
+}
+
+impl ChordState {
+    /// Check if waiting for a follow-up key
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Leader pressed: register follow-up keys (pressing it again cancels)
+    pub fn begin(&mut self, manager: &GlobalHotKeyManager) {
+        if self.is_pending() {
+            self.end(manager);
+            tracing::debug!("Chord cancelled (leader pressed again)");
+            return;
+        }
+
+        let registered = FOLLOW_UPS
+            .iter()
+            .map(|(code, _)| HotKey::new(None, *code))
+            .filter(|hotkey| {
+                manager
+                    .register(*hotkey)
+                    .inspect_err(
+                        |e| tracing::debug!(key = ?hotkey.key, "Chord key register failed: {e}"),
+                    )
+                    .is_ok()
+            })
+            .collect();
+        self.pending = Some(Pending {
+            since: Instant::now(),
+            registered,
+        });
+        tracing::debug!("Chord started");
+    }
+
+    /// Resolve a hotkey event; Some when it was a follow-up (chord ends)
+    pub fn take(&mut self, manager: &GlobalHotKeyManager, id: u32) -> Option<ChordAction> {
+        let hotkey = self
+            .pending
+            .as_ref()?
+            .registered
+            .iter()
+            .find(|h| h.id() == id)
+            .copied()?;
+        self.end(manager);
+        follow_up_action(hotkey.key)
+    }
+
+    /// Expire chord mode after CHORD_TIMEOUT
+    pub fn poll(&mut self, manager: &GlobalHotKeyManager) {
+        if self
+            .pending
+            .as_ref()
+            .is_some_and(|p| p.since.elapsed() >= CHORD_TIMEOUT)
+        {
+            self.end(manager);
+            tracing::debug!("Chord timed out");
+        }
+    }
+
+    /// Release follow-up keys
+    pub fn end(&mut self, manager: &GlobalHotKeyManager) {
+        if let Some(pending) = self.pending.take() {
+            let _ = manager.unregister_all(&pending.registered);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follow_up_action() {
+        assert_eq!(follow_up_action(Code::KeyT), Some(ChordAction::Track));
+        assert_eq!(follow_up_action(Code::Space), Some(ChordAction::Toggle));
+        assert_eq!(follow_up_action(Code::Digit3), Some(ChordAction::Slot(3)));
+        assert_eq!(
+            follow_up_action(Code::ArrowUp),
+            Some(ChordAction::Snap(Snap::Top))
+        );
+        assert_eq!(follow_up_action(Code::KeyZ), None);
+    }
+
+    #[test]
+    fn test_follow_ups_distinct_from_leader() {
+        let leader = leader_hotkey();
+        for (code, _) in FOLLOW_UPS {
+            assert_ne!(HotKey::new(None, *code).id(), leader.id());
+        }
+    }
+
+    #[test]
+    fn test_default_state_not_pending() {
+        assert!(!ChordState::default().is_pending());
+    }
+}
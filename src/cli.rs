@@ -0,0 +1,203 @@
+//! Command-line arguments
+//!
+//! Requests (`--launch`, `--find` [`--class`], `--terminal`, `--toggle`,
+//! `--untrack`, `--about`) go to the running instance. Actions (`--install-autostart`, `--uninstall-autostart`,
+//! `--status`) run without the tray UI and exit, for deployment scripts.
+//! `--simulate` runs the toggle pipeline headless against a recording backend.
+//! `--bench` (feature `bench`) runs the animation benchmark and exits.
+
+use std::path::PathBuf;
+use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+
+use crate::autolaunch;
+use crate::ipc::{self, Request};
+
+/// One-shot action (no tray, exits with a status code)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    InstallAutostart,
+    UninstallAutostart,
+    /// Print autostart / running state
+    Status,
+    /// Headless pipeline run against a recording backend
+    Simulate,
+    /// Animation benchmark (feature `bench`)
+    #[cfg(feature = "bench")]
+    Bench,
+}
+
+/// Parsed command line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Args {
+    /// Command for the running instance (handled locally if none runs)
+    pub request: Option<Request>,
+    pub action: Option<Action>,
+    /// Started by auto-launch (apply the startup delay)
+    pub autostart: bool,
+}
+
+/// Parse arguments (without program name); unknown arguments are logged and ignored
+pub fn parse(args: impl IntoIterator<Item = String>) -> Args {
+    let mut parsed = Args::default();
+    let mut find_class = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--launch" => parsed.request = args.next().map(|p| Request::Launch(PathBuf::from(p))),
+            "--find" => {
+                parsed.request = args
+                    .next()
+                    .map(|title| Request::Find { title, class: None })
+            }
+            "--class" => find_class = args.next(),
+            "--terminal" => parsed.request = Some(Request::Terminal),
+            "--toggle" => parsed.request = Some(Request::Toggle),
+            "--untrack" => parsed.request = Some(Request::Untrack),
+            "--about" => parsed.request = Some(Request::About),
+            "--install-autostart" => parsed.action = Some(Action::InstallAutostart),
+            "--uninstall-autostart" => parsed.action = Some(Action::UninstallAutostart),
+            "--status" => parsed.action = Some(Action::Status),
+            "--simulate" => parsed.action = Some(Action::Simulate),
+            #[cfg(feature = "bench")]
+            "--bench" => parsed.action = Some(Action::Bench),
+            autolaunch::AUTOSTART_ARG => parsed.autostart = true,
+            _ => tracing::warn!(arg = %arg, "Unknown argument ignored"),
+        }
+    }
+    if let Some(Request::Find { class, .. }) = &mut parsed.request {
+        *class = find_class;
+    }
+    parsed
+}
+
+/// Run a one-shot action, printing to the parent console; returns the exit code
+pub fn run(action: Action) -> i32 {
+    // Release builds have no console of their own
+    unsafe {
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+
+    let result = match action {
+        Action::InstallAutostart => autolaunch::enable()
+            .map(|()| "Autostart installed".to_string())
+            .map_err(|e| e.to_string()),
+        Action::UninstallAutostart => autolaunch::disable()
+            .map(|()| "Autostart uninstalled".to_string())
+            .map_err(|e| e.to_string()),
+        Action::Status => Ok(format_status(autolaunch::is_enabled(), ipc::is_running())),
+        Action::Simulate => crate::simulate::run().map_err(|e| e.to_string()),
+        #[cfg(feature = "bench")]
+        Action::Bench => crate::bench::run().map_err(|e| e.to_string()),
+    };
+    match result {
+        Ok(message) => {
+            tracing::info!(?action, "{message}");
+            println!("{message}");
+            0
+        }
+        Err(e) => {
+            tracing::error!(?action, "CLI action failed: {e}");
+            eprintln!("Error: {e}");
+            1
+        }
+    }
+}
+
+fn format_status(autostart: bool, running: bool) -> String {
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
+    format!(
+        "version: {}\nautostart: {}\nrunning: {}",
+        env!("CARGO_PKG_VERSION"),
+        yes_no(autostart),
+        yes_no(running)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        assert_eq!(parse(args(&[])), Args::default());
+    }
+
+    #[test]
+    fn test_parse_launch() {
+        let parsed = parse(args(&["--launch", r"C:\Apps\My App.lnk"]));
+        assert_eq!(
+            parsed.request,
+            Some(Request::Launch(PathBuf::from(r"C:\Apps\My App.lnk")))
+        );
+    }
+
+    #[test]
+    fn test_parse_launch_missing_path() {
+        assert_eq!(parse(args(&["--launch"])).request, None);
+    }
+
+    #[test]
+    fn test_parse_find() {
+        assert_eq!(
+            parse(args(&["--class", "Notepad", "--find", "/log$/"])).request,
+            Some(Request::Find {
+                title: "/log$/".to_string(),
+                class: Some("Notepad".to_string()),
+            })
+        );
+        assert_eq!(
+            parse(args(&["--find", "Terminal"])).request,
+            Some(Request::Find {
+                title: "Terminal".to_string(),
+                class: None,
+            })
+        );
+        assert_eq!(parse(args(&["--find"])).request, None);
+    }
+
+    #[test]
+    fn test_parse_requests() {
+        assert_eq!(
+            parse(args(&["--terminal"])).request,
+            Some(Request::Terminal)
+        );
+        assert_eq!(parse(args(&["--toggle"])).request, Some(Request::Toggle));
+        assert_eq!(parse(args(&["--untrack"])).request, Some(Request::Untrack));
+        assert_eq!(parse(args(&["--about"])).request, Some(Request::About));
+    }
+
+    #[test]
+    fn test_parse_actions() {
+        assert_eq!(
+            parse(args(&["--install-autostart"])).action,
+            Some(Action::InstallAutostart)
+        );
+        assert_eq!(
+            parse(args(&["--uninstall-autostart"])).action,
+            Some(Action::UninstallAutostart)
+        );
+        assert_eq!(parse(args(&["--simulate"])).action, Some(Action::Simulate));
+        let parsed = parse(args(&["--status"]));
+        assert_eq!(parsed.action, Some(Action::Status));
+        assert_eq!(parsed.request, None);
+    }
+
+    #[test]
+    fn test_parse_autostart() {
+        let parsed = parse(args(&["--autostart"]));
+        assert!(parsed.autostart);
+        assert_eq!(parsed.request, None);
+        assert!(!parse(args(&[])).autostart);
+    }
+
+    #[test]
+    fn test_format_status() {
+        let status = format_status(true, false);
+        assert!(status.contains("autostart: yes"));
+        assert!(status.contains("running: no"));
+    }
+}
@@ -0,0 +1,134 @@
+//! External commands on lifecycle events (lighter-weight than scripting)
+//!
+//! Registry strings `OnShowCommand`, `OnHideCommand`, `OnTrackCommand`,
+//! `OnUntrackCommand` are run through `cmd /C` without a console window and
+//! are not waited for.
+//! `%TITLE%` / `%EXE%` are substituted with shell metacharacters removed;
+//! the exact values are also passed as `QM_TITLE` / `QM_EXE` env vars.
+
+use std::os::windows::process::CommandExt;
+use std::process::Command;
+use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+use crate::script::{Event, WindowInfo};
+use crate::settings;
+
+/// Registry value holding the command for `event`
+fn setting_name(event: Event) -> &'static str {
+    match event {
+        Event::Show => "OnShowCommand",
+        Event::Hide => "OnHideCommand",
+        Event::Track => "OnTrackCommand",
+        Event::Untrack => "OnUntrackCommand",
+    }
+}
+
+/// Drop characters cmd.exe would interpret (quotes, redirection, chaining, expansion)
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, '"' | '%' | '^' | '&' | '|' | '<' | '>' | '\r' | '\n'))
+        .collect()
+}
+
+/// Substitute %TITLE% / %EXE% (case-insensitive placeholders)
+fn expand(template: &str, window: &WindowInfo) -> String {
+    let title = sanitize(&window.title);
+    let exe = sanitize(&window.exe);
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('%') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        // Compare bytes: a char after '%' may not end on a byte boundary
+        let starts_with = |placeholder: &str| {
+            tail.as_bytes()
+                .get(..placeholder.len())
+                .is_some_and(|b| b.eq_ignore_ascii_case(placeholder.as_bytes()))
+        };
+        if starts_with("%TITLE%") {
+            out.push_str(&title);
+            rest = &tail[7..];
+        } else if starts_with("%EXE%") {
+            out.push_str(&exe);
+            rest = &tail[5..];
+        } else {
+            out.push('%');
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Spawn the configured command for `event` (no-op if unset)
+pub fn run(event: Event, window: &WindowInfo) {
+    let Some(template) = settings::get_string(setting_name(event)).filter(|s| !s.trim().is_empty())
+    else {
+        return;
+    };
+    let command_line = expand(&template, window);
+    let result = Command::new("cmd")
+        .arg("/C")
+        .raw_arg(&command_line)
+        .env("QM_TITLE", &window.title)
+        .env("QM_EXE", &window.exe)
+        .creation_flags(CREATE_NO_WINDOW.0)
+        .spawn();
+    match result {
+        Ok(_) => tracing::debug!(?event, command = %command_line, "Event command started"),
+        Err(e) => tracing::warn!(?event, command = %command_line, "Event command failed: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(title: &str, exe: &str) -> WindowInfo {
+        WindowInfo {
+            title: title.into(),
+            exe: exe.into(),
+            hwnd: 0,
+        }
+    }
+
+    #[test]
+    fn test_expand_placeholders() {
+        let w = window("vim", r"C:\Tools\wt.exe");
+        assert_eq!(
+            expand(r#"notify.bat "%TITLE%" "%exe%""#, &w),
+            r#"notify.bat "vim" "C:\Tools\wt.exe""#
+        );
+    }
+
+    #[test]
+    fn test_expand_keeps_other_percent() {
+        let w = window("vim", "wt.exe");
+        assert_eq!(expand("echo %USERNAME% 50%", &w), "echo %USERNAME% 50%");
+    }
+
+    #[test]
+    fn test_expand_non_ascii_after_percent() {
+        let w = window("vim", "wt.exe");
+        assert_eq!(
+            expand("notify.exe 100%完了です", &w),
+            "notify.exe 100%完了です"
+        );
+        assert_eq!(expand("%題%TITLE%", &w), "%題vim");
+    }
+
+    #[test]
+    fn test_expand_sanitizes_values() {
+        let w = window(r#"a" & del x | y > z %PATH%"#, "wt.exe");
+        assert_eq!(expand("log %TITLE%", &w), "log a  del x  y  z PATH");
+    }
+
+    #[test]
+    fn test_setting_names_distinct() {
+        let names = [Event::Show, Event::Hide, Event::Track, Event::Untrack].map(setting_name);
+        for (i, a) in names.iter().enumerate() {
+            assert!(!names[i + 1..].contains(a));
+        }
+    }
+}
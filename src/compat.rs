@@ -0,0 +1,164 @@
+//! Per-app compositing compatibility during slides
+//!
+//! WS_EX_COMPOSITED double-buffering stops flicker in classic GDI apps but
+//! shows black frames or drops input in apps that render through DirectX
+//! (Chromium / Electron, Windows Terminal). A built-in table keyed by window
+//! class or exe file name picks, per window, whether the slide applies the
+//! composited style and whether it invalidates around style changes. String
+//! values under `Compat` (name = exe file name or class, data = flags such as
+//! "composited,invalidate", "invalidate" or "none") extend or override it.
+
+use windows::Win32::Foundation::HWND;
+
+use crate::context;
+use crate::settings;
+use crate::tracking;
+
+const COMPAT_KEY: &str = "Compat";
+
+/// How a window is treated while it slides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compat {
+    /// Double-buffer with WS_EX_COMPOSITED
+    pub composited: bool,
+    /// Repaint around the style changes
+    pub invalidate: bool,
+}
+
+impl Default for Compat {
+    fn default() -> Self {
+        Self {
+            composited: true,
+            invalidate: true,
+        }
+    }
+}
+
+const DIRECT_RENDER: Compat = Compat {
+    composited: false,
+    invalidate: false,
+};
+
+/// Built-in entries: (lowercase class or exe file name, treatment)
+const BUILT_IN: [(&str, Compat); 3] = [
+    // Chromium and Electron apps (Chrome, Edge, VS Code, Obsidian, Spotify, ...)
+    ("chrome_widgetwin_1", DIRECT_RENDER),
+    ("cascadia_hosting_window_class", DIRECT_RENDER),
+    ("windowsterminal.exe", DIRECT_RENDER),
+];
+
+/// Parse flags separated by `,` / spaces ("none" or empty: neither)
+pub fn parse_flags(text: &str) -> Option<Compat> {
+    let mut compat = DIRECT_RENDER;
+    for flag in text
+        .split([',', ' ', ';'])
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+    {
+        match flag.to_ascii_lowercase().as_str() {
+            "composited" => compat.composited = true,
+            "invalidate" => compat.invalidate = true,
+            "none" => {}
+            _ => return None,
+        }
+    }
+    Some(compat)
+}
+
+/// Treatment of a window: user entries first (class, then exe), then built-in
+fn lookup(class: &str, exe: Option<&str>, user: &[(String, String)]) -> Compat {
+    let keys: Vec<String> = [Some(class.to_ascii_lowercase()), exe.map(context::exe_name)]
+        .into_iter()
+        .flatten()
+        .filter(|k| !k.is_empty())
+        .collect();
+    let user_entry = keys.iter().find_map(|key| {
+        let (name, flags) = user
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))?;
+        parse_flags(flags).or_else(|| {
+            tracing::warn!(%name, %flags, "Invalid compat flags ignored");
+            None
+        })
+    });
+    user_entry
+        .or_else(|| {
+            keys.iter().find_map(|key| {
+                BUILT_IN
+                    .iter()
+                    .find(|(name, _)| name == key)
+                    .map(|(_, compat)| *compat)
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Treatment of `hwnd` while it slides
+pub fn for_window(hwnd: HWND) -> Compat {
+    let class = tracking::get_window_class(hwnd);
+    let exe = tracking::get_process_path(hwnd);
+    let compat = lookup(
+        &class,
+        exe.as_deref(),
+        &settings::get_string_values(COMPAT_KEY),
+    );
+    if compat != Compat::default() {
+        tracing::trace!(hwnd = ?hwnd, %class, ?compat, "Compositing compatibility");
+    }
+    compat
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flags() {
+        assert_eq!(
+            parse_flags("composited, invalidate"),
+            Some(Compat::default())
+        );
+        assert_eq!(
+            parse_flags("Invalidate"),
+            Some(Compat {
+                composited: false,
+                invalidate: true,
+            })
+        );
+        assert_eq!(parse_flags("none"), Some(DIRECT_RENDER));
+        assert_eq!(parse_flags(""), Some(DIRECT_RENDER));
+        assert_eq!(parse_flags("thumbnail"), None);
+    }
+
+    #[test]
+    fn test_lookup_built_in() {
+        assert_eq!(
+            lookup("Chrome_WidgetWin_1", Some(r"C:\x\Code.exe"), &[]),
+            DIRECT_RENDER
+        );
+        assert_eq!(
+            lookup("Notepad", Some(r"C:\Windows\notepad.exe"), &[]),
+            Compat::default()
+        );
+    }
+
+    #[test]
+    fn test_lookup_user_overrides() {
+        let entries = settings::string_values(&[("code.exe", "composited"), ("Notepad", "none")]);
+        // Exe entry beats the built-in class entry
+        assert_eq!(
+            lookup("Chrome_WidgetWin_1", Some(r"C:\x\Code.exe"), &entries),
+            Compat {
+                composited: true,
+                invalidate: false,
+            }
+        );
+        assert_eq!(lookup("notepad", None, &entries), DIRECT_RENDER);
+        // Invalid flags fall back
+        let entries = settings::string_values(&[("notepad.exe", "sparkle")]);
+        assert_eq!(
+            lookup("Notepad", Some("notepad.exe"), &entries),
+            Compat::default()
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Startup self-check: detect other quake-style tools competing for hotkeys
+//!
+//! Known tools are matched by running process name; a warning is raised when
+//! a tool's usual hotkey equals one of ours, with free toggle candidates
+//! suggested as alternatives.
+
+use crate::process;
+
+/// Quake-style / launcher tool and the hotkey it typically owns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownTool {
+    pub exe: &'static str,
+    pub name: &'static str,
+    pub hotkey: &'static str,
+}
+
+const KNOWN_TOOLS: &[KnownTool] = &[
+    KnownTool {
+        exe: "WindowsTerminal.exe",
+        name: "Windows Terminal (quake mode)",
+        hotkey: "Win+`",
+    },
+    KnownTool {
+        exe: "PowerToys.PowerLauncher.exe",
+        name: "PowerToys Run",
+        hotkey: "Alt+Space",
+    },
+    KnownTool {
+        exe: "ConEmu64.exe",
+        name: "ConEmu (quake style)",
+        hotkey: "Ctrl+`",
+    },
+    KnownTool {
+        exe: "ConEmu.exe",
+        name: "ConEmu (quake style)",
+        hotkey: "Ctrl+`",
+    },
+    KnownTool {
+        exe: "Tabby.exe",
+        name: "Tabby",
+        hotkey: "Ctrl+`",
+    },
+    KnownTool {
+        exe: "Hyper.exe",
+        name: "Hyper",
+        hotkey: "Ctrl+`",
+    },
+    KnownTool {
+        exe: "Terminus.exe",
+        name: "Terminus",
+        hotkey: "Ctrl+`",
+    },
+];
+
+/// Detected tool; `clashes` when its hotkey is one of our active bindings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict {
+    pub tool: KnownTool,
+    pub clashes: bool,
+}
+
+/// Match running processes against KNOWN_TOOLS (case-insensitive, one entry per tool name)
+fn find_conflicts(running: &[String], active: &[&str]) -> Vec<Conflict> {
+    let mut found: Vec<Conflict> = Vec::new();
+    for tool in KNOWN_TOOLS {
+        let is_running = running
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(tool.exe));
+        if is_running && !found.iter().any(|c| c.tool.name == tool.name) {
+            found.push(Conflict {
+                tool: *tool,
+                clashes: active.contains(&tool.hotkey),
+            });
+        }
+    }
+    found
+}
+
+/// Candidates not active and not claimed by a detected tool
+fn suggest(candidates: &[String], active: &[&str], conflicts: &[Conflict]) -> Vec<String> {
+    candidates
+        .iter()
+        .filter(|c| !active.contains(&c.as_str()) && !conflicts.iter().any(|x| x.tool.hotkey == *c))
+        .cloned()
+        .collect()
+}
+
+/// Result of the startup self-check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfCheck {
+    pub conflicts: Vec<Conflict>,
+    pub suggestions: Vec<String>,
+}
+
+/// Scan running processes
+/// active: our registered binding labels; candidates: alternates known to be free
+pub fn self_check(active: &[&str], candidates: &[String]) -> SelfCheck {
+    let conflicts = find_conflicts(&process::running_process_names(), active);
+    let suggestions = suggest(candidates, active, &conflicts);
+    SelfCheck {
+        conflicts,
+        suggestions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_find_conflicts_none_running() {
+        let conflicts = find_conflicts(&names(&["explorer.exe", "code.exe"]), &["F8"]);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_find_conflicts_case_insensitive_and_clash() {
+        let conflicts = find_conflicts(&names(&["windowsterminal.exe"]), &["Win+`"]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].tool.hotkey, "Win+`");
+        assert!(conflicts[0].clashes);
+    }
+
+    #[test]
+    fn test_find_conflicts_dedup_by_tool() {
+        let conflicts = find_conflicts(&names(&["ConEmu.exe", "ConEmu64.exe"]), &["F8"]);
+        assert_eq!(conflicts.len(), 1);
+        assert!(!conflicts[0].clashes);
+    }
+
+    #[test]
+    fn test_suggest_skips_active_and_claimed() {
+        let conflicts = find_conflicts(&names(&["ConEmu64.exe"]), &["F8"]);
+        let candidates = names(&["F8", "F7", "Ctrl+`", "Win+`"]);
+        let suggestions = suggest(&candidates, &["F8"], &conflicts);
+        assert_eq!(suggestions, vec!["F7", "Win+`"]);
+    }
+}
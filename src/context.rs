@@ -0,0 +1,172 @@
+//! Context-sensitive hotkeys: toggle bindings active only while a given app is focused
+//!
+//! Rules live under HKCU\Software\QuakeModoki\ContextHotkeys: value name is
+//! the hotkey (global-hotkey form, e.g. "control+Enter"), data is a `;`
+//! separated list of exe names (e.g. "game.exe;game-dx12.exe").
+//! A rule's hotkey is only registered while a matching app is in the
+//! foreground, so the keys keep working normally everywhere else.
+
+use global_hotkey::GlobalHotKeyManager;
+use global_hotkey::hotkey::HotKey;
+use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+use crate::{hotkey, settings, tracking};
+
+const CONTEXT_HOTKEYS: &str = "ContextHotkeys";
+
+/// Hotkey bound to a set of foreground apps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextRule {
+    pub hotkey: HotKey,
+    /// Lowercase exe file names
+    pub exes: Vec<String>,
+}
+
+/// Parse one registry value; None if the hotkey or exe list is invalid
+fn parse_rule(name: &str, data: &str) -> Option<ContextRule> {
+    let hotkey = hotkey::parse(name).ok()?;
+    let exes: Vec<String> = data
+        .split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    (!exes.is_empty()).then_some(ContextRule { hotkey, exes })
+}
+
+/// Load rules from registry (invalid entries skipped with a warning)
+pub fn load_rules() -> Vec<ContextRule> {
+    settings::get_string_values(CONTEXT_HOTKEYS)
+        .into_iter()
+        .filter_map(|(name, data)| {
+            let rule = parse_rule(&name, &data);
+            if rule.is_none() {
+                tracing::warn!(hotkey = %name, exes = %data, "Invalid context hotkey rule");
+            }
+            rule
+        })
+        .collect()
+}
+
+/// Exe file name of a full path, lowercase
+pub fn exe_name(path: &str) -> String {
+    path.rsplit(['\\', '/'])
+        .next()
+        .unwrap_or(path)
+        .to_ascii_lowercase()
+}
+
+impl ContextRule {
+    fn matches(&self, exe: Option<&str>) -> bool {
+        exe.is_some_and(|exe| self.exes.iter().any(|e| *e == exe))
+    }
+}
+
+/// Rules + which hotkeys are registered for the current foreground app
+#[derive(Debug, Default)]
+pub struct ContextHotkeys {
+    rules: Vec<ContextRule>,
+    /// Foreground window seen by the last poll
+    last_foreground: usize,
+    /// Hotkeys currently registered
+    armed: Vec<HotKey>,
+}
+
+impl ContextHotkeys {
+    pub fn new(rules: Vec<ContextRule>) -> Self {
+        Self {
+            rules,
+            ..Default::default()
+        }
+    }
+
+    /// Check if any rule is configured (foreground needs polling)
+    pub fn has_rules(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    /// Re-arm hotkeys when the foreground window changes (cheap when unchanged)
+    pub fn poll(&mut self, manager: &GlobalHotKeyManager) {
+        if !self.has_rules() {
+            return;
+        }
+        let hwnd = unsafe { GetForegroundWindow() };
+        if hwnd.0 as usize == self.last_foreground {
+            return;
+        }
+        self.last_foreground = hwnd.0 as usize;
+
+        let exe = tracking::get_process_path(hwnd).map(|p| exe_name(&p));
+        let wanted: Vec<HotKey> = self
+            .rules
+            .iter()
+            .filter(|r| r.matches(exe.as_deref()))
+            .map(|r| r.hotkey)
+            .collect();
+
+        for hotkey in self.armed.iter().filter(|h| !wanted.contains(h)) {
+            let _ = manager.unregister(*hotkey);
+        }
+        self.armed.retain(|h| wanted.contains(h));
+        for hotkey in wanted {
+            if self.armed.contains(&hotkey) {
+                continue;
+            }
+            match manager.register(hotkey) {
+                Ok(()) => self.armed.push(hotkey),
+                Err(e) => {
+                    let label = crate::hotkey::label(&hotkey);
+                    tracing::debug!(hotkey = %label, "Context hotkey register failed: {e}");
+                }
+            }
+        }
+    }
+
+    /// Check a hotkey event: true if it is an armed rule and its app is still focused
+    pub fn take(&self, id: u32) -> bool {
+        let Some(hotkey) = self.armed.iter().find(|h| h.id() == id) else {
+            return false;
+        };
+        let hwnd = unsafe { GetForegroundWindow() };
+        let exe = tracking::get_process_path(hwnd).map(|p| exe_name(&p));
+        self.rules
+            .iter()
+            .any(|r| r.hotkey == *hotkey && r.matches(exe.as_deref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use global_hotkey::hotkey::{Code, Modifiers};
+
+    #[test]
+    fn test_parse_rule() {
+        let rule = parse_rule("control+Enter", "Game.exe; other.EXE ;").unwrap();
+        assert_eq!(
+            rule.hotkey,
+            HotKey::new(Some(Modifiers::CONTROL), Code::Enter)
+        );
+        assert_eq!(rule.exes, vec!["game.exe", "other.exe"]);
+    }
+
+    #[test]
+    fn test_parse_rule_invalid() {
+        assert!(parse_rule("control+NotAKey", "game.exe").is_none());
+        assert!(parse_rule("control+Enter", " ; ").is_none());
+    }
+
+    #[test]
+    fn test_exe_name() {
+        assert_eq!(exe_name(r"C:\Games\Game.exe"), "game.exe");
+        assert_eq!(exe_name("game.exe"), "game.exe");
+    }
+
+    #[test]
+    fn test_rule_matches() {
+        let rule = parse_rule("control+Enter", "game.exe").unwrap();
+        assert!(rule.matches(Some("game.exe")));
+        assert!(!rule.matches(Some("notepad.exe")));
+        assert!(!rule.matches(None));
+    }
+}
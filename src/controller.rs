@@ -0,0 +1,370 @@
+//! Tracked window controller: owned state of the window under toggle control
+//!
+//! Owned by the event loop and passed to the handlers. Only what unsafe
+//! callbacks read stays global: the focus hook target (`focus`), the flash
+//! filter (`flash`) and the original state restored by the console / panic
+//! handlers (`tracking`). The controller keeps those in sync.
+
+use windows::Win32::Foundation::HWND;
+
+use crate::error::FocusError;
+use crate::flash;
+use crate::focus::{self, FocusHook};
+use crate::rules::Overrides;
+use crate::slots::{Parked, Slots};
+use crate::tracking::{self, BoundsStore, WindowBounds};
+use crate::win32;
+
+/// Tracked window, its visibility and the bounds used for animation
+#[derive(Debug, Default)]
+pub struct WindowController {
+    hwnd: Option<HWND>,
+    /// Executable captured at track time (kept for relaunch after close)
+    exe: Option<String>,
+    visible: bool,
+    /// Bounds captured before the last slide-out (slide-in targets per window)
+    bounds: BoundsStore,
+    /// Foreground window before the last slide-in (focus restored on hide)
+    previous_foreground: Option<HWND>,
+    focus_hook: Option<FocusHook>,
+    /// Extra windows sliding with the tracked one (group toggle)
+    group: Vec<HWND>,
+    /// Settings replaced by the matching per-app rule
+    overrides: Overrides,
+    /// Windows of the other slots
+    slots: Slots,
+}
+
+impl WindowController {
+    /// Take control of `hwnd` (shown); the previous window is released first
+    pub fn track(&mut self, hwnd: HWND) -> Result<(), FocusError> {
+        self.release()?;
+        self.hwnd = Some(hwnd);
+        self.exe = tracking::get_process_path(hwnd);
+        self.visible = true;
+        self.save_bounds();
+        flash::set_watched(hwnd);
+        self.watch_focus()
+    }
+
+    /// Forget the tracked window and drop its focus hook
+    /// Returns true if the window was visible
+    pub fn release(&mut self) -> Result<bool, FocusError> {
+        let was_visible = std::mem::take(&mut self.visible);
+        if let Some(hwnd) = self.hwnd.take() {
+            self.bounds.remove(hwnd);
+        }
+        for member in std::mem::take(&mut self.group) {
+            self.bounds.remove(member);
+        }
+        self.exe = None;
+        self.overrides = Overrides::default();
+        self.previous_foreground = None;
+        flash::set_watched(HWND::default());
+        self.unwatch_focus()?;
+        Ok(was_visible)
+    }
+
+    /// Release the tracked window into its slot (bounds and visibility kept)
+    /// Returns false if nothing was tracked
+    pub fn park(&mut self) -> Result<bool, FocusError> {
+        let Some(hwnd) = self.hwnd.filter(|&hwnd| win32::api().is_window(hwnd)) else {
+            return Ok(false);
+        };
+        if self.visible {
+            self.save_bounds();
+        }
+        let Some(bounds) = self.bounds() else {
+            return Ok(false);
+        };
+        let parked = Parked {
+            hwnd,
+            bounds,
+            visible: self.visible,
+        };
+        self.release()?;
+        self.slots.park(self.slots.active(), parked);
+        Ok(true)
+    }
+
+    /// Take control of a parked window with its stored bounds and visibility
+    pub fn unpark(&mut self, parked: Parked) -> Result<(), FocusError> {
+        let result = self.track(parked.hwnd);
+        self.set_bounds(parked.bounds);
+        self.visible = parked.visible;
+        result
+    }
+
+    pub fn slots(&self) -> &Slots {
+        &self.slots
+    }
+
+    pub fn slots_mut(&mut self) -> &mut Slots {
+        &mut self.slots
+    }
+
+    /// Per-app rule overrides of the tracked window
+    pub fn overrides(&self) -> &Overrides {
+        &self.overrides
+    }
+
+    pub fn set_overrides(&mut self, overrides: Overrides) {
+        self.overrides = overrides;
+    }
+
+    /// Tracked window handle (null if none)
+    pub fn hwnd(&self) -> HWND {
+        self.hwnd.unwrap_or_default()
+    }
+
+    /// Check if a window is tracked (it may have been destroyed since)
+    pub fn is_tracked(&self) -> bool {
+        self.hwnd.is_some()
+    }
+
+    /// Check if a window is tracked and still exists
+    pub fn is_valid(&self) -> bool {
+        self.hwnd.is_some_and(|hwnd| win32::api().is_window(hwnd))
+    }
+
+    /// Check if a window is tracked but has been destroyed
+    pub fn is_closed(&self) -> bool {
+        self.is_tracked() && !self.is_valid()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Executable path captured when the window was tracked
+    pub fn exe(&self) -> Option<&str> {
+        self.exe.as_deref()
+    }
+
+    /// Bounds captured by the last `save_bounds` (or at track time)
+    pub fn bounds(&self) -> Option<WindowBounds> {
+        self.bounds.get(self.hwnd?)
+    }
+
+    /// Capture the tracked window's (and group's) current bounds before slide-out
+    /// Returns the tracked window's bounds
+    pub fn save_bounds(&mut self) -> Option<WindowBounds> {
+        for member in self.group() {
+            if let Some(bounds) = tracking::window_bounds(member) {
+                self.bounds.save(member, bounds);
+            }
+        }
+        let hwnd = self.hwnd?;
+        let bounds = tracking::window_bounds(hwnd)?;
+        self.bounds.save(hwnd, bounds);
+        Some(bounds)
+    }
+
+    /// Add `hwnd` to the group sliding with the tracked window
+    /// False if nothing is tracked, or it is the tracked window / already a member
+    pub fn add_to_group(&mut self, hwnd: HWND) -> bool {
+        if self.hwnd.is_none_or(|tracked| tracked == hwnd)
+            || hwnd == HWND::default()
+            || self.group.contains(&hwnd)
+        {
+            return false;
+        }
+        self.group.push(hwnd);
+        if let Some(bounds) = tracking::window_bounds(hwnd) {
+            self.bounds.save(hwnd, bounds);
+        }
+        true
+    }
+
+    /// Group members that still exist (without the tracked window)
+    pub fn group(&self) -> Vec<HWND> {
+        let api = win32::api();
+        self.group
+            .iter()
+            .copied()
+            .filter(|&hwnd| api.is_window(hwnd))
+            .collect()
+    }
+
+    /// Check if `hwnd` is a group member (focus moving to it is not focus loss)
+    pub fn in_group(&self, hwnd: HWND) -> bool {
+        self.group.contains(&hwnd)
+    }
+
+    /// Replace the tracked window's stored bounds (slide-in target)
+    pub fn set_bounds(&mut self, bounds: WindowBounds) {
+        if let Some(hwnd) = self.hwnd {
+            self.bounds.save(hwnd, bounds);
+        }
+    }
+
+    /// Replace the stored bounds of one member (tracked window or group)
+    pub fn set_member_bounds(&mut self, hwnd: HWND, bounds: WindowBounds) {
+        if self.hwnd == Some(hwnd) || self.in_group(hwnd) {
+            self.bounds.save(hwnd, bounds);
+        }
+    }
+
+    /// Store layout bounds as slide-in targets, in member order (tracked window first)
+    pub fn set_layout_bounds(&mut self, bounds: &[WindowBounds]) {
+        let members: Vec<HWND> = self.hwnd.into_iter().chain(self.group()).collect();
+        for (hwnd, bounds) in members.into_iter().zip(bounds) {
+            self.bounds.save(hwnd, *bounds);
+        }
+    }
+
+    /// Tracked window followed by the group members that still exist
+    pub fn members(&self) -> Vec<HWND> {
+        self.hwnd.into_iter().chain(self.group()).collect()
+    }
+
+    /// Tracked window followed by the group, each with its saved bounds
+    pub fn members_with_bounds(&self) -> Vec<(HWND, WindowBounds)> {
+        self.hwnd
+            .into_iter()
+            .chain(self.group())
+            .filter_map(|hwnd| Some((hwnd, self.bounds.get(hwnd)?)))
+            .collect()
+    }
+
+    /// Remember the foreground window before taking focus
+    pub fn save_previous_foreground(&mut self, hwnd: HWND) {
+        self.previous_foreground = (hwnd != HWND::default()).then_some(hwnd);
+    }
+
+    /// Window to give focus back to on hide (null if none)
+    pub fn previous_foreground(&self) -> HWND {
+        self.previous_foreground.unwrap_or_default()
+    }
+
+    /// (Re)install the focus-loss hook for the tracked window
+    pub fn watch_focus(&mut self) -> Result<(), FocusError> {
+        self.unwatch_focus()?;
+        // Hiding on focus loss turned off: no hook at all
+        if !focus::is_enabled() {
+            return Ok(());
+        }
+        if let Some(hwnd) = self.hwnd {
+            self.focus_hook = Some(focus::install_hook(hwnd)?);
+        }
+        Ok(())
+    }
+
+    /// Remove the focus-loss hook (no-op if none)
+    pub fn unwatch_focus(&mut self) -> Result<(), FocusError> {
+        match self.focus_hook.take() {
+            Some(hook) => hook.uninstall(),
+            None => Ok(()),
+        }
+    }
+
+    /// Check if the focus-loss hook is installed
+    pub fn has_focus_hook(&self) -> bool {
+        self.focus_hook.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_untracked() {
+        let window = WindowController::default();
+        assert_eq!(window.hwnd(), HWND::default());
+        assert!(!window.is_tracked());
+        assert!(!window.is_valid());
+        assert!(!window.is_closed());
+        assert!(!window.is_visible());
+        assert!(window.bounds().is_none());
+        assert!(!window.has_focus_hook());
+    }
+
+    #[test]
+    fn test_save_bounds_without_window() {
+        let mut window = WindowController::default();
+        assert!(window.save_bounds().is_none());
+        assert!(window.bounds().is_none());
+    }
+
+    #[test]
+    fn test_previous_foreground_ignores_null() {
+        let mut window = WindowController::default();
+        window.save_previous_foreground(HWND::default());
+        assert_eq!(window.previous_foreground(), HWND::default());
+
+        let fake = HWND(0x1234 as *mut _);
+        window.save_previous_foreground(fake);
+        assert_eq!(window.previous_foreground(), fake);
+    }
+
+    #[test]
+    fn test_park_without_window() {
+        let mut window = WindowController::default();
+        assert!(!window.park().expect("park"));
+        assert!(window.slots().parked_windows().is_empty());
+    }
+
+    #[test]
+    fn test_add_to_group_requires_tracked_window() {
+        let mut window = WindowController::default();
+        assert!(!window.add_to_group(HWND(0x1234 as *mut _)));
+        assert!(window.group().is_empty());
+    }
+
+    #[test]
+    fn test_add_to_group_rejects_duplicates() {
+        let tracked = HWND(0x1000 as *mut _);
+        let member = HWND(0x2000 as *mut _);
+        let mut window = WindowController {
+            hwnd: Some(tracked),
+            ..Default::default()
+        };
+        assert!(!window.add_to_group(tracked));
+        assert!(!window.add_to_group(HWND::default()));
+        assert!(window.add_to_group(member));
+        assert!(!window.add_to_group(member));
+        assert!(window.in_group(member));
+        assert!(!window.in_group(tracked));
+
+        window.release().expect("release failed");
+        assert!(!window.in_group(member));
+    }
+
+    #[test]
+    fn test_set_layout_bounds_in_member_order() {
+        let layout = [
+            WindowBounds {
+                x: 0,
+                y: 0,
+                width: 960,
+                height: 520,
+            },
+            WindowBounds {
+                x: 960,
+                y: 0,
+                width: 960,
+                height: 520,
+            },
+        ];
+        let mut window = WindowController {
+            hwnd: Some(HWND(0x1000 as *mut _)),
+            ..Default::default()
+        };
+        window.set_layout_bounds(&layout);
+        assert_eq!(window.bounds(), Some(layout[0]));
+    }
+
+    #[test]
+    fn test_release_resets_state() {
+        let mut window = WindowController::default();
+        window.set_visible(true);
+        assert!(window.release().expect("release failed"));
+        assert!(!window.is_visible());
+        assert!(!window.release().expect("release failed"));
+    }
+}
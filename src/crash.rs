@@ -0,0 +1,103 @@
+//! Crash handling: panic hook that restores the tracked window and leaves evidence
+//!
+//! On panic: restore window → write backtrace report + minidump to the log
+//! directory → toast with the panic location → default hook.
+
+use std::backtrace::Backtrace;
+use std::fs::{self, File};
+use std::io::Write;
+use std::os::windows::io::AsRawHandle;
+use std::panic::PanicHookInfo;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::System::Diagnostics::Debug::{MiniDumpWithThreadInfo, MiniDumpWriteDump};
+use windows::Win32::System::Threading::{GetCurrentProcess, GetCurrentProcessId};
+
+use crate::{logging, notification, tracking};
+
+/// Install panic hook (chains to the default hook)
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Never leave the tracked window stranded off-screen
+        let _ = tracking::restore_original();
+
+        let location = describe_location(info);
+        tracing::error!(location = %location, "Panic: {}", panic_message(info));
+
+        if let Some(dir) = logging::log_dir() {
+            let stem = format!("crash-{}", unix_timestamp());
+            write_report(&dir.join(format!("{stem}.txt")), info, &location);
+            write_minidump(&dir.join(format!("{stem}.dmp")));
+        }
+
+        notification::show_crash(&location);
+        default_hook(info);
+    }));
+}
+
+/// "file:line:col" of the panic, or "unknown location"
+fn describe_location(info: &PanicHookInfo<'_>) -> String {
+    info.location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "unknown location".to_string())
+}
+
+/// Panic payload as text (&str / String payloads only)
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "<non-string panic payload>".to_string())
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Plain-text report: version, location, message, backtrace
+fn write_report(path: &Path, info: &PanicHookInfo<'_>, location: &str) {
+    let report = format!(
+        "Quake Modoki {}\nLocation: {}\nMessage: {}\n\nBacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        location,
+        panic_message(info),
+        Backtrace::force_capture(),
+    );
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| File::create(path))
+        .and_then(|mut f| f.write_all(report.as_bytes()));
+    if let Err(e) = result {
+        tracing::error!("Crash report write failed: {e}");
+    }
+}
+
+/// Minidump of this process (thread info, no heap)
+fn write_minidump(path: &Path) {
+    let Ok(file) = File::create(path) else {
+        return;
+    };
+
+    let result = unsafe {
+        MiniDumpWriteDump(
+            GetCurrentProcess(),
+            GetCurrentProcessId(),
+            HANDLE(file.as_raw_handle()),
+            MiniDumpWithThreadInfo,
+            None,
+            None,
+            None,
+        )
+    };
+    if let Err(e) = result {
+        tracing::error!("Minidump write failed: {e}");
+    }
+}
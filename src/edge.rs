@@ -1,27 +1,87 @@
 //! Edge trigger module: show/hide window when cursor reaches screen edge
+//!
+//! Threshold and delays are stored as registry DWORDs, picked from the tray
+//! presets or the "Custom…" prompt; the event loop reloads them on change.
+//!
+//! When the taskbar sits on the trigger edge, a fixed taskbar moves the zone
+//! to the strip just inside it (hovering the taskbar does not count), and an
+//! auto-hide taskbar, which reveals itself at the same edge, needs a longer
+//! dwell before the window shows.
+//!
+//! With `EdgeScroll` on (default off), a wheel tick while the cursor rests in
+//! the zone shows the window (scroll down) or hides it (scroll up); a window
+//! hidden that way stays hidden until the cursor leaves the edge.
+//! With `EdgeSwipe` on (default off), a touch swipe that starts at the edge
+//! and moves inward shows the window.
+//!
+//! The distance from the edge depends on the device that moved the cursor
+//! (see `input`): exact for a mouse, wider for a touchpad, widest for touch.
+//!
+//! Each window slot may have its own edge profile under `EdgeProfiles\SlotN`
+//! (string `Edge` = Top / Bottom / Left / Right plus any of the threshold and
+//! delay names above, as DWORDs or strings; missing ones follow the global
+//! values).
+//! A slot with a profile triggers on its own edge, and a parked one is shown
+//! from there too while the active slot's window is hidden.
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use winreg::RegKey;
-use winreg::enums::{HKEY_CURRENT_USER, KEY_READ};
 
 use crate::animation::Direction;
+use crate::error::AppError;
+use crate::events;
+use crate::i18n::{Text, t};
+use crate::input::InputKind;
+use crate::prompt;
+use crate::settings::{self, SettingValue, SettingsError};
+use crate::slots;
+use crate::snap;
+use crate::swipe::Swipe;
 use crate::tracking::WindowBounds;
 use windows::Win32::Foundation::{POINT, RECT};
+use windows::Win32::UI::Shell::{
+    ABE_BOTTOM, ABE_LEFT, ABE_RIGHT, ABE_TOP, ABM_GETSTATE, ABM_GETTASKBARPOS, ABS_AUTOHIDE,
+    APPBARDATA, SHAppBarMessage,
+};
+use windows::Win32::UI::WindowsAndMessaging::WM_USER;
 
-const SETTINGS_KEY: &str = r"Software\QuakeModoki";
 const EDGE_ENABLED: &str = "EdgeEnabled";
+const EDGE_THRESHOLD_PX: &str = "EdgeThresholdPx";
+const EDGE_TOUCHPAD_THRESHOLD_PX: &str = "EdgeTouchpadThresholdPx";
+const EDGE_TOUCH_THRESHOLD_PX: &str = "EdgeTouchThresholdPx";
+const EDGE_SHOW_DELAY_MS: &str = "EdgeShowDelayMs";
+const EDGE_HIDE_DELAY_MS: &str = "EdgeHideDelayMs";
+const EDGE_SCROLL: &str = "EdgeScroll";
+const EDGE_SWIPE: &str = "EdgeSwipe";
+const EDGE_PROFILES: &str = "EdgeProfiles";
+
+/// Upper bounds for custom values
+const MAX_THRESHOLD_PX: u32 = 50;
+const MAX_DELAY_MS: u32 = 5000;
+
+/// Extra show delay on the edge of an auto-hide taskbar
+const TASKBAR_DWELL_MS: u32 = 400;
+
+/// Taskbar position is queried again after this
+const TASKBAR_REFRESH: Duration = Duration::from_secs(5);
+
+/// Posted to the event loop after custom values are saved (reload the config)
+pub const WM_EDGE_CONFIG_CHANGED: u32 = WM_USER + 13;
 
 #[derive(Debug, Error)]
 pub enum EdgeError {
-    #[error("Registry access failed: {0}")]
-    Registry(#[from] std::io::Error),
+    #[error("Settings write failed: {0}")]
+    Settings(#[from] SettingsError),
 }
 
 /// Edge trigger configuration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct EdgeConfig {
+    /// Distance from the edge for a mouse
     pub threshold_px: i32,
+    pub touchpad_threshold_px: i32,
+    /// Also the start zone of swipes
+    pub touch_threshold_px: i32,
     pub show_delay_ms: u32,
     pub hide_delay_ms: u32,
 }
@@ -30,12 +90,175 @@ impl Default for EdgeConfig {
     fn default() -> Self {
         Self {
             threshold_px: 1,
+            touchpad_threshold_px: 4,
+            touch_threshold_px: 24,
             show_delay_ms: 100,
             hide_delay_ms: 300,
         }
     }
 }
 
+impl EdgeConfig {
+    /// Distance from the edge for `kind`
+    pub fn threshold(&self, kind: InputKind) -> i32 {
+        match kind {
+            InputKind::Mouse => self.threshold_px,
+            InputKind::Touchpad => self.touchpad_threshold_px,
+            InputKind::Touch => self.touch_threshold_px,
+        }
+    }
+}
+
+/// Edge trigger of one window slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeProfile {
+    /// Trigger edge (None: the side the window sits on)
+    pub direction: Option<Direction>,
+    pub config: EdgeConfig,
+}
+
+/// Tray presets for threshold and delays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgePreset {
+    /// Show at once, hide quickly
+    Instant,
+    /// Defaults
+    Fast,
+    /// Wider edge, longer delays (fewer accidental triggers)
+    Relaxed,
+}
+
+impl EdgePreset {
+    pub const ALL: [EdgePreset; 3] = [EdgePreset::Instant, EdgePreset::Fast, EdgePreset::Relaxed];
+
+    pub fn config(self) -> EdgeConfig {
+        match self {
+            EdgePreset::Instant => EdgeConfig {
+                show_delay_ms: 0,
+                hide_delay_ms: 150,
+                ..EdgeConfig::default()
+            },
+            EdgePreset::Fast => EdgeConfig::default(),
+            EdgePreset::Relaxed => EdgeConfig {
+                threshold_px: 3,
+                touchpad_threshold_px: 8,
+                touch_threshold_px: 32,
+                show_delay_ms: 300,
+                hide_delay_ms: 800,
+            },
+        }
+    }
+
+    /// Preset with exactly these values (None: custom)
+    pub fn matching(config: &EdgeConfig) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.config() == *config)
+    }
+}
+
+/// Taskbar position (primary taskbar, shown position)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Taskbar {
+    pub edge: Direction,
+    pub rect: RECT,
+    pub auto_hide: bool,
+}
+
+impl Taskbar {
+    /// Check if the taskbar lies on `direction`'s edge of the monitor with `work_area`
+    fn on_edge(&self, direction: Direction, work_area: &RECT) -> bool {
+        let r = &self.rect;
+        let spans = |low: i32, high: i32, line: i32| low <= line && line <= high;
+        let overlaps = |low: i32, high: i32, from: i32, to: i32| low < to && high > from;
+        self.edge == direction
+            && match direction {
+                Direction::Top => {
+                    spans(r.top, r.bottom, work_area.top)
+                        && overlaps(r.left, r.right, work_area.left, work_area.right)
+                }
+                Direction::Bottom => {
+                    spans(r.top, r.bottom, work_area.bottom)
+                        && overlaps(r.left, r.right, work_area.left, work_area.right)
+                }
+                Direction::Left => {
+                    spans(r.left, r.right, work_area.left)
+                        && overlaps(r.top, r.bottom, work_area.top, work_area.bottom)
+                }
+                Direction::Right => {
+                    spans(r.left, r.right, work_area.right)
+                        && overlaps(r.top, r.bottom, work_area.top, work_area.bottom)
+                }
+            }
+    }
+
+    fn contains(&self, cursor: POINT) -> bool {
+        let r = &self.rect;
+        cursor.x >= r.left && cursor.x < r.right && cursor.y >= r.top && cursor.y < r.bottom
+    }
+}
+
+/// Query the taskbar position (None: no taskbar, e.g. another shell)
+pub fn query_taskbar() -> Option<Taskbar> {
+    let mut data = APPBARDATA {
+        cbSize: size_of::<APPBARDATA>() as u32,
+        ..Default::default()
+    };
+    if unsafe { SHAppBarMessage(ABM_GETTASKBARPOS, &mut data) } == 0 {
+        return None;
+    }
+    let edge = match data.uEdge {
+        ABE_LEFT => Direction::Left,
+        ABE_TOP => Direction::Top,
+        ABE_RIGHT => Direction::Right,
+        ABE_BOTTOM => Direction::Bottom,
+        _ => return None,
+    };
+    let state = unsafe { SHAppBarMessage(ABM_GETSTATE, &mut data) } as u32;
+    Some(Taskbar {
+        edge,
+        rect: data.rc,
+        auto_hide: state & ABS_AUTOHIDE != 0,
+    })
+}
+
+/// Taskbar position, queried again after TASKBAR_REFRESH
+#[derive(Debug, Default)]
+pub struct TaskbarCache {
+    taskbar: Option<Taskbar>,
+    checked: Option<Instant>,
+}
+
+impl TaskbarCache {
+    pub fn get(&mut self) -> Option<Taskbar> {
+        if self
+            .checked
+            .is_none_or(|at| at.elapsed() >= TASKBAR_REFRESH)
+        {
+            self.taskbar = query_taskbar();
+            self.checked = Some(Instant::now());
+        }
+        self.taskbar
+    }
+
+    /// Query again on the next `get`
+    pub fn invalidate(&mut self) {
+        self.checked = None;
+    }
+}
+
+/// Trigger edge of the monitor under the cursor
+#[derive(Debug, Clone, Copy)]
+pub struct EdgeZone<'a> {
+    pub direction: Direction,
+    pub work_area: &'a RECT,
+    pub taskbar: Option<Taskbar>,
+    /// False when the edge is a seam to a neighbouring monitor
+    pub exterior: bool,
+    /// Device behind the cursor (picks the threshold)
+    pub input: InputKind,
+}
+
 /// Edge trigger state machine
 #[derive(Debug, Clone, Default)]
 pub enum EdgeState {
@@ -48,6 +271,19 @@ pub enum EdgeState {
     PendingHide {
         since: Instant,
     },
+    /// Hidden by scrolling at the edge: no dwell show until the cursor leaves
+    Dismissed,
+}
+
+/// What the edge trigger is checked for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeInput {
+    /// Cursor position (edge timer)
+    Dwell,
+    /// Wheel tick with its delta
+    Scroll(i32),
+    /// Touch swipe (zone taken at its start)
+    Swipe(Swipe),
 }
 
 /// Action to perform after state transition
@@ -68,6 +304,29 @@ pub fn detect_edge(cursor: POINT, work_area: &RECT, direction: Direction, thresh
     }
 }
 
+/// Pixel just past `monitor`'s `direction` edge, in the cursor's row/column
+/// (lands on another monitor only when the edge is an interior seam)
+pub fn beyond_edge(direction: Direction, monitor: &RECT, cursor: POINT) -> POINT {
+    match direction {
+        Direction::Left => POINT {
+            x: monitor.left - 1,
+            y: cursor.y,
+        },
+        Direction::Right => POINT {
+            x: monitor.right,
+            y: cursor.y,
+        },
+        Direction::Top => POINT {
+            x: cursor.x,
+            y: monitor.top - 1,
+        },
+        Direction::Bottom => POINT {
+            x: cursor.x,
+            y: monitor.bottom,
+        },
+    }
+}
+
 /// Check if cursor inside window bounds
 pub fn cursor_in_window(cursor: POINT, bounds: &WindowBounds) -> bool {
     cursor.x >= bounds.x
@@ -76,18 +335,36 @@ pub fn cursor_in_window(cursor: POINT, bounds: &WindowBounds) -> bool {
         && cursor.y < bounds.y + bounds.height
 }
 
+/// Check if `cursor` is in the trigger zone of `zone`'s edge
+/// Fixed taskbar: the zone is the strip inside it. Seams between monitors never trigger
+fn in_zone(zone: &EdgeZone, cursor: POINT, threshold: i32) -> bool {
+    let taskbar = zone
+        .taskbar
+        .filter(|t| t.on_edge(zone.direction, zone.work_area));
+    zone.exterior
+        && detect_edge(cursor, zone.work_area, zone.direction, threshold)
+        && !taskbar.is_some_and(|t| !t.auto_hide && t.contains(cursor))
+}
+
 /// Check and transition state machine
 /// Returns Some(action) when show/hide needed, None otherwise
 pub fn check_and_transition(
     state: &mut EdgeState,
     config: &EdgeConfig,
-    direction: Direction,
+    zone: &EdgeZone,
     visible: bool,
     cursor: POINT,
-    work_area: &RECT,
     bounds: Option<&WindowBounds>,
 ) -> Option<EdgeAction> {
-    let at_edge = detect_edge(cursor, work_area, direction, config.threshold_px);
+    let taskbar = zone
+        .taskbar
+        .filter(|t| t.on_edge(zone.direction, zone.work_area));
+    let at_edge = in_zone(zone, cursor, config.threshold(zone.input));
+    // Auto-hide taskbar on the edge: longer dwell
+    let show_delay_ms = match taskbar {
+        Some(t) if t.auto_hide => config.show_delay_ms + TASKBAR_DWELL_MS,
+        _ => config.show_delay_ms,
+    };
     let in_window = bounds.is_some_and(|b| cursor_in_window(cursor, b));
 
     match state {
@@ -104,7 +381,7 @@ pub fn check_and_transition(
                 // Left edge before delay
                 *state = EdgeState::Idle;
                 None
-            } else if since.elapsed().as_millis() >= config.show_delay_ms as u128 {
+            } else if since.elapsed().as_millis() >= show_delay_ms as u128 {
                 // Delay elapsed, trigger show
                 *state = EdgeState::Active;
                 Some(EdgeAction::Show)
@@ -134,9 +411,66 @@ pub fn check_and_transition(
                 None
             }
         }
+        EdgeState::Dismissed => {
+            if !at_edge {
+                *state = EdgeState::Idle;
+            }
+            None
+        }
     }
 }
 
+/// Wheel tick with the cursor at the edge: down (negative delta) shows,
+/// up hides. Returns Some(action) when show/hide needed, None otherwise
+pub fn check_scroll(
+    state: &mut EdgeState,
+    config: &EdgeConfig,
+    zone: &EdgeZone,
+    visible: bool,
+    cursor: POINT,
+    delta: i32,
+) -> Option<EdgeAction> {
+    if !in_zone(zone, cursor, config.threshold(zone.input)) {
+        return None;
+    }
+    match (delta.signum(), visible) {
+        (-1, false) => {
+            *state = EdgeState::Active;
+            Some(EdgeAction::Show)
+        }
+        (1, true) => {
+            *state = EdgeState::Dismissed;
+            Some(EdgeAction::Hide)
+        }
+        _ => None,
+    }
+}
+
+/// Touch swipe from `from` to `to`, with `zone` taken at `from`: shows the
+/// window when it started at the edge and moved mostly inward
+pub fn check_swipe(
+    state: &mut EdgeState,
+    config: &EdgeConfig,
+    zone: &EdgeZone,
+    visible: bool,
+    from: POINT,
+    to: POINT,
+) -> Option<EdgeAction> {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    // Travel toward the screen's interior, and across it
+    let (inward, across) = match zone.direction {
+        Direction::Top => (dy, dx),
+        Direction::Bottom => (-dy, dx),
+        Direction::Left => (dx, dy),
+        Direction::Right => (-dx, dy),
+    };
+    if visible || inward <= across.abs() || !in_zone(zone, from, config.touch_threshold_px) {
+        return None;
+    }
+    *state = EdgeState::Active;
+    Some(EdgeAction::Show)
+}
+
 /// Reset state machine to Idle
 pub fn reset_state(state: &mut EdgeState) {
     *state = EdgeState::Idle;
@@ -144,20 +478,19 @@ pub fn reset_state(state: &mut EdgeState) {
 
 // ========== Registry Persistence ==========
 
-/// Check if edge trigger enabled in registry
+/// Check if edge trigger enabled in registry (default: on)
 pub fn is_enabled() -> bool {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    hkcu.open_subkey_with_flags(SETTINGS_KEY, KEY_READ)
-        .ok()
-        .and_then(|key| key.get_value::<u32, _>(EDGE_ENABLED).ok())
-        != Some(0)
+    settings::get_bool(EDGE_ENABLED, true)
+}
+
+/// Check if edge trigger is fixed by policy
+pub fn is_locked() -> bool {
+    settings::is_locked(EDGE_ENABLED)
 }
 
 /// Enable/disable edge trigger
 pub fn set_enabled(enabled: bool) -> Result<(), EdgeError> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let (key, _) = hkcu.create_subkey(SETTINGS_KEY)?;
-    key.set_value(EDGE_ENABLED, &(enabled as u32))?;
+    settings::set_bool(EDGE_ENABLED, enabled)?;
     Ok(())
 }
 
@@ -168,6 +501,190 @@ pub fn toggle() -> Result<bool, EdgeError> {
     Ok(new_state)
 }
 
+/// Check if scrolling at the edge shows / hides (default: off)
+pub fn is_scroll_enabled() -> bool {
+    settings::get_bool(EDGE_SCROLL, false)
+}
+
+/// Check if scrolling at the edge is fixed by policy
+pub fn is_scroll_locked() -> bool {
+    settings::is_locked(EDGE_SCROLL)
+}
+
+/// Toggle scrolling at the edge, returns new state
+pub fn toggle_scroll() -> Result<bool, EdgeError> {
+    Ok(settings::toggle_bool(EDGE_SCROLL, false)?)
+}
+
+/// Check if swiping in from the edge shows the window (default: off)
+pub fn is_swipe_enabled() -> bool {
+    settings::get_bool(EDGE_SWIPE, false)
+}
+
+/// Check if swiping in from the edge is fixed by policy
+pub fn is_swipe_locked() -> bool {
+    settings::is_locked(EDGE_SWIPE)
+}
+
+/// Toggle swiping in from the edge, returns new state
+pub fn toggle_swipe() -> Result<bool, EdgeError> {
+    Ok(settings::toggle_bool(EDGE_SWIPE, false)?)
+}
+
+/// Load threshold and delays (defaults for missing values)
+pub fn load_config() -> EdgeConfig {
+    let defaults = EdgeConfig::default();
+    EdgeConfig {
+        threshold_px: settings::get_u32(EDGE_THRESHOLD_PX, defaults.threshold_px as u32)
+            .min(MAX_THRESHOLD_PX) as i32,
+        touchpad_threshold_px: settings::get_u32(
+            EDGE_TOUCHPAD_THRESHOLD_PX,
+            defaults.touchpad_threshold_px as u32,
+        )
+        .min(MAX_THRESHOLD_PX) as i32,
+        touch_threshold_px: settings::get_u32(
+            EDGE_TOUCH_THRESHOLD_PX,
+            defaults.touch_threshold_px as u32,
+        )
+        .min(MAX_THRESHOLD_PX) as i32,
+        show_delay_ms: settings::get_u32(EDGE_SHOW_DELAY_MS, defaults.show_delay_ms)
+            .min(MAX_DELAY_MS),
+        hide_delay_ms: settings::get_u32(EDGE_HIDE_DELAY_MS, defaults.hide_delay_ms)
+            .min(MAX_DELAY_MS),
+    }
+}
+
+/// Parse a slot's edge profile; missing or invalid values keep `base`'s
+/// Numbers may be DWORDs (like the global values) or strings
+fn parse_profile(slot: &str, values: &[(String, SettingValue)], base: &EdgeConfig) -> EdgeProfile {
+    let value = |name: &str| {
+        values
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    };
+    let number = |name: &str, default: u32, max: u32| match value(name) {
+        None => default,
+        Some(SettingValue::Dword(number)) => (*number).min(max),
+        Some(SettingValue::Text(text)) => match text.trim().parse::<u32>() {
+            Ok(number) => number.min(max),
+            Err(_) => {
+                tracing::warn!(slot, name, text, "Invalid edge profile value");
+                default
+            }
+        },
+        Some(SettingValue::Other) => {
+            tracing::warn!(slot, name, "Edge profile value is neither DWORD nor string");
+            default
+        }
+    };
+    let direction = match value("Edge") {
+        None => None,
+        Some(SettingValue::Text(text)) => {
+            let dock = snap::parse_dock(text);
+            if dock.is_none() {
+                tracing::warn!(slot, text, "Invalid edge profile side");
+            }
+            dock
+        }
+        Some(_) => {
+            tracing::warn!(slot, "Edge profile side is not a string");
+            None
+        }
+    };
+    let px = |name: &str, default: i32| number(name, default as u32, MAX_THRESHOLD_PX) as i32;
+    EdgeProfile {
+        direction,
+        config: EdgeConfig {
+            threshold_px: px(EDGE_THRESHOLD_PX, base.threshold_px),
+            touchpad_threshold_px: px(EDGE_TOUCHPAD_THRESHOLD_PX, base.touchpad_threshold_px),
+            touch_threshold_px: px(EDGE_TOUCH_THRESHOLD_PX, base.touch_threshold_px),
+            show_delay_ms: number(EDGE_SHOW_DELAY_MS, base.show_delay_ms, MAX_DELAY_MS),
+            hide_delay_ms: number(EDGE_HIDE_DELAY_MS, base.hide_delay_ms, MAX_DELAY_MS),
+        },
+    }
+}
+
+/// Edge profile of each slot (`EdgeProfiles\Slot1`..`Slot4`), None without one
+pub fn load_profiles(base: &EdgeConfig) -> [Option<EdgeProfile>; slots::COUNT] {
+    let names = settings::get_subkey_names(EDGE_PROFILES);
+    std::array::from_fn(|slot| {
+        let name = format!("Slot{}", slot + 1);
+        names
+            .iter()
+            .any(|n| n.eq_ignore_ascii_case(&name))
+            .then(|| {
+                let values = settings::get_values(&format!(r"{EDGE_PROFILES}\{name}"));
+                parse_profile(&name, &values, base)
+            })
+    })
+}
+
+/// Persist threshold and delays
+pub fn save_config(config: &EdgeConfig) -> Result<(), EdgeError> {
+    settings::set_u32(EDGE_THRESHOLD_PX, config.threshold_px.max(0) as u32)?;
+    settings::set_u32(
+        EDGE_TOUCHPAD_THRESHOLD_PX,
+        config.touchpad_threshold_px.max(0) as u32,
+    )?;
+    settings::set_u32(
+        EDGE_TOUCH_THRESHOLD_PX,
+        config.touch_threshold_px.max(0) as u32,
+    )?;
+    settings::set_u32(EDGE_SHOW_DELAY_MS, config.show_delay_ms)?;
+    settings::set_u32(EDGE_HIDE_DELAY_MS, config.hide_delay_ms)?;
+    Ok(())
+}
+
+/// Prompt for custom values on a worker thread; posts WM_EDGE_CONFIG_CHANGED once saved
+pub fn edit_custom(current: EdgeConfig) {
+    std::thread::spawn(move || {
+        let fields = [
+            prompt::Field {
+                label: t(Text::EdgeDistancePx),
+                value: current.threshold_px.max(0) as u32,
+                max: MAX_THRESHOLD_PX,
+            },
+            prompt::Field {
+                label: t(Text::TouchpadDistancePx),
+                value: current.touchpad_threshold_px.max(0) as u32,
+                max: MAX_THRESHOLD_PX,
+            },
+            prompt::Field {
+                label: t(Text::TouchDistancePx),
+                value: current.touch_threshold_px.max(0) as u32,
+                max: MAX_THRESHOLD_PX,
+            },
+            prompt::Field {
+                label: t(Text::ShowDelayMs),
+                value: current.show_delay_ms,
+                max: MAX_DELAY_MS,
+            },
+            prompt::Field {
+                label: t(Text::HideDelayMs),
+                value: current.hide_delay_ms,
+                max: MAX_DELAY_MS,
+            },
+        ];
+        let Some(values) = prompt::numbers(t(Text::EdgeTriggerSettings), &fields) else {
+            return;
+        };
+        let config = EdgeConfig {
+            threshold_px: values[0] as i32,
+            touchpad_threshold_px: values[1] as i32,
+            touch_threshold_px: values[2] as i32,
+            show_delay_ms: values[3],
+            hide_delay_ms: values[4],
+        };
+        match save_config(&config) {
+            Ok(()) => {
+                events::post(WM_EDGE_CONFIG_CHANGED, 0);
+            }
+            Err(e) => AppError::recoverable("Edge settings not saved", e).report(),
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,6 +705,17 @@ mod tests {
         POINT { x, y }
     }
 
+    /// Zone without a taskbar
+    fn zone(direction: Direction, work_area: &RECT) -> EdgeZone<'_> {
+        EdgeZone {
+            direction,
+            work_area,
+            taskbar: None,
+            exterior: true,
+            input: InputKind::Mouse,
+        }
+    }
+
     fn make_bounds(x: i32, y: i32, width: i32, height: i32) -> WindowBounds {
         WindowBounds {
             x,
@@ -197,6 +725,79 @@ mod tests {
         }
     }
 
+    // ========== Preset Tests ==========
+
+    #[test]
+    fn test_preset_matching() {
+        for preset in EdgePreset::ALL {
+            assert_eq!(EdgePreset::matching(&preset.config()), Some(preset));
+        }
+        assert_eq!(
+            EdgePreset::matching(&EdgeConfig::default()),
+            Some(EdgePreset::Fast)
+        );
+        let custom = EdgeConfig {
+            threshold_px: 2,
+            ..EdgeConfig::default()
+        };
+        assert_eq!(EdgePreset::matching(&custom), None);
+    }
+
+    #[test]
+    #[serial]
+    fn test_config_round_trip() {
+        let previous = load_config();
+        let relaxed = EdgePreset::Relaxed.config();
+        if save_config(&relaxed).is_ok() {
+            assert_eq!(load_config(), relaxed);
+            let _ = save_config(&previous);
+        }
+    }
+
+    #[test]
+    fn test_parse_profile() {
+        let base = EdgePreset::Relaxed.config();
+
+        // Empty: global values, side from the window
+        let profile = parse_profile("Slot2", &[], &base);
+        assert_eq!(profile.direction, None);
+        assert_eq!(profile.config, base);
+
+        let text =
+            |name: &str, text: &str| (name.to_string(), SettingValue::Text(text.to_string()));
+        let dword = |name: &str, number| (name.to_string(), SettingValue::Dword(number));
+        let profile = parse_profile(
+            "Slot2",
+            &[
+                text("Edge", "top"),
+                text("edgeshowdelayms", "0"),
+                dword("EdgeThresholdPx", 999),
+            ],
+            &base,
+        );
+        assert_eq!(profile.direction, Some(Direction::Top));
+        assert_eq!(profile.config.show_delay_ms, 0);
+        assert_eq!(profile.config.threshold_px, MAX_THRESHOLD_PX as i32);
+        assert_eq!(profile.config.hide_delay_ms, base.hide_delay_ms);
+
+        // Invalid values fall back
+        let profile = parse_profile(
+            "Slot3",
+            &[
+                text("Edge", "middle"),
+                text("EdgeHideDelayMs", "soon"),
+                ("EdgeShowDelayMs".to_string(), SettingValue::Other),
+            ],
+            &base,
+        );
+        assert_eq!(profile.direction, None);
+        assert_eq!(profile.config, base);
+
+        // DWORDs as copied from the global values
+        let profile = parse_profile("Slot4", &[dword("EdgeHideDelayMs", 500)], &base);
+        assert_eq!(profile.config.hide_delay_ms, 500);
+    }
+
     // ========== Edge Detection Tests ==========
 
     #[test]
@@ -341,6 +942,7 @@ mod tests {
             threshold_px: 1,
             show_delay_ms: 100,
             hide_delay_ms: 300,
+            ..EdgeConfig::default()
         };
         let work_area = make_rect(0, 0, 1920, 1080);
         let mut state = EdgeState::Idle;
@@ -349,10 +951,9 @@ mod tests {
         let action = check_and_transition(
             &mut state,
             &config,
-            Direction::Left,
+            &zone(Direction::Left, &work_area),
             false,
             make_point(0, 500),
-            &work_area,
             None,
         );
         assert_eq!(action, None);
@@ -365,6 +966,7 @@ mod tests {
             threshold_px: 1,
             show_delay_ms: 100,
             hide_delay_ms: 300,
+            ..EdgeConfig::default()
         };
         let work_area = make_rect(0, 0, 1920, 1080);
         let mut state = EdgeState::PendingShow {
@@ -375,10 +977,9 @@ mod tests {
         let action = check_and_transition(
             &mut state,
             &config,
-            Direction::Left,
+            &zone(Direction::Left, &work_area),
             false,
             make_point(100, 500),
-            &work_area,
             None,
         );
         assert_eq!(action, None);
@@ -391,6 +992,7 @@ mod tests {
             threshold_px: 1,
             show_delay_ms: 10,
             hide_delay_ms: 300,
+            ..EdgeConfig::default()
         };
         let work_area = make_rect(0, 0, 1920, 1080);
         let mut state = EdgeState::PendingShow {
@@ -404,10 +1006,9 @@ mod tests {
         let action = check_and_transition(
             &mut state,
             &config,
-            Direction::Left,
+            &zone(Direction::Left, &work_area),
             false,
             make_point(0, 500),
-            &work_area,
             None,
         );
         assert_eq!(action, Some(EdgeAction::Show));
@@ -420,6 +1021,7 @@ mod tests {
             threshold_px: 1,
             show_delay_ms: 100,
             hide_delay_ms: 300,
+            ..EdgeConfig::default()
         };
         let work_area = make_rect(0, 0, 1920, 1080);
         let bounds = make_bounds(0, 0, 400, 1080);
@@ -429,10 +1031,9 @@ mod tests {
         let action = check_and_transition(
             &mut state,
             &config,
-            Direction::Left,
+            &zone(Direction::Left, &work_area),
             true,
             make_point(500, 500),
-            &work_area,
             Some(&bounds),
         );
         assert_eq!(action, None);
@@ -445,6 +1046,7 @@ mod tests {
             threshold_px: 1,
             show_delay_ms: 100,
             hide_delay_ms: 300,
+            ..EdgeConfig::default()
         };
         let work_area = make_rect(0, 0, 1920, 1080);
         let bounds = make_bounds(0, 0, 400, 1080);
@@ -456,10 +1058,9 @@ mod tests {
         let action = check_and_transition(
             &mut state,
             &config,
-            Direction::Left,
+            &zone(Direction::Left, &work_area),
             true,
             make_point(200, 500),
-            &work_area,
             Some(&bounds),
         );
         assert_eq!(action, None);
@@ -472,6 +1073,7 @@ mod tests {
             threshold_px: 1,
             show_delay_ms: 100,
             hide_delay_ms: 10,
+            ..EdgeConfig::default()
         };
         let work_area = make_rect(0, 0, 1920, 1080);
         let bounds = make_bounds(0, 0, 400, 1080);
@@ -486,10 +1088,9 @@ mod tests {
         let action = check_and_transition(
             &mut state,
             &config,
-            Direction::Left,
+            &zone(Direction::Left, &work_area),
             true,
             make_point(500, 500),
-            &work_area,
             Some(&bounds),
         );
         assert_eq!(action, Some(EdgeAction::Hide));
@@ -506,16 +1107,280 @@ mod tests {
         let action = check_and_transition(
             &mut state,
             &config,
-            Direction::Left,
+            &zone(Direction::Left, &work_area),
             true,
             make_point(0, 500),
-            &work_area,
             None,
         );
         assert_eq!(action, None);
         assert!(matches!(state, EdgeState::Idle));
     }
 
+    // ========== Taskbar Tests ==========
+
+    fn bottom_taskbar(auto_hide: bool) -> Taskbar {
+        Taskbar {
+            edge: Direction::Bottom,
+            rect: make_rect(0, 1040, 1920, 1080),
+            auto_hide,
+        }
+    }
+
+    #[test]
+    fn test_taskbar_on_edge() {
+        let taskbar = bottom_taskbar(false);
+        // Fixed: work area ends at the taskbar
+        assert!(taskbar.on_edge(Direction::Bottom, &make_rect(0, 0, 1920, 1040)));
+        // Auto-hide: work area is the whole monitor
+        assert!(taskbar.on_edge(Direction::Bottom, &make_rect(0, 0, 1920, 1080)));
+        assert!(!taskbar.on_edge(Direction::Top, &make_rect(0, 0, 1920, 1040)));
+        // Monitor above the primary one
+        assert!(!taskbar.on_edge(Direction::Bottom, &make_rect(0, -1080, 1920, 0)));
+        // Monitor to the right
+        assert!(!taskbar.on_edge(Direction::Bottom, &make_rect(1920, 0, 3840, 1040)));
+    }
+
+    #[test]
+    fn test_fixed_taskbar_not_a_trigger() {
+        let config = EdgeConfig::default();
+        let work_area = make_rect(0, 0, 1920, 1040);
+        let taskbar = bottom_taskbar(false);
+        let check = |cursor: POINT| {
+            let mut state = EdgeState::Idle;
+            check_and_transition(
+                &mut state,
+                &config,
+                &EdgeZone {
+                    direction: Direction::Bottom,
+                    work_area: &work_area,
+                    taskbar: Some(taskbar),
+                    exterior: true,
+                    input: InputKind::Mouse,
+                },
+                false,
+                cursor,
+                None,
+            );
+            matches!(state, EdgeState::PendingShow { .. })
+        };
+        // Over the taskbar
+        assert!(!check(make_point(500, 1079)));
+        // Strip just inside it
+        assert!(check(make_point(500, 1039)));
+    }
+
+    #[test]
+    fn test_auto_hide_taskbar_longer_dwell() {
+        let config = EdgeConfig {
+            threshold_px: 1,
+            show_delay_ms: 10,
+            hide_delay_ms: 300,
+            ..EdgeConfig::default()
+        };
+        let work_area = make_rect(0, 0, 1920, 1080);
+        let taskbar = bottom_taskbar(true);
+        let mut state = EdgeState::PendingShow {
+            since: Instant::now(),
+        };
+        sleep(Duration::from_millis(20));
+
+        let action = check_and_transition(
+            &mut state,
+            &config,
+            &EdgeZone {
+                direction: Direction::Bottom,
+                work_area: &work_area,
+                taskbar: Some(taskbar),
+                exterior: true,
+                input: InputKind::Mouse,
+            },
+            false,
+            make_point(500, 1079),
+            None,
+        );
+        // Plain delay elapsed, taskbar dwell not yet
+        assert_eq!(action, None);
+        assert!(matches!(state, EdgeState::PendingShow { .. }));
+    }
+
+    #[test]
+    fn test_threshold_follows_input() {
+        let config = EdgeConfig::default();
+        let work_area = make_rect(0, 0, 1920, 1080);
+        let near_edge = make_point(3, 500);
+        let mut state = EdgeState::Idle;
+
+        // 3px off the edge: outside the mouse zone, inside the touchpad zone
+        check_and_transition(
+            &mut state,
+            &config,
+            &zone(Direction::Left, &work_area),
+            false,
+            near_edge,
+            None,
+        );
+        assert!(matches!(state, EdgeState::Idle));
+
+        check_and_transition(
+            &mut state,
+            &config,
+            &EdgeZone {
+                input: InputKind::Touchpad,
+                ..zone(Direction::Left, &work_area)
+            },
+            false,
+            near_edge,
+            None,
+        );
+        assert!(matches!(state, EdgeState::PendingShow { .. }));
+    }
+
+    // ========== Monitor Seam Tests ==========
+
+    #[test]
+    fn test_beyond_edge() {
+        // Right-hand monitor of a side-by-side pair
+        let monitor = make_rect(1920, 0, 3840, 1080);
+        let cursor = make_point(1925, 500);
+        let left = beyond_edge(Direction::Left, &monitor, cursor);
+        assert_eq!((left.x, left.y), (1919, 500));
+        let right = beyond_edge(Direction::Right, &monitor, cursor);
+        assert_eq!((right.x, right.y), (3840, 500));
+        let top = beyond_edge(Direction::Top, &monitor, cursor);
+        assert_eq!((top.x, top.y), (1925, -1));
+        let bottom = beyond_edge(Direction::Bottom, &monitor, cursor);
+        assert_eq!((bottom.x, bottom.y), (1925, 1080));
+    }
+
+    #[test]
+    fn test_interior_seam_never_triggers() {
+        let config = EdgeConfig::default();
+        let work_area = make_rect(1920, 0, 3840, 1080);
+        let mut state = EdgeState::Idle;
+
+        check_and_transition(
+            &mut state,
+            &config,
+            &EdgeZone {
+                exterior: false,
+                ..zone(Direction::Left, &work_area)
+            },
+            false,
+            make_point(1920, 500),
+            None,
+        );
+        assert!(matches!(state, EdgeState::Idle));
+    }
+
+    // ========== Scroll Tests ==========
+
+    #[test]
+    fn test_scroll_at_edge_shows_and_hides() {
+        let config = EdgeConfig::default();
+        let work_area = make_rect(0, 0, 1920, 1080);
+        let zone = zone(Direction::Top, &work_area);
+        let at_edge = make_point(500, 0);
+        let mut state = EdgeState::Idle;
+
+        let action = check_scroll(&mut state, &config, &zone, false, at_edge, -120);
+        assert_eq!(action, Some(EdgeAction::Show));
+        assert!(matches!(state, EdgeState::Active));
+
+        // Scrolling down again while shown does nothing
+        assert_eq!(
+            check_scroll(&mut state, &config, &zone, true, at_edge, -120),
+            None
+        );
+
+        let action = check_scroll(&mut state, &config, &zone, true, at_edge, 120);
+        assert_eq!(action, Some(EdgeAction::Hide));
+        assert!(matches!(state, EdgeState::Dismissed));
+    }
+
+    #[test]
+    fn test_scroll_away_from_edge_ignored() {
+        let config = EdgeConfig::default();
+        let work_area = make_rect(0, 0, 1920, 1080);
+        let mut state = EdgeState::Idle;
+
+        let action = check_scroll(
+            &mut state,
+            &config,
+            &zone(Direction::Top, &work_area),
+            false,
+            make_point(500, 400),
+            -120,
+        );
+        assert_eq!(action, None);
+        assert!(matches!(state, EdgeState::Idle));
+    }
+
+    #[test]
+    fn test_dismissed_waits_for_cursor_to_leave() {
+        let config = EdgeConfig::default();
+        let work_area = make_rect(0, 0, 1920, 1080);
+        let zone = zone(Direction::Top, &work_area);
+        let mut state = EdgeState::Dismissed;
+
+        // Resting at the edge: no dwell show
+        let action =
+            check_and_transition(&mut state, &config, &zone, false, make_point(500, 0), None);
+        assert_eq!(action, None);
+        assert!(matches!(state, EdgeState::Dismissed));
+
+        check_and_transition(
+            &mut state,
+            &config,
+            &zone,
+            false,
+            make_point(500, 400),
+            None,
+        );
+        assert!(matches!(state, EdgeState::Idle));
+    }
+
+    // ========== Swipe Tests ==========
+
+    #[test]
+    fn test_swipe_in_from_edge_shows() {
+        let work_area = make_rect(0, 0, 1920, 1080);
+        let mut state = EdgeState::Idle;
+
+        let action = check_swipe(
+            &mut state,
+            &EdgeConfig::default(),
+            &zone(Direction::Top, &work_area),
+            false,
+            make_point(800, 10),
+            make_point(820, 90),
+        );
+        assert_eq!(action, Some(EdgeAction::Show));
+        assert!(matches!(state, EdgeState::Active));
+    }
+
+    #[test]
+    fn test_swipe_ignored_unless_inward_from_edge() {
+        let config = EdgeConfig::default();
+        let work_area = make_rect(0, 0, 1920, 1080);
+        let zone = zone(Direction::Right, &work_area);
+        let mut state = EdgeState::Idle;
+        let cases = [
+            // Started away from the edge
+            (make_point(1800, 500), make_point(1700, 500)),
+            // Outward
+            (make_point(1910, 500), make_point(1990, 500)),
+            // Along the edge
+            (make_point(1910, 300), make_point(1890, 500)),
+        ];
+        for (from, to) in cases {
+            assert_eq!(
+                check_swipe(&mut state, &config, &zone, false, from, to),
+                None
+            );
+        }
+        assert!(matches!(state, EdgeState::Idle));
+    }
+
     // ========== Registry Tests ==========
 
     #[test]
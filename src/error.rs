@@ -1,6 +1,119 @@
 //! Error types for Quake Modoki
+//!
+//! Module errors below describe what went wrong; `AppError` wraps one with a
+//! user-facing message and a severity that decides how it is reported
+//! (log only, log + toast, or abort startup).
 
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use windows::Win32::UI::WindowsAndMessaging::WM_USER;
+
+use crate::settings::SettingsError;
+
+/// Posted to the event loop when a non-minor error was reported (tray badge)
+pub const WM_ERROR_REPORTED: u32 = WM_USER + 11;
+
+/// Same error toast is not repeated within this window (e.g. a failing hotkey pressed repeatedly)
+const TOAST_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Last error toast (message, shown at)
+static LAST_TOAST: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+
+/// Last non-minor error until cleared from the tray
+static LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+/// How loudly an error is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Background failure with a fallback: logged only
+    Minor,
+    /// Action or feature failed, app keeps running: logged + toast
+    Recoverable,
+    /// Startup cannot continue: logged + toast, process exits
+    Fatal,
+}
+
+/// Application error: user-facing message, cause and severity
+#[derive(Debug, Error)]
+#[error("{message}: {source}")]
+pub struct AppError {
+    severity: Severity,
+    message: String,
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl AppError {
+    pub fn new(
+        severity: Severity,
+        message: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            source: source.into(),
+        }
+    }
+
+    pub fn minor(
+        message: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self::new(Severity::Minor, message, source)
+    }
+
+    pub fn recoverable(
+        message: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self::new(Severity::Recoverable, message, source)
+    }
+
+    pub fn fatal(
+        message: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self::new(Severity::Fatal, message, source)
+    }
+
+    /// Log; unless minor also remember for the tray badge and toast
+    /// (the same toast is not repeated within the cooldown)
+    pub fn report(&self) {
+        match self.severity {
+            Severity::Minor => tracing::warn!("{self}"),
+            Severity::Recoverable | Severity::Fatal => {
+                tracing::error!(severity = ?self.severity, "{self}");
+                let text = self.to_string();
+                *LAST_ERROR.lock().unwrap_or_else(|e| e.into_inner()) = Some(text.clone());
+                crate::events::post(WM_ERROR_REPORTED, 0);
+                let mut last = LAST_TOAST.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+                if should_toast(last.as_ref(), &text, now) {
+                    crate::notification::show_error(&text);
+                    *last = Some((text, now));
+                }
+            }
+        }
+    }
+}
+
+/// Last reported non-minor error (None after `clear_last_error`)
+pub fn last_error() -> Option<String> {
+    LAST_ERROR.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn clear_last_error() {
+    *LAST_ERROR.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Check if `text` may be toasted now given the last toast
+fn should_toast(last: Option<&(String, Instant)>, text: &str, now: Instant) -> bool {
+    match last {
+        Some((last_text, at)) => last_text != text || now.duration_since(*at) >= TOAST_COOLDOWN,
+        None => true,
+    }
+}
 
 /// Focus tracking errors (graceful degradation)
 #[derive(Debug, Error)]
@@ -12,6 +125,133 @@ pub enum FocusError {
     HookUninstall,
 }
 
+/// Shell hook errors (flash suppression unavailable)
+#[derive(Debug, Error)]
+pub enum FlashError {
+    #[error("CreateWindowExW → shell hook window failed")]
+    WindowCreate,
+
+    #[error("RegisterShellHookWindow failed")]
+    HookRegister,
+}
+
+/// Lifecycle script errors (hooks disabled)
+#[derive(Debug, Error)]
+pub enum ScriptError {
+    #[error("Script read failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Script compile failed: {0}")]
+    Compile(String),
+}
+
+/// Plugin load errors (plugin skipped)
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("LoadLibraryW failed: {0}")]
+    Load(String),
+
+    #[error("quake_modoki_plugin export missing or returned null")]
+    MissingEntryPoint,
+
+    #[error("Plugin ABI {found} not supported (expected {expected})")]
+    AbiMismatch { found: u32, expected: u32 },
+
+    #[error("Plugin vtable has no name")]
+    MissingName,
+
+    #[error("Plugin {0} init returned false")]
+    InitFailed(String),
+}
+
+/// Launch-and-track errors (nothing tracked)
+#[derive(Debug, Error)]
+pub enum LaunchError {
+    #[error("ShellExecuteExW failed: {0}")]
+    Start(String),
+
+    #[error("Launched without a process handle (handed off to another app)")]
+    NoProcess,
+}
+
+/// Peek flyout errors (no thumbnail shown)
+#[derive(Debug, Error)]
+pub enum PeekError {
+    #[error("Tracked window has no bounds")]
+    NoWindow,
+
+    #[error("CreateWindowExW → peek window failed")]
+    WindowCreate,
+
+    #[error("DwmRegisterThumbnail failed: {0}")]
+    Thumbnail(String),
+}
+
+/// Hotkey capture errors (capture not started)
+#[derive(Debug, Error)]
+pub enum CaptureError {
+    #[error("CreateWindowExW → capture window failed")]
+    WindowCreate,
+
+    #[error("SetWindowsHookExW(WH_KEYBOARD_LL) failed")]
+    HookInstall,
+}
+
+/// Mouse hook errors (shake and scroll-at-edge triggers unavailable)
+#[derive(Debug, Error)]
+pub enum MouseError {
+    #[error("SetWindowsHookExW(WH_MOUSE_LL) failed")]
+    HookInstall,
+}
+
+/// Per-app rule errors (rule skipped)
+#[derive(Debug, Error)]
+pub enum RuleError {
+    #[error("No Exe / Class / Title condition")]
+    NoCondition,
+
+    #[error("Invalid Title regex: {0}")]
+    Title(#[from] regex::Error),
+
+    #[error("Invalid {name} value: {value}")]
+    Value { name: String, value: String },
+}
+
+/// Find-and-track errors (tracking unchanged)
+#[derive(Debug, Error)]
+pub enum WindowError {
+    #[error("No window matches {0}")]
+    NotFound(String),
+
+    #[error("Invalid title pattern: {0}")]
+    Pattern(#[from] regex::Error),
+}
+
+/// Hotkey rebinding errors (previous binding stays active)
+#[derive(Debug, Error)]
+pub enum HotkeyError {
+    #[error("{0} could not be registered (in use by another app?)")]
+    Register(String),
+
+    #[error("Virtual key 0x{0:02X} is not supported")]
+    UnsupportedKey(u32),
+
+    #[error("{0} needs a modifier (Ctrl / Alt / Shift / Win)")]
+    NeedsModifier(String),
+
+    #[error("\"{0}\" has no key (e.g. Ctrl+Alt+T, F12)")]
+    MissingKey(String),
+
+    #[error("Unknown modifier \"{0}\" (Ctrl / Alt / Shift / Win)")]
+    UnknownModifier(String),
+
+    #[error("Unknown key \"{0}\" (letters, digits, F1-F24, ` or a key code such as Space)")]
+    UnknownKey(String),
+
+    #[error("Hotkey save failed: {0}")]
+    Settings(#[from] SettingsError),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -21,4 +261,57 @@ mod tests {
         let err = FocusError::HookInstall;
         assert_eq!(err.to_string(), "SetWinEventHook → invalid handle");
     }
+
+    #[test]
+    fn test_flash_error_display() {
+        let err = FlashError::HookRegister;
+        assert_eq!(err.to_string(), "RegisterShellHookWindow failed");
+    }
+
+    #[test]
+    fn test_hotkey_error_display() {
+        let err = HotkeyError::UnsupportedKey(0xE8);
+        assert_eq!(err.to_string(), "Virtual key 0xE8 is not supported");
+    }
+
+    #[test]
+    fn test_app_error_display() {
+        let err = AppError::recoverable("Edge trigger toggle failed", "access denied");
+        assert_eq!(err.severity, Severity::Recoverable);
+        assert_eq!(err.message, "Edge trigger toggle failed");
+        assert_eq!(err.to_string(), "Edge trigger toggle failed: access denied");
+    }
+
+    #[test]
+    fn test_app_error_wraps_module_error() {
+        let err = AppError::minor("Focus hook error", FocusError::HookInstall);
+        assert_eq!(err.severity, Severity::Minor);
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_clear_last_error() {
+        *LAST_ERROR.lock().unwrap() = Some("Toggle failed: GetMonitorInfo failed".to_string());
+        assert!(last_error().is_some());
+        clear_last_error();
+        assert_eq!(last_error(), None);
+    }
+
+    #[test]
+    fn test_should_toast_cooldown() {
+        let now = Instant::now();
+        let last = ("A: x".to_string(), now);
+        assert!(should_toast(None, "A: x", now));
+        assert!(!should_toast(
+            Some(&last),
+            "A: x",
+            now + Duration::from_secs(1)
+        ));
+        assert!(should_toast(
+            Some(&last),
+            "B: y",
+            now + Duration::from_secs(1)
+        ));
+        assert!(should_toast(Some(&last), "A: x", now + TOAST_COOLDOWN));
+    }
 }
@@ -0,0 +1,385 @@
+//! Main hidden window: the single wake-up source of the event loop
+//!
+//! Hotkey and tray menu events, hook notifications (focus, flash, IPC, toast,
+//! update, capture), timers and session / power changes all arrive as messages
+//! for this window, so the loop blocks in GetMessageW until there is work.
+//! Polling timers are armed only while something needs them (see `arm`);
+//! `Wakeups` counts what woke the loop, logged once a minute at debug level.
+//! Session / power notifications are sent, not posted: the window procedure
+//! re-posts them as WM_SESSION_EVENT / WM_POWER_EVENT for the loop.
+//! The window is a never-shown top-level window rather than message-only, as
+//! only those receive broadcasts: work area and display changes are re-posted
+//! as WM_WORK_AREA_EVENT. Touchpad / touch screen raw input (see `input`) is
+//! consumed by the window procedure.
+
+use global_hotkey::GlobalHotKeyEvent;
+use muda::MenuEvent;
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicIsize, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::{HANDLE, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Power::{
+    HPOWERNOTIFY, RegisterSuspendResumeNotification, UnregisterSuspendResumeNotification,
+};
+use windows::Win32::System::RemoteDesktop::{
+    NOTIFY_FOR_THIS_SESSION, WTSRegisterSessionNotification, WTSUnRegisterSessionNotification,
+};
+use windows::Win32::UI::Input::HRAWINPUT;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DEVICE_NOTIFY_WINDOW_HANDLE, DefWindowProcW, DestroyWindow, KillTimer, MSG,
+    PostMessageW, RegisterClassW, SPI_SETWORKAREA, SetTimer, WM_DISPLAYCHANGE, WM_INPUT, WM_NULL,
+    WM_POWERBROADCAST, WM_SETTINGCHANGE, WM_TIMER, WM_USER, WM_WTSSESSION_CHANGE, WNDCLASSW,
+    WS_EX_TOOLWINDOW, WS_POPUP,
+};
+use windows::core::{PCWSTR, w};
+
+use crate::input;
+use crate::sysprefs;
+
+/// Hotkey event queued (drain the hotkey receiver)
+pub const WM_HOTKEY_EVENT: u32 = WM_USER + 7;
+
+/// Tray menu event queued (drain the menu receiver)
+pub const WM_MENU_EVENT: u32 = WM_USER + 8;
+
+/// Session changed (WPARAM = WTS_* code, e.g. lock / unlock)
+pub const WM_SESSION_EVENT: u32 = WM_USER + 9;
+
+/// Power state changed (WPARAM = PBT_* code, e.g. suspend / resume)
+pub const WM_POWER_EVENT: u32 = WM_USER + 10;
+
+/// Work area or display layout changed (taskbar moved / resized / auto-hide, resolution)
+pub const WM_WORK_AREA_EVENT: u32 = WM_USER + 14;
+
+/// Edge trigger polling (cursor position)
+pub const TIMER_EDGE: usize = 1;
+
+/// Housekeeping polling (chord expiry, launch, plugins, memory trim, closed window)
+pub const TIMER_POLL: usize = 2;
+
+/// Slow housekeeping while only the tracked window needs it (closed window, memory trim)
+pub const TIMER_WATCH: usize = 3;
+
+const EDGE_INTERVAL_MS: u32 = 16;
+/// Edge polling while battery saver is on
+const EDGE_SAVER_INTERVAL_MS: u32 = 50;
+const POLL_INTERVAL_MS: u32 = 50;
+const WATCH_INTERVAL_MS: u32 = 1000;
+
+/// Housekeeping interval (also when it runs from the edge tick)
+pub const POLL_INTERVAL: Duration = Duration::from_millis(POLL_INTERVAL_MS as u64);
+
+/// Wakeup count log interval
+const WAKEUP_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+const CLASS_NAME: PCWSTR = w!("QuakeModokiEvents");
+
+/// Hidden top-level window of the event loop (top-level so it receives
+/// broadcasts like WM_DISPLAYCHANGE)
+static WINDOW: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(null_mut());
+
+/// Armed timers (bit per timer id)
+static ARMED: AtomicUsize = AtomicUsize::new(0);
+
+/// Suspend / resume registration (0 = none)
+static POWER_NOTIFY: AtomicIsize = AtomicIsize::new(0);
+
+/// Hotkey and menu events forwarded to the window
+pub struct Receivers {
+    pub hotkeys: Receiver<GlobalHotKeyEvent>,
+    pub menu: Receiver<MenuEvent>,
+}
+
+/// Create the window and route hotkey / menu events to it (timers start disarmed)
+/// Call on the main thread before hooks and the tray are installed
+pub fn start() -> windows::core::Result<Receivers> {
+    let hwnd = unsafe {
+        let instance = GetModuleHandleW(None)?;
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(wnd_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        RegisterClassW(&class);
+
+        CreateWindowExW(
+            WS_EX_TOOLWINDOW,
+            CLASS_NAME,
+            CLASS_NAME,
+            WS_POPUP,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )?
+    };
+    WINDOW.store(hwnd.0, Ordering::SeqCst);
+
+    // Handlers run on the libraries' threads: queue, then wake the loop
+    let (hotkey_tx, hotkeys) = mpsc::channel();
+    GlobalHotKeyEvent::set_event_handler(Some(move |event| {
+        if hotkey_tx.send(event).is_ok() {
+            post(WM_HOTKEY_EVENT, 0);
+        }
+    }));
+    let (menu_tx, menu) = mpsc::channel();
+    MenuEvent::set_event_handler(Some(move |event| {
+        if menu_tx.send(event).is_ok() {
+            post(WM_MENU_EVENT, 0);
+        }
+    }));
+
+    // Session / power notifications are optional (logged, loop still runs)
+    unsafe {
+        if let Err(e) = WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) {
+            tracing::warn!("Session notification register failed: {e}");
+        }
+        match RegisterSuspendResumeNotification(HANDLE(hwnd.0), DEVICE_NOTIFY_WINDOW_HANDLE) {
+            Ok(handle) => POWER_NOTIFY.store(handle.0, Ordering::SeqCst),
+            Err(e) => tracing::warn!("Power notification register failed: {e}"),
+        }
+    }
+
+    Ok(Receivers { hotkeys, menu })
+}
+
+/// Stop timers and notifications, destroy the window
+pub fn stop() {
+    let hwnd = WINDOW.swap(null_mut(), Ordering::SeqCst);
+    if hwnd.is_null() {
+        return;
+    }
+    let hwnd = HWND(hwnd);
+    ARMED.store(0, Ordering::SeqCst);
+    input::watch(hwnd, false);
+    unsafe {
+        let _ = KillTimer(Some(hwnd), TIMER_EDGE);
+        let _ = KillTimer(Some(hwnd), TIMER_POLL);
+        let _ = KillTimer(Some(hwnd), TIMER_WATCH);
+        let _ = WTSUnRegisterSessionNotification(hwnd);
+        let power = POWER_NOTIFY.swap(0, Ordering::SeqCst);
+        if power != 0 {
+            let _ = UnregisterSuspendResumeNotification(HPOWERNOTIFY(power));
+        }
+        let _ = DestroyWindow(hwnd);
+    }
+}
+
+/// Receive touchpad / touch screen raw input while `on` (no-op if already so)
+pub fn watch_input(on: bool) {
+    let hwnd = WINDOW.load(Ordering::SeqCst);
+    if !hwnd.is_null() {
+        input::watch(HWND(hwnd), on);
+    }
+}
+
+/// Post `msg` to the event loop (any thread); false if the window is gone
+pub fn post(msg: u32, wparam: usize) -> bool {
+    let hwnd = WINDOW.load(Ordering::SeqCst);
+    if hwnd.is_null() {
+        return false;
+    }
+    unsafe { PostMessageW(Some(HWND(hwnd)), msg, WPARAM(wparam), LPARAM(0)) }.is_ok()
+}
+
+/// Start or stop timer `id` (no-op if already in that state)
+pub fn arm(id: usize, armed: bool) {
+    let hwnd = WINDOW.load(Ordering::SeqCst);
+    if hwnd.is_null() {
+        return;
+    }
+    let bit = 1 << id;
+    let was_armed = ARMED.load(Ordering::SeqCst) & bit != 0;
+    if armed == was_armed {
+        return;
+    }
+    let hwnd = HWND(hwnd);
+    unsafe {
+        if armed {
+            SetTimer(
+                Some(hwnd),
+                id,
+                interval_ms(id, sysprefs::is_battery_saver()),
+                None,
+            );
+            ARMED.fetch_or(bit, Ordering::SeqCst);
+        } else {
+            let _ = KillTimer(Some(hwnd), id);
+            ARMED.fetch_and(!bit, Ordering::SeqCst);
+        }
+    }
+    tracing::trace!(id, armed, "Timer");
+}
+
+/// Re-arm running timers with the intervals for the current power state
+pub fn refresh_intervals() {
+    let hwnd = WINDOW.load(Ordering::SeqCst);
+    if hwnd.is_null() {
+        return;
+    }
+    let saver = sysprefs::is_battery_saver();
+    let armed = ARMED.load(Ordering::SeqCst);
+    for id in [TIMER_EDGE, TIMER_POLL, TIMER_WATCH] {
+        if armed & (1 << id) != 0 {
+            // Same id: replaces the running timer
+            unsafe { SetTimer(Some(HWND(hwnd)), id, interval_ms(id, saver), None) };
+        }
+    }
+    tracing::debug!(saver, "Timer intervals refreshed");
+}
+
+fn interval_ms(id: usize, saver: bool) -> u32 {
+    match id {
+        TIMER_EDGE if saver => EDGE_SAVER_INTERVAL_MS,
+        TIMER_EDGE => EDGE_INTERVAL_MS,
+        TIMER_WATCH => WATCH_INTERVAL_MS,
+        _ => POLL_INTERVAL_MS,
+    }
+}
+
+/// Wake the loop without an event (e.g. to notice a shutdown flag)
+pub fn wake() {
+    post(WM_NULL, 0);
+}
+
+/// Check if `msg` is our timer `id` (other windows' timers share the queue)
+pub fn is_timer(msg: &MSG, id: usize) -> bool {
+    msg.message == WM_TIMER
+        && msg.wParam.0 == id
+        && msg.hwnd.0 == WINDOW.load(Ordering::SeqCst)
+        && !msg.hwnd.is_invalid()
+}
+
+/// Event loop wakeups by source since `since`
+#[derive(Debug)]
+pub struct Wakeups {
+    since: Instant,
+    edge: u32,
+    poll: u32,
+    watch: u32,
+    /// Hotkeys, tray, hooks and other threads' windows
+    other: u32,
+}
+
+impl Default for Wakeups {
+    fn default() -> Self {
+        Self {
+            since: Instant::now(),
+            edge: 0,
+            poll: 0,
+            watch: 0,
+            other: 0,
+        }
+    }
+}
+
+impl Wakeups {
+    /// Count `msg`; logs and starts over on the first wakeup after a minute
+    /// (an idle loop logs nothing until woken: `secs` covers the whole span)
+    pub fn record(&mut self, msg: &MSG) {
+        match msg {
+            _ if is_timer(msg, TIMER_EDGE) => self.edge += 1,
+            _ if is_timer(msg, TIMER_POLL) => self.poll += 1,
+            _ if is_timer(msg, TIMER_WATCH) => self.watch += 1,
+            _ => self.other += 1,
+        }
+        if self.since.elapsed() >= WAKEUP_REPORT_INTERVAL {
+            tracing::debug!(
+                secs = self.since.elapsed().as_secs(),
+                edge = self.edge,
+                poll = self.poll,
+                watch = self.watch,
+                other = self.other,
+                "Event loop wakeups"
+            );
+            *self = Self::default();
+        }
+    }
+}
+
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_WTSSESSION_CHANGE => {
+            post(WM_SESSION_EVENT, wparam.0);
+            LRESULT(0)
+        }
+        WM_POWERBROADCAST => {
+            post(WM_POWER_EVENT, wparam.0);
+            LRESULT(1)
+        }
+        WM_SETTINGCHANGE if wparam.0 == SPI_SETWORKAREA.0 as usize => {
+            post(WM_WORK_AREA_EVENT, 0);
+            LRESULT(0)
+        }
+        WM_INPUT => {
+            input::record(HRAWINPUT(lparam.0 as *mut _));
+            unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+        }
+        WM_DISPLAYCHANGE => {
+            post(WM_WORK_AREA_EVENT, 0);
+            unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+        }
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_without_window() {
+        // Not started in tests: posting is a no-op
+        assert!(!post(WM_HOTKEY_EVENT, 0));
+    }
+
+    #[test]
+    fn test_arm_without_window() {
+        arm(TIMER_EDGE, true);
+        assert_eq!(ARMED.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_interval_per_timer() {
+        assert_eq!(interval_ms(TIMER_EDGE, false), EDGE_INTERVAL_MS);
+        assert_eq!(interval_ms(TIMER_POLL, false), POLL_INTERVAL_MS);
+        assert_eq!(interval_ms(TIMER_WATCH, false), WATCH_INTERVAL_MS);
+        // Battery saver slows edge polling only
+        assert_eq!(interval_ms(TIMER_EDGE, true), EDGE_SAVER_INTERVAL_MS);
+        assert_eq!(interval_ms(TIMER_POLL, true), POLL_INTERVAL_MS);
+    }
+
+    #[test]
+    fn test_is_timer_rejects_foreign() {
+        let msg = MSG {
+            message: WM_TIMER,
+            wParam: WPARAM(TIMER_EDGE),
+            ..Default::default()
+        };
+        assert!(!is_timer(&msg, TIMER_EDGE));
+    }
+
+    #[test]
+    fn test_wakeups_count_foreign_timer_as_other() {
+        let mut wakeups = Wakeups::default();
+        let msg = MSG {
+            message: WM_TIMER,
+            wParam: WPARAM(TIMER_POLL),
+            ..Default::default()
+        };
+        wakeups.record(&msg);
+        assert_eq!((wakeups.poll, wakeups.other), (0, 1));
+    }
+}
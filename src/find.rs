@@ -0,0 +1,219 @@
+//! Find and track: pick a running window by title pattern (optional class)
+//!
+//! A title pattern is a case-insensitive substring, or a regex when wrapped in
+//! slashes (`/^nvim\b/`); an empty one matches any title. A given class must
+//! match exactly (case-insensitive). Candidates are visible, unowned, titled
+//! top-level windows that may be tracked: an exact title wins, otherwise the
+//! topmost match in Z-order. Used by `--find` and by rules with `Track` set.
+//! `LastWindow` finds the window untracked last again: the same handle while
+//! it lives, else the same app's window with the same title, else any of its
+//! windows.
+
+use regex::{Regex, RegexBuilder};
+use std::fmt;
+use windows::Win32::Foundation::HWND;
+
+use crate::blocklist;
+use crate::error::WindowError;
+use crate::rules::WindowInfo;
+use crate::tracking;
+
+#[derive(Debug, Clone)]
+enum TitlePattern {
+    /// Lowercase substring
+    Substring(String),
+    Regex(Regex),
+}
+
+/// Title pattern + optional window class
+#[derive(Debug, Clone)]
+pub struct Query {
+    /// Pattern as given (for messages)
+    spec: String,
+    title: TitlePattern,
+    class: Option<String>,
+}
+
+impl Query {
+    /// Parse a title pattern (`/regex/` or substring) and an optional class
+    pub fn parse(title: &str, class: Option<&str>) -> Result<Self, WindowError> {
+        let pattern = match title.strip_prefix('/').and_then(|t| t.strip_suffix('/')) {
+            Some(re) => TitlePattern::Regex(RegexBuilder::new(re).case_insensitive(true).build()?),
+            None => TitlePattern::Substring(title.to_lowercase()),
+        };
+        Ok(Self {
+            spec: title.to_string(),
+            title: pattern,
+            class: class
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(str::to_string),
+        })
+    }
+
+    /// Check if `window` matches title and class
+    pub fn matches(&self, window: &WindowInfo) -> bool {
+        self.class
+            .as_ref()
+            .is_none_or(|c| c.eq_ignore_ascii_case(window.class))
+            && match &self.title {
+                TitlePattern::Substring(s) => window.title.to_lowercase().contains(s.as_str()),
+                TitlePattern::Regex(re) => re.is_match(window.title),
+            }
+    }
+
+    /// Match covering the whole title
+    fn matches_exactly(&self, window: &WindowInfo) -> bool {
+        self.matches(window)
+            && match &self.title {
+                TitlePattern::Substring(s) => window.title.to_lowercase() == *s,
+                TitlePattern::Regex(re) => re
+                    .find(window.title)
+                    .is_some_and(|m| m.len() == window.title.len()),
+            }
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\"", self.spec)?;
+        if let Some(class) = &self.class {
+            write!(f, " (class {class})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Window untracked last (for "Re-track Last Window")
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastWindow {
+    pub hwnd: isize,
+    /// Full executable path
+    pub exe: Option<String>,
+    pub title: String,
+}
+
+impl LastWindow {
+    /// Same app (same exe; same title when the exe is unknown)
+    fn is_same_app(&self, window: &WindowInfo) -> bool {
+        match (&self.exe, window.exe) {
+            (Some(exe), Some(path)) => exe.eq_ignore_ascii_case(path),
+            (Some(_), None) => false,
+            (None, _) => !self.title.is_empty() && window.title == self.title,
+        }
+    }
+
+    /// Same app and same title
+    fn is_same_window(&self, window: &WindowInfo) -> bool {
+        self.is_same_app(window) && window.title == self.title
+    }
+
+    /// The window again (see module docs), None if the app has none left
+    pub fn resolve(&self) -> Option<HWND> {
+        let hwnd = HWND(self.hwnd as *mut _);
+        // A reused handle belongs to another app
+        if crate::win32::api().is_window(hwnd)
+            && self.exe.as_deref() == tracking::get_process_path(hwnd).as_deref()
+            && blocklist::check(hwnd).is_none()
+        {
+            return Some(hwnd);
+        }
+        find_window(&|window| self.is_same_window(window))
+            .or_else(|| find_window(&|window| self.is_same_app(window)))
+    }
+}
+
+/// Best trackable window for `query`
+pub fn find(query: &Query) -> Result<HWND, WindowError> {
+    find_window(&|window| query.matches_exactly(window))
+        .or_else(|| find_window(&|window| query.matches(window)))
+        .ok_or_else(|| WindowError::NotFound(query.to_string()))
+}
+
+/// Topmost trackable window accepted by `matches`
+pub fn find_window(matches: &dyn Fn(&WindowInfo) -> bool) -> Option<HWND> {
+    tracking::find_app_window(&|hwnd| {
+        let (class, title) = (
+            tracking::get_window_class(hwnd),
+            tracking::get_window_title(hwnd),
+        );
+        let exe = tracking::get_process_path(hwnd);
+        let info = WindowInfo {
+            exe: exe.as_deref(),
+            class: &class,
+            title: &title,
+        };
+        matches(&info) && blocklist::check(hwnd).is_none()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window<'a>(class: &'a str, title: &'a str) -> WindowInfo<'a> {
+        WindowInfo {
+            exe: None,
+            class,
+            title,
+        }
+    }
+
+    #[test]
+    fn test_last_window_matching() {
+        let last = LastWindow {
+            hwnd: 0x1000,
+            exe: Some(r"C:\Windows\System32\cmd.exe".to_string()),
+            title: "build".to_string(),
+        };
+        let info = |exe, title| WindowInfo {
+            exe,
+            class: "ConsoleWindowClass",
+            title,
+        };
+        let cmd = Some(r"c:\windows\system32\CMD.EXE");
+        assert!(last.is_same_window(&info(cmd, "build")));
+        assert!(!last.is_same_window(&info(cmd, "other")));
+        assert!(last.is_same_app(&info(cmd, "other")));
+        assert!(!last.is_same_app(&info(Some(r"C:\x\pwsh.exe"), "build")));
+        assert!(!last.is_same_app(&info(None, "build")));
+
+        // Exe unknown: the title alone identifies it
+        let last = LastWindow { exe: None, ..last };
+        assert!(last.is_same_app(&info(None, "build")));
+        assert!(!last.is_same_app(&info(None, "")));
+    }
+
+    #[test]
+    fn test_substring_case_insensitive() {
+        let query = Query::parse("terminal", None).expect("query");
+        assert!(query.matches(&window("C", "Windows Terminal")));
+        assert!(!query.matches(&window("C", "Notepad")));
+        assert!(!query.matches_exactly(&window("C", "Windows Terminal")));
+        assert!(query.matches_exactly(&window("C", "Terminal")));
+    }
+
+    #[test]
+    fn test_regex_and_class() {
+        let query =
+            Query::parse("/^nvim .*/", Some("CASCADIA_HOSTING_WINDOW_CLASS")).expect("query");
+        assert!(query.matches(&window("cascadia_hosting_window_class", "NVIM ~/src")));
+        assert!(query.matches_exactly(&window("CASCADIA_HOSTING_WINDOW_CLASS", "nvim x")));
+        assert!(!query.matches(&window("Notepad", "nvim x")));
+        assert!(!query.matches(&window("CASCADIA_HOSTING_WINDOW_CLASS", "vim x")));
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        assert!(matches!(
+            Query::parse("/(/", None),
+            Err(WindowError::Pattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_display() {
+        let query = Query::parse("log", Some("Notepad")).expect("query");
+        assert_eq!(query.to_string(), "\"log\" (class Notepad)");
+    }
+}
@@ -0,0 +1,201 @@
+//! Integration test fixture: a real top-level window driven end-to-end
+//!
+//! `TestWindow` creates a plain popup window at known bounds on the primary
+//! monitor and destroys it on drop. The tests below track it and run the
+//! toggle / focus-loss / restore paths with real Win32 calls, then assert
+//! where the window ended up. Settings start from defaults (test builds use a
+//! scratch key, see `settings`): no rules, hooks or commands of the user apply.
+
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, IsWindowVisible, MSG,
+    PM_REMOVE, PeekMessageW, RegisterClassW, SW_SHOW, ShowWindow, TranslateMessage,
+    WINDOW_EX_STYLE, WNDCLASSW, WS_POPUP,
+};
+use windows::core::{PCWSTR, w};
+
+use crate::tracking::{self, WindowBounds};
+
+const CLASS_NAME: PCWSTR = w!("QuakeModokiTestWindow");
+
+/// Shown popup window owned by the test thread
+pub struct TestWindow {
+    hwnd: HWND,
+}
+
+impl TestWindow {
+    /// Create and show a window at `bounds` (panics if the desktop refuses)
+    pub fn new(bounds: WindowBounds) -> Self {
+        let hwnd = unsafe {
+            let instance = GetModuleHandleW(None).expect("GetModuleHandleW failed");
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(wnd_proc),
+                hInstance: instance.into(),
+                lpszClassName: CLASS_NAME,
+                ..Default::default()
+            };
+            RegisterClassW(&class);
+
+            let hwnd = CreateWindowExW(
+                WINDOW_EX_STYLE(0),
+                CLASS_NAME,
+                CLASS_NAME,
+                WS_POPUP,
+                bounds.x,
+                bounds.y,
+                bounds.width,
+                bounds.height,
+                None,
+                None,
+                Some(instance.into()),
+                None,
+            )
+            .expect("CreateWindowExW failed");
+            let _ = ShowWindow(hwnd, SW_SHOW);
+            hwnd
+        };
+        pump_messages();
+        Self { hwnd }
+    }
+
+    pub fn hwnd(&self) -> HWND {
+        self.hwnd
+    }
+
+    pub fn bounds(&self) -> Option<WindowBounds> {
+        tracking::window_bounds(self.hwnd)
+    }
+
+    pub fn is_visible(&self) -> bool {
+        unsafe { IsWindowVisible(self.hwnd) }.as_bool()
+    }
+}
+
+impl Drop for TestWindow {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = DestroyWindow(self.hwnd);
+        }
+        pump_messages();
+    }
+}
+
+/// Primary monitor work area
+pub fn primary_work_area() -> RECT {
+    tracking::get_work_area(HWND::default()).expect("GetMonitorInfo failed")
+}
+
+/// Bounds in the left part of the primary work area (slides to the left)
+pub fn left_bounds() -> WindowBounds {
+    let work_area = primary_work_area();
+    WindowBounds {
+        x: work_area.left + 40,
+        y: work_area.top + 40,
+        width: 480,
+        height: 320,
+    }
+}
+
+/// Dispatch pending messages of the test thread
+pub fn pump_messages() {
+    let mut msg = MSG::default();
+    unsafe {
+        while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+}
+
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+mod tests {
+    use super::*;
+    use crate::controller::WindowController;
+    use serial_test::serial;
+
+    fn tracked(window: &TestWindow) -> WindowController {
+        crate::settings::clear();
+        let mut controller = WindowController::default();
+        controller.track(window.hwnd()).expect("track failed");
+        controller
+    }
+
+    #[test]
+    #[serial]
+    fn test_toggle_round_trip_restores_placement() {
+        let bounds = left_bounds();
+        let window = TestWindow::new(bounds);
+        let mut controller = tracked(&window);
+
+        crate::toggle_window(&mut controller);
+        assert!(!controller.is_visible());
+        assert!(!window.is_visible());
+        let hidden = window.bounds().expect("hidden bounds");
+        assert!(hidden.x + hidden.width <= primary_work_area().left);
+
+        crate::toggle_window(&mut controller);
+        assert!(controller.is_visible());
+        assert!(window.is_visible());
+        assert_eq!(window.bounds(), Some(bounds));
+
+        controller.release().expect("release failed");
+    }
+
+    #[test]
+    #[serial]
+    fn test_focus_lost_hides_window() {
+        let window = TestWindow::new(left_bounds());
+        let mut controller = tracked(&window);
+
+        crate::handle_focus_lost(&mut controller, HWND::default());
+        assert!(!controller.is_visible());
+        assert!(!window.is_visible());
+
+        // Already hidden: a second focus change is ignored
+        let hidden = window.bounds();
+        crate::handle_focus_lost(&mut controller, HWND::default());
+        assert_eq!(window.bounds(), hidden);
+
+        controller.release().expect("release failed");
+    }
+
+    #[test]
+    #[serial]
+    fn test_restore_original_after_hide() {
+        let bounds = left_bounds();
+        let window = TestWindow::new(bounds);
+        tracking::save_original(window.hwnd());
+        let mut controller = tracked(&window);
+
+        crate::toggle_window(&mut controller);
+        assert!(!window.is_visible());
+
+        assert!(tracking::restore_original().is_some());
+        pump_messages();
+        assert!(window.is_visible());
+        assert_eq!(window.bounds(), Some(bounds));
+
+        controller.release().expect("release failed");
+    }
+
+    #[test]
+    #[serial]
+    fn test_destroyed_window_reported_closed() {
+        let window = TestWindow::new(left_bounds());
+        let mut controller = tracked(&window);
+        assert!(controller.is_valid());
+
+        drop(window);
+        assert!(controller.is_closed());
+        controller.release().expect("release failed");
+    }
+}
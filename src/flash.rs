@@ -0,0 +1,166 @@
+//! Flash suppression: stop taskbar flashing from the hidden tracked window
+//!
+//! A hidden shell-hook window receives HSHELL_FLASH notifications; flashes from
+//! the tracked window are forwarded to the main loop as WM_TRACKED_FLASH.
+
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, Ordering};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DeregisterShellHookWindow, DestroyWindow, RegisterClassW,
+    RegisterShellHookWindow, RegisterWindowMessageW, WINDOW_EX_STYLE, WINDOW_STYLE, WM_USER,
+    WNDCLASSW,
+};
+use windows::core::{PCWSTR, w};
+
+use crate::error::FlashError;
+use crate::settings::{self, SettingsError};
+
+/// Custom message for tracked window flash notification (WPARAM = HWND)
+pub const WM_TRACKED_FLASH: u32 = WM_USER + 2;
+
+const SUPPRESS_FLASH: &str = "SuppressFlash";
+const CLASS_NAME: PCWSTR = w!("QuakeModokiShellHook");
+
+// Win32 constants (HSHELL_FLASH = HSHELL_REDRAW | HSHELL_HIGHBIT)
+const HSHELL_FLASH: usize = 0x8006;
+
+/// Hidden window registered for shell hook messages
+static HOOK_WINDOW: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(null_mut());
+
+/// Registered "SHELLHOOK" message id
+static SHELLHOOK_MSG: AtomicU32 = AtomicU32::new(0);
+
+/// Window whose flashes are forwarded (tracked window; read by the hook)
+static WATCHED_HWND: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(null_mut());
+
+/// Toast already shown for the current hidden period
+static NOTIFIED: AtomicBool = AtomicBool::new(false);
+
+/// Check if flash suppression enabled in registry (default: off)
+pub fn is_enabled() -> bool {
+    settings::get_bool(SUPPRESS_FLASH, false)
+}
+
+/// Check if flash suppression is fixed by policy
+pub fn is_locked() -> bool {
+    settings::is_locked(SUPPRESS_FLASH)
+}
+
+/// Toggle flash suppression, returns new state
+pub fn toggle() -> Result<bool, SettingsError> {
+    settings::toggle_bool(SUPPRESS_FLASH, false)
+}
+
+/// Create shell hook window and register for HSHELL_* notifications
+pub fn install_hook() -> Result<(), FlashError> {
+    unsafe {
+        let instance = GetModuleHandleW(None).map_err(|_| FlashError::WindowCreate)?;
+
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(wnd_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        RegisterClassW(&class);
+
+        // Top-level but never shown (message-only windows miss shell hooks)
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            CLASS_NAME,
+            CLASS_NAME,
+            WINDOW_STYLE(0),
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )
+        .map_err(|_| FlashError::WindowCreate)?;
+
+        SHELLHOOK_MSG.store(RegisterWindowMessageW(w!("SHELLHOOK")), Ordering::SeqCst);
+        if !RegisterShellHookWindow(hwnd).as_bool() {
+            let _ = DestroyWindow(hwnd);
+            return Err(FlashError::HookRegister);
+        }
+        HOOK_WINDOW.store(hwnd.0, Ordering::SeqCst);
+    }
+
+    Ok(())
+}
+
+/// Deregister and destroy shell hook window
+pub fn uninstall_hook() {
+    let handle = HOOK_WINDOW.swap(null_mut(), Ordering::SeqCst);
+    if !handle.is_null() {
+        unsafe {
+            let _ = DeregisterShellHookWindow(HWND(handle));
+            let _ = DestroyWindow(HWND(handle));
+        }
+    }
+}
+
+/// Check if shell hook window currently registered
+pub fn is_hook_installed() -> bool {
+    !HOOK_WINDOW.load(Ordering::SeqCst).is_null()
+}
+
+/// Stop flashing taskbar button / caption of hwnd
+pub fn stop(hwnd: HWND) {
+    crate::win32::api().stop_flash(hwnd);
+}
+
+/// Set the window whose flashes are reported (null = none)
+pub fn set_watched(hwnd: HWND) {
+    WATCHED_HWND.store(hwnd.0, Ordering::SeqCst);
+}
+
+/// Returns true once per hidden period (debounce repeated flashes)
+pub fn should_notify() -> bool {
+    !NOTIFIED.swap(true, Ordering::SeqCst)
+}
+
+/// Re-arm notification (window shown again)
+pub fn reset_notified() {
+    NOTIFIED.store(false, Ordering::SeqCst);
+}
+
+/// Shell hook window procedure: forward tracked window flashes to main loop
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let shellhook = SHELLHOOK_MSG.load(Ordering::SeqCst);
+    if shellhook != 0 && msg == shellhook {
+        let watched = WATCHED_HWND.load(Ordering::SeqCst);
+        if wparam.0 == HSHELL_FLASH && !watched.is_null() && lparam.0 as *mut _ == watched {
+            crate::events::post(WM_TRACKED_FLASH, lparam.0 as usize);
+        }
+        return LRESULT(0);
+    }
+
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_notify_once_until_reset() {
+        reset_notified();
+        assert!(should_notify());
+        assert!(!should_notify());
+
+        reset_notified();
+        assert!(should_notify());
+        reset_notified();
+    }
+}
@@ -1,12 +1,27 @@
 //! Focus tracking module: detect foreground window changes via SetWinEventHook
+//!
+//! Hiding on focus loss can be turned off (`HideOnFocusLoss`): no hook is
+//! installed then. How the window hides is configured separately from the
+//! toggle animation (`FocusLossAction`), and so is its delay
+//! (`FocusLossDelayMs`, default 0: at once) from the edge trigger's hide delay.
+//! `FocusLossGraceMs` (default 0) ignores brief foreground flips: the window
+//! hides only once the window that took focus has kept it that long.
+//! Focus moving to some apps never hides it: a built-in list (snipping tools,
+//! IME candidates, the emoji / clipboard panel) plus string values under
+//! `FocusLossIgnore` (name = exe file name or window class; data "0" turns a
+//! built-in entry off).
 
 use std::ptr::null_mut;
 use std::sync::atomic::{AtomicPtr, Ordering};
-use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
-use windows::Win32::UI::Accessibility::{HWINEVENTHOOK, SetWinEventHook, UnhookWinEvent};
-use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_USER};
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Accessibility::HWINEVENTHOOK;
+use windows::Win32::UI::WindowsAndMessaging::WM_USER;
 
+use crate::context;
 use crate::error::FocusError;
+use crate::settings::{self, SettingsError};
+use crate::tracking;
 
 /// Custom message for focus change notification
 pub const WM_FOCUS_CHANGED: u32 = WM_USER + 1;
@@ -16,71 +31,184 @@ const EVENT_SYSTEM_FOREGROUND: u32 = 0x0003;
 const WINEVENT_OUTOFCONTEXT: u32 = 0x0000;
 const WINEVENT_SKIPOWNPROCESS: u32 = 0x0002;
 
-/// Global hook handle for cleanup
-static HOOK_HANDLE: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(null_mut());
+const HIDE_ON_FOCUS_LOSS: &str = "HideOnFocusLoss";
+const FOCUS_LOSS_ACTION: &str = "FocusLossAction";
+const FOCUS_LOSS_DELAY_MS: &str = "FocusLossDelayMs";
+const FOCUS_LOSS_GRACE_MS: &str = "FocusLossGraceMs";
+const FOCUS_LOSS_IGNORE: &str = "FocusLossIgnore";
 
-/// Target window being monitored
-static TARGET_HWND: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(null_mut());
+/// Built-in apps whose focus does not hide the window (lowercase class or exe file name)
+const BUILT_IN_IGNORED: [&str; 4] = [
+    "snippingtool.exe",
+    "screenclippinghost.exe",
+    // Emoji panel, clipboard history (Win+V), touch keyboard
+    "textinputhost.exe",
+    "microsoft.ime.uimanager.candidatewindow.host",
+];
 
-/// Previous foreground window (for focus restoration)
-static PREV_HWND: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(null_mut());
+/// Upper bound of the focus-loss delay and grace period
+const MAX_DELAY_MS: u32 = 5000;
 
-/// Install focus hook
-/// target_hwnd: window being monitored for focus loss
-pub fn install_hook(target_hwnd: HWND) -> Result<(), FocusError> {
-    TARGET_HWND.store(target_hwnd.0 as *mut _, Ordering::SeqCst);
+/// How the shown tracked window hides on focus loss
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FocusLossAction {
+    /// Slide out like the toggle
+    #[default]
+    Slide,
+    /// Hide without animation
+    Instant,
+}
+
+impl FocusLossAction {
+    pub const ALL: [FocusLossAction; 2] = [FocusLossAction::Slide, FocusLossAction::Instant];
 
-    unsafe {
-        let hook = SetWinEventHook(
-            EVENT_SYSTEM_FOREGROUND,
-            EVENT_SYSTEM_FOREGROUND,
-            None,
-            Some(win_event_proc),
-            0,
-            0,
-            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
-        );
-
-        if hook.is_invalid() {
-            return Err(FocusError::HookInstall);
+    /// Decode registry value (unknown → Slide)
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => FocusLossAction::Instant,
+            _ => FocusLossAction::Slide,
         }
-        HOOK_HANDLE.store(hook.0, Ordering::SeqCst);
     }
 
-    Ok(())
+    /// Encode as registry value
+    pub fn as_u32(self) -> u32 {
+        match self {
+            FocusLossAction::Slide => 0,
+            FocusLossAction::Instant => 1,
+        }
+    }
+}
+
+/// Check if losing focus hides the window (default: on)
+pub fn is_enabled() -> bool {
+    settings::get_bool(HIDE_ON_FOCUS_LOSS, true)
 }
 
-/// Uninstall focus hook
-pub fn uninstall_hook() -> Result<(), FocusError> {
-    let handle = HOOK_HANDLE.swap(null_mut(), Ordering::SeqCst);
-    if !handle.is_null() {
-        unsafe {
-            if !UnhookWinEvent(HWINEVENTHOOK(handle)).as_bool() {
-                return Err(FocusError::HookUninstall);
+/// Toggle hiding on focus loss, returns the new state
+pub fn toggle_enabled() -> Result<bool, SettingsError> {
+    settings::toggle_bool(HIDE_ON_FOCUS_LOSS, true)
+}
+
+/// Get configured focus-loss action from registry (default: Slide)
+pub fn get_action() -> FocusLossAction {
+    FocusLossAction::from_u32(settings::get_u32(FOCUS_LOSS_ACTION, 0))
+}
+
+/// Persist focus-loss action
+pub fn set_action(action: FocusLossAction) -> Result<(), SettingsError> {
+    settings::set_u32(FOCUS_LOSS_ACTION, action.as_u32())
+}
+
+/// Wait between losing focus and hiding (default: none)
+pub fn delay() -> Duration {
+    Duration::from_millis(settings::get_u32(FOCUS_LOSS_DELAY_MS, 0).min(MAX_DELAY_MS) as u64)
+}
+
+/// Time the window that took focus must keep it before hiding (default: none)
+pub fn grace() -> Duration {
+    Duration::from_millis(settings::get_u32(FOCUS_LOSS_GRACE_MS, 0).min(MAX_DELAY_MS) as u64)
+}
+
+/// Check a window's class / exe against the user entries, then the built-in list
+fn is_ignored_app(class: &str, exe: Option<&str>, user: &[(String, String)]) -> bool {
+    [Some(class.to_ascii_lowercase()), exe.map(context::exe_name)]
+        .into_iter()
+        .flatten()
+        .filter(|key| !key.is_empty())
+        .any(|key| {
+            match user
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&key))
+            {
+                Some((_, data)) => data.trim() != "0",
+                None => BUILT_IN_IGNORED.contains(&key.as_str()),
             }
-        }
+        })
+}
+
+/// Check if focus moving to `hwnd` should leave the window shown
+pub fn is_ignored(hwnd: HWND) -> bool {
+    if hwnd == HWND::default() {
+        return false;
     }
-    Ok(())
+    let class = tracking::get_window_class(hwnd);
+    let exe = tracking::get_process_path(hwnd);
+    is_ignored_app(
+        &class,
+        exe.as_deref(),
+        &settings::get_string_values(FOCUS_LOSS_IGNORE),
+    )
 }
 
-/// Update target window
-pub fn set_target(hwnd: HWND) {
-    TARGET_HWND.store(hwnd.0 as *mut _, Ordering::SeqCst);
+/// Hide waiting for `delay` / `grace` after focus loss
+#[derive(Debug, Clone, Copy)]
+pub struct PendingHide {
+    /// When focus first left
+    lost: Instant,
+    /// Window in front now, and since when
+    holder: HWND,
+    held: Instant,
 }
 
-/// Get current target window
-pub fn get_target() -> HWND {
-    HWND(TARGET_HWND.load(Ordering::SeqCst) as *mut _)
+impl PendingHide {
+    pub fn new(holder: HWND, now: Instant) -> Self {
+        Self {
+            lost: now,
+            holder,
+            held: now,
+        }
+    }
+
+    /// Another window took focus: its grace period starts over
+    pub fn hand_over(&mut self, holder: HWND, now: Instant) {
+        if holder != self.holder {
+            self.holder = holder;
+            self.held = now;
+        }
+    }
+
+    /// Hide now: `delay` since focus left, and `foreground` is the holder for `grace`
+    pub fn is_due(&self, foreground: HWND, now: Instant, delay: Duration, grace: Duration) -> bool {
+        foreground == self.holder
+            && now.duration_since(self.lost) >= delay
+            && now.duration_since(self.held) >= grace
+    }
 }
 
-/// Save previous foreground window for focus restoration
-pub fn save_previous(hwnd: HWND) {
-    PREV_HWND.store(hwnd.0 as *mut _, Ordering::SeqCst);
+/// Target window being monitored (read by the hook callback)
+static TARGET_HWND: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(null_mut());
+
+/// Installed focus hook (owned by the window controller)
+#[derive(Debug)]
+pub struct FocusHook(HWINEVENTHOOK);
+
+impl FocusHook {
+    /// Unhook and stop monitoring the target
+    pub fn uninstall(self) -> Result<(), FocusError> {
+        TARGET_HWND.store(null_mut(), Ordering::SeqCst);
+        if crate::win32::api().unhook_event(self.0) {
+            Ok(())
+        } else {
+            Err(FocusError::HookUninstall)
+        }
+    }
 }
 
-/// Get previous foreground window
-pub fn get_previous() -> HWND {
-    HWND(PREV_HWND.load(Ordering::SeqCst) as *mut _)
+/// Install focus hook
+/// target_hwnd: window being monitored for focus loss
+pub fn install_hook(target_hwnd: HWND) -> Result<FocusHook, FocusError> {
+    TARGET_HWND.store(target_hwnd.0 as *mut _, Ordering::SeqCst);
+
+    let hook = crate::win32::api().hook_event(
+        EVENT_SYSTEM_FOREGROUND,
+        Some(win_event_proc),
+        WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+    );
+    let Some(hook) = hook else {
+        TARGET_HWND.store(null_mut(), Ordering::SeqCst);
+        return Err(FocusError::HookInstall);
+    };
+    Ok(FocusHook(hook))
 }
 
 /// Win event callback: fired when foreground window changes
@@ -97,9 +225,63 @@ unsafe extern "system" fn win_event_proc(
 
     // Only notify if focus moved away from target window
     if target != HWND::default() && hwnd != target {
-        // Post to thread's message queue (NULL hwnd posts to thread)
-        unsafe {
-            let _ = PostMessageW(None, WM_FOCUS_CHANGED, WPARAM(hwnd.0 as usize), LPARAM(0));
+        crate::events::post(WM_FOCUS_CHANGED, hwnd.0 as usize);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_loss_action_registry_round_trip() {
+        for action in FocusLossAction::ALL {
+            assert_eq!(FocusLossAction::from_u32(action.as_u32()), action);
         }
+        assert_eq!(FocusLossAction::from_u32(99), FocusLossAction::Slide);
+    }
+
+    #[test]
+    fn test_is_ignored_app() {
+        let snip = Some(r"C:\Windows\SystemApps\ScreenClippingHost.exe");
+        assert!(is_ignored_app("ApplicationFrameWindow", snip, &[]));
+        assert!(!is_ignored_app(
+            "Notepad",
+            Some(r"C:\Windows\notepad.exe"),
+            &[]
+        ));
+
+        // User entries by exe or class; "0" turns a built-in one off
+        let entries = settings::string_values(&[
+            ("Ditto.exe", "1"),
+            ("ConsoleWindowClass", ""),
+            ("screenclippinghost.exe", "0"),
+        ]);
+        assert!(is_ignored_app(
+            "QPasteClass",
+            Some(r"C:\Ditto\Ditto.exe"),
+            &entries
+        ));
+        assert!(is_ignored_app("ConsoleWindowClass", None, &entries));
+        assert!(!is_ignored_app("ApplicationFrameWindow", snip, &entries));
+    }
+
+    #[test]
+    fn test_pending_hide_grace() {
+        let ms = Duration::from_millis;
+        let (popup, editor) = (HWND(0x10 as *mut _), HWND(0x20 as *mut _));
+        let start = Instant::now();
+        let mut pending = PendingHide::new(popup, start);
+        assert!(!pending.is_due(popup, start + ms(100), ms(0), ms(200)));
+        assert!(pending.is_due(popup, start + ms(200), ms(0), ms(200)));
+
+        // A flip to another window restarts the grace period
+        pending.hand_over(editor, start + ms(150));
+        assert!(!pending.is_due(editor, start + ms(300), ms(0), ms(200)));
+        assert!(pending.is_due(editor, start + ms(350), ms(0), ms(200)));
+        // ... but not the delay, counted from the first loss
+        assert!(pending.is_due(editor, start + ms(500), ms(500), ms(200)));
+        // Not due while another window is in front
+        assert!(!pending.is_due(popup, start + ms(500), ms(0), ms(200)));
     }
 }
@@ -0,0 +1,500 @@
+//! Hotkey registration with fallback when a binding is owned by another app
+//!
+//! A toggle binding chosen via capture is persisted as `ToggleHotkey`
+//! (global-hotkey string form, e.g. "control+alt+KeyT") and tried first.
+//! A captured track binding is persisted the same way as `TrackHotkey`.
+//! Both can also be written by hand in label form ("Ctrl+Alt+T", "F12");
+//! `parse` accepts either, and an invalid value is logged and ignored.
+
+use global_hotkey::GlobalHotKeyManager;
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use std::sync::Mutex;
+
+use crate::error::HotkeyError;
+use crate::settings;
+
+const TOGGLE_HOTKEY: &str = "ToggleHotkey";
+const TRACK_HOTKEY: &str = "TrackHotkey";
+const RETRACK_HOTKEY: &str = "RetrackHotkey";
+const TRACK_UNDER_CURSOR_HOTKEY: &str = "TrackUnderCursorHotkey";
+const PALETTE_HOTKEY: &str = "PaletteHotkey";
+
+/// Binding changed by hotkey capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Binding {
+    #[default]
+    Toggle,
+    Track,
+}
+
+/// Result of trying candidates in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registration {
+    /// First candidate that registered (None: all taken)
+    pub active: Option<HotKey>,
+    /// Candidates that failed before `active`
+    pub conflicts: Vec<HotKey>,
+}
+
+/// Active toggle hotkey (event dispatch, tray tooltip, status panel)
+static ACTIVE_TOGGLE: Mutex<Option<HotKey>> = Mutex::new(None);
+
+/// Display label, e.g. "Ctrl+Alt+Q", "Win+`", "F8"
+pub fn label(hotkey: &HotKey) -> String {
+    let mut parts: Vec<String> = [
+        (Modifiers::CONTROL, "Ctrl"),
+        (Modifiers::ALT, "Alt"),
+        (Modifiers::SHIFT, "Shift"),
+        (Modifiers::SUPER, "Win"),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| hotkey.mods.contains(*modifier))
+    .map(|(_, name)| name.to_string())
+    .collect();
+    parts.push(key_label(hotkey.key));
+    parts.join("+")
+}
+
+fn key_label(code: Code) -> String {
+    if let Some((c, _)) = PUNCTUATION.iter().find(|(_, key)| *key == code) {
+        return c.to_string();
+    }
+    let name = code.to_string();
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+/// Built-in toggle candidates, preferred first: F8, F7, Ctrl+`, Win+`
+fn default_toggle_candidates() -> [HotKey; 4] {
+    [
+        HotKey::new(None, Code::F8),
+        HotKey::new(None, Code::F7),
+        HotKey::new(Some(Modifiers::CONTROL), Code::Backquote),
+        HotKey::new(Some(Modifiers::SUPER), Code::Backquote),
+    ]
+}
+
+/// Parse a hotkey: modifiers and one key joined by `+`, case-insensitive
+/// ("Ctrl+Alt+T", "Win+`", "F12", or the persisted "control+alt+KeyT")
+pub fn parse(text: &str) -> Result<HotKey, HotkeyError> {
+    let parts: Vec<&str> = text.split('+').map(str::trim).collect();
+    let (key, modifiers) = match parts.split_last() {
+        Some((key, modifiers)) if !key.is_empty() => (*key, modifiers),
+        _ => return Err(HotkeyError::MissingKey(text.trim().to_string())),
+    };
+    let mut mods = Modifiers::empty();
+    for modifier in modifiers {
+        mods |= match modifier.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => Modifiers::CONTROL,
+            "alt" | "option" => Modifiers::ALT,
+            "shift" => Modifiers::SHIFT,
+            "win" | "super" | "meta" | "cmd" | "command" => Modifiers::SUPER,
+            _ => return Err(HotkeyError::UnknownModifier(modifier.to_string())),
+        };
+    }
+    let code = parse_code(key).ok_or_else(|| HotkeyError::UnknownKey(key.to_string()))?;
+    Ok(HotKey::new((!mods.is_empty()).then_some(mods), code))
+}
+
+/// OEM keys by their character on a US layout (` is the key under Esc)
+const PUNCTUATION: [(char, Code); 10] = [
+    ('`', Code::Backquote),
+    ('\\', Code::Backslash),
+    ('[', Code::BracketLeft),
+    (']', Code::BracketRight),
+    (';', Code::Semicolon),
+    ('\'', Code::Quote),
+    (',', Code::Comma),
+    ('.', Code::Period),
+    ('/', Code::Slash),
+    ('-', Code::Minus),
+];
+
+/// Multi-word key names, matched case-insensitively ("numpadadd", "mediaplaypause")
+const COMPOUND_KEYS: [Code; 27] = [
+    Code::PageUp,
+    Code::PageDown,
+    Code::ArrowLeft,
+    Code::ArrowUp,
+    Code::ArrowRight,
+    Code::ArrowDown,
+    Code::PrintScreen,
+    Code::CapsLock,
+    Code::NumLock,
+    Code::ScrollLock,
+    Code::BracketLeft,
+    Code::BracketRight,
+    Code::NumpadAdd,
+    Code::NumpadDecimal,
+    Code::NumpadDivide,
+    Code::NumpadEnter,
+    Code::NumpadMultiply,
+    Code::NumpadSubtract,
+    Code::AudioVolumeDown,
+    Code::AudioVolumeUp,
+    Code::AudioVolumeMute,
+    Code::MediaPlay,
+    Code::MediaPause,
+    Code::MediaPlayPause,
+    Code::MediaStop,
+    Code::MediaTrackNext,
+    Code::MediaTrackPrevious,
+];
+
+/// Short names of media keys
+const MEDIA_ALIASES: [(&str, Code); 6] = [
+    ("PlayPause", Code::MediaPlayPause),
+    ("NextTrack", Code::MediaTrackNext),
+    ("PrevTrack", Code::MediaTrackPrevious),
+    ("VolumeUp", Code::AudioVolumeUp),
+    ("VolumeDown", Code::AudioVolumeDown),
+    ("Mute", Code::AudioVolumeMute),
+];
+
+/// Key code from a label ("T", "1", "`", "\\", "f12", "Numpad5", "PlayPause")
+/// or a code name ("KeyT", "Space", "MediaTrackNext")
+fn parse_code(key: &str) -> Option<Code> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if let Some((_, code)) = PUNCTUATION.iter().find(|(p, _)| *p == c) {
+            return Some(*code);
+        }
+        let name = match c {
+            c if c.is_ascii_alphabetic() => format!("Key{}", c.to_ascii_uppercase()),
+            c if c.is_ascii_digit() => format!("Digit{c}"),
+            _ => return None,
+        };
+        return name.parse().ok();
+    }
+    let named = COMPOUND_KEYS
+        .into_iter()
+        .find(|code| code.to_string().eq_ignore_ascii_case(key))
+        .or_else(|| {
+            MEDIA_ALIASES
+                .iter()
+                .find(|(alias, _)| alias.eq_ignore_ascii_case(key))
+                .map(|(_, code)| *code)
+        });
+    named.or_else(|| key.parse().ok()).or_else(|| {
+        // Code names are case-sensitive: retry "f12" as "F12", "space" as "Space"
+        let mut chars = key.chars();
+        let first = chars.next()?.to_ascii_uppercase();
+        format!("{first}{}", chars.as_str().to_ascii_lowercase())
+            .parse()
+            .ok()
+    })
+}
+
+/// Hotkey stored under `name`, if set and valid (an invalid value is logged)
+pub fn configured(name: &str) -> Option<HotKey> {
+    let text = settings::get_string(name)?;
+    parse(&text)
+        .inspect_err(|e| tracing::warn!(setting = name, "Invalid hotkey ignored: {e}"))
+        .ok()
+}
+
+/// Persisted toggle binding, if any
+fn configured_toggle() -> Option<HotKey> {
+    configured(TOGGLE_HOTKEY)
+}
+
+/// Toggle candidates: configured binding first, then the built-in list
+pub fn toggle_candidates() -> Vec<HotKey> {
+    let mut candidates: Vec<HotKey> = configured_toggle().into_iter().collect();
+    for hotkey in default_toggle_candidates() {
+        if !candidates.contains(&hotkey) {
+            candidates.push(hotkey);
+        }
+    }
+    candidates
+}
+
+/// Track hotkey: configured binding or Ctrl+Alt+Q
+pub fn track_hotkey() -> HotKey {
+    configured(TRACK_HOTKEY).unwrap_or_else(default_track_hotkey)
+}
+
+fn default_track_hotkey() -> HotKey {
+    HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::KeyQ)
+}
+
+/// Re-track hotkey: configured binding or Ctrl+Alt+Shift+Q
+pub fn retrack_hotkey() -> HotKey {
+    configured(RETRACK_HOTKEY).unwrap_or_else(|| {
+        HotKey::new(
+            Some(Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT),
+            Code::KeyQ,
+        )
+    })
+}
+
+/// Track-under-cursor hotkey: configured binding or Ctrl+Alt+Shift+W
+pub fn track_under_cursor_hotkey() -> HotKey {
+    configured(TRACK_UNDER_CURSOR_HOTKEY).unwrap_or_else(|| {
+        HotKey::new(
+            Some(Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT),
+            Code::KeyW,
+        )
+    })
+}
+
+/// Window palette hotkey: configured binding or Ctrl+Alt+Shift+P
+pub fn palette_hotkey() -> HotKey {
+    configured(PALETTE_HOTKEY).unwrap_or_else(|| {
+        HotKey::new(
+            Some(Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT),
+            Code::KeyP,
+        )
+    })
+}
+
+/// Register the first candidate accepted by `try_register`
+fn register_first(
+    candidates: &[HotKey],
+    mut try_register: impl FnMut(HotKey) -> bool,
+) -> Registration {
+    let mut conflicts = Vec::new();
+    for hotkey in candidates {
+        if try_register(*hotkey) {
+            return Registration {
+                active: Some(*hotkey),
+                conflicts,
+            };
+        }
+        conflicts.push(*hotkey);
+    }
+    Registration {
+        active: None,
+        conflicts,
+    }
+}
+
+/// Register toggle hotkey, falling back through `toggle_candidates`
+pub fn register_toggle(manager: &GlobalHotKeyManager) -> Registration {
+    let registration = register_first(&toggle_candidates(), |hotkey| {
+        manager
+            .register(hotkey)
+            .inspect_err(
+                |e| tracing::warn!(hotkey = %label(&hotkey), "Hotkey register failed: {e}"),
+            )
+            .is_ok()
+    });
+    set_active_toggle(registration.active);
+    registration
+}
+
+/// Replace the toggle hotkey and persist it
+/// The previous binding stays registered if `new` is taken
+pub fn rebind_toggle(manager: &GlobalHotKeyManager, new: HotKey) -> Result<(), HotkeyError> {
+    let old = active_toggle();
+    if old == Some(new) {
+        return Ok(());
+    }
+    swap(manager, old, new)?;
+    set_active_toggle(Some(new));
+    settings::set_string(TOGGLE_HOTKEY, &new.into_string())?;
+    Ok(())
+}
+
+/// Replace the track hotkey and persist it
+/// The previous binding stays registered if `new` is taken
+pub fn rebind_track(manager: &GlobalHotKeyManager, new: HotKey) -> Result<(), HotkeyError> {
+    let old = track_hotkey();
+    if old == new {
+        return Ok(());
+    }
+    swap(manager, Some(old), new)?;
+    settings::set_string(TRACK_HOTKEY, &new.into_string())?;
+    Ok(())
+}
+
+/// Unregister `old` and register `new`, putting `old` back on failure
+fn swap(
+    manager: &GlobalHotKeyManager,
+    old: Option<HotKey>,
+    new: HotKey,
+) -> Result<(), HotkeyError> {
+    if let Some(old) = old {
+        let _ = manager.unregister(old);
+    }
+    if let Err(e) = manager.register(new) {
+        tracing::warn!(hotkey = %label(&new), "Hotkey register failed: {e}");
+        if let Some(old) = old {
+            let _ = manager.register(old);
+        }
+        return Err(HotkeyError::Register(label(&new)));
+    }
+    Ok(())
+}
+
+/// Toggle candidates (other than the active one) currently free to register
+pub fn free_toggle_candidates(manager: &GlobalHotKeyManager) -> Vec<HotKey> {
+    let active = active_toggle();
+    toggle_candidates()
+        .into_iter()
+        .filter(|h| Some(*h) != active)
+        .filter(|h| {
+            // Probe: register + immediately unregister
+            manager.register(*h).is_ok() && manager.unregister(*h).is_ok()
+        })
+        .collect()
+}
+
+fn set_active_toggle(hotkey: Option<HotKey>) {
+    *ACTIVE_TOGGLE.lock().unwrap_or_else(|e| e.into_inner()) = hotkey;
+}
+
+/// Registered toggle hotkey, if any
+pub fn active_toggle() -> Option<HotKey> {
+    *ACTIVE_TOGGLE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Label of the registered toggle hotkey, if any
+pub fn active_toggle_label() -> Option<String> {
+    active_toggle().map(|h| label(&h))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label() {
+        assert_eq!(label(&HotKey::new(None, Code::F8)), "F8");
+        assert_eq!(label(&default_track_hotkey()), "Ctrl+Alt+Q");
+        assert_eq!(
+            label(&HotKey::new(Some(Modifiers::SUPER), Code::Backquote)),
+            "Win+`"
+        );
+        assert_eq!(
+            label(&HotKey::new(
+                Some(Modifiers::SHIFT | Modifiers::CONTROL),
+                Code::Digit1
+            )),
+            "Ctrl+Shift+1"
+        );
+    }
+
+    #[test]
+    fn test_parse_label_form() {
+        assert_eq!(parse("Ctrl+Alt+Q").ok(), Some(default_track_hotkey()));
+        assert_eq!(parse("f12").ok(), Some(HotKey::new(None, Code::F12)));
+        assert_eq!(
+            parse(" win + ` ").ok(),
+            Some(HotKey::new(Some(Modifiers::SUPER), Code::Backquote))
+        );
+        assert_eq!(
+            parse("Ctrl+Shift+1").ok(),
+            Some(HotKey::new(
+                Some(Modifiers::CONTROL | Modifiers::SHIFT),
+                Code::Digit1
+            ))
+        );
+        assert_eq!(
+            parse("control+space").ok(),
+            Some(HotKey::new(Some(Modifiers::CONTROL), Code::Space))
+        );
+    }
+
+    #[test]
+    fn test_parse_numpad_media_oem() {
+        assert_eq!(
+            parse("Ctrl+\\").ok(),
+            Some(HotKey::new(Some(Modifiers::CONTROL), Code::Backslash))
+        );
+        assert_eq!(
+            parse("alt+numpad5").ok(),
+            Some(HotKey::new(Some(Modifiers::ALT), Code::Numpad5))
+        );
+        assert_eq!(
+            parse("Ctrl+NUMPADADD").ok(),
+            Some(HotKey::new(Some(Modifiers::CONTROL), Code::NumpadAdd))
+        );
+        assert_eq!(
+            parse("playpause").ok(),
+            Some(HotKey::new(None, Code::MediaPlayPause))
+        );
+        assert_eq!(
+            parse("MediaTrackNext").ok(),
+            Some(HotKey::new(None, Code::MediaTrackNext))
+        );
+    }
+
+    #[test]
+    fn test_label_roundtrip_extended_keys() {
+        for code in COMPOUND_KEYS
+            .into_iter()
+            .chain(PUNCTUATION.map(|(_, code)| code))
+            .chain([Code::Numpad0, Code::Numpad9])
+        {
+            let hotkey = HotKey::new(Some(Modifiers::CONTROL), code);
+            assert_eq!(parse(&label(&hotkey)).ok(), Some(hotkey), "{code}");
+        }
+        assert_eq!(label(&HotKey::new(None, Code::Backslash)), "\\");
+    }
+
+    #[test]
+    fn test_parse_label_roundtrip() {
+        for hotkey in default_toggle_candidates() {
+            assert_eq!(parse(&label(&hotkey)).ok(), Some(hotkey));
+            assert_eq!(parse(&hotkey.into_string()).ok(), Some(hotkey));
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse(""), Err(HotkeyError::MissingKey(_))));
+        assert!(matches!(parse("Ctrl+"), Err(HotkeyError::MissingKey(_))));
+        assert!(matches!(
+            parse("Hyper+T"),
+            Err(HotkeyError::UnknownModifier(m)) if m == "Hyper"
+        ));
+        assert!(matches!(
+            parse("Ctrl+Banana"),
+            Err(HotkeyError::UnknownKey(k)) if k == "Banana"
+        ));
+    }
+
+    #[test]
+    fn test_persisted_form_roundtrip() {
+        let hotkey = HotKey::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::Backquote);
+        assert_eq!(hotkey.into_string().parse::<HotKey>().ok(), Some(hotkey));
+    }
+
+    #[test]
+    fn test_register_first_prefers_first_candidate() {
+        let registration = register_first(&default_toggle_candidates(), |_| true);
+        assert_eq!(registration.active, Some(HotKey::new(None, Code::F8)));
+        assert!(registration.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_register_first_falls_back() {
+        let taken = [HotKey::new(None, Code::F8), HotKey::new(None, Code::F7)];
+        let registration = register_first(&default_toggle_candidates(), |h| !taken.contains(&h));
+        assert_eq!(
+            registration.active.map(|h| label(&h)).as_deref(),
+            Some("Ctrl+`")
+        );
+        assert_eq!(registration.conflicts, taken.to_vec());
+    }
+
+    #[test]
+    fn test_register_first_all_taken() {
+        let registration = register_first(&default_toggle_candidates(), |_| false);
+        assert_eq!(registration.active, None);
+        assert_eq!(registration.conflicts.len(), 4);
+    }
+
+    #[test]
+    fn test_candidates_distinct() {
+        let candidates = default_toggle_candidates();
+        for (i, a) in candidates.iter().enumerate() {
+            for b in &candidates[i + 1..] {
+                assert_ne!(a.id(), b.id());
+            }
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! Localization: embedded string tables (English, Japanese)
+//!
+//! Language: `Language` registry value ("en" / "ja") if set, otherwise the
+//! user's UI language. Templates use positional `{0}`, `{1}`... placeholders.
+
+use std::sync::OnceLock;
+use windows::Win32::Globalization::GetUserDefaultUILanguage;
+
+use crate::settings;
+
+const LANGUAGE: &str = "Language";
+
+/// Primary language id of Japanese (LANG_JAPANESE)
+const LANG_JAPANESE: u16 = 0x11;
+
+/// Supported UI languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Ja,
+}
+
+impl Lang {
+    /// Parse "en" / "ja" (case-insensitive, region suffix ignored)
+    pub fn from_code(code: &str) -> Option<Self> {
+        let primary = code.split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Some(Self::En),
+            "ja" => Some(Self::Ja),
+            _ => None,
+        }
+    }
+
+    /// Map Win32 LANGID (primary language in low 10 bits)
+    pub fn from_langid(langid: u16) -> Self {
+        if langid & 0x3ff == LANG_JAPANESE {
+            Self::Ja
+        } else {
+            Self::En
+        }
+    }
+}
+
+macro_rules! texts {
+    ($($key:ident => $en:literal, $ja:literal;)*) => {
+        /// Localizable UI strings
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Text {
+            $($key),*
+        }
+
+        impl Text {
+            #[cfg(test)]
+            const ALL: &[Text] = &[$(Text::$key),*];
+
+            fn get(self, lang: Lang) -> &'static str {
+                match (self, lang) {
+                    $(
+                        (Text::$key, Lang::En) => $en,
+                        (Text::$key, Lang::Ja) => $ja,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+texts! {
+    // Tray menu
+    NoWindowTracked => "No window tracked", "ウィンドウ未登録";
+    Tracking => "Tracking: {0}", "追跡中: {0}";
+    TrackingApp => "Tracking: {0} — {1}", "追跡中: {0} — {1}";
+    TrackForeground => "Track Foreground Window", "前面のウィンドウを追跡";
+    Untrack => "Untrack", "登録解除";
+    RetrackLast => "Re-track Last Window", "直前のウィンドウを再登録";
+    Peek => "Peek", "のぞき見";
+    ResetPosition => "Reset Position", "位置をリセット";
+    ApplyLayoutNow => "Apply Layout Now", "レイアウトを今すぐ適用";
+    WindowsTerminal => "Windows Terminal (Quake)", "Windows Terminal (Quake)";
+    Presets => "Presets", "プリセット";
+    WindowSlots => "Window Slots", "ウィンドウ スロット";
+    SlotHotkeys => "Slot Hotkeys", "スロットのホットキー";
+    Slot => "Slot {0}", "スロット {0}";
+    SlotToggleHotkey => "toggle hotkey", "切り替えホットキー";
+    Toggle => "Toggle", "表示切り替え";
+    StartWithWindows => "Start with Windows", "Windows 起動時に開始";
+    StartElevated => "Start Elevated (Task Scheduler)", "管理者権限で開始 (タスク スケジューラ)";
+    ExplorerContextMenu => "Explorer Context Menu", "エクスプローラーの右クリックメニュー";
+    EdgeTrigger => "Edge Trigger", "エッジトリガー";
+    ScrollAtEdge => "Scroll Wheel at Edge", "エッジでホイール操作";
+    SwipeFromEdge => "Swipe In from Edge (Touch)", "エッジからスワイプ（タッチ）";
+    Enabled => "Enabled", "有効";
+    EdgeInstant => "Instant", "即時";
+    EdgeFast => "Fast", "速い";
+    EdgeRelaxed => "Relaxed", "ゆったり";
+    CustomEllipsis => "Custom…", "カスタム…";
+    HideWhenTracked => "Hide When Tracked", "登録時に隠す";
+    AlwaysOnTop => "Always on Top", "常に手前に表示";
+    WhileHidden => "While Hidden", "非表示中";
+    OnFocusLoss => "On Focus Loss", "フォーカスを失ったとき";
+    HideOnFocusLoss => "Hide Window", "ウィンドウを隠す";
+    SlideOut => "Slide Out", "スライドして隠す";
+    Animation => "Animation", "アニメーション";
+    SlideIn => "Slide In", "スライドして表示";
+    AnimSlide => "Slide", "スライド";
+    AnimSlideFade => "Slide + Fade", "スライド＋フェード";
+    AnimZoom => "Zoom", "ズーム";
+    HideInstantly => "Hide Instantly", "すぐに隠す";
+    HideFromTaskbar => "Hide from Taskbar", "タスクバーから隠す";
+    HideFromAltTab => "Hide from Alt-Tab", "Alt-Tab から隠す";
+    SuppressFlashing => "Suppress Flashing", "点滅を抑制";
+    MuteAudio => "Mute Audio", "音声をミュート";
+    TrimMemoryAfter => "Trim Memory After {0} min", "{0} 分後にメモリを解放";
+    NormalPriority => "Normal Priority", "通常の優先度";
+    LowerPriority => "Lower Priority", "優先度を下げる";
+    SuspendProcess => "Suspend Process", "プロセスを一時停止";
+    GroupLayout => "Group Layout", "グループのレイアウト";
+    KeepPositions => "Keep Positions", "位置を保持";
+    TopHalfSideBySide => "Top Half, Side by Side", "上半分に横並び";
+    LeftColumnStack => "Left Column, Stacked", "左列に縦積み";
+    SnapTo => "Snap To", "位置のプリセット";
+    SnapLeft => "Left 40%", "左 40%";
+    SnapRight => "Right 40%", "右 40%";
+    SnapTop => "Top 50%", "上 50%";
+    SnapBottom => "Bottom 30%", "下 30%";
+    SnapCenter => "Center 60%", "中央 60%";
+    Opacity => "Opacity", "不透明度";
+    ShakeToToggle => "Shake Mouse to Toggle", "マウスを振って切り替え";
+    AnnounceStateChanges => "Announce State Changes", "状態の変化を読み上げ";
+    DebugLogging => "Debug Logging", "デバッグログ";
+    ChangeToggleHotkey => "Change Toggle Hotkey…", "切り替えホットキーを変更…";
+    ChangeTrackHotkey => "Change Track Hotkey…", "追跡ホットキーを変更…";
+    ExportSettings => "Export Settings…", "設定をエクスポート…";
+    ImportSettings => "Import Settings…", "設定をインポート…";
+    CheckForUpdates => "Check for Updates", "更新を確認";
+    CheckForUpdatesAtStartup => "Check for Updates at Startup", "起動時に更新を確認";
+    RestartToUpdate => "Restart to Update", "再起動して更新";
+    RestartToUpdateTag => "Restart to Update ({0})", "再起動して更新 ({0})";
+    Plugins => "Plugins", "プラグイン";
+    LastError => "Last error: {0}", "最後のエラー: {0}";
+    ClearError => "Clear", "クリア";
+    AboutStatus => "About / Status", "バージョン情報 / 状態";
+    Exit => "Exit", "終了";
+
+    // About / Status panel
+    StatusTitle => "Quake Modoki - About / Status", "Quake Modoki - バージョン情報 / 状態";
+    StatusNoHotkey => "(none)", "(なし)";
+    StatusToggleAction => "Toggle window visibility", "ウィンドウの表示を切り替え";
+    StatusTrackAction => "Track foreground window", "前面のウィンドウを追跡";
+    StatusLeaderAction => "Chord leader (then T / U / G / A / L / Space / 1-4 / arrows / C)",
+        "コードのリーダー (続けて T / U / G / A / L / Space / 1-4 / 矢印 / C)";
+    StatusHotkeys => "Hotkeys:", "ホットキー:";
+    StatusTracked => "Tracked window:", "追跡中のウィンドウ:";
+    StatusUntracked => "Tracked window: none", "追跡中のウィンドウ: なし";
+    StatusWindowTitle => "Title: {0}", "タイトル: {0}";
+    StatusExe => "Exe: {0}", "実行ファイル: {0}";
+    StatusUnavailable => "(unavailable)", "(取得できません)";
+    StatusBounds => "Bounds: {0}x{1} at ({2}, {3})", "位置とサイズ: {0}x{1} ({2}, {3})";
+    StatusBoundsNotSaved => "Bounds: (not saved)", "位置とサイズ: (未保存)";
+    StatusVisible => "State: visible", "状態: 表示中";
+    StatusHidden => "State: hidden", "状態: 非表示";
+    StatusEdge => "Edge trigger: {0} (threshold {1}px, touchpad {2}px, touch {3}px, show {4}ms, hide {5}ms)",
+        "エッジトリガー: {0} (しきい値 {1}px、タッチパッド {2}px、タッチ {3}px、表示 {4}ms、非表示 {5}ms)";
+    StatusHooks => "Hooks: focus {0}, shell/flash {1}", "フック: フォーカス {0}、シェル/点滅 {1}";
+    StatusOn => "on", "オン";
+    StatusOff => "off", "オフ";
+
+    // Explorer context menu verb
+    LaunchAndTrack => "Launch and track with Quake Modoki", "Quake Modoki で起動して追跡";
+
+    // Edge trigger settings prompt
+    EdgeTriggerSettings => "Quake Modoki - Edge Trigger", "Quake Modoki - エッジトリガー";
+    EdgeDistancePx => "Distance from edge (px)", "端からの距離 (px)";
+    TouchpadDistancePx => "Touchpad distance (px)", "タッチパッドの距離 (px)";
+    TouchDistancePx => "Touch distance (px)", "タッチの距離 (px)";
+    ShowDelayMs => "Show delay (ms)", "表示までの遅延 (ms)";
+    HideDelayMs => "Hide delay (ms)", "非表示までの遅延 (ms)";
+    Cancel => "Cancel", "キャンセル";
+
+    // Window palette
+    WindowPalette => "Quake Modoki - Find Window (Enter: track / toggle, Esc: close)", "Quake Modoki - ウィンドウ検索 (Enter: 追跡 / 切替, Esc: 閉じる)";
+
+    // Screen reader announcements
+    AnnounceShown => "Window shown", "ウィンドウを表示しました";
+    AnnounceHidden => "Window hidden", "ウィンドウを隠しました";
+
+    // Notifications
+    Hide => "Hide", "隠す";
+    Relaunch => "Relaunch", "再起動";
+    TrackedWindow => "Tracked window", "追跡中のウィンドウ";
+    WindowClosed => "{0} was closed", "{0} が閉じられました";
+    NothingTracked => "No window is tracked yet. Focus a window and press {0} to register it, or pick the window in front below.", "まだウィンドウが追跡されていません。ウィンドウを選んで {0} を押すか、下のボタンで前面のウィンドウを登録してください。";
+    CannotTrack => "Cannot track this window: {0}", "このウィンドウは追跡できません: {0}";
+    BlockedShell => "it is the shell window", "シェルのウィンドウです";
+    BlockedTaskbar => "it is the taskbar", "タスクバーです";
+    BlockedDesktop => "it is the desktop", "デスクトップです";
+    BlockedOwnWindow => "it belongs to Quake Modoki", "Quake Modoki のウィンドウです";
+    BlockedSecureSurface => "it is secure system UI (UAC, lock screen)",
+        "保護されたシステム UI (UAC、ロック画面) です";
+    BlockedProtected => "its process is protected", "保護されたプロセスです";
+    BlockedCloaked => "it is cloaked (another virtual desktop or a suspended app)",
+        "クローク中です (別の仮想デスクトップまたは中断中のアプリ)";
+    HotkeyFallback => "{0} is in use by another app. Toggle hotkey: {1}",
+        "{0} は他のアプリが使用中です。切り替えホットキー: {1}";
+    HotkeyUnavailable => "No toggle hotkey available (tried {0}). Use the edge trigger or tray.",
+        "切り替えホットキーを登録できません ({0} を試行)。エッジトリガーかトレイを使用してください。";
+    HotkeyConflict => "{0} is running and usually uses {1}, same as Quake Modoki. Free alternatives: {2}",
+        "{0} が実行中で、通常 Quake Modoki と同じ {1} を使用します。空いている候補: {2}";
+    PressNewShortcut => "Press the new toggle shortcut (Esc to cancel)",
+        "新しい切り替えショートカットを押してください (Esc でキャンセル)";
+    PressNewTrackShortcut => "Press the new track shortcut (Esc to cancel)",
+        "新しい追跡ショートカットを押してください (Esc でキャンセル)";
+    HotkeyChanged => "Toggle hotkey: {0}", "切り替えホットキー: {0}";
+    TrackHotkeyChanged => "Track hotkey: {0}", "追跡ホットキー: {0}";
+    HotkeyChangeFailed => "Hotkey not changed: {0}", "ホットキーは変更されていません: {0}";
+    TooltipToggle => "Quake Modoki - Toggle: {0}", "Quake Modoki - 切り替え: {0}";
+    TooltipNoHotkey => "Quake Modoki - no toggle hotkey", "Quake Modoki - 切り替えホットキーなし";
+    NeedsAttention => "{0} needs attention", "{0} が応答を求めています";
+    Crashed => "Crashed at {0}. Window restored; report saved to logs.",
+        "{0} でクラッシュしました。ウィンドウを復元し、レポートをログに保存しました。";
+    UpdateReady => "Update {0} downloaded. Applied on exit (or Restart to Update).",
+        "更新 {0} をダウンロードしました。終了時 (または「再起動して更新」) に適用されます。";
+    SettingsExported => "Settings exported to {0}", "設定を {0} にエクスポートしました";
+    SettingsImported => "{0} settings imported. Restart to apply.",
+        "{0} 件の設定をインポートしました。再起動すると反映されます。";
+    SettingsImportedBlocked =>
+        "{0} settings imported, {1} commands skipped (set them by hand). Restart to apply.",
+        "{0} 件の設定をインポートしました。コマンド {1} 件は取り込んでいません (手動で設定してください)。再起動すると反映されます。";
+    UpToDate => "Up to date (v{0})", "最新版です (v{0})";
+    UpdateFailed => "Update check failed: {0}", "更新の確認に失敗しました: {0}";
+    ErrorOccurred => "Something went wrong. {0}", "問題が発生しました。{0}";
+    SetupDone => "Setup complete. Toggle: {0}, track the focused window: {1}",
+        "セットアップが完了しました。切り替え: {0}、前面のウィンドウを追跡: {1}";
+
+    // First-run setup wizard
+    SetupTitle => "Quake Modoki - Setup", "Quake Modoki - セットアップ";
+    SetupWelcome => "Welcome to Quake Modoki! Set up a drop-down window now?\n\nNo: skip (track any window later with Ctrl+Alt+Q)",
+        "Quake Modoki へようこそ! ドロップダウンするウィンドウを今すぐ設定しますか?\n\nいいえ: スキップ (後で Ctrl+Alt+Q で任意のウィンドウを追跡できます)";
+    SetupPickTarget => "Choose the app to drop down (for example your terminal)? It is started and tracked at every launch.\n\nNo: track windows with Ctrl+Alt+Q instead",
+        "ドロップダウンするアプリ (ターミナルなど) を選びますか? 起動のたびに開始して追跡します。\n\nいいえ: 代わりに Ctrl+Alt+Q でウィンドウを追跡";
+    SetupOfferPresets => "Drop down a common app (Windows Terminal, PowerShell, Command Prompt, Obsidian or Spotify) with a ready-made position and size?\n\nNo: choose any app",
+        "よく使うアプリ (Windows Terminal、PowerShell、コマンド プロンプト、Obsidian、Spotify) を位置とサイズ設定済みでドロップダウンしますか?\n\nいいえ: 任意のアプリを選ぶ";
+    SetupUsePreset => "Drop down {0}?\n\nNo: next app",
+        "{0} をドロップダウンしますか?\n\nいいえ: 次のアプリ";
+    SetupUseHotkey => "Show and hide it with {0}?\n\nNo: next option",
+        "{0} で表示 / 非表示を切り替えますか?\n\nいいえ: 次の候補";
+    SetupSlideFrom => "Slide it in from the {0} edge of the screen?\n\nNo: next edge",
+        "画面の{0}端からスライドさせますか?\n\nいいえ: 次の端";
+    EdgeTop => "top", "上";
+    EdgeLeft => "left", "左";
+    EdgeRight => "right", "右";
+    EdgeBottom => "bottom", "下";
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// Active language (resolved once)
+pub fn lang() -> Lang {
+    *LANG.get_or_init(|| {
+        settings::get_string(LANGUAGE)
+            .and_then(|code| Lang::from_code(&code))
+            .unwrap_or_else(|| Lang::from_langid(unsafe { GetUserDefaultUILanguage() }))
+    })
+}
+
+/// Localized string
+pub fn t(text: Text) -> &'static str {
+    text.get(lang())
+}
+
+/// Localized string with `{0}`, `{1}`... replaced by args
+pub fn tf(text: Text, args: &[&str]) -> String {
+    format_template(t(text), args)
+}
+
+fn format_template(template: &str, args: &[&str]) -> String {
+    args.iter()
+        .enumerate()
+        .fold(template.to_string(), |s, (i, arg)| {
+            s.replace(&format!("{{{i}}}"), arg)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(s: &str) -> Vec<usize> {
+        (0..6).filter(|i| s.contains(&format!("{{{i}}}"))).collect()
+    }
+
+    #[test]
+    fn test_lang_from_code() {
+        assert_eq!(Lang::from_code("ja"), Some(Lang::Ja));
+        assert_eq!(Lang::from_code("ja-JP"), Some(Lang::Ja));
+        assert_eq!(Lang::from_code("EN_us"), Some(Lang::En));
+        assert_eq!(Lang::from_code("fr"), None);
+    }
+
+    #[test]
+    fn test_lang_from_langid() {
+        assert_eq!(Lang::from_langid(0x0411), Lang::Ja); // ja-JP
+        assert_eq!(Lang::from_langid(0x0409), Lang::En); // en-US
+        assert_eq!(Lang::from_langid(0x040C), Lang::En); // fr-FR falls back
+    }
+
+    #[test]
+    fn test_all_texts_translated_with_same_placeholders() {
+        for text in Text::ALL {
+            let en = text.get(Lang::En);
+            let ja = text.get(Lang::Ja);
+            assert!(!en.is_empty() && !ja.is_empty(), "{text:?} empty");
+            assert_eq!(placeholders(en), placeholders(ja), "{text:?} placeholders");
+        }
+    }
+
+    #[test]
+    fn test_format_template() {
+        assert_eq!(format_template("Tracking: {0}", &["vim"]), "Tracking: vim");
+        assert_eq!(format_template("{1} / {0}", &["a", "b"]), "b / a");
+        assert_eq!(format_template("No args", &[]), "No args");
+    }
+}
@@ -0,0 +1,166 @@
+//! Which kind of device moved the cursor last (input-aware edge thresholds)
+//!
+//! While the edge trigger needs it, the event window registers for Raw Input
+//! from touchpads and touch screens (HID digitizer usages; RIDEV_INPUTSINK, so
+//! reports arrive while other apps are in the foreground). Each report is
+//! timestamped: cursor positions within `RECENT_MS` of one count as that
+//! device's, anything else as a mouse's. Mouse raw input is not requested, so
+//! plain mouse moves cause no wakeups.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use windows::Win32::Foundation::{HANDLE, HWND};
+use windows::Win32::System::SystemInformation::GetTickCount64;
+use windows::Win32::UI::Input::{
+    GetRawInputData, GetRawInputDeviceInfoW, HRAWINPUT, RAWINPUTDEVICE, RAWINPUTHEADER,
+    RID_DEVICE_INFO, RID_HEADER, RIDEV_INPUTSINK, RIDEV_REMOVE, RIDI_DEVICEINFO, RIM_TYPEHID,
+    RegisterRawInputDevices,
+};
+
+const USAGE_PAGE_DIGITIZER: u16 = 0x0D;
+const USAGE_TOUCH_SCREEN: u16 = 0x04;
+const USAGE_TOUCHPAD: u16 = 0x05;
+
+/// A device's report counts for cursor positions this long after it
+const RECENT_MS: u64 = 250;
+
+/// Registered for digitizer raw input
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+/// Tick count of the last touchpad / touch screen report (0: none)
+static LAST_TOUCHPAD: AtomicU64 = AtomicU64::new(0);
+static LAST_TOUCH: AtomicU64 = AtomicU64::new(0);
+
+/// Device kind behind the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputKind {
+    #[default]
+    Mouse,
+    Touchpad,
+    Touch,
+}
+
+/// Start or stop receiving digitizer raw input on `hwnd` (no-op if already so)
+pub fn watch(hwnd: HWND, on: bool) {
+    if WATCHING.load(Ordering::SeqCst) == on {
+        return;
+    }
+    let (flags, target) = if on {
+        (RIDEV_INPUTSINK, hwnd)
+    } else {
+        (RIDEV_REMOVE, HWND::default())
+    };
+    let devices = [USAGE_TOUCHPAD, USAGE_TOUCH_SCREEN].map(|usage| RAWINPUTDEVICE {
+        usUsagePage: USAGE_PAGE_DIGITIZER,
+        usUsage: usage,
+        dwFlags: flags,
+        hwndTarget: target,
+    });
+    let size = size_of::<RAWINPUTDEVICE>() as u32;
+    // Failure leaves the mouse threshold in use (not retried until toggled)
+    if let Err(e) = unsafe { RegisterRawInputDevices(&devices, size) } {
+        tracing::warn!(on, "Digitizer raw input registration failed: {e}");
+    }
+    LAST_TOUCHPAD.store(0, Ordering::SeqCst);
+    LAST_TOUCH.store(0, Ordering::SeqCst);
+    WATCHING.store(on, Ordering::SeqCst);
+}
+
+/// Note a WM_INPUT report (event window procedure)
+pub fn record(input: HRAWINPUT) {
+    let mut header = RAWINPUTHEADER::default();
+    let mut size = size_of::<RAWINPUTHEADER>() as u32;
+    let copied = unsafe {
+        GetRawInputData(
+            input,
+            RID_HEADER,
+            Some(&mut header as *mut _ as *mut _),
+            &mut size,
+            size_of::<RAWINPUTHEADER>() as u32,
+        )
+    };
+    if copied == u32::MAX || header.dwType != RIM_TYPEHID.0 {
+        return;
+    }
+    let last = match device_usage(header.hDevice).and_then(|(page, usage)| kind_of(page, usage)) {
+        Some(InputKind::Touchpad) => &LAST_TOUCHPAD,
+        Some(InputKind::Touch) => &LAST_TOUCH,
+        _ => return,
+    };
+    last.store(unsafe { GetTickCount64() }, Ordering::SeqCst);
+}
+
+/// HID usage page and usage of a raw input device
+fn device_usage(device: HANDLE) -> Option<(u16, u16)> {
+    let mut info = RID_DEVICE_INFO {
+        cbSize: size_of::<RID_DEVICE_INFO>() as u32,
+        ..Default::default()
+    };
+    let mut size = info.cbSize;
+    let copied = unsafe {
+        GetRawInputDeviceInfoW(
+            Some(device),
+            RIDI_DEVICEINFO,
+            Some(&mut info as *mut _ as *mut _),
+            &mut size,
+        )
+    };
+    if copied == u32::MAX || info.dwType != RIM_TYPEHID {
+        return None;
+    }
+    let hid = unsafe { info.Anonymous.hid };
+    Some((hid.usUsagePage, hid.usUsage))
+}
+
+/// Kind of a digitizer by HID usage (None: not a touchpad / touch screen)
+fn kind_of(usage_page: u16, usage: u16) -> Option<InputKind> {
+    match (usage_page, usage) {
+        (USAGE_PAGE_DIGITIZER, USAGE_TOUCHPAD) => Some(InputKind::Touchpad),
+        (USAGE_PAGE_DIGITIZER, USAGE_TOUCH_SCREEN) => Some(InputKind::Touch),
+        _ => None,
+    }
+}
+
+/// Kind at tick `now` given the last report ticks (the most recent one wins)
+fn kind_at(now: u64, touchpad: u64, touch: u64) -> InputKind {
+    let recent = |at: u64| at != 0 && now.saturating_sub(at) <= RECENT_MS;
+    match (recent(touchpad), recent(touch)) {
+        (true, true) if touch >= touchpad => InputKind::Touch,
+        (true, _) => InputKind::Touchpad,
+        (false, true) => InputKind::Touch,
+        (false, false) => InputKind::Mouse,
+    }
+}
+
+/// Device kind that moved the cursor last (Mouse unless watching)
+pub fn last_kind() -> InputKind {
+    if !WATCHING.load(Ordering::SeqCst) {
+        return InputKind::Mouse;
+    }
+    kind_at(
+        unsafe { GetTickCount64() },
+        LAST_TOUCHPAD.load(Ordering::SeqCst),
+        LAST_TOUCH.load(Ordering::SeqCst),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_of() {
+        assert_eq!(kind_of(0x0D, 0x05), Some(InputKind::Touchpad));
+        assert_eq!(kind_of(0x0D, 0x04), Some(InputKind::Touch));
+        assert_eq!(kind_of(0x0D, 0x02), None); // Pen
+        assert_eq!(kind_of(0x01, 0x02), None); // Generic mouse
+    }
+
+    #[test]
+    fn test_kind_at() {
+        assert_eq!(kind_at(10_000, 0, 0), InputKind::Mouse);
+        assert_eq!(kind_at(10_000, 9_900, 0), InputKind::Touchpad);
+        assert_eq!(kind_at(10_000, 9_000, 0), InputKind::Mouse);
+        assert_eq!(kind_at(10_000, 9_800, 9_900), InputKind::Touch);
+        assert_eq!(kind_at(10_000, 9_950, 9_900), InputKind::Touchpad);
+    }
+}
@@ -0,0 +1,279 @@
+//! Single-instance forwarding: a second process hands its request to the running one
+//!
+//! The running instance owns a message-only window ("QuakeModokiIpc"); a new
+//! process started with a command (`--launch`, `--find`, `--toggle`, ...) finds it and
+//! sends the request via WM_COPYDATA, then exits. Requests are queued and
+//! announced to the main loop as WM_IPC_REQUEST.
+
+use std::path::PathBuf;
+use std::ptr::null_mut;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::DataExchange::COPYDATASTRUCT;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, FindWindowExW, HWND_MESSAGE, RegisterClassW,
+    SendMessageW, WINDOW_EX_STYLE, WINDOW_STYLE, WM_COPYDATA, WM_USER, WNDCLASSW,
+};
+use windows::core::{PCWSTR, w};
+
+/// Posted to the event loop when a forwarded request is queued
+pub const WM_IPC_REQUEST: u32 = WM_USER + 6;
+
+const CLASS_NAME: PCWSTR = w!("QuakeModokiIpc");
+
+// COPYDATASTRUCT.dwData tags ("QM" + command); launch and find carry a UTF-16
+// payload (path; title pattern and class separated by FIND_SEPARATOR)
+const COPYDATA_LAUNCH: usize = 0x514D_0001;
+const COPYDATA_TOGGLE: usize = 0x514D_0002;
+const COPYDATA_UNTRACK: usize = 0x514D_0003;
+const COPYDATA_ABOUT: usize = 0x514D_0004;
+const COPYDATA_FIND: usize = 0x514D_0005;
+const COPYDATA_TERMINAL: usize = 0x514D_0006;
+
+const FIND_SEPARATOR: char = '\n';
+
+/// Command for the running instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// Start an exe / shortcut and track its first window
+    Launch(PathBuf),
+    /// Track the best window matching a title pattern (optional class)
+    Find {
+        title: String,
+        class: Option<String>,
+    },
+    /// Launch (or show) the Windows Terminal preset window
+    Terminal,
+    Toggle,
+    Untrack,
+    /// Show the About / Status panel
+    About,
+}
+
+/// Message-only window of this instance
+static IPC_WINDOW: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(null_mut());
+
+/// Forwarded requests awaiting the main loop
+static REQUESTS: Mutex<Vec<Request>> = Mutex::new(Vec::new());
+
+/// Create the message-only window (call on the main thread; non-fatal)
+pub fn start() -> windows::core::Result<()> {
+    unsafe {
+        let instance = GetModuleHandleW(None)?;
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(wnd_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        RegisterClassW(&class);
+
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            CLASS_NAME,
+            CLASS_NAME,
+            WINDOW_STYLE(0),
+            0,
+            0,
+            0,
+            0,
+            Some(HWND_MESSAGE),
+            None,
+            Some(instance.into()),
+            None,
+        )?;
+        IPC_WINDOW.store(hwnd.0, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+/// Destroy the message-only window
+pub fn stop() {
+    let hwnd = IPC_WINDOW.swap(null_mut(), Ordering::SeqCst);
+    if !hwnd.is_null() {
+        unsafe {
+            let _ = DestroyWindow(HWND(hwnd));
+        }
+    }
+}
+
+/// Check if another instance owns the IPC window
+pub fn is_running() -> bool {
+    unsafe { FindWindowExW(Some(HWND_MESSAGE), None, CLASS_NAME, None) }.is_ok()
+}
+
+/// Send a request to a running instance; false if none is running
+pub fn forward(request: &Request) -> bool {
+    let Ok(target) = (unsafe { FindWindowExW(Some(HWND_MESSAGE), None, CLASS_NAME, None) }) else {
+        return false;
+    };
+    let (tag, text) = encode(request);
+    let mut payload: Vec<u16> = text.encode_utf16().collect();
+    let data = COPYDATASTRUCT {
+        dwData: tag,
+        cbData: (payload.len() * size_of::<u16>()) as u32,
+        lpData: if payload.is_empty() {
+            null_mut()
+        } else {
+            payload.as_mut_ptr().cast()
+        },
+    };
+    let handled = unsafe {
+        SendMessageW(
+            target,
+            WM_COPYDATA,
+            None,
+            Some(LPARAM(&data as *const _ as isize)),
+        )
+    };
+    handled.0 != 0
+}
+
+/// Take queued requests
+pub fn take_requests() -> Vec<Request> {
+    std::mem::take(&mut *REQUESTS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// dwData tag + payload text (empty for plain commands)
+fn encode(request: &Request) -> (usize, String) {
+    match request {
+        Request::Launch(path) => (COPYDATA_LAUNCH, path.to_string_lossy().into_owned()),
+        Request::Find { title, class } => (
+            COPYDATA_FIND,
+            format!("{title}{FIND_SEPARATOR}{}", class.as_deref().unwrap_or("")),
+        ),
+        Request::Terminal => (COPYDATA_TERMINAL, String::new()),
+        Request::Toggle => (COPYDATA_TOGGLE, String::new()),
+        Request::Untrack => (COPYDATA_UNTRACK, String::new()),
+        Request::About => (COPYDATA_ABOUT, String::new()),
+    }
+}
+
+/// Payload text of a WM_COPYDATA request (None if empty)
+fn payload(data: &COPYDATASTRUCT) -> Option<String> {
+    if data.lpData.is_null() || data.cbData == 0 {
+        return None;
+    }
+    let units = unsafe {
+        std::slice::from_raw_parts(
+            data.lpData as *const u16,
+            data.cbData as usize / size_of::<u16>(),
+        )
+    };
+    Some(String::from_utf16_lossy(units))
+}
+
+/// Decode a WM_COPYDATA request
+fn decode(data: &COPYDATASTRUCT) -> Option<Request> {
+    match data.dwData {
+        COPYDATA_LAUNCH => payload(data).map(|path| Request::Launch(PathBuf::from(path))),
+        COPYDATA_FIND => {
+            let text = payload(data)?;
+            let (title, class) = text.split_once(FIND_SEPARATOR).unwrap_or((&text, ""));
+            Some(Request::Find {
+                title: title.to_string(),
+                class: (!class.is_empty()).then(|| class.to_string()),
+            })
+        }
+        COPYDATA_TERMINAL => Some(Request::Terminal),
+        COPYDATA_TOGGLE => Some(Request::Toggle),
+        COPYDATA_UNTRACK => Some(Request::Untrack),
+        COPYDATA_ABOUT => Some(Request::About),
+        _ => None,
+    }
+}
+
+/// IPC window procedure: queue forwarded requests for the main loop
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_COPYDATA {
+        let data = unsafe { &*(lparam.0 as *const COPYDATASTRUCT) };
+        if let Some(request) = decode(data) {
+            REQUESTS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(request);
+            crate::events::post(WM_IPC_REQUEST, 0);
+            return LRESULT(1);
+        }
+        return LRESULT(0);
+    }
+
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_launch() {
+        let mut payload: Vec<u16> = r"C:\Apps\tool.exe".encode_utf16().collect();
+        let data = COPYDATASTRUCT {
+            dwData: COPYDATA_LAUNCH,
+            cbData: (payload.len() * 2) as u32,
+            lpData: payload.as_mut_ptr().cast(),
+        };
+        assert_eq!(
+            decode(&data),
+            Some(Request::Launch(PathBuf::from(r"C:\Apps\tool.exe")))
+        );
+    }
+
+    #[test]
+    fn test_find_round_trip() {
+        for class in [Some("Notepad".to_string()), None] {
+            let request = Request::Find {
+                title: "/log$/".to_string(),
+                class,
+            };
+            let (tag, text) = encode(&request);
+            let mut payload: Vec<u16> = text.encode_utf16().collect();
+            let data = COPYDATASTRUCT {
+                dwData: tag,
+                cbData: (payload.len() * 2) as u32,
+                lpData: payload.as_mut_ptr().cast(),
+            };
+            assert_eq!(decode(&data), Some(request));
+        }
+    }
+
+    #[test]
+    fn test_decode_commands_without_payload() {
+        for request in [
+            Request::Terminal,
+            Request::Toggle,
+            Request::Untrack,
+            Request::About,
+        ] {
+            let data = COPYDATASTRUCT {
+                dwData: encode(&request).0,
+                cbData: 0,
+                lpData: null_mut(),
+            };
+            assert_eq!(decode(&data), Some(request));
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_or_empty() {
+        let empty_launch = COPYDATASTRUCT {
+            dwData: COPYDATA_LAUNCH,
+            cbData: 0,
+            lpData: null_mut(),
+        };
+        assert_eq!(decode(&empty_launch), None);
+        let unknown = COPYDATASTRUCT {
+            dwData: 0,
+            cbData: 0,
+            lpData: null_mut(),
+        };
+        assert_eq!(decode(&unknown), None);
+    }
+}
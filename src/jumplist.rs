@@ -0,0 +1,67 @@
+//! Taskbar jump list tasks (Toggle / Untrack / About)
+//!
+//! Each task re-runs this exe with a command flag; the new process forwards
+//! it to the running instance through `ipc` and exits.
+
+use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+use windows::Win32::System::Com::StructuredStorage::PROPVARIANT;
+use windows::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+};
+use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+use windows::Win32::UI::Shell::{
+    DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW, ShellLink,
+};
+use windows::core::{HSTRING, Interface};
+
+use crate::i18n::{Text, t};
+
+/// Jump list task: command flag + label
+const TASKS: [(&str, Text); 3] = [
+    ("--toggle", Text::Toggle),
+    ("--untrack", Text::Untrack),
+    ("--about", Text::AboutStatus),
+];
+
+/// Replace the exe's jump list tasks (call once at startup; non-fatal)
+pub fn install() -> windows::core::Result<()> {
+    let exe = std::env::current_exe().map_err(|_| windows::core::Error::from_thread())?;
+    let exe = HSTRING::from(exe.as_path());
+
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let list: ICustomDestinationList =
+            CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+        let mut min_slots = 0u32;
+        let _removed: IObjectArray = list.BeginList(&mut min_slots)?;
+
+        let tasks: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        for (flag, label) in TASKS {
+            tasks.AddObject(&task_link(&exe, flag, t(label))?)?;
+        }
+
+        list.AddUserTasks(&tasks.cast::<IObjectArray>()?)?;
+        list.CommitList()?;
+    }
+    Ok(())
+}
+
+/// Shell link running `exe flag`, titled `label`
+unsafe fn task_link(exe: &HSTRING, flag: &str, label: &str) -> windows::core::Result<IShellLinkW> {
+    unsafe {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(exe)?;
+        link.SetArguments(&HSTRING::from(flag))?;
+        link.SetIconLocation(exe, 0)?;
+        link.SetDescription(&HSTRING::from(label))?;
+
+        // Jump list shows PKEY_Title, not the description
+        let store: IPropertyStore = link.cast()?;
+        store.SetValue(&PKEY_Title, &PROPVARIANT::from(label))?;
+        store.Commit()?;
+        Ok(link)
+    }
+}
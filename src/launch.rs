@@ -0,0 +1,287 @@
+//! Launch and track: start an exe / shortcut, then track its first top-level window
+//!
+//! The process is started via ShellExecuteExW (so .lnk files resolve) and its
+//! windows are polled until one is visible or LAUNCH_TIMEOUT elapses.
+//! Apps that hand off to another process (launcher stubs) are not followed,
+//! unless started with `start_for_class` / `start_for_exe`: then the first
+//! window of that class / exe that did not exist before the launch is taken,
+//! whichever process owns it.
+//! A `Target` is a configured launch command (program plus arguments).
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::{CloseHandle, HWND};
+use windows::Win32::System::Threading::GetProcessId;
+use windows::Win32::UI::Shell::{SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW, ShellExecuteExW};
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+use windows::core::{HSTRING, PCWSTR};
+
+use crate::error::LaunchError;
+use crate::tracking;
+
+/// Give up waiting for a window after this long
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Window property a hand-off launch waits for
+#[derive(Debug, Clone)]
+enum WindowKey {
+    Class(&'static str),
+    /// Exe file name or path (as in `find_window_by_exe`)
+    Exe(String),
+}
+
+impl WindowKey {
+    fn matches(&self, hwnd: HWND) -> bool {
+        match self {
+            WindowKey::Class(class) => tracking::get_window_class(hwnd) == *class,
+            WindowKey::Exe(exe) => {
+                tracking::get_process_path(hwnd).is_some_and(|path| exe_matches(&path, exe))
+            }
+        }
+    }
+}
+
+/// What identifies the launched app's window
+#[derive(Debug, Clone)]
+enum Wait {
+    /// Owned by the started process
+    Process(u32),
+    /// Matching `key` and not open before the launch
+    NewWindow { key: WindowKey, existing: Vec<HWND> },
+}
+
+#[derive(Debug, Clone)]
+struct Pending {
+    wait: Wait,
+    since: Instant,
+    hide: bool,
+}
+
+/// Window of a finished launch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Launched {
+    pub hwnd: HWND,
+    /// Slide out once tracked (launched as the startup target)
+    pub hide: bool,
+    /// Class waited for (`start_for_class`)
+    pub class: Option<&'static str>,
+}
+
+/// Launch command: program (path or bare exe name) and its arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub program: String,
+    pub args: Option<String>,
+}
+
+impl Target {
+    /// Split a command line; a program path with spaces must be quoted (None if empty)
+    pub fn parse(command: &str) -> Option<Self> {
+        let command = command.trim();
+        let (program, rest) = match command.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => command.split_once(' ').unwrap_or((command, "")),
+        };
+        if program.is_empty() {
+            return None;
+        }
+        let args = rest.trim();
+        Some(Self {
+            program: program.to_string(),
+            args: (!args.is_empty()).then(|| args.to_string()),
+        })
+    }
+}
+
+/// Launch awaiting its first window (owned by the event loop)
+#[derive(Debug, Default)]
+pub struct LaunchState {
+    pending: Option<Pending>,
+}
+
+impl LaunchState {
+    /// Start `path`; a previous pending launch is dropped
+    pub fn start(&mut self, path: &Path) -> Result<(), LaunchError> {
+        let pid = spawn(&HSTRING::from(path), None)?.ok_or(LaunchError::NoProcess)?;
+        self.wait_for(Wait::Process(pid), false);
+        Ok(())
+    }
+
+    /// Start `target`; with `hide` its window slides out once tracked
+    pub fn start_target(&mut self, target: &Target, hide: bool) -> Result<(), LaunchError> {
+        let args = target.args.as_deref().map(HSTRING::from);
+        let pid = spawn(&HSTRING::from(target.program.as_str()), args.as_ref())?
+            .ok_or(LaunchError::NoProcess)?;
+        self.wait_for(Wait::Process(pid), hide);
+        Ok(())
+    }
+
+    /// Start `target` (may hand off to another process) and take its new `class` window
+    pub fn start_for_class(
+        &mut self,
+        target: &Target,
+        class: &'static str,
+    ) -> Result<(), LaunchError> {
+        self.start_new_window(target, WindowKey::Class(class), false)
+    }
+
+    /// Start `target` (may hand off to another process) and take a new window of `exe`
+    pub fn start_for_exe(
+        &mut self,
+        target: &Target,
+        exe: &str,
+        hide: bool,
+    ) -> Result<(), LaunchError> {
+        self.start_new_window(target, WindowKey::Exe(exe.to_string()), hide)
+    }
+
+    fn start_new_window(
+        &mut self,
+        target: &Target,
+        key: WindowKey,
+        hide: bool,
+    ) -> Result<(), LaunchError> {
+        let existing = matching_windows(&key);
+        let args = target.args.as_deref().map(HSTRING::from);
+        spawn(&HSTRING::from(target.program.as_str()), args.as_ref())?;
+        self.wait_for(Wait::NewWindow { key, existing }, hide);
+        Ok(())
+    }
+
+    fn wait_for(&mut self, wait: Wait, hide: bool) {
+        self.pending = Some(Pending {
+            wait,
+            since: Instant::now(),
+            hide,
+        });
+    }
+
+    /// Check if a launch is waiting for its window
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// First visible top-level window of the launched app (clears pending)
+    pub fn poll(&mut self) -> Option<Launched> {
+        let pending = self.pending.as_ref()?;
+        if pending.since.elapsed() >= LAUNCH_TIMEOUT {
+            tracing::warn!(wait = ?pending.wait, "Launched app showed no window; not tracked");
+            self.pending = None;
+            return None;
+        }
+        let (hwnd, class) = match &pending.wait {
+            Wait::Process(pid) => (find_main_window(*pid)?, None),
+            Wait::NewWindow { key, existing } => (
+                tracking::find_app_window(&|hwnd| !existing.contains(&hwnd) && key.matches(hwnd))?,
+                match key {
+                    WindowKey::Class(class) => Some(*class),
+                    WindowKey::Exe(_) => None,
+                },
+            ),
+        };
+        let hide = pending.hide;
+        self.pending = None;
+        Some(Launched { hwnd, hide, class })
+    }
+}
+
+/// Start `file` via the shell; returns the process id when a process was created
+fn spawn(file: &HSTRING, args: Option<&HSTRING>) -> Result<Option<u32>, LaunchError> {
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: SEE_MASK_NOCLOSEPROCESS,
+        lpFile: PCWSTR(file.as_ptr()),
+        lpParameters: args.map_or(PCWSTR::null(), |args| PCWSTR(args.as_ptr())),
+        nShow: SW_SHOWNORMAL.0,
+        ..Default::default()
+    };
+    unsafe { ShellExecuteExW(&mut info) }.map_err(|e| LaunchError::Start(e.to_string()))?;
+    if info.hProcess.is_invalid() {
+        tracing::info!(file = %file, "Launched (no process handle); waiting for window");
+        return Ok(None);
+    }
+    let pid = unsafe { GetProcessId(info.hProcess) };
+    unsafe {
+        let _ = CloseHandle(info.hProcess);
+    }
+    tracing::info!(pid, file = %file, "Launched; waiting for window");
+    Ok(Some(pid))
+}
+
+/// Visible, unowned, titled top-level windows matching `key`
+fn matching_windows(key: &WindowKey) -> Vec<HWND> {
+    let found = std::cell::RefCell::new(Vec::new());
+    tracking::find_app_window(&|hwnd| {
+        if key.matches(hwnd) {
+            found.borrow_mut().push(hwnd);
+        }
+        false
+    });
+    found.into_inner()
+}
+
+/// Visible, unowned, titled top-level window of `pid`
+fn find_main_window(pid: u32) -> Option<HWND> {
+    tracking::find_app_window(&|hwnd| tracking::get_process_id(hwnd) == pid)
+}
+
+/// Visible, unowned, titled top-level window of a running `exe` (case-insensitive)
+pub fn find_window_by_exe(exe: &str) -> Option<HWND> {
+    tracking::find_app_window(&|hwnd| {
+        tracking::get_process_path(hwnd).is_some_and(|path| exe_matches(&path, exe))
+    })
+}
+
+/// Compare a process path with a full path, or only its file name for a bare exe name
+fn exe_matches(path: &str, exe: &str) -> bool {
+    if exe.contains(['\\', '/']) {
+        return path.eq_ignore_ascii_case(exe);
+    }
+    Path::new(path)
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(exe))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_not_pending() {
+        let mut state = LaunchState::default();
+        assert!(!state.is_pending());
+        assert_eq!(state.poll(), None);
+    }
+
+    #[test]
+    fn test_target_parse() {
+        assert_eq!(
+            Target::parse(r#""C:\Program Files\Alacritty\alacritty.exe" -e pwsh"#),
+            Some(Target {
+                program: r"C:\Program Files\Alacritty\alacritty.exe".to_string(),
+                args: Some("-e pwsh".to_string()),
+            })
+        );
+        assert_eq!(
+            Target::parse("wt.exe"),
+            Some(Target {
+                program: "wt.exe".to_string(),
+                args: None,
+            })
+        );
+        assert_eq!(Target::parse("  "), None);
+        assert_eq!(Target::parse(r#""""#), None);
+    }
+
+    #[test]
+    fn test_exe_matches() {
+        let path = r"C:\Program Files\WindowsApps\WindowsTerminal.exe";
+        assert!(exe_matches(path, "windowsterminal.exe"));
+        assert!(exe_matches(
+            path,
+            r"c:\program files\windowsapps\WindowsTerminal.exe"
+        ));
+        assert!(!exe_matches(path, r"D:\WindowsTerminal.exe"));
+        assert!(!exe_matches(path, "Terminal.exe"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Group layout presets: visible bounds for the tracked window and its group
+//!
+//! Members are placed in order (tracked window first). `Free` keeps each
+//! window's own bounds; the presets tile the monitor's work area and slide
+//! from the side they are docked to. With `ExactBounds` = 1 a slide-in targets
+//! exactly the rect captured at the last hide (position and size), so a preset
+//! is only applied when picked from the tray, not re-applied on every show.
+
+use windows::Win32::Foundation::RECT;
+
+use crate::animation::Direction;
+use crate::settings::{self, SettingsError};
+use crate::tracking::WindowBounds;
+
+const GROUP_LAYOUT: &str = "GroupLayout";
+const EXACT_BOUNDS: &str = "ExactBounds";
+
+/// How the tracked window and its group are placed when shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    /// Keep each window's own bounds
+    #[default]
+    Free,
+    /// Top half of the work area, side by side
+    TopSplit,
+    /// Left half of the work area, stacked top to bottom
+    LeftStack,
+}
+
+impl Layout {
+    /// Decode registry value (unknown → Free)
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Layout::TopSplit,
+            2 => Layout::LeftStack,
+            _ => Layout::Free,
+        }
+    }
+
+    /// Encode as registry value
+    pub fn as_u32(self) -> u32 {
+        match self {
+            Layout::Free => 0,
+            Layout::TopSplit => 1,
+            Layout::LeftStack => 2,
+        }
+    }
+
+    /// Slide direction of the preset (None: from each toggle's bounds)
+    pub fn direction(self) -> Option<Direction> {
+        match self {
+            Layout::Free => None,
+            Layout::TopSplit => Some(Direction::Top),
+            Layout::LeftStack => Some(Direction::Left),
+        }
+    }
+}
+
+/// Get configured layout from registry (default: Free)
+pub fn get() -> Layout {
+    Layout::from_u32(settings::get_u32(GROUP_LAYOUT, 0))
+}
+
+/// Persist layout
+pub fn set(layout: Layout) -> Result<(), SettingsError> {
+    settings::set_u32(GROUP_LAYOUT, layout.as_u32())
+}
+
+/// Check if slide-in keeps the stored bounds exactly (default: no)
+pub fn keeps_exact_bounds() -> bool {
+    settings::get_bool(EXACT_BOUNDS, false)
+}
+
+/// Layout applied before a slide-in (Free when exact bounds are kept)
+pub fn on_show() -> Layout {
+    if keeps_exact_bounds() {
+        Layout::Free
+    } else {
+        get()
+    }
+}
+
+/// Bounds for `count` windows in `work_area` (None for Free or no windows)
+pub fn arrange(layout: Layout, work_area: &RECT, count: usize) -> Option<Vec<WindowBounds>> {
+    if count == 0 {
+        return None;
+    }
+    let width = work_area.right - work_area.left;
+    let height = work_area.bottom - work_area.top;
+    match layout {
+        Layout::Free => None,
+        Layout::TopSplit => Some(
+            split(work_area.left, width, count)
+                .map(|(x, w)| WindowBounds {
+                    x,
+                    y: work_area.top,
+                    width: w,
+                    height: height / 2,
+                })
+                .collect(),
+        ),
+        Layout::LeftStack => Some(
+            split(work_area.top, height, count)
+                .map(|(y, h)| WindowBounds {
+                    x: work_area.left,
+                    y,
+                    width: width / 2,
+                    height: h,
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Split `length` from `start` into `count` (offset, size) cells; the last takes the remainder
+fn split(start: i32, length: i32, count: usize) -> impl Iterator<Item = (i32, i32)> {
+    let count = count as i32;
+    let cell = length / count;
+    (0..count).map(move |i| {
+        let offset = start + cell * i;
+        let size = if i == count - 1 {
+            length - cell * i
+        } else {
+            cell
+        };
+        (offset, size)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORK_AREA: RECT = RECT {
+        left: 0,
+        top: 0,
+        right: 1920,
+        bottom: 1040,
+    };
+
+    #[test]
+    fn test_layout_registry_round_trip() {
+        for layout in [Layout::Free, Layout::TopSplit, Layout::LeftStack] {
+            assert_eq!(Layout::from_u32(layout.as_u32()), layout);
+        }
+        assert_eq!(Layout::from_u32(99), Layout::Free);
+    }
+
+    #[test]
+    fn test_arrange_free_keeps_bounds() {
+        assert!(arrange(Layout::Free, &WORK_AREA, 2).is_none());
+        assert!(arrange(Layout::TopSplit, &WORK_AREA, 0).is_none());
+    }
+
+    #[test]
+    fn test_arrange_top_split() {
+        let bounds = arrange(Layout::TopSplit, &WORK_AREA, 2).expect("layout");
+        assert_eq!(
+            bounds,
+            [
+                WindowBounds {
+                    x: 0,
+                    y: 0,
+                    width: 960,
+                    height: 520
+                },
+                WindowBounds {
+                    x: 960,
+                    y: 0,
+                    width: 960,
+                    height: 520
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arrange_left_stack_remainder() {
+        let work_area = RECT {
+            left: 100,
+            top: 10,
+            right: 1100,
+            bottom: 1010,
+        };
+        let bounds = arrange(Layout::LeftStack, &work_area, 3).expect("layout");
+        assert_eq!(bounds.len(), 3);
+        assert!(bounds.iter().all(|b| b.x == 100 && b.width == 500));
+        assert_eq!(bounds[0].y, 10);
+        assert_eq!(bounds[1].y, 343);
+        // Last cell reaches the bottom edge
+        assert_eq!(bounds[2].y + bounds[2].height, 1010);
+    }
+
+    #[test]
+    fn test_layout_direction() {
+        assert_eq!(Layout::Free.direction(), None);
+        assert_eq!(Layout::TopSplit.direction(), Some(Direction::Top));
+        assert_eq!(Layout::LeftStack.direction(), Some(Direction::Left));
+    }
+}
@@ -0,0 +1,186 @@
+//! Logging: console + daily-rotated file under %LOCALAPPDATA%\quake-modoki\logs
+//!
+//! Level: RUST_LOG if set, otherwise the `LogLevel` registry value (default "info").
+//! The filter is reloadable so the tray can switch to TRACE at runtime.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+use thiserror::Error;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{EnvFilter, Registry, fmt, reload};
+
+use crate::settings;
+
+const LOG_LEVEL: &str = "LogLevel";
+const DEFAULT_LOG_LEVEL: &str = "info";
+const FILE_PREFIX: &str = "quake-modoki";
+const FILE_SUFFIX: &str = "log";
+
+/// Rotated files kept (one per day)
+const MAX_LOG_FILES: usize = 7;
+
+/// Total size cap for the log directory
+const MAX_LOG_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Filter directive used while debug logging is on
+const DEBUG_DIRECTIVE: &str = "trace";
+
+#[derive(Debug, Error)]
+pub enum LoggingError {
+    #[error("Logging not initialized")]
+    NotInitialized,
+
+    #[error("Filter reload failed: {0}")]
+    Reload(#[from] reload::Error),
+}
+
+/// Reload handle + startup directive (restored when debug logging is turned off)
+struct FilterControl {
+    handle: reload::Handle<EnvFilter, Registry>,
+    base: String,
+}
+
+static FILTER: OnceLock<FilterControl> = OnceLock::new();
+
+/// Log directory: %LOCALAPPDATA%\quake-modoki\logs
+pub fn log_dir() -> Option<PathBuf> {
+    std::env::var_os("LOCALAPPDATA")
+        .map(|base| PathBuf::from(base).join("quake-modoki").join("logs"))
+}
+
+/// Install global subscriber; keep the returned guard alive until exit to flush
+pub fn init() -> Option<WorkerGuard> {
+    let base = std::env::var(EnvFilter::DEFAULT_ENV)
+        .ok()
+        .or_else(|| settings::get_string(LOG_LEVEL))
+        .unwrap_or_else(|| DEFAULT_LOG_LEVEL.to_string());
+    let (filter, handle) = reload::Layer::new(EnvFilter::new(&base));
+    let _ = FILTER.set(FilterControl { handle, base });
+
+    let (file_layer, guard) = match log_dir().and_then(|dir| file_appender(&dir)) {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = fmt::layer().with_writer(writer).with_ansi(false);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer)
+        .init();
+
+    if guard.is_none() {
+        tracing::warn!("File logging unavailable (LOCALAPPDATA or log directory)");
+    }
+    guard
+}
+
+/// Switch between TRACE (debug on) and the startup level (debug off)
+pub fn set_debug(enabled: bool) -> Result<(), LoggingError> {
+    let control = FILTER.get().ok_or(LoggingError::NotInitialized)?;
+    let directive = if enabled {
+        DEBUG_DIRECTIVE
+    } else {
+        &control.base
+    };
+    control.handle.reload(EnvFilter::new(directive))?;
+    Ok(())
+}
+
+/// Create daily appender after pruning oversized history
+fn file_appender(dir: &Path) -> Option<RollingFileAppender> {
+    fs::create_dir_all(dir).ok()?;
+    prune(dir);
+
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(FILE_PREFIX)
+        .filename_suffix(FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(dir)
+        .ok()
+}
+
+/// Delete oldest log files until the directory fits MAX_LOG_BYTES
+fn prune(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let files: Vec<(PathBuf, u64, SystemTime)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let meta = entry.metadata().ok()?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            (meta.is_file() && name.starts_with(FILE_PREFIX)).then(|| {
+                (
+                    entry.path(),
+                    meta.len(),
+                    meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                )
+            })
+        })
+        .collect();
+
+    for path in files_to_prune(files, MAX_LOG_BYTES) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Oldest-first selection of files to delete so total size ≤ max_bytes
+fn files_to_prune(mut files: Vec<(PathBuf, u64, SystemTime)>, max_bytes: u64) -> Vec<PathBuf> {
+    files.sort_by_key(|(_, _, modified)| *modified);
+
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    let mut pruned = Vec::new();
+    for (path, len, _) in files {
+        if total <= max_bytes {
+            break;
+        }
+        total -= len;
+        pruned.push(path);
+    }
+    pruned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn file(name: &str, len: u64, age_secs: u64) -> (PathBuf, u64, SystemTime) {
+        (
+            PathBuf::from(name),
+            len,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 - age_secs),
+        )
+    }
+
+    #[test]
+    fn test_files_to_prune_under_cap() {
+        let files = vec![file("a", 10, 2), file("b", 10, 1)];
+        assert!(files_to_prune(files, 100).is_empty());
+    }
+
+    #[test]
+    fn test_files_to_prune_removes_oldest_first() {
+        let files = vec![file("new", 40, 1), file("old", 40, 3), file("mid", 40, 2)];
+        let pruned = files_to_prune(files, 80);
+        assert_eq!(pruned, vec![PathBuf::from("old")]);
+    }
+
+    #[test]
+    fn test_files_to_prune_until_fits() {
+        let files = vec![file("a", 50, 3), file("b", 50, 2), file("c", 50, 1)];
+        let pruned = files_to_prune(files, 50);
+        assert_eq!(pruned, vec![PathBuf::from("a"), PathBuf::from("b")]);
+    }
+}
@@ -1,50 +1,168 @@
 // Hide console in release builds (background mode)
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod adjust;
 mod animation;
+mod announce;
+mod audio;
 mod autolaunch;
+mod backup;
+#[cfg(feature = "bench")]
+mod bench;
+mod blocklist;
+mod capture;
+mod chord;
+mod cli;
+mod commands;
+mod compat;
+mod conflicts;
+mod context;
+mod controller;
+mod crash;
 mod edge;
 mod error;
+mod events;
+mod find;
+#[cfg(test)]
+mod fixture;
+mod flash;
 mod focus;
+mod hotkey;
+mod i18n;
+mod input;
+mod ipc;
+mod jumplist;
+mod launch;
+mod layout;
+mod logging;
+mod memory;
+mod mouse;
 mod notification;
+mod opacity;
+mod palette;
+mod peek;
+mod plugin;
+mod presets;
+mod process;
+mod prompt;
+mod rules;
+mod script;
+mod session;
+mod settings;
+mod shake;
+mod shellmenu;
+mod simulate;
+mod slots;
+mod snap;
+mod stats;
+mod status;
+mod swipe;
+mod sysprefs;
+mod taskbar;
+mod terminal;
 mod tracking;
 mod tray;
+mod update;
+mod win32;
+mod wizard;
 
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 use tracing::{debug, error, info, trace, warn};
 
-use animation::{AnimConfig, run_animation};
-use global_hotkey::hotkey::{Code, HotKey, Modifiers};
-use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use animation::{AnimConfig, Slide, run_group_animation};
+use controller::WindowController;
+use error::AppError;
+use global_hotkey::{GlobalHotKeyManager, HotKeyState};
 use tray::TrayState;
 use windows::Win32::Foundation::{HWND, LPARAM, POINT, RECT};
-use windows::Win32::Graphics::Gdi::{
-    GetMonitorInfoW, MONITOR_DEFAULTTONEAREST, MONITOR_DEFAULTTOPRIMARY, MONITORINFO,
-    MonitorFromPoint, MonitorFromWindow,
-};
 use windows::Win32::System::Console::{
     CTRL_BREAK_EVENT, CTRL_C_EVENT, CTRL_CLOSE_EVENT, SetConsoleCtrlHandler,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
-    DispatchMessageW, EnumWindows, GetCursorPos, GetForegroundWindow, GetWindowTextLengthW,
-    GetWindowTextW, IsWindowVisible, MSG, MWMO_INPUTAVAILABLE, MsgWaitForMultipleObjectsEx,
-    PM_REMOVE, PeekMessageW, QS_ALLINPUT, SetForegroundWindow, TranslateMessage, WM_ENDSESSION,
-    WM_QUERYENDSESSION, WM_QUIT,
+    DispatchMessageW, EnumWindows, GetForegroundWindow, GetMessageW, GetWindowTextLengthW,
+    GetWindowTextW, IsWindowVisible, MSG, PBT_APMPOWERSTATUSCHANGE, PBT_APMRESUMEAUTOMATIC,
+    SWP_NOACTIVATE, TranslateMessage, WM_ENDSESSION, WM_QUERYENDSESSION,
 };
 use windows::core::BOOL;
 
-/// Track window visibility state (atomic for thread safety)
-static WINDOW_VISIBLE: AtomicBool = AtomicBool::new(false);
-
 /// Shutdown requested via signal (Ctrl-C, console close, etc.)
 static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
 
+/// Executable offered by the "closed" toast's Relaunch button
+static RELAUNCH_EXE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Relaunch after swapping in a staged update (tray "Restart to Update")
+static RESTART_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// State owned by the event loop and passed to its handlers
+#[derive(Default)]
+struct AppState {
+    window: WindowController,
+    /// Trigger switches (read at startup, refreshed when changed from the tray)
+    triggers: TriggerFlags,
+    /// Last housekeeping pass (it rides on the edge tick while that is armed)
+    last_poll: Option<Instant>,
+    edge_config: edge::EdgeConfig,
+    edge_state: edge::EdgeState,
+    /// Per-slot edge profiles, and the edge state of parked slots with one
+    edge_profiles: [Option<edge::EdgeProfile>; slots::COUNT],
+    parked_edge_states: [edge::EdgeState; slots::COUNT],
+    /// Taskbar position (edge zone / dwell on its edge)
+    taskbar: edge::TaskbarCache,
+    /// Focus left the shown window, hide pending (`FocusLossDelayMs` / `FocusLossGraceMs`)
+    pending_hide: Option<focus::PendingHide>,
+    /// Leader-key chord state
+    chord_state: chord::ChordState,
+    /// Keyboard move / resize mode
+    adjust_state: adjust::AdjustState,
+    /// Launch and track (command line / forwarded from another instance)
+    launch_state: launch::LaunchState,
+    /// Hotkeys armed only while a configured app is focused
+    context_hotkeys: context::ContextHotkeys,
+    /// Setup wizard finished: dock the target before sliding it out
+    setup_preview: bool,
+    /// Binding the running hotkey capture will change
+    capture_binding: hotkey::Binding,
+    /// Registered hotkeys of slots 2-4 (slot, hotkey)
+    slot_hotkeys: Vec<(usize, global_hotkey::hotkey::HotKey)>,
+    /// Registered opacity hotkeys (true: more opaque, hotkey)
+    opacity_hotkeys: Vec<(bool, global_hotkey::hotkey::HotKey)>,
+    /// Start of the tray icon's attention flash
+    tray_flash: Option<Instant>,
+    /// Window untracked last ("Re-track Last Window")
+    last_untracked: Option<find::LastWindow>,
+}
+
+/// Trigger switches the timers and the mouse hook follow; cached because the
+/// loop re-arms on every pass
+#[derive(Debug, Clone, Copy, Default)]
+struct TriggerFlags {
+    edge: bool,
+    scroll: bool,
+    swipe: bool,
+    shake: bool,
+}
+
+impl TriggerFlags {
+    fn load() -> Self {
+        Self {
+            edge: edge::is_enabled(),
+            scroll: edge::is_scroll_enabled(),
+            swipe: edge::is_swipe_enabled(),
+            shake: shake::is_enabled(),
+        }
+    }
+}
+
 /// Console control handler: signal shutdown via atomic flag
 unsafe extern "system" fn ctrl_handler(ctrl_type: u32) -> BOOL {
     match ctrl_type {
         x if x == CTRL_C_EVENT || x == CTRL_BREAK_EVENT => {
             // Signal main loop to exit gracefully
             SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+            events::wake();
             BOOL(1)
         }
         x if x == CTRL_CLOSE_EVENT => {
@@ -52,181 +170,840 @@ unsafe extern "system" fn ctrl_handler(ctrl_type: u32) -> BOOL {
             // Process terminates after handler returns
             let _ = tracking::restore_original();
             SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+            events::wake();
             BOOL(1)
         }
         _ => BOOL(0),
     }
 }
 
-fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
+fn main() {
+    // Keep guard alive until exit so buffered log lines are flushed
+    let log_guard = logging::init();
+    crash::install_panic_hook();
+
+    let mut args = cli::parse(std::env::args().skip(1));
+
+    // Deployment actions (autostart install / uninstall / status): no tray, exit
+    if let Some(action) = args.action.take() {
+        let code = cli::run(action);
+        drop(log_guard);
+        std::process::exit(code);
+    }
+
+    let code = match run(args) {
+        Ok(()) => 0,
+        Err(e) => {
+            e.report();
+            1
+        }
+    };
+    drop(log_guard);
+    std::process::exit(code);
+}
+
+/// Tray app: set up, run the event loop, clean up (Err = fatal startup error)
+fn run(args: cli::Args) -> Result<(), AppError> {
+    // Command-line request with an instance already running: hand over and exit
+    if let Some(request) = &args.request
+        && ipc::forward(request)
+    {
+        info!(?request, "Request forwarded to running instance");
+        return Ok(());
+    }
+
+    // Before anything writes settings
+    let first_run = wizard::is_first_run();
+
+    // Auto-launched: let other login-time apps (and the terminal) start first
+    if args.autostart {
+        let delay = autolaunch::startup_delay();
+        if !delay.is_zero() {
+            info!(?delay, "Delaying startup after auto-launch");
+            std::thread::sleep(delay);
+        }
+    }
+
+    update::cleanup();
 
     debug!("=== Window List ===");
     list_windows();
     debug!("===================");
 
+    // Native plugins (before the tray: they contribute menu items)
+    let plugins = plugin::load_all();
+    if !plugins.is_empty() {
+        info!(?plugins, "Plugins loaded");
+    }
+
+    // Event loop window: hotkeys, menu, hooks and timers all post here
+    let receivers = events::start().map_err(|e| AppError::fatal("Event window", e))?;
+
     // Initialize system tray
-    let tray = TrayState::new().map_err(|e| anyhow::anyhow!("TrayState: {e}"))?;
+    let tray = TrayState::new(memory::delay_minutes(), &plugin::menu_items())
+        .map_err(|e| AppError::fatal("System tray", e))?;
     tray.set_autolaunch_checked(autolaunch::is_enabled());
+    tray.set_autolaunch_task_checked(autolaunch::backend() == autolaunch::Backend::TaskScheduler);
+    tray.set_shell_menu_checked(shellmenu::is_enabled());
     tray.set_edge_trigger_checked(edge::is_enabled());
+    tray.set_hide_on_track_checked(session::is_hide_on_track());
+    tray.set_always_on_top_checked(tracking::is_always_on_top());
+    tray.set_slots_checked(slots::is_enabled());
+    tray.set_hide_taskbar_checked(taskbar::is_enabled());
+    tray.set_hide_alt_tab_checked(taskbar::is_alt_tab_enabled());
+    tray.set_suppress_flash_checked(flash::is_enabled());
+    tray.set_mute_hidden_checked(audio::is_enabled());
+    tray.set_process_mode_checked(process::get_mode());
+    tray.set_hide_on_focus_loss_checked(focus::is_enabled());
+    tray.set_focus_loss_checked(focus::get_action());
+    tray.set_anim_style_checked(animation::style());
+    tray.set_show_easing_checked(animation::easing(true));
+    tray.set_hide_easing_checked(animation::easing(false));
+    tray.set_layout_checked(layout::get());
+    tray.set_trim_memory_checked(memory::is_enabled());
+    tray.set_auto_update_checked(update::is_enabled());
+    tray.set_announce_checked(announce::is_enabled());
+    tray.set_shake_checked(shake::is_enabled());
+    tray.set_edge_scroll_checked(edge::is_scroll_enabled());
+    tray.set_edge_swipe_checked(edge::is_swipe_enabled());
+    tray.apply_policy_locks(&tray::PolicyLocks {
+        edge_trigger: edge::is_locked(),
+        edge_scroll: edge::is_scroll_locked(),
+        edge_swipe: edge::is_swipe_locked(),
+        shake: shake::is_locked(),
+        always_on_top: tracking::is_always_on_top_locked(),
+        announce: announce::is_locked(),
+        auto_update: update::is_locked(),
+        hide_taskbar: taskbar::is_locked(),
+        hide_alt_tab: taskbar::is_alt_tab_locked(),
+        suppress_flash: flash::is_locked(),
+        mute_hidden: audio::is_locked(),
+        trim_memory: memory::is_locked(),
+        process_mode: process::is_locked(),
+    });
+    tray.update_stats(&stats::summary());
     info!("System tray initialized");
 
-    let manager =
-        GlobalHotKeyManager::new().map_err(|e| anyhow::anyhow!("GlobalHotKeyManager: {e}"))?;
+    let manager = GlobalHotKeyManager::new().map_err(|e| AppError::fatal("Hotkey manager", e))?;
 
-    // Toggle hotkey: F8
-    let hotkey_toggle = HotKey::new(None, Code::F8);
-    manager
-        .register(hotkey_toggle)
-        .map_err(|e| anyhow::anyhow!("Toggle hotkey register: {e}"))?;
+    // Toggle hotkey: configured binding or F8, falling back to F7 / Ctrl+` / Win+` if taken
+    let toggle = hotkey::register_toggle(&manager);
+    report_toggle_registration(&toggle, &tray);
 
-    // Tracking hotkey: Ctrl+Alt+Q
-    let hotkey_track = HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::KeyQ);
+    // Tracking hotkey: configured binding or Ctrl+Alt+Q
+    let hotkey_track = hotkey::track_hotkey();
     manager
         .register(hotkey_track)
-        .map_err(|e| anyhow::anyhow!("Track hotkey register: {e}"))?;
+        .map_err(|e| AppError::fatal("Track hotkey register", e))?;
+
+    // Re-track hotkey: configured binding or Ctrl+Alt+Shift+Q (non-fatal)
+    if let Err(e) = manager.register(hotkey::retrack_hotkey()) {
+        warn!("Re-track hotkey register failed: {e}");
+    }
 
-    info!("Hotkeys registered: F8 (toggle), Ctrl+Alt+Q (track)");
-    info!("Focus a window and press Ctrl+Alt+Q to register it, then F8 to toggle.");
+    // Track-under-cursor hotkey: configured binding or Ctrl+Alt+Shift+W (non-fatal)
+    if let Err(e) = manager.register(hotkey::track_under_cursor_hotkey()) {
+        warn!("Track-under-cursor hotkey register failed: {e}");
+    }
+
+    // Window palette hotkey: configured binding or Ctrl+Alt+Shift+P (non-fatal)
+    if let Err(e) = manager.register(hotkey::palette_hotkey()) {
+        warn!("Window palette hotkey register failed: {e}");
+    }
+
+    // Chord leader: Ctrl+Alt+Space, then T / U / G / A / L / Space / 1-4 / arrows / C (non-fatal)
+    let leader = chord::leader_hotkey();
+    let leader_id = match manager.register(leader) {
+        Ok(()) => Some(leader.id()),
+        Err(e) => {
+            warn!("Chord leader register failed: {e}");
+            None
+        }
+    };
+
+    // Slot hotkeys (F9 / F10 / F11 unless configured; non-fatal)
+    let slot_hotkeys = if slots::is_enabled() {
+        slots::register(&manager)
+    } else {
+        Vec::new()
+    };
+
+    // Opacity step hotkeys (Ctrl+Alt+PageUp / PageDown unless configured; non-fatal)
+    let opacity_hotkeys = opacity::register(&manager);
+
+    let toggle_label = hotkey::active_toggle_label().unwrap_or_else(|| "-".to_string());
+    let track_label = hotkey::label(&hotkey_track);
+    info!("Hotkeys registered: {toggle_label} (toggle), {track_label} (track)");
+    info!("Focus a window and press {track_label} to register it, then {toggle_label} to toggle.");
+
+    // Self-check: other quake-style tools competing for our hotkeys
+    check_conflicts(&manager, &track_label);
+
+    // Receive command-line requests from later instances (non-fatal)
+    if let Err(e) = ipc::start() {
+        warn!("IPC window error: {e}");
+    }
+
+    // Taskbar jump list tasks, routed through the IPC window (non-fatal)
+    if let Err(e) = jumplist::install() {
+        warn!("Jump list error: {e}");
+    }
+
+    // Lifecycle hook script (non-fatal)
+    match script::load() {
+        Ok(true) => info!(path = ?script::script_path(), "Hook script loaded"),
+        Ok(false) => {}
+        Err(e) => warn!("Hook script disabled: {e}"),
+    }
+
+    // Shell hook for flash suppression (non-fatal)
+    if let Err(e) = flash::install_hook() {
+        warn!("Flash hook error: {e}");
+    }
 
     // Install Ctrl-C handler for graceful shutdown
     unsafe { SetConsoleCtrlHandler(Some(ctrl_handler), true) }
-        .map_err(|e| anyhow::anyhow!("SetConsoleCtrlHandler: {e}"))?;
+        .map_err(|e| AppError::fatal("SetConsoleCtrlHandler", e))?;
+
+    // Background update check (non-fatal)
+    if update::is_enabled() {
+        update::spawn_check(false);
+    }
+
+    let edge_config = edge::load_config();
+    tray.set_edge_preset_checked(edge::EdgePreset::matching(&edge_config));
+    let mut app = AppState {
+        triggers: TriggerFlags::load(),
+        edge_profiles: edge::load_profiles(&edge_config),
+        edge_config,
+        context_hotkeys: context::ContextHotkeys::new(context::load_rules()),
+        slot_hotkeys,
+        opacity_hotkeys,
+        ..Default::default()
+    };
+
+    // Pick up the app tracked at the last exit, else the configured target,
+    // else a running window of a `Track` rule
+    resume_session(&tray, &mut app);
+    if !app.window.is_valid() {
+        track_default_target(&tray, &mut app, true);
+    }
+    if !app.window.is_valid() && !app.launch_state.is_pending() {
+        track_rule_window(&tray, &mut app);
+    }
+
+    // First run: offer the setup wizard (answers arrive as WM_WIZARD_DONE)
+    if first_run {
+        let mut hotkeys: Vec<_> = hotkey::active_toggle().into_iter().collect();
+        hotkeys.extend(hotkey::free_toggle_candidates(&manager));
+        info!("First run: setup wizard");
+        wizard::start(hotkeys);
+    }
+
+    run_event_loop(
+        leader_id,
+        &tray,
+        &manager,
+        &receivers,
+        &mut app,
+        args.request,
+    );
+
+    // Capture popup may still be up when exiting mid-capture
+    capture::stop();
 
-    run_event_loop(hotkey_toggle.id(), hotkey_track.id(), &tray)?;
+    // Remember the tracked app and its visibility for the next launch
+    if app.window.is_valid()
+        && let Some(exe) = app.window.exe()
+        && let Err(e) = session::save(exe, app.window.is_visible())
+    {
+        AppError::minor("Session save failed", e).report();
+    }
 
     // Restore tracked window to original state on exit
+    peek::close();
     if tracking::restore_original().is_some() {
         info!("Window restored on exit");
     }
 
-    if let Err(e) = focus::uninstall_hook() {
-        error!("Focus unhook error: {e}");
+    if let Err(e) = app.window.unwatch_focus() {
+        AppError::minor("Focus unhook error", e).report();
+    }
+    flash::uninstall_hook();
+    mouse::uninstall_hook();
+    ipc::stop();
+    events::stop();
+    plugin::unload_all();
+
+    // Swap in a staged update (restart only if requested via tray)
+    let restart = RESTART_REQUESTED.load(Ordering::SeqCst);
+    match update::apply_staged(restart) {
+        Ok(true) => info!(restart, "Staged update applied"),
+        Ok(false) => {}
+        Err(e) => AppError::minor("Update apply failed", e).report(),
     }
 
     Ok(())
 }
 
-fn run_event_loop(toggle_id: u32, track_id: u32, tray: &TrayState) -> anyhow::Result<()> {
-    let hotkey_rx = GlobalHotKeyEvent::receiver();
-    let menu_rx = tray::menu_receiver();
+/// Notify about taken toggle bindings and show the active one in the tray tooltip
+fn report_toggle_registration(registration: &hotkey::Registration, tray: &TrayState) {
+    let active = registration.active.map(|h| hotkey::label(&h));
+    let conflicts: Vec<String> = registration.conflicts.iter().map(hotkey::label).collect();
+    match (&active, conflicts.first()) {
+        (Some(active), Some(conflict)) => {
+            warn!(conflicts = ?conflicts, active = %active, "Toggle hotkey fallback");
+            notification::show_hotkey_fallback(conflict, active);
+        }
+        (None, _) => {
+            error!(conflicts = ?conflicts, "No toggle hotkey available");
+            notification::show_hotkey_unavailable(&conflicts.join(", "));
+        }
+        (Some(_), None) => {}
+    }
+    tray.set_toggle_hotkey(active.as_deref());
+}
+
+/// Warn about known tools whose usual hotkey equals one of ours
+fn check_conflicts(manager: &GlobalHotKeyManager, track_label: &str) {
+    let toggle_label = hotkey::active_toggle_label();
+    let mut active = vec![track_label];
+    active.extend(toggle_label.as_deref());
+    let candidates: Vec<String> = hotkey::free_toggle_candidates(manager)
+        .iter()
+        .map(hotkey::label)
+        .collect();
+    let check = conflicts::self_check(&active, &candidates);
+
+    for conflict in &check.conflicts {
+        info!(
+            tool = conflict.tool.name,
+            hotkey = conflict.tool.hotkey,
+            clashes = conflict.clashes,
+            "Known quake-style tool running"
+        );
+    }
+    let suggestions = if check.suggestions.is_empty() {
+        "-".to_string()
+    } else {
+        check.suggestions.join(", ")
+    };
+    for conflict in check.conflicts.iter().filter(|c| c.clashes) {
+        warn!(tool = conflict.tool.name, hotkey = conflict.tool.hotkey, suggestions = %suggestions, "Hotkey conflict");
+        notification::show_hotkey_conflict(conflict.tool.name, conflict.tool.hotkey, &suggestions);
+    }
+}
+
+/// Toggle dispatches on `hotkey::active_toggle()` (rebindable; None = edge trigger / tray only)
+/// leader_id: None when the chord leader could not be registered
+/// request: command-line request given to this instance (no other instance was running)
+fn run_event_loop(
+    leader_id: Option<u32>,
+    tray: &TrayState,
+    manager: &GlobalHotKeyManager,
+    receivers: &events::Receivers,
+    app: &mut AppState,
+    request: Option<ipc::Request>,
+) {
     let mut msg = MSG::default();
+    let mut wakeups = events::Wakeups::default();
 
-    // Edge trigger state
-    let edge_config = edge::EdgeConfig::default();
-    let mut edge_state = edge::EdgeState::default();
+    // Launch and track (command line / forwarded from another instance)
+    if let Some(request) = request {
+        handle_request(request, tray, app);
+    }
 
     loop {
-        // Check shutdown flag (set by ctrl_handler)
+        update_timers(app);
+
+        // Check shutdown flag (set by ctrl_handler / tray Exit)
         if SHUTDOWN_REQUESTED.load(Ordering::SeqCst) {
             info!("Shutdown requested");
-            return Ok(());
+            return;
         }
 
-        // Wait for message OR 16ms timeout
-        unsafe {
-            MsgWaitForMultipleObjectsEx(None, 16, QS_ALLINPUT, MWMO_INPUTAVAILABLE);
+        // Block until the next message (0 = WM_QUIT, -1 = error)
+        if unsafe { GetMessageW(&mut msg, None, 0, 0) }.0 <= 0 {
+            return;
         }
+        wakeups.record(&msg);
 
-        // Check hotkey events (non-blocking)
-        while let Ok(event) = hotkey_rx.try_recv() {
-            if event.state() == HotKeyState::Pressed {
-                match event.id() {
-                    id if id == toggle_id => {
-                        toggle_window();
-                        edge::reset_state(&mut edge_state); // Hotkey wins, reset edge
+        match msg.message {
+            WM_QUERYENDSESSION => {
+                // Allow system to proceed with logoff/shutdown
+            }
+            WM_ENDSESSION if msg.wParam.0 != 0 => {
+                info!("Session ending");
+                return;
+            }
+            events::WM_HOTKEY_EVENT => {
+                while let Ok(event) = receivers.hotkeys.try_recv() {
+                    if event.state() == HotKeyState::Pressed {
+                        handle_hotkey(event.id(), leader_id, tray, manager, app);
                     }
-                    id if id == track_id => register_foreground_with_tray(tray),
-                    _ => {}
                 }
             }
-        }
-
-        // Check menu events (non-blocking)
-        while let Ok(event) = menu_rx.try_recv() {
-            handle_menu_event(&event, tray, &mut edge_state);
-        }
-
-        // Edge trigger check (polling)
-        if edge::is_enabled()
-            && tracking::is_tracked_valid()
-            && let Some(action) = check_edge_trigger(&mut edge_state, &edge_config)
-        {
-            match action {
-                edge::EdgeAction::Show if !WINDOW_VISIBLE.load(Ordering::SeqCst) => {
-                    toggle_window();
+            events::WM_MENU_EVENT => {
+                while let Ok(event) = receivers.menu.try_recv() {
+                    handle_menu_event(&event, tray, manager, app);
                 }
-                edge::EdgeAction::Hide if WINDOW_VISIBLE.load(Ordering::SeqCst) => {
-                    toggle_window();
+            }
+            _ if events::is_timer(&msg, events::TIMER_POLL)
+                || events::is_timer(&msg, events::TIMER_WATCH) =>
+            {
+                poll(tray, manager, app)
+            }
+            _ if events::is_timer(&msg, events::TIMER_EDGE) => poll_edge(tray, manager, app),
+            events::WM_SESSION_EVENT => {
+                debug!(code = msg.wParam.0, "Session changed");
+            }
+            events::WM_POWER_EVENT => {
+                debug!(code = msg.wParam.0, "Power state changed");
+                if msg.wParam.0 == PBT_APMRESUMEAUTOMATIC as usize {
+                    // Cursor may rest on an edge after wake: start from idle
+                    edge::reset_state(&mut app.edge_state);
+                } else if msg.wParam.0 == PBT_APMPOWERSTATUSCHANGE as usize {
+                    // Battery saver / AC changes edge polling speed
+                    events::refresh_intervals();
                 }
-                _ => {}
             }
-        }
-
-        // Process Win32 messages
-        while unsafe { PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE) }.as_bool() {
-            match msg.message {
-                WM_QUIT => return Ok(()),
-                WM_QUERYENDSESSION => {
-                    // Allow system to proceed with logoff/shutdown
+            events::WM_WORK_AREA_EVENT => handle_work_area_changed(app),
+            m if m == focus::WM_FOCUS_CHANGED => {
+                // Focus moving within the group is not focus loss
+                let hwnd = HWND(msg.wParam.0 as *mut _);
+                if app.window.in_group(hwnd) {
+                    app.pending_hide = None;
+                } else if focus::delay().is_zero() && focus::grace().is_zero() {
+                    hide_on_focus_loss(tray, app, hwnd);
+                } else if let Some(pending) = &mut app.pending_hide {
+                    pending.hand_over(hwnd, Instant::now());
+                } else if app.window.is_visible() {
+                    app.pending_hide = Some(focus::PendingHide::new(hwnd, Instant::now()));
                 }
-                WM_ENDSESSION if msg.wParam.0 != 0 => {
-                    info!("Session ending");
-                    return Ok(());
+            }
+            m if m == flash::WM_TRACKED_FLASH => {
+                handle_tracked_flash(&app.window, HWND(msg.wParam.0 as *mut _));
+            }
+            m if m == notification::WM_TOAST_ACTION => {
+                if let Some(action) = notification::ToastAction::from_wparam(msg.wParam.0) {
+                    handle_toast_action(action, tray, app);
+                }
+            }
+            m if m == error::WM_ERROR_REPORTED => {
+                tray.set_last_error(error::last_error().as_deref());
+            }
+            m if m == update::WM_UPDATE_STAGED => {
+                if let Some(tag) = update::staged_tag() {
+                    tray.set_update_staged(&tag);
                 }
-                m if m == focus::WM_FOCUS_CHANGED => {
-                    handle_focus_lost();
-                    edge::reset_state(&mut edge_state); // Focus lost resets edge state
+            }
+            m if m == ipc::WM_IPC_REQUEST => {
+                for request in ipc::take_requests() {
+                    handle_request(request, tray, app);
                 }
-                _ => unsafe {
-                    let _ = TranslateMessage(&msg);
-                    DispatchMessageW(&msg);
-                },
             }
+            m if m == shake::WM_SHAKE_DETECTED => handle_shake(tray, app),
+            m if m == mouse::WM_EDGE_SCROLL => {
+                handle_edge_scroll(mouse::scroll_delta(msg.wParam.0), tray, app);
+            }
+            m if m == swipe::WM_EDGE_SWIPE => {
+                handle_edge_swipe(swipe::Swipe::unpack(msg.wParam.0), tray, app);
+            }
+            m if m == edge::WM_EDGE_CONFIG_CHANGED => {
+                reload_edge_config(tray, app);
+            }
+            m if m == wizard::WM_WIZARD_DONE => {
+                finish_setup(wizard::take(), tray, manager, app);
+            }
+            m if m == palette::WM_PALETTE_PICKED => {
+                handle_palette_pick(HWND(msg.wParam.0 as *mut _), tray, app);
+            }
+            m if m == capture::WM_HOTKEY_CAPTURED => {
+                capture::stop();
+                handle_hotkey_captured(msg.wParam.0, app.capture_binding, tray, manager);
+            }
+            _ => unsafe {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            },
         }
     }
 }
 
-/// Check edge trigger and return action if any
-fn check_edge_trigger(
-    state: &mut edge::EdgeState,
-    config: &edge::EdgeConfig,
-) -> Option<edge::EdgeAction> {
-    // Get cursor position
-    let mut cursor = POINT::default();
-    if unsafe { GetCursorPos(&mut cursor) }.is_err() {
-        return None;
+/// Arm polling timers and the mouse hook only while something needs them (idle: no wakeups)
+fn update_timers(app: &AppState) {
+    let tracked = app.window.is_tracked();
+    let flags = app.triggers;
+    let edge = tracked && flags.edge;
+    events::arm(events::TIMER_EDGE, edge);
+    events::watch_input(tracked && (flags.edge || flags.scroll));
+    let triggers = mouse::Triggers {
+        shake: tracked && flags.shake,
+        scroll: tracked && flags.scroll,
+        swipe: tracked && flags.swipe,
+    };
+    if let Err(e) = mouse::arm(triggers) {
+        AppError::minor("Shake / scroll / swipe trigger unavailable", e).report();
     }
 
-    // Get work area for monitor containing cursor
-    let monitor = unsafe { MonitorFromPoint(cursor, MONITOR_DEFAULTTONEAREST) };
-    let mut info = MONITORINFO {
-        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
-        ..Default::default()
-    };
-    if !unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool() {
-        return None;
+    // Housekeeping runs from the edge tick while that is armed
+    let busy = app.pending_hide.is_some()
+        || app.chord_state.is_pending()
+        || app.adjust_state.is_active()
+        || app.launch_state.is_pending()
+        || app.context_hotkeys.has_rules()
+        || app.tray_flash.is_some()
+        || (tracked && plugin::has_triggers());
+    events::arm(events::TIMER_POLL, busy && !edge);
+    // Otherwise a tracked window only needs the closed / memory trim check
+    events::arm(events::TIMER_WATCH, tracked && !busy && !edge);
+}
+
+/// Dispatch a pressed global hotkey
+fn handle_hotkey(
+    id: u32,
+    leader_id: Option<u32>,
+    tray: &TrayState,
+    manager: &GlobalHotKeyManager,
+    app: &mut AppState,
+) {
+    if let Some(&(slot, _)) = app.slot_hotkeys.iter().find(|(_, key)| key.id() == id) {
+        select_slot(slot, tray, app);
+        return;
+    }
+    if let Some(&(up, _)) = app.opacity_hotkeys.iter().find(|(_, key)| key.id() == id) {
+        step_opacity(tray, &app.window, up);
+        return;
+    }
+    match id {
+        // With slots on, the toggle hotkey is slot 1's
+        id if Some(id) == hotkey::active_toggle().map(|h| h.id()) && slots::is_enabled() => {
+            select_slot(0, tray, app)
+        }
+        id if Some(id) == hotkey::active_toggle().map(|h| h.id()) => {
+            if explain_nothing_tracked(app) {
+                return;
+            }
+            toggle_window(&mut app.window);
+            record_toggle(tray, &app.window, stats::Trigger::Hotkey);
+            edge::reset_state(&mut app.edge_state); // Hotkey wins, reset edge
+        }
+        id if id == hotkey::track_hotkey().id() => {
+            register_foreground_with_tray(tray, &mut app.window)
+        }
+        id if id == hotkey::retrack_hotkey().id() => retrack_last(tray, app),
+        id if id == hotkey::track_under_cursor_hotkey().id() => {
+            let hwnd = tracking::window_under_cursor().unwrap_or_default();
+            info!(hwnd = ?hwnd, "Track under cursor requested");
+            register_window_with_tray(tray, &mut app.window, hwnd);
+        }
+        id if id == hotkey::palette_hotkey().id() => palette::open(),
+        id if Some(id) == leader_id => app.chord_state.begin(manager),
+        id if app.context_hotkeys.take(id) => {
+            toggle_window(&mut app.window);
+            record_toggle(tray, &app.window, stats::Trigger::Hotkey);
+            edge::reset_state(&mut app.edge_state);
+        }
+        id => {
+            if let Some(step) = app.adjust_state.take(manager, id) {
+                handle_adjust(step, &mut app.window);
+            } else if let Some(action) = app.chord_state.take(manager, id) {
+                handle_chord(action, tray, manager, app);
+            }
+        }
+    }
+}
+
+/// Toggle pressed with nothing tracked: explain how to register a window
+/// (toast with a Track button) and flash the tray icon; false if tracked
+fn explain_nothing_tracked(app: &mut AppState) -> bool {
+    if app.window.is_valid() || app.window.is_closed() {
+        return false;
+    }
+    warn!("No tracked window - press the track hotkey to register one");
+    // One toast per flash (repeated presses while it runs add nothing)
+    if app
+        .tray_flash
+        .is_none_or(|since| since.elapsed() >= tray::FLASH_DURATION)
+    {
+        notification::show_nothing_tracked(&hotkey::label(&hotkey::track_hotkey()));
+        app.tray_flash = Some(Instant::now());
+    }
+    true
+}
+
+/// Housekeeping timer: sources without a notification of their own
+/// Every 50 ms while something is pending (or from the edge tick), else once a
+/// second while a window is tracked (see `update_timers`)
+fn poll(tray: &TrayState, manager: &GlobalHotKeyManager, app: &mut AppState) {
+    app.last_poll = Some(Instant::now());
+
+    // Chord mode expiry
+    app.chord_state.poll(manager);
+
+    // Tray icon attention flash
+    if let Some(since) = app.tray_flash
+        && !tray.flash_frame(since.elapsed())
+    {
+        app.tray_flash = None;
+    }
+
+    // Adjust mode: ends with the window hidden / gone, or after idling
+    if app.adjust_state.is_active() && !(app.window.is_valid() && app.window.is_visible()) {
+        app.adjust_state.end(manager);
+    }
+    if let Some(step) = app.adjust_state.poll(manager) {
+        handle_adjust(step, &mut app.window);
+    }
+
+    // Context hotkeys follow the foreground app
+    app.context_hotkeys.poll(manager);
+
+    // Plugin triggers
+    if app.window.is_valid()
+        && let Some(action) = plugin::poll_trigger()
+    {
+        let visible = app.window.is_visible();
+        let wanted = match action {
+            plugin::TriggerAction::Toggle => true,
+            plugin::TriggerAction::Show => !visible,
+            plugin::TriggerAction::Hide => visible,
+        };
+        if wanted {
+            toggle_window(&mut app.window);
+            record_toggle(tray, &app.window, stats::Trigger::Other);
+            edge::reset_state(&mut app.edge_state);
+        }
+    }
+
+    // Launched app's first window
+    if app.launch_state.is_pending()
+        && let Some(launched) = app.launch_state.poll()
+    {
+        register_window_with_tray(tray, &mut app.window, launched.hwnd);
+        if launched.class == Some(terminal::CLASS) && app.window.hwnd() == launched.hwnd {
+            terminal::remember(launched.hwnd);
+            dock_window(
+                &mut app.window,
+                Some(animation::Direction::Top),
+                (100, terminal::height_pct()),
+            );
+        }
+        if launched.hide {
+            hide_startup_target(app);
+        }
+    }
+
+    // Delayed hide on focus loss (focus coming back to the group cancels it;
+    // the hook reports no change back to the tracked window itself)
+    if let Some(pending) = app.pending_hide {
+        let foreground = unsafe { GetForegroundWindow() };
+        if app.window.in_group(foreground) || !app.window.is_visible() {
+            app.pending_hide = None;
+        } else if pending.is_due(foreground, Instant::now(), focus::delay(), focus::grace()) {
+            app.pending_hide = None;
+            hide_on_focus_loss(tray, app, foreground);
+        }
+    }
+
+    // Working-set trim of long-hidden window
+    memory::poll();
+
+    // Tracked window destroyed by its owner
+    if app.window.is_closed() {
+        handle_tracked_closed(tray, app);
+    }
+}
+
+/// Edge timer: cursor position against the screen edge (and housekeeping
+/// every `POLL_INTERVAL`, as its own timer is off meanwhile)
+fn poll_edge(tray: &TrayState, manager: &GlobalHotKeyManager, app: &mut AppState) {
+    if app
+        .last_poll
+        .is_none_or(|last| last.elapsed() >= events::POLL_INTERVAL)
+    {
+        poll(tray, manager, app);
+    }
+    if !app.triggers.edge || !app.window.is_valid() {
+        return;
+    }
+    match check_edge_trigger(app, edge::EdgeInput::Dwell) {
+        Some(edge::EdgeAction::Show) if !app.window.is_visible() => {
+            toggle_window(&mut app.window);
+            record_toggle(tray, &app.window, stats::Trigger::Edge);
+        }
+        Some(edge::EdgeAction::Hide) if app.window.is_visible() => {
+            toggle_window(&mut app.window);
+            record_toggle(tray, &app.window, stats::Trigger::Edge);
+        }
+        _ => {
+            if let Some(slot) = check_parked_edges(app) {
+                info!(slot = slot + 1, "Parked slot shown from its edge");
+                select_slot(slot, tray, app);
+                // Now the active slot: hide once the cursor leaves
+                app.edge_state = edge::EdgeState::Active;
+            }
+        }
+    }
+}
+
+/// Parked slot whose own edge profile shows it (only while the active
+/// slot's window is hidden and its edge trigger idle)
+fn check_parked_edges(app: &mut AppState) -> Option<usize> {
+    let active = app.window.slots().active();
+    let idle = !app.window.is_visible() && matches!(app.edge_state, edge::EdgeState::Idle);
+    let api = win32::api();
+    let cursor = api.cursor_pos()?;
+    let work_area = api.work_area_at(cursor)?;
+    for slot in 0..slots::COUNT {
+        let parked = app.window.slots().parked(slot);
+        let (Some(profile), Some(parked)) = (app.edge_profiles[slot], parked) else {
+            continue;
+        };
+        if !idle || slot == active || parked.visible {
+            edge::reset_state(&mut app.parked_edge_states[slot]);
+            continue;
+        }
+        let direction = profile
+            .direction
+            .unwrap_or_else(|| tracking::calc_direction(&parked.bounds, &work_area));
+        let zone = edge_zone(app, &work_area, cursor, direction, input::last_kind());
+        let action = edge::check_and_transition(
+            &mut app.parked_edge_states[slot],
+            &profile.config,
+            &zone,
+            false,
+            cursor,
+            None,
+        );
+        if action == Some(edge::EdgeAction::Show) {
+            app.parked_edge_states = Default::default();
+            return Some(slot);
+        }
+    }
+    None
+}
+
+/// Mouse shake: toggle like the hotkey
+fn handle_shake(tray: &TrayState, app: &mut AppState) {
+    if !app.window.is_valid() {
+        return;
+    }
+    debug!("Mouse shake detected");
+    toggle_window(&mut app.window);
+    record_toggle(tray, &app.window, stats::Trigger::Other);
+    edge::reset_state(&mut app.edge_state);
+}
+
+/// Wheel tick: show / hide when the cursor rests at the trigger edge
+fn handle_edge_scroll(delta: i32, tray: &TrayState, app: &mut AppState) {
+    if !app.window.is_valid() {
+        return;
+    }
+    if let Some(action) = check_edge_trigger(app, edge::EdgeInput::Scroll(delta)) {
+        debug!(?action, delta, "Scroll at edge");
+        toggle_window(&mut app.window);
+        record_toggle(tray, &app.window, stats::Trigger::Edge);
+    }
+}
+
+/// Touch swipe: show when it came in from the trigger edge
+fn handle_edge_swipe(swipe: swipe::Swipe, tray: &TrayState, app: &mut AppState) {
+    if !app.window.is_valid() {
+        return;
+    }
+    if let Some(action) = check_edge_trigger(app, edge::EdgeInput::Swipe(swipe)) {
+        debug!(?action, ?swipe, "Swipe from edge");
+        toggle_window(&mut app.window);
+        record_toggle(tray, &app.window, stats::Trigger::Edge);
+    }
+}
+
+/// Count a toggle (only when a window is tracked) and refresh the tray line
+fn record_toggle(tray: &TrayState, window: &WindowController, trigger: stats::Trigger) {
+    if window.is_valid() {
+        stats::record_toggle(trigger);
     }
-    let work_area = info.rcWork;
+    tray.update_stats(&stats::summary());
+}
+
+/// Check edge trigger for `input` and return action if any
+fn check_edge_trigger(app: &mut AppState, input: edge::EdgeInput) -> Option<edge::EdgeAction> {
+    // Cursor position (swipe: its start) and work area of the monitor containing it
+    let api = win32::api();
+    let cursor = match input {
+        edge::EdgeInput::Swipe(swipe) => swipe.from,
+        _ => api.cursor_pos()?,
+    };
+    let work_area = api.work_area_at(cursor)?;
 
-    // Get window bounds and direction
-    let bounds = tracking::load_bounds();
-    let direction = bounds
-        .as_ref()
-        .map(|b| tracking::calc_direction(b, &work_area))
+    // Active slot's edge profile, else the global config and the window's side
+    let profile = app.edge_profiles[app.window.slots().active()];
+    let config = profile.map_or(app.edge_config, |p| p.config);
+    let bounds = app.window.bounds();
+    let direction = profile
+        .and_then(|p| p.direction)
+        .or_else(|| {
+            bounds
+                .as_ref()
+                .map(|b| tracking::calc_direction(b, &work_area))
+        })
         .unwrap_or(animation::Direction::Left);
 
-    let visible = WINDOW_VISIBLE.load(Ordering::SeqCst);
+    let kind = match input {
+        edge::EdgeInput::Swipe(_) => input::InputKind::Touch,
+        _ => input::last_kind(),
+    };
+    let zone = edge_zone(app, &work_area, cursor, direction, kind);
+    match input {
+        edge::EdgeInput::Scroll(delta) => edge::check_scroll(
+            &mut app.edge_state,
+            &config,
+            &zone,
+            app.window.is_visible(),
+            cursor,
+            delta,
+        ),
+        edge::EdgeInput::Swipe(swipe) => edge::check_swipe(
+            &mut app.edge_state,
+            &config,
+            &zone,
+            app.window.is_visible(),
+            swipe.from,
+            swipe.to,
+        ),
+        edge::EdgeInput::Dwell => edge::check_and_transition(
+            &mut app.edge_state,
+            &config,
+            &zone,
+            app.window.is_visible(),
+            cursor,
+            bounds.as_ref(),
+        ),
+    }
+}
 
-    edge::check_and_transition(
-        state,
-        config,
+/// Trigger zone of `direction`'s edge on the monitor under `cursor`
+fn edge_zone<'a>(
+    app: &mut AppState,
+    work_area: &'a RECT,
+    cursor: POINT,
+    direction: animation::Direction,
+    kind: input::InputKind,
+) -> edge::EdgeZone<'a> {
+    // Interior edge: another monitor continues past it in the cursor's row/column
+    let api = win32::api();
+    let exterior = api.monitor_at(cursor).is_none_or(|monitor| {
+        api.monitor_at(edge::beyond_edge(direction, &monitor, cursor))
+            .is_none()
+    });
+    edge::EdgeZone {
         direction,
-        visible,
-        cursor,
-        &work_area,
-        bounds.as_ref(),
-    )
+        work_area,
+        taskbar: app.taskbar.get(),
+        exterior,
+        input: kind,
+    }
 }
 
 fn list_windows() {
@@ -252,36 +1029,37 @@ fn list_windows() {
     }
 }
 
-/// Get monitor work area for a window
-fn get_work_area(hwnd: HWND) -> Option<RECT> {
-    let monitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTOPRIMARY) };
-    let mut info = MONITORINFO {
-        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
-        ..Default::default()
-    };
-    if unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool() {
-        Some(info.rcWork)
-    } else {
-        None
+/// Configured animation with the tracked window's rule style, as far as the
+/// system's visual-effect settings allow
+fn anim_config(window: &WindowController) -> AnimConfig {
+    AnimConfig {
+        style: window.overrides().anim_style(),
+        ..AnimConfig::load()
     }
+    .follow_system()
 }
 
-fn toggle_window() {
+fn toggle_window(window: &mut WindowController) {
+    peek::close();
     // Get tracked window (registered via Ctrl+Alt+Q)
-    if !tracking::is_tracked_valid() {
+    if window.is_closed() {
+        AppError::recoverable("Toggle failed", "tracked window no longer exists").report();
+        return;
+    }
+    if !window.is_valid() {
         warn!("No tracked window - press Ctrl+Alt+Q to register");
         return;
     }
 
-    let hwnd = tracking::get_tracked();
-    let config = AnimConfig::default();
-    let currently_visible = WINDOW_VISIBLE.load(Ordering::SeqCst);
+    let hwnd = window.hwnd();
+    let config = anim_config(window);
+    let currently_visible = window.is_visible();
 
     // Get work area for direction calculation
-    let work_area = match get_work_area(hwnd) {
+    let work_area = match tracking::get_work_area(hwnd) {
         Some(wa) => wa,
         None => {
-            error!("GetMonitorInfo failed");
+            AppError::recoverable("Toggle failed", "GetMonitorInfo failed").report();
             return;
         }
     };
@@ -289,165 +1067,1349 @@ fn toggle_window() {
     if currently_visible {
         // === SLIDE OUT (visible → hidden) ===
         // 1. Capture current bounds BEFORE hiding
-        let bounds = match tracking::save_bounds(hwnd) {
+        let bounds = match window.save_bounds() {
             Some(b) => b,
             None => {
-                error!("GetWindowRect failed");
+                AppError::recoverable("Toggle failed", "GetWindowRect failed").report();
                 return;
             }
         };
 
-        // 2. Calculate direction based on overlap
-        let direction = tracking::calc_direction(&bounds, &work_area);
+        // 2. Calculate direction based on overlap (or the layout's side)
+        let direction = slide_direction(window, &bounds, &work_area);
 
         // 3. Restore focus before animation starts
-        let prev = focus::get_previous();
+        let prev = window.previous_foreground();
         if prev != HWND::default() {
-            let _ = unsafe { SetForegroundWindow(prev) };
+            win32::api().set_foreground(prev);
         }
 
         // 4. Slide out
-        run_animation(hwnd, &config, direction, &bounds, &work_area, false);
-        WINDOW_VISIBLE.store(false, Ordering::SeqCst);
+        if !plugin::hide(hwnd) {
+            slide_group(window, &config, direction, &work_area, false);
+        }
+        finish_hide(window, hwnd);
         info!(direction = ?direction, "Window: focus restored → slide out → hidden");
     } else {
         // === SLIDE IN (hidden → visible) ===
-        // 1. Load stored bounds or capture current position
-        let bounds = tracking::load_bounds()
-            .unwrap_or_else(|| tracking::save_bounds(hwnd).expect("GetWindowRect failed"));
+        // 1. Place per layout, else load stored bounds or capture current position
+        apply_layout(window, &work_area, layout::on_show());
+        let bounds = window
+            .bounds()
+            .unwrap_or_else(|| window.save_bounds().expect("GetWindowRect failed"));
 
-        // 2. Calculate direction based on stored position
-        let direction = tracking::calc_direction(&bounds, &work_area);
+        // 2. Calculate direction based on stored position (or the layout's side)
+        let direction = slide_direction(window, &bounds, &work_area);
 
         // 3. Save current foreground window before taking focus
-        let prev = unsafe { GetForegroundWindow() };
-        focus::save_previous(prev);
+        let prev = win32::api().foreground();
+        window.save_previous_foreground(prev);
 
-        // 4. Slide in
-        run_animation(hwnd, &config, direction, &bounds, &work_area, true);
-        let _ = unsafe { SetForegroundWindow(hwnd) };
-        focus::set_target(hwnd);
-        if let Err(e) = focus::install_hook(hwnd) {
-            error!("Focus hook error: {e}");
+        // 4. Slide in (resume process + taskbar button before the window shows)
+        process::restore(hwnd);
+        memory::clear(hwnd);
+        taskbar::show(hwnd);
+        audio::unmute(hwnd);
+        if !plugin::show(hwnd) {
+            slide_group(window, &config, direction, &work_area, true);
+        }
+        win32::api().set_foreground(hwnd);
+        flash::reset_notified();
+        if let Err(e) = window.watch_focus() {
+            AppError::recoverable("Focus hook error", e).report();
         }
-        WINDOW_VISIBLE.store(true, Ordering::SeqCst);
+        window.set_visible(true);
+        stats::mark_shown();
+        announce::shown();
+        fire_event(window, script::Event::Show);
         info!(direction = ?direction, "Window: slide in → visible + focused");
     }
 }
 
-fn handle_focus_lost() {
-    if !WINDOW_VISIBLE.load(Ordering::SeqCst) {
+/// Focus left the tracked window (and its group): hide it
+fn hide_on_focus_loss(tray: &TrayState, app: &mut AppState, foreground: HWND) {
+    handle_focus_lost(&mut app.window, foreground);
+    tray.update_stats(&stats::summary());
+    edge::reset_state(&mut app.edge_state); // Focus lost resets edge state
+}
+
+fn handle_focus_lost(window: &mut WindowController, foreground: HWND) {
+    if !window.is_visible() || !window.is_valid() {
         return;
     }
-
-    let target = focus::get_target();
-    if target == HWND::default() {
+    // Turned off globally (event still in flight) or by a per-app rule
+    if !focus::is_enabled() || !window.overrides().hides_on_focus_loss() {
+        debug!("Focus lost: kept shown");
+        return;
+    }
+    // Snipping tool, IME candidates, clipboard manager, ...
+    if focus::is_ignored(foreground) {
+        debug!(hwnd = ?foreground, "Focus moved to an ignored app: kept shown");
         return;
     }
+    let action = focus::get_action();
+    let target = window.hwnd();
 
     // Get work area
-    let work_area = match get_work_area(target) {
+    let work_area = match tracking::get_work_area(target) {
         Some(wa) => wa,
         None => {
-            error!("GetMonitorInfo failed");
+            AppError::recoverable("Hide on focus loss failed", "GetMonitorInfo failed").report();
             return;
         }
     };
 
     // Capture current bounds before hiding
-    let bounds = match tracking::save_bounds(target) {
+    let bounds = match window.save_bounds() {
         Some(b) => b,
         None => {
-            error!("GetWindowRect failed");
+            AppError::recoverable("Hide on focus loss failed", "GetWindowRect failed").report();
             return;
         }
     };
 
-    // Calculate direction based on overlap
-    let direction = tracking::calc_direction(&bounds, &work_area);
+    // Calculate direction based on overlap (or the layout's side)
+    let direction = slide_direction(window, &bounds, &work_area);
 
-    let config = AnimConfig::default();
-    run_animation(target, &config, direction, &bounds, &work_area, false);
-    WINDOW_VISIBLE.store(false, Ordering::SeqCst);
-    info!(direction = ?direction, "Window: focus lost → hidden");
+    // Instant: the same slide finished in one frame (windows end up off screen)
+    let config = match action {
+        focus::FocusLossAction::Instant => AnimConfig {
+            duration_ms: 0,
+            ..anim_config(window)
+        },
+        focus::FocusLossAction::Slide => anim_config(window),
+    };
+    if !plugin::hide(target) {
+        slide_group(window, &config, direction, &work_area, false);
+    }
+    finish_hide(window, target);
+    info!(direction = ?direction, action = ?action, "Window: focus lost → hidden");
 }
 
-/// Handle tray menu events
-fn handle_menu_event(event: &muda::MenuEvent, tray: &TrayState, edge_state: &mut edge::EdgeState) {
-    let id = event.id();
+/// After the slide-out (hotkey / edge and focus loss alike): hidden-state side
+/// effects, bookkeeping, announcement and the Hide event
+fn finish_hide(window: &mut WindowController, hwnd: HWND) {
+    tracking::restore_z_order(&window.members());
+    taskbar::hide(hwnd);
+    audio::mute(hwnd);
+    process::apply(hwnd, window.overrides().hidden_mode());
+    memory::mark_hidden(hwnd);
+    stats::mark_hidden();
+    window.set_visible(false);
+    announce::hidden();
+    fire_event(window, script::Event::Hide);
+}
 
-    if tray.is_exit(id) {
-        info!("Exit requested via tray menu");
-        SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
-    } else if tray.is_untrack(id) {
-        // Untrack: restore window and clear status
-        if tracking::restore_original().is_some() {
-            info!("Window untracked via tray menu");
-        }
-        if let Err(e) = focus::uninstall_hook() {
-            error!("Focus unhook error: {e}");
-        }
-        WINDOW_VISIBLE.store(false, Ordering::SeqCst);
-        edge::reset_state(edge_state);
-        tray.update_status(None);
-    } else if tray.is_autolaunch(id) {
-        // Toggle auto-launch
-        match autolaunch::toggle() {
-            Ok(enabled) => {
-                tray.set_autolaunch_checked(enabled);
-                info!(enabled, "Auto-launch toggled");
-            }
-            Err(e) => {
-                error!("Auto-launch toggle failed: {e}");
-            }
-        }
-    } else if tray.is_edge_trigger(id) {
-        // Toggle edge trigger
-        match edge::toggle() {
-            Ok(enabled) => {
-                tray.set_edge_trigger_checked(enabled);
-                edge::reset_state(edge_state);
-                info!(enabled, "Edge trigger toggled");
-            }
-            Err(e) => {
-                error!("Edge trigger toggle failed: {e}");
-            }
+/// Slide direction: the per-app rule's side, else the layout preset's, else from
+/// the window's overlap
+fn slide_direction(
+    window: &WindowController,
+    bounds: &tracking::WindowBounds,
+    work_area: &RECT,
+) -> animation::Direction {
+    window
+        .overrides()
+        .direction
+        .or_else(|| layout::on_show().direction())
+        .unwrap_or_else(|| tracking::calc_direction(bounds, work_area))
+}
+
+/// Taskbar moved / resized / auto-hide toggled, or displays changed: refresh the
+/// taskbar position, keep stored bounds inside their work area, restart edge detection
+fn handle_work_area_changed(app: &mut AppState) {
+    app.taskbar.invalidate();
+    edge::reset_state(&mut app.edge_state);
+
+    let api = win32::api();
+    let mut moved = Vec::new();
+    for (hwnd, bounds) in app.window.members_with_bounds() {
+        // Hidden members sit off screen: use the monitor of their stored bounds
+        let center = POINT {
+            x: bounds.x + bounds.width / 2,
+            y: bounds.y + bounds.height / 2,
+        };
+        let Some(work_area) = api.work_area_at(center) else {
+            continue;
+        };
+        let clamped = tracking::clamp_to_work_area(&bounds, &work_area);
+        if clamped != bounds {
+            app.window.set_member_bounds(hwnd, clamped);
+            moved.push((hwnd, clamped));
         }
     }
+    if app.window.is_visible() && !moved.is_empty() {
+        place(&moved);
+    }
+    info!(clamped = moved.len(), "Work area changed");
 }
 
-/// Register foreground window with tray status update
-fn register_foreground_with_tray(tray: &TrayState) {
-    // Restore previous tracked window before registering new one
-    if tracking::restore_original().is_some() {
-        info!("Previous window restored");
+/// Store `layout`'s bounds as slide-in targets (no-op for Free)
+fn apply_layout(window: &mut WindowController, work_area: &RECT, layout: layout::Layout) {
+    let count = 1 + window.group().len();
+    if let Some(bounds) = layout::arrange(layout, work_area, count) {
+        window.set_layout_bounds(&bounds);
     }
+}
 
-    let hwnd = unsafe { GetForegroundWindow() };
-    if hwnd == HWND::default() {
-        warn!("No foreground window");
-        tray.update_status(None);
+/// Move a shown group into the active layout right away
+fn arrange_now(window: &mut WindowController) {
+    if !window.is_valid() || !window.is_visible() || layout::get() == layout::Layout::Free {
         return;
     }
+    let Some(work_area) = tracking::get_work_area(window.hwnd()) else {
+        return;
+    };
+    apply_layout(window, &work_area, layout::get());
+    place(&window.members_with_bounds());
+}
 
-    let title = tracking::get_window_title(hwnd);
-
-    // Save original state before tracking
-    if tracking::save_original(hwnd).is_none() {
-        warn!("Failed to save original state");
+/// Snap the tracked window's stored bounds to a preset (moved now if shown)
+fn snap_window(tray: &TrayState, window: &mut WindowController, snap: snap::Snap) {
+    if !window.is_valid() {
+        warn!("No tracked window to snap");
+        return;
     }
-
-    tracking::set_tracked(hwnd);
-    tracking::save_bounds(hwnd);
-    focus::set_target(hwnd);
-    if let Err(e) = focus::install_hook(hwnd) {
-        error!("Focus hook error: {e}");
+    let Some(work_area) = tracking::get_work_area(window.hwnd()) else {
+        AppError::recoverable("Snap failed", "GetMonitorInfo failed").report();
+        return;
+    };
+    let bounds = snap.bounds(&work_area);
+    window.set_bounds(bounds);
+
+    // A group layout would replace the snapped bounds on the next show
+    if layout::get() != layout::Layout::Free {
+        if let Err(e) = layout::set(layout::Layout::Free) {
+            AppError::recoverable("Group layout save failed", e).report();
+        }
+        tray.set_layout_checked(layout::get());
+    }
+    if window.is_visible() {
+        place(&[(window.hwnd(), bounds)]);
+    }
+    info!(?snap, ?bounds, "Window snapped");
+}
+
+/// Discard stored bounds: group layout, else the configured default position
+/// (moved now if shown)
+fn reset_position(window: &mut WindowController) {
+    if !window.is_valid() {
+        warn!("No tracked window to reset");
+        return;
+    }
+    let Some(work_area) = tracking::get_work_area(window.hwnd()) else {
+        AppError::recoverable("Reset position failed", "GetMonitorInfo failed").report();
+        return;
+    };
+    if layout::get() == layout::Layout::Free {
+        window.set_bounds(snap::default_bounds(&work_area));
+    } else {
+        apply_layout(window, &work_area, layout::get());
+    }
+    if window.is_visible() {
+        place(&window.members_with_bounds());
+    }
+    info!(bounds = ?window.bounds(), "Position reset");
+}
+
+/// Put a shown, drifted window back into its layout without a hide / show:
+/// the group layout, else docked to its side (the rule's, else the nearest)
+/// at the rule's size or the configured default share
+fn relayout_now(window: &mut WindowController) {
+    if !window.is_valid() || !window.is_visible() {
+        warn!("No shown window to lay out");
+        return;
+    }
+    if layout::get() != layout::Layout::Free {
+        arrange_now(window);
+        info!("Group layout re-applied");
+        return;
+    }
+    let overrides = window.overrides();
+    let (Some(work_area), Some(bounds)) = (tracking::get_work_area(window.hwnd()), window.bounds())
+    else {
+        return;
+    };
+    let dock = overrides
+        .direction
+        .unwrap_or_else(|| tracking::calc_direction(&bounds, &work_area));
+    dock_window(
+        window,
+        Some(dock),
+        overrides.size.unwrap_or_else(|| snap::default_size(dock)),
+    );
+    info!(?dock, bounds = ?window.bounds(), "Layout re-applied");
+}
+
+/// Apply and remember the tracked app's opacity
+fn set_opacity(tray: &TrayState, window: &WindowController, percent: u32) {
+    let exe = window.exe().filter(|_| window.is_valid());
+    let Some(exe) = exe else {
+        warn!("No tracked window for opacity");
+        tray.set_opacity_checked(100);
+        return;
+    };
+    opacity::apply(window.hwnd(), percent);
+    tray.set_opacity_checked(percent);
+    if let Err(e) = opacity::set(exe, percent) {
+        AppError::recoverable("Opacity not saved", e).report();
+    }
+    info!(percent, exe, "Opacity changed");
+}
+
+/// Step the shown tracked window's opacity by one hotkey press
+fn step_opacity(tray: &TrayState, window: &WindowController, up: bool) {
+    if !(window.is_valid() && window.is_visible()) {
+        return;
+    }
+    let percent = opacity::step(opacity::current(window.hwnd()), up);
+    set_opacity(tray, window, percent);
+}
+
+/// Apply stored edge threshold / delays (tray preset or custom prompt)
+fn reload_edge_config(tray: &TrayState, app: &mut AppState) {
+    app.edge_config = edge::load_config();
+    app.triggers = TriggerFlags::load();
+    app.edge_profiles = edge::load_profiles(&app.edge_config);
+    edge::reset_state(&mut app.edge_state);
+    app.parked_edge_states = Default::default();
+    tray.set_edge_preset_checked(edge::EdgePreset::matching(&app.edge_config));
+    debug!(config = ?app.edge_config, "Edge trigger config reloaded");
+}
+
+/// Move shown windows to their bounds without animation
+fn place(targets: &[(HWND, tracking::WindowBounds)]) {
+    let moves: Vec<win32::Move> = targets
+        .iter()
+        .map(|&(hwnd, bounds)| win32::Move {
+            hwnd,
+            x: bounds.x,
+            y: bounds.y,
+            width: bounds.width,
+            height: bounds.height,
+            flags: SWP_NOACTIVATE,
+        })
+        .collect();
+    win32::api().move_windows(&moves);
+}
+
+/// Slide the tracked window and its group (staggered) in or out
+fn slide_group(
+    window: &WindowController,
+    config: &AnimConfig,
+    direction: animation::Direction,
+    work_area: &RECT,
+    slide_in: bool,
+) {
+    let members = window.members_with_bounds();
+    let delays = animation::stagger_delays(members.len(), animation::group_stagger(), slide_in);
+    let slides: Vec<Slide> = members
+        .into_iter()
+        .zip(delays)
+        .map(|((hwnd, bounds), delay)| Slide {
+            hwnd,
+            bounds,
+            delay,
+            compat: compat::for_window(hwnd),
+        })
+        .collect();
+    run_group_animation(&slides, config, direction, work_area, slide_in);
+}
+
+/// Hidden tracked window flashed: stop it and raise a toast instead
+fn handle_tracked_flash(window: &WindowController, hwnd: HWND) {
+    if window.is_visible() || !flash::is_enabled() {
+        return;
+    }
+
+    flash::stop(hwnd);
+    if flash::should_notify() {
+        notification::show_attention(&tracking::get_window_title(hwnd));
+    }
+    debug!(hwnd = ?hwnd, "Flash suppressed (hidden)");
+}
+
+/// Handle tray menu events
+fn handle_menu_event(
+    event: &muda::MenuEvent,
+    tray: &TrayState,
+    manager: &GlobalHotKeyManager,
+    app: &mut AppState,
+) {
+    let id = event.id();
+
+    if tray.is_exit(id) {
+        info!("Exit requested via tray menu");
+        SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+    } else if tray.is_toggle(id) {
+        toggle_window(&mut app.window);
+        record_toggle(tray, &app.window, stats::Trigger::Other);
+        edge::reset_state(&mut app.edge_state);
+    } else if tray.is_track(id) {
+        // The menu holds the foreground: track the window that had it before
+        info!("Track requested via tray menu");
+        let hwnd = tracking::topmost_app_window().unwrap_or_default();
+        register_window_with_tray(tray, &mut app.window, hwnd);
+    } else if tray.is_untrack(id) {
+        info!("Untrack requested via tray menu");
+        untrack(tray, app);
+    } else if tray.is_retrack(id) {
+        info!("Re-track requested via tray menu");
+        retrack_last(tray, app);
+    } else if tray.is_reset_position(id) {
+        info!("Reset position requested via tray menu");
+        reset_position(&mut app.window);
+    } else if tray.is_peek(id) {
+        if app.window.is_valid() {
+            if let Err(e) = peek::show(app.window.hwnd(), app.window.is_visible()) {
+                AppError::recoverable("Peek failed", e).report();
+            }
+        } else {
+            warn!("No tracked window to peek at");
+        }
+    } else if tray.is_relayout(id) {
+        info!("Layout requested via tray menu");
+        relayout_now(&mut app.window);
+    } else if tray.is_terminal(id) {
+        info!("Windows Terminal preset requested via tray menu");
+        launch_terminal(tray, app);
+    } else if tray.is_autolaunch(id) {
+        // Toggle auto-launch
+        match autolaunch::toggle() {
+            Ok(enabled) => {
+                tray.set_autolaunch_checked(enabled);
+                info!(enabled, "Auto-launch toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Auto-launch toggle failed", e).report();
+            }
+        }
+    } else if tray.is_autolaunch_task(id) {
+        // Switch auto-launch backend (Run key <-> elevated scheduled task)
+        let use_task = autolaunch::backend() != autolaunch::Backend::TaskScheduler;
+        let backend = if use_task {
+            autolaunch::Backend::TaskScheduler
+        } else {
+            autolaunch::Backend::RunKey
+        };
+        match autolaunch::set_backend(backend) {
+            Ok(()) => {
+                tray.set_autolaunch_task_checked(use_task);
+                info!(?backend, "Auto-launch backend changed");
+            }
+            Err(e) => {
+                tray.set_autolaunch_task_checked(!use_task);
+                AppError::recoverable("Auto-launch backend change failed", e).report();
+            }
+        }
+    } else if tray.is_shell_menu(id) {
+        // Toggle Explorer context menu verb
+        match shellmenu::toggle() {
+            Ok(enabled) => {
+                tray.set_shell_menu_checked(enabled);
+                info!(enabled, "Explorer context menu toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Explorer context menu toggle failed", e).report();
+            }
+        }
+    } else if tray.is_hide_on_track(id) {
+        // Toggle hide when tracked
+        match session::toggle_hide_on_track() {
+            Ok(enabled) => {
+                tray.set_hide_on_track_checked(enabled);
+                info!(enabled, "Hide when tracked toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Hide when tracked toggle failed", e).report();
+            }
+        }
+    } else if let Some(preset) = tray.edge_preset(id) {
+        match edge::save_config(&preset.config()) {
+            Ok(()) => {
+                info!(?preset, "Edge trigger preset selected");
+                reload_edge_config(tray, app);
+            }
+            Err(e) => {
+                tray.set_edge_preset_checked(edge::EdgePreset::matching(&app.edge_config));
+                AppError::recoverable("Edge trigger preset not saved", e).report();
+            }
+        }
+    } else if let Some(percent) = tray.opacity(id) {
+        set_opacity(tray, &app.window, percent);
+    } else if tray.is_edge_custom(id) {
+        // Keep the check on the active preset until the prompt is confirmed
+        tray.set_edge_preset_checked(edge::EdgePreset::matching(&app.edge_config));
+        edge::edit_custom(app.edge_config);
+    } else if tray.is_edge_trigger(id) {
+        // Toggle edge trigger
+        match edge::toggle() {
+            Ok(enabled) => {
+                app.triggers.edge = enabled;
+                tray.set_edge_trigger_checked(enabled);
+                edge::reset_state(&mut app.edge_state);
+                info!(enabled, "Edge trigger toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Edge trigger toggle failed", e).report();
+            }
+        }
+    } else if tray.is_edge_scroll(id) {
+        // Toggle scroll at edge (hook follows on the next loop pass)
+        match edge::toggle_scroll() {
+            Ok(enabled) => {
+                app.triggers.scroll = enabled;
+                tray.set_edge_scroll_checked(enabled);
+                info!(enabled, "Scroll at edge toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Scroll at edge toggle failed", e).report();
+            }
+        }
+    } else if tray.is_edge_swipe(id) {
+        // Toggle swipe from edge (hook follows on the next loop pass)
+        match edge::toggle_swipe() {
+            Ok(enabled) => {
+                app.triggers.swipe = enabled;
+                tray.set_edge_swipe_checked(enabled);
+                info!(enabled, "Swipe from edge toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Swipe from edge toggle failed", e).report();
+            }
+        }
+    } else if tray.is_always_on_top(id) {
+        match tracking::toggle_always_on_top() {
+            Ok(enabled) => {
+                tray.set_always_on_top_checked(enabled);
+                let members = app.window.members();
+                if enabled {
+                    tracking::apply_always_on_top(&members);
+                } else {
+                    tracking::release_always_on_top(&members, app.window.is_visible());
+                }
+                info!(enabled, "Always on top toggled");
+            }
+            Err(e) => AppError::recoverable("Always on top toggle failed", e).report(),
+        }
+    } else if tray.is_shake(id) {
+        // Toggle mouse shake trigger (hook follows on the next loop pass)
+        match shake::toggle() {
+            Ok(enabled) => {
+                app.triggers.shake = enabled;
+                tray.set_shake_checked(enabled);
+                info!(enabled, "Shake trigger toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Shake trigger toggle failed", e).report();
+            }
+        }
+    } else if tray.is_announce(id) {
+        // Toggle screen reader announcements
+        match announce::toggle() {
+            Ok(enabled) => {
+                tray.set_announce_checked(enabled);
+                info!(enabled, "Screen reader announcements toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Screen reader announcements toggle failed", e).report();
+            }
+        }
+    } else if tray.is_debug_logging(id) {
+        // Flip log filter between TRACE and startup level (not persisted)
+        // muda has already flipped the check mark on click
+        let enabled = tray.is_debug_logging_checked();
+        match logging::set_debug(enabled) {
+            Ok(()) => {
+                info!(enabled, "Debug logging toggled");
+            }
+            Err(e) => {
+                tray.set_debug_logging_checked(!enabled);
+                AppError::recoverable("Debug logging toggle failed", e).report();
+            }
+        }
+    } else if tray.is_clear_error(id) {
+        error::clear_last_error();
+        tray.set_last_error(None);
+    } else if tray.is_about(id) {
+        show_status(app);
+    } else if let Some(position) = tray.plugin_item(id) {
+        plugin::on_menu_item(position);
+    } else if tray.is_export_settings(id) {
+        if let Some(path) = backup::pick_export_path() {
+            match backup::export(&path) {
+                Ok(()) => {
+                    info!(path = ?path, "Settings exported");
+                    notification::show_settings_exported(&path.display().to_string());
+                }
+                Err(e) => {
+                    AppError::recoverable("Settings export failed", e).report();
+                }
+            }
+        }
+    } else if tray.is_import_settings(id) {
+        if let Some(path) = backup::pick_import_path() {
+            match backup::import(&path) {
+                Ok(imported) => {
+                    info!(path = ?path, ?imported, "Settings imported");
+                    notification::show_settings_imported(imported);
+                }
+                Err(e) => {
+                    AppError::recoverable("Settings import failed", e).report();
+                }
+            }
+        }
+    } else if tray.is_change_hotkey(id) {
+        info!("Hotkey capture requested via tray menu");
+        start_capture(hotkey::Binding::Toggle, app);
+    } else if tray.is_change_track_hotkey(id) {
+        info!("Track hotkey capture requested via tray menu");
+        start_capture(hotkey::Binding::Track, app);
+    } else if tray.is_check_update(id) {
+        info!("Update check requested via tray menu");
+        update::spawn_check(true);
+    } else if tray.is_auto_update(id) {
+        // Toggle startup update check
+        match update::toggle() {
+            Ok(enabled) => {
+                tray.set_auto_update_checked(enabled);
+                info!(enabled, "Auto update toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Auto update toggle failed", e).report();
+            }
+        }
+    } else if tray.is_restart_update(id) {
+        // Exit normally; staged binary is swapped in and relaunched after cleanup
+        info!("Restart to update requested via tray menu");
+        RESTART_REQUESTED.store(true, Ordering::SeqCst);
+        SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+    } else if tray.is_hide_taskbar(id) {
+        // Toggle hide-from-taskbar (takes effect on next slide-out)
+        match taskbar::toggle() {
+            Ok(enabled) => {
+                tray.set_hide_taskbar_checked(enabled);
+                info!(enabled, "Hide from taskbar toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Hide from taskbar toggle failed", e).report();
+            }
+        }
+    } else if tray.is_hide_alt_tab(id) {
+        // Toggle hide-from-Alt-Tab (takes effect on next slide-out)
+        match taskbar::toggle_alt_tab() {
+            Ok(enabled) => {
+                tray.set_hide_alt_tab_checked(enabled);
+                info!(enabled, "Hide from Alt-Tab toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Hide from Alt-Tab toggle failed", e).report();
+            }
+        }
+    } else if tray.is_suppress_flash(id) {
+        // Toggle flash suppression
+        match flash::toggle() {
+            Ok(enabled) => {
+                tray.set_suppress_flash_checked(enabled);
+                info!(enabled, "Flash suppression toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Flash suppression toggle failed", e).report();
+            }
+        }
+    } else if tray.is_mute_hidden(id) {
+        // Toggle mute-while-hidden (takes effect on next slide-out)
+        match audio::toggle() {
+            Ok(enabled) => {
+                tray.set_mute_hidden_checked(enabled);
+                info!(enabled, "Mute while hidden toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Mute while hidden toggle failed", e).report();
+            }
+        }
+    } else if tray.is_trim_memory(id) {
+        // Toggle working-set trim (checked when the hidden delay elapses)
+        match memory::toggle() {
+            Ok(enabled) => {
+                tray.set_trim_memory_checked(enabled);
+                info!(enabled, "Trim memory toggled");
+            }
+            Err(e) => {
+                AppError::recoverable("Trim memory toggle failed", e).report();
+            }
+        }
+    } else if let Some(mode) = tray.process_mode(id) {
+        // Select hidden process mode (takes effect on next slide-out)
+        match process::set_mode(mode) {
+            Ok(()) => info!(mode = ?mode, "Hidden process mode set"),
+            Err(e) => AppError::recoverable("Hidden process mode save failed", e).report(),
+        }
+        tray.set_process_mode_checked(process::get_mode());
+    } else if tray.is_hide_on_focus_loss(id) {
+        // Install / drop the focus hook right away
+        match focus::toggle_enabled() {
+            Ok(enabled) => {
+                tray.set_hide_on_focus_loss_checked(enabled);
+                let result = if enabled && app.window.is_visible() {
+                    app.window.watch_focus()
+                } else {
+                    app.window.unwatch_focus()
+                };
+                if let Err(e) = result {
+                    AppError::recoverable("Focus hook error", e).report();
+                }
+                info!(enabled, "Hide on focus loss toggled");
+            }
+            Err(e) => AppError::recoverable("Hide on focus loss toggle failed", e).report(),
+        }
+    } else if let Some(action) = tray.focus_loss_action(id) {
+        match focus::set_action(action) {
+            Ok(()) => info!(action = ?action, "Focus-loss action set"),
+            Err(e) => AppError::recoverable("Focus-loss action save failed", e).report(),
+        }
+        tray.set_focus_loss_checked(focus::get_action());
+    } else if let Some(style) = tray.anim_style(id) {
+        match animation::set_style(style) {
+            Ok(()) => info!(style = style.name(), "Animation style set"),
+            Err(e) => AppError::recoverable("Animation style save failed", e).report(),
+        }
+        tray.set_anim_style_checked(animation::style());
+    } else if let Some(easing) = tray.show_easing(id) {
+        match animation::set_easing(true, easing) {
+            Ok(()) => info!(easing = easing.name(), "Slide-in easing set"),
+            Err(e) => AppError::recoverable("Slide-in easing save failed", e).report(),
+        }
+        tray.set_show_easing_checked(animation::easing(true));
+    } else if let Some(easing) = tray.hide_easing(id) {
+        match animation::set_easing(false, easing) {
+            Ok(()) => info!(easing = easing.name(), "Slide-out easing set"),
+            Err(e) => AppError::recoverable("Slide-out easing save failed", e).report(),
+        }
+        tray.set_hide_easing_checked(animation::easing(false));
+    } else if tray.is_slots_enabled(id) {
+        match slots::toggle_enabled() {
+            Ok(enabled) => {
+                slots::unregister(manager, &std::mem::take(&mut app.slot_hotkeys));
+                if enabled {
+                    app.slot_hotkeys = slots::register(manager);
+                }
+                tray.set_slots_checked(enabled);
+                info!(enabled, "Slot hotkeys toggled");
+            }
+            Err(e) => AppError::recoverable("Slot hotkeys toggle failed", e).report(),
+        }
+    } else if let Some(slot) = tray.slot(id) {
+        select_slot(slot, tray, app);
+    } else if let Some(preset) = tray.preset(id) {
+        apply_preset(tray, app, preset);
+    } else if let Some(snap) = tray.snap(id) {
+        snap_window(tray, &mut app.window, snap);
+    } else if let Some(layout) = tray.layout(id) {
+        // Select group layout (applied now if shown, else on next slide-in)
+        match layout::set(layout) {
+            Ok(()) => {
+                info!(?layout, "Group layout set");
+                arrange_now(&mut app.window);
+            }
+            Err(e) => AppError::recoverable("Group layout save failed", e).report(),
+        }
+        tray.set_layout_checked(layout::get());
     }
-    WINDOW_VISIBLE.store(true, Ordering::SeqCst);
+}
+
+/// Untrack: restore window, drop hooks and clear status
+fn untrack(tray: &TrayState, app: &mut AppState) {
+    peek::close();
+    if app.window.is_tracked() {
+        let hwnd = app.window.hwnd();
+        app.last_untracked = Some(find::LastWindow {
+            hwnd: hwnd.0 as isize,
+            exe: app.window.exe().map(str::to_string),
+            title: tracking::get_window_title(hwnd),
+        });
+    }
+    if app.window.is_valid() {
+        fire_event(&app.window, script::Event::Untrack);
+    }
+    if tracking::restore_original_except(&app.window.slots().parked_windows()).is_some() {
+        info!("Window untracked");
+    }
+    match app.window.release() {
+        Ok(true) => stats::mark_hidden(),
+        Ok(false) => {}
+        Err(e) => AppError::minor("Focus unhook error", e).report(),
+    }
+    edge::reset_state(&mut app.edge_state);
+    tray.update_status(None, None);
+    tray.set_opacity_checked(100);
+    // Explicitly untracked (or closed): not resumed on the next launch
+    if let Err(e) = session::clear() {
+        AppError::minor("Session clear failed", e).report();
+    }
+}
+
+/// Track the window untracked last again (found anew if it was closed)
+fn retrack_last(tray: &TrayState, app: &mut AppState) {
+    let Some(last) = app.last_untracked.clone() else {
+        info!("Nothing untracked to re-track");
+        return;
+    };
+    let Some(hwnd) = last.resolve() else {
+        warn!(exe = ?last.exe, title = %last.title, "Last untracked window not found");
+        return;
+    };
+    register_window_with_tray(tray, &mut app.window, hwnd);
+    if app.window.hwnd() == hwnd {
+        app.last_untracked = None;
+        info!(title = %last.title, "Last untracked window re-tracked");
+    }
+}
+
+/// Palette pick: toggle the tracked window, track any other
+fn handle_palette_pick(hwnd: HWND, tray: &TrayState, app: &mut AppState) {
+    if app.window.is_tracked() && app.window.hwnd() == hwnd {
+        toggle_window(&mut app.window);
+        record_toggle(tray, &app.window, stats::Trigger::Hotkey);
+        edge::reset_state(&mut app.edge_state);
+        return;
+    }
+    register_window_with_tray(tray, &mut app.window, hwnd);
+}
+
+/// Track the app saved at the last exit again, hidden if it was (or `ResumeHidden`)
+fn resume_session(tray: &TrayState, app: &mut AppState) {
+    let Some(session) = session::load() else {
+        return;
+    };
+    let Some(hwnd) = launch::find_window_by_exe(&session.exe) else {
+        debug!(exe = %session.exe, "Last tracked app not running");
+        return;
+    };
+    register_window_with_tray(tray, &mut app.window, hwnd);
+    let hidden = session.start_hidden();
+    if hidden && app.window.is_visible() {
+        toggle_window(&mut app.window);
+    }
+    info!(exe = %session.exe, hidden, "Tracking resumed");
+}
+
+/// Track the configured startup target, launching it if not running
+/// hide: slide it out once tracked (startup)
+fn track_default_target(tray: &TrayState, app: &mut AppState, hide: bool) {
+    let Some(target) = session::default_target() else {
+        return;
+    };
+    let window_exe = session::default_target_exe();
+    let exe = window_exe.as_deref().unwrap_or(&target.program);
+    if let Some(hwnd) = launch::find_window_by_exe(exe) {
+        register_window_with_tray(tray, &mut app.window, hwnd);
+        if hide {
+            hide_startup_target(app);
+        }
+        info!(program = %target.program, "Default target tracked");
+        return;
+    }
+    let started = match &window_exe {
+        Some(exe) => app.launch_state.start_for_exe(&target, exe, hide),
+        None => app.launch_state.start_target(&target, hide),
+    };
+    if let Err(e) = started {
+        AppError::recoverable(format!("Launch failed ({})", target.program), e).report();
+    }
+}
+
+/// Make `preset` the startup target and track it now (shown, docked by its rule)
+fn apply_preset(tray: &TrayState, app: &mut AppState, preset: presets::Preset) {
+    match presets::apply(preset) {
+        Ok(()) => track_default_target(tray, app, false),
+        Err(e) => {
+            AppError::recoverable(format!("Preset not applied ({})", preset.name()), e).report()
+        }
+    }
+}
+
+/// Slide the just-tracked startup target out (docked first after the setup wizard)
+fn hide_startup_target(app: &mut AppState) {
+    if std::mem::take(&mut app.setup_preview) {
+        reset_position(&mut app.window);
+    }
+    if app.window.is_visible() {
+        toggle_window(&mut app.window);
+    }
+}
+
+/// Apply the setup wizard's answers, then preview them with the target
+fn finish_setup(
+    setup: Option<wizard::Setup>,
+    tray: &TrayState,
+    manager: &GlobalHotKeyManager,
+    app: &mut AppState,
+) {
+    let Some(setup) = setup else {
+        info!("Setup wizard skipped");
+        if let Err(e) = wizard::mark_done() {
+            AppError::minor("Setup state save failed", e).report();
+        }
+        return;
+    };
+    info!(?setup, "Setup wizard finished");
+    if let Err(e) = wizard::save(&setup) {
+        AppError::recoverable("Setup not saved", e).report();
+    }
+
+    match setup.hotkey {
+        wizard::HotkeyChoice::Use(new) => match hotkey::rebind_toggle(manager, new) {
+            Ok(()) => tray.set_toggle_hotkey(hotkey::active_toggle_label().as_deref()),
+            Err(e) => AppError::recoverable("Toggle hotkey not changed", e).report(),
+        },
+        wizard::HotkeyChoice::Capture => start_capture(hotkey::Binding::Toggle, app),
+    }
+
+    // Preview: the target slides out to the chosen edge
+    if setup.preset.is_some() || setup.target.is_some() {
+        app.setup_preview = true;
+        track_default_target(tray, app, true);
+    }
+
+    let toggle_label = hotkey::active_toggle_label().unwrap_or_else(|| "-".to_string());
+    notification::show_setup_done(&toggle_label, &hotkey::label(&hotkey::track_hotkey()));
+}
+
+/// Tracked window destroyed: release hidden-state side effects, offer relaunch
+fn handle_tracked_closed(tray: &TrayState, app: &mut AppState) {
+    let exe = app.window.exe().map(str::to_string);
+    let hwnd = app.window.hwnd();
+    // restore_original skips destroyed windows; clear our own state explicitly
+    let _ = tracking::restore_original_except(&app.window.slots().parked_windows());
+    process::restore(hwnd);
+    audio::unmute(hwnd);
+    memory::clear(hwnd);
+    untrack(tray, app);
+
+    let name = exe
+        .as_deref()
+        .and_then(|path| std::path::Path::new(path).file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| i18n::t(i18n::Text::TrackedWindow).to_string());
+    info!(exe = ?exe, "Tracked window closed");
+    *RELAUNCH_EXE.lock().unwrap_or_else(|e| e.into_inner()) = exe.clone();
+    notification::show_closed(&name, exe.is_some());
+}
+
+/// Run the user script hook and configured command for `event` with the tracked window
+fn fire_event(window: &WindowController, event: script::Event) {
+    let hwnd = window.hwnd();
+    let info = script::WindowInfo {
+        title: tracking::get_window_title(hwnd),
+        exe: window.exe().unwrap_or_default().to_string(),
+        hwnd: hwnd.0 as usize,
+    };
+    script::fire(event, &info);
+    commands::run(event, &info);
+}
+
+/// Show the About / Status panel
+fn show_status(app: &AppState) {
+    let toggle_label = hotkey::active_toggle_label()
+        .unwrap_or_else(|| i18n::t(i18n::Text::StatusNoHotkey).to_string());
+    let track_label = hotkey::label(&hotkey::track_hotkey());
+    let leader_label = hotkey::label(&chord::leader_hotkey());
+    let bindings = [
+        (
+            toggle_label.as_str(),
+            i18n::t(i18n::Text::StatusToggleAction),
+        ),
+        (track_label.as_str(), i18n::t(i18n::Text::StatusTrackAction)),
+        (
+            leader_label.as_str(),
+            i18n::t(i18n::Text::StatusLeaderAction),
+        ),
+    ];
+    let info = status::collect(&bindings, &app.edge_config, &app.window);
+    let report = status::format_report(&info);
+    debug!("Status report:\n{report}");
+    status::show(report);
+}
+
+/// Run a command-line / jump list request
+fn handle_request(request: ipc::Request, tray: &TrayState, app: &mut AppState) {
+    info!(?request, "Request");
+    match request {
+        ipc::Request::Launch(path) => start_launch(&mut app.launch_state, &path),
+        ipc::Request::Find { title, class } => find_and_track(tray, app, &title, class.as_deref()),
+        ipc::Request::Toggle => {
+            toggle_window(&mut app.window);
+            record_toggle(tray, &app.window, stats::Trigger::Other);
+            edge::reset_state(&mut app.edge_state);
+        }
+        ipc::Request::Terminal => launch_terminal(tray, app),
+        ipc::Request::Untrack => untrack(tray, app),
+        ipc::Request::About => show_status(app),
+    }
+}
+
+/// Track the best running window matching a title pattern (optional class)
+fn find_and_track(tray: &TrayState, app: &mut AppState, title: &str, class: Option<&str>) {
+    match find::Query::parse(title, class).and_then(|query| find::find(&query)) {
+        Ok(hwnd) => {
+            info!(hwnd = ?hwnd, title, class, "Found window tracked");
+            register_window_with_tray(tray, &mut app.window, hwnd);
+        }
+        Err(e) => AppError::recoverable("Window not tracked", e).report(),
+    }
+}
+
+/// Windows Terminal preset: show its window if open, else launch it
+fn launch_terminal(tray: &TrayState, app: &mut AppState) {
+    if let Some(hwnd) = terminal::window() {
+        if app.window.hwnd() != hwnd {
+            register_window_with_tray(tray, &mut app.window, hwnd);
+        }
+        if app.window.is_valid() && !app.window.is_visible() {
+            toggle_window(&mut app.window);
+        }
+        return;
+    }
+    if let Err(e) = app
+        .launch_state
+        .start_for_class(&terminal::target(), terminal::CLASS)
+    {
+        AppError::recoverable("Windows Terminal launch failed", e).report();
+    }
+}
+
+/// Track a running window of the first `Track` rule that has one
+fn track_rule_window(tray: &TrayState, app: &mut AppState) {
+    let rules = rules::load();
+    if let Some((rule, hwnd)) = rules::find_tracked_window(&rules) {
+        info!(rule = %rule.name, hwnd = ?hwnd, "Rule window tracked");
+        register_window_with_tray(tray, &mut app.window, hwnd);
+    }
+}
+
+/// Start an exe / shortcut; its first window is tracked by the event loop
+fn start_launch(state: &mut launch::LaunchState, path: &std::path::Path) {
+    if let Err(e) = state.start(path) {
+        AppError::recoverable(format!("Launch failed ({})", path.display()), e).report();
+    }
+}
+
+/// Run a leader-key chord action
+fn handle_chord(
+    action: chord::ChordAction,
+    tray: &TrayState,
+    manager: &GlobalHotKeyManager,
+    app: &mut AppState,
+) {
+    debug!(?action, "Chord");
+    match action {
+        chord::ChordAction::Track => register_foreground_with_tray(tray, &mut app.window),
+        chord::ChordAction::Untrack => untrack(tray, app),
+        chord::ChordAction::Group => add_foreground_to_group(&mut app.window),
+        chord::ChordAction::Adjust => begin_adjust(manager, app),
+        chord::ChordAction::Relayout => relayout_now(&mut app.window),
+        chord::ChordAction::Slot(n) if slots::is_enabled() => {
+            select_slot(usize::from(n).saturating_sub(1), tray, app)
+        }
+        // Slots off: slot 1 is the tracked window
+        chord::ChordAction::Toggle | chord::ChordAction::Slot(1) => {
+            if explain_nothing_tracked(app) {
+                return;
+            }
+            toggle_window(&mut app.window);
+            record_toggle(tray, &app.window, stats::Trigger::Hotkey);
+            edge::reset_state(&mut app.edge_state);
+        }
+        chord::ChordAction::Slot(n) => info!(slot = n, "Chord slot empty (slots off)"),
+        chord::ChordAction::Snap(snap) => snap_window(tray, &mut app.window, snap),
+        chord::ChordAction::Cancel => {}
+    }
+}
+
+/// Bring up `slot` (0-based): toggle it if active, else park the active window
+/// and take over the slot's (an empty slot tracks the focused window)
+fn select_slot(slot: usize, tray: &TrayState, app: &mut AppState) {
+    edge::reset_state(&mut app.edge_state);
+    if slot == app.window.slots().active() {
+        if app.window.is_valid() {
+            toggle_window(&mut app.window);
+            record_toggle(tray, &app.window, stats::Trigger::Hotkey);
+        } else {
+            register_foreground_with_tray(tray, &mut app.window);
+        }
+        return;
+    }
+
+    if let Err(e) = app.window.park() {
+        AppError::minor("Focus unhook error", e).report();
+    }
+    app.window.slots_mut().set_active(slot);
+    let parked = app
+        .window
+        .slots_mut()
+        .take(slot)
+        .filter(|p| win32::api().is_window(p.hwnd));
+    let Some(parked) = parked else {
+        info!(slot = slot + 1, "Empty slot: tracking the focused window");
+        register_foreground_with_tray(tray, &mut app.window);
+        return;
+    };
+
+    if let Err(e) = app.window.unpark(parked) {
+        AppError::recoverable("Focus hook error", e).report();
+    }
+    let title = tracking::get_window_title(parked.hwnd);
+    if let Some(rule) = matching_rule(&app.window, &title) {
+        app.window.set_overrides(rule.overrides);
+    }
+    tray.update_status(Some(&title), app.window.exe());
+    info!(slot = slot + 1, title = %title, "Slot selected");
+    toggle_window(&mut app.window);
+    record_toggle(tray, &app.window, stats::Trigger::Hotkey);
+}
+
+/// Enter keyboard adjust mode for the shown tracked window
+fn begin_adjust(manager: &GlobalHotKeyManager, app: &mut AppState) {
+    if !app.window.is_valid() || !app.window.is_visible() {
+        warn!("Adjust mode needs a shown tracked window");
+        return;
+    }
+    let Some(bounds) = tracking::window_bounds(app.window.hwnd()) else {
+        AppError::recoverable("Adjust mode failed", "GetWindowRect failed").report();
+        return;
+    };
+    if app.adjust_state.begin(manager, bounds) {
+        info!(
+            step = adjust::step_px(),
+            "Adjust mode: arrows move, Shift+arrows resize, Enter / Esc"
+        );
+    } else {
+        warn!("Adjust mode unavailable (arrow keys taken)");
+    }
+}
+
+/// Apply an adjust mode key press; the final bounds become the slide-in target
+fn handle_adjust(step: adjust::AdjustStep, window: &mut WindowController) {
+    if !window.is_valid() {
+        return;
+    }
+    let hwnd = window.hwnd();
+    match step {
+        adjust::AdjustStep::Moved(bounds) => place(&[(hwnd, bounds)]),
+        adjust::AdjustStep::Done(bounds) => {
+            window.set_bounds(bounds);
+            info!(?bounds, "Adjust mode: bounds kept");
+        }
+        adjust::AdjustStep::Cancelled(bounds) => {
+            place(&[(hwnd, bounds)]);
+            window.set_bounds(bounds);
+            info!("Adjust mode: cancelled");
+        }
+    }
+}
+
+/// Show the capture popup for `binding`
+fn start_capture(binding: hotkey::Binding, app: &mut AppState) {
+    let prompt = match binding {
+        hotkey::Binding::Toggle => i18n::Text::PressNewShortcut,
+        hotkey::Binding::Track => i18n::Text::PressNewTrackShortcut,
+    };
+    app.capture_binding = binding;
+    if let Err(e) = capture::start(prompt) {
+        AppError::recoverable("Hotkey capture failed", e).report();
+    }
+}
+
+/// Validate and apply a captured hotkey to `binding` (wparam 0 = cancelled)
+fn handle_hotkey_captured(
+    wparam: usize,
+    binding: hotkey::Binding,
+    tray: &TrayState,
+    manager: &GlobalHotKeyManager,
+) {
+    let Some((mods, vk)) = capture::decode(wparam) else {
+        info!("Hotkey capture cancelled");
+        return;
+    };
+    let result = capture::to_hotkey(mods, vk).and_then(|h| match binding {
+        hotkey::Binding::Toggle => hotkey::rebind_toggle(manager, h),
+        hotkey::Binding::Track => hotkey::rebind_track(manager, h),
+    });
+    match (result, binding) {
+        (Ok(()), hotkey::Binding::Toggle) => {
+            let label = hotkey::active_toggle_label();
+            info!(hotkey = ?label, "Toggle hotkey changed");
+            tray.set_toggle_hotkey(label.as_deref());
+            if let Some(label) = label {
+                notification::show_hotkey_changed(&label);
+            }
+        }
+        (Ok(()), hotkey::Binding::Track) => {
+            let label = hotkey::label(&hotkey::track_hotkey());
+            info!(hotkey = %label, "Track hotkey changed");
+            tray.set_track_hotkey(&label);
+            notification::show_track_hotkey_changed(&label);
+        }
+        (Err(e), _) => {
+            warn!(binding = ?binding, "Hotkey not changed: {e}");
+            notification::show_hotkey_change_failed(&e.to_string());
+        }
+    }
+}
+
+/// Route a toast button click
+fn handle_toast_action(action: notification::ToastAction, tray: &TrayState, app: &mut AppState) {
+    info!(action = ?action, "Toast action");
+    match action {
+        notification::ToastAction::Untrack => untrack(tray, app),
+        notification::ToastAction::Hide => {
+            if app.window.is_valid() && app.window.is_visible() {
+                toggle_window(&mut app.window);
+                record_toggle(tray, &app.window, stats::Trigger::Other);
+                edge::reset_state(&mut app.edge_state);
+            }
+        }
+        notification::ToastAction::Track => {
+            let hwnd = tracking::topmost_app_window().unwrap_or_default();
+            register_window_with_tray(tray, &mut app.window, hwnd);
+        }
+        notification::ToastAction::Relaunch => {
+            let exe = RELAUNCH_EXE
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take();
+            if let Some(exe) = exe
+                && let Err(e) = std::process::Command::new(&exe).spawn()
+            {
+                AppError::recoverable(format!("Relaunch failed ({exe})"), e).report();
+            }
+        }
+    }
+}
+
+/// Add the foreground window to the tracked window's group
+fn add_foreground_to_group(window: &mut WindowController) {
+    let hwnd = unsafe { GetForegroundWindow() };
+    if let Some(blocked) = blocklist::check(hwnd) {
+        warn!(hwnd = ?hwnd, ?blocked, "Window not added to group");
+        notification::show_cannot_track(blocked.reason());
+        return;
+    }
+    // Saved like the tracked window: restored on untrack / exit
+    if window.add_to_group(hwnd) {
+        tracking::save_original(hwnd);
+        info!(hwnd = ?hwnd, title = %tracking::get_window_title(hwnd), "Window added to group");
+    } else {
+        warn!(hwnd = ?hwnd, "Not added to group (nothing tracked, or already tracked / grouped)");
+    }
+}
+
+/// Match the per-app rules against a newly tracked window and apply its size
+fn apply_rule(window: &mut WindowController, title: &str) {
+    let Some(rule) = matching_rule(window, title) else {
+        return;
+    };
+    info!(rule = %rule.name, overrides = ?rule.overrides, "Per-app rule matched");
+    window.set_overrides(rule.overrides);
+
+    // Size: docked to the rule's side (else the current one) like the default position
+    if let Some(size) = rule.overrides.size {
+        dock_window(window, rule.overrides.direction, size);
+    }
+}
+
+/// First per-app rule matching the tracked window
+fn matching_rule(window: &WindowController, title: &str) -> Option<rules::Rule> {
+    let class = tracking::get_window_class(window.hwnd());
+    let info = rules::WindowInfo {
+        exe: window.exe(),
+        class: &class,
+        title,
+    };
+    rules::find(&rules::load(), &info).cloned()
+}
+
+/// Resize the tracked window docked to `dock` (else its current side), size in percent
+fn dock_window(
+    window: &mut WindowController,
+    dock: Option<animation::Direction>,
+    size: (u32, u32),
+) {
+    let hwnd = window.hwnd();
+    let (Some(work_area), Some(bounds)) = (tracking::get_work_area(hwnd), window.bounds()) else {
+        return;
+    };
+    let dock = dock.unwrap_or_else(|| tracking::calc_direction(&bounds, &work_area));
+    let bounds = snap::docked_bounds(dock, size.0, size.1, &work_area);
+    window.set_bounds(bounds);
+    place(&[(hwnd, bounds)]);
+}
+
+/// Register foreground window with tray status update
+fn register_foreground_with_tray(tray: &TrayState, window: &mut WindowController) {
+    let hwnd = unsafe { GetForegroundWindow() };
+    register_window_with_tray(tray, window, hwnd);
+}
+
+fn register_window_with_tray(tray: &TrayState, window: &mut WindowController, hwnd: HWND) {
+    // Refused targets leave the current tracking untouched
+    if let Some(blocked) = (hwnd != HWND::default())
+        .then(|| blocklist::check(hwnd))
+        .flatten()
+    {
+        warn!(hwnd = ?hwnd, ?blocked, "Window not trackable");
+        notification::show_cannot_track(blocked.reason());
+        return;
+    }
+
+    // Restore previous tracked window before registering new one (parked slots stay)
+    peek::close();
+    let parked = window.slots().parked_windows();
+    if tracking::restore_original_except(&parked).is_some() {
+        info!("Previous window restored");
+    }
+
+    if hwnd == HWND::default() {
+        warn!("No foreground window");
+        tray.update_status(None, None);
+        return;
+    }
+
+    let title = tracking::get_window_title(hwnd);
+
+    // Save original state before tracking (a parked window's is already stored)
+    if parked.contains(&hwnd) {
+        window.slots_mut().remove(hwnd);
+    } else if tracking::save_original(hwnd).is_none() {
+        warn!("Failed to save original state");
+    }
+
+    if let Err(e) = window.track(hwnd) {
+        AppError::recoverable("Focus hook error", e).report();
+    }
+
+    // Per-app rule, then opacity (a rule's wins over the tray choice)
+    apply_rule(window, &title);
+    let percent = window
+        .overrides()
+        .opacity
+        .or_else(|| window.exe().map(opacity::get))
+        .unwrap_or(100);
+    opacity::apply(hwnd, percent);
+    tray.set_opacity_checked(percent);
+    tracking::apply_always_on_top(&[hwnd]);
+    stats::mark_shown();
 
     // Update tray status
-    tray.update_status(Some(&title));
+    tray.update_status(Some(&title), window.exe());
 
     notification::show_tracked(&title);
+    announce::tracked(&title);
+    fire_event(window, script::Event::Track);
     info!(hwnd = ?hwnd, title = %title, "Window tracked (visible)");
+
+    // Tuck the window away until summoned
+    if session::is_hide_on_track() {
+        toggle_window(window);
+    }
 }
@@ -0,0 +1,140 @@
+//! Working-set trim: page out the tracked process after it stays hidden for a while
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::{CloseHandle, HWND};
+use windows::Win32::System::Threading::{
+    OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SET_QUOTA, SetProcessWorkingSetSize,
+};
+
+use crate::settings::{self, SettingsError};
+
+const TRIM_MEMORY: &str = "TrimMemory";
+const TRIM_DELAY_MIN: &str = "TrimMemoryDelayMin";
+const DEFAULT_TRIM_DELAY_MIN: u32 = 10;
+
+/// Hidden process awaiting trim
+#[derive(Debug, Clone, Copy)]
+struct Pending {
+    pid: u32,
+    since: Instant,
+}
+
+/// Pending trims by hidden window
+static PENDING: Mutex<BTreeMap<isize, Pending>> = Mutex::new(BTreeMap::new());
+
+/// Check if working-set trim enabled in registry (default: off)
+pub fn is_enabled() -> bool {
+    settings::get_bool(TRIM_MEMORY, false)
+}
+
+/// Check if working-set trim is fixed by policy
+pub fn is_locked() -> bool {
+    settings::is_locked(TRIM_MEMORY)
+}
+
+/// Toggle working-set trim, returns new state
+pub fn toggle() -> Result<bool, SettingsError> {
+    settings::toggle_bool(TRIM_MEMORY, false)
+}
+
+/// Minutes hidden before trimming (registry-only, default 10)
+pub fn delay_minutes() -> u32 {
+    settings::get_u32(TRIM_DELAY_MIN, DEFAULT_TRIM_DELAY_MIN)
+}
+
+/// Start hidden timer after slide-out
+pub fn mark_hidden(hwnd: HWND) {
+    let pid = crate::tracking::get_process_id(hwnd);
+    if pid == 0 || pid == std::process::id() {
+        return;
+    }
+
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).insert(
+        hwnd.0 as isize,
+        Pending {
+            pid,
+            since: Instant::now(),
+        },
+    );
+}
+
+/// Cancel pending trim of `hwnd` (window shown / untracked)
+pub fn clear(hwnd: HWND) {
+    PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&(hwnd.0 as isize));
+}
+
+/// Whether a trim is due after `elapsed` hidden time
+fn is_due(elapsed: Duration, delay_min: u32) -> bool {
+    elapsed >= Duration::from_secs(delay_min as u64 * 60)
+}
+
+/// Called from the event loop: trim once the hidden delay has elapsed
+pub fn poll() {
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    if pending.is_empty() {
+        return;
+    }
+    let delay_min = delay_minutes();
+    // One trim per hidden period
+    let mut due = Vec::new();
+    pending.retain(|_, p| {
+        let trim_now = is_due(p.since.elapsed(), delay_min);
+        if trim_now {
+            due.push(p.pid);
+        }
+        !trim_now
+    });
+    drop(pending);
+    if !is_enabled() {
+        return;
+    }
+
+    for pid in due {
+        trim(pid);
+    }
+}
+
+/// Page out the working set of `pid`
+fn trim(pid: u32) {
+    unsafe {
+        let Ok(process) = OpenProcess(
+            PROCESS_SET_QUOTA | PROCESS_QUERY_LIMITED_INFORMATION,
+            false,
+            pid,
+        ) else {
+            return;
+        };
+        // (-1, -1) asks the memory manager to trim as much as possible
+        match SetProcessWorkingSetSize(process, usize::MAX, usize::MAX) {
+            Ok(()) => tracing::info!(pid, "Working set trimmed"),
+            Err(e) => tracing::warn!("Working set trim failed: {e}"),
+        }
+        let _ = CloseHandle(process);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_due_before_delay() {
+        assert!(!is_due(Duration::from_secs(59), 1));
+    }
+
+    #[test]
+    fn test_is_due_at_delay() {
+        assert!(is_due(Duration::from_secs(60), 1));
+        assert!(is_due(Duration::from_secs(600), 10));
+    }
+
+    #[test]
+    fn test_is_due_zero_delay_immediate() {
+        assert!(is_due(Duration::ZERO, 0));
+    }
+}
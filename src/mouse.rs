@@ -0,0 +1,173 @@
+//! Low-level mouse hook shared by the shake, scroll-at-edge and swipe triggers
+//!
+//! Installed only while a window is tracked and one of the triggers is on
+//! (no hook otherwise). Cursor moves feed the shake detector; wheel ticks are
+//! posted as WM_EDGE_SCROLL for the event loop to check against the trigger
+//! edge; touch contacts (promoted to mouse messages) feed the swipe tracker.
+//! Events are always passed on.
+//! The hook lives on a thread of its own with its own message loop: a busy
+//! event loop (group animation, synchronous scripts / commands) must not stall
+//! the system cursor. Results reach the event loop as posted messages.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::thread::JoinHandle;
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GetMessageW, LLMHF_INJECTED, MSG, MSLLHOOKSTRUCT, PM_NOREMOVE, PeekMessageW,
+    PostThreadMessageW, SetWindowsHookExW, UnhookWindowsHookEx, WH_MOUSE_LL, WM_LBUTTONDOWN,
+    WM_LBUTTONUP, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_QUIT, WM_USER,
+};
+
+use crate::error::MouseError;
+use crate::events;
+use crate::shake;
+use crate::swipe;
+
+/// Posted on a wheel tick (WPARAM: wheel delta as u16, positive = away from the user)
+pub const WM_EDGE_SCROLL: u32 = WM_USER + 16;
+
+/// Hook thread while installed (thread id for WM_QUIT, handle)
+static WORKER: Mutex<Option<(u32, JoinHandle<()>)>> = Mutex::new(None);
+
+/// Install failed while wanted (not retried until disarmed)
+static FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Triggers fed by the hook
+static SHAKE: AtomicBool = AtomicBool::new(false);
+static SCROLL: AtomicBool = AtomicBool::new(false);
+static SWIPE: AtomicBool = AtomicBool::new(false);
+
+/// Triggers wanting the hook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Triggers {
+    pub shake: bool,
+    pub scroll: bool,
+    pub swipe: bool,
+}
+
+impl Triggers {
+    fn any(self) -> bool {
+        self.shake || self.scroll || self.swipe
+    }
+}
+
+/// Install or remove the hook for `triggers` (no-op when already in that state)
+/// A failed install is reported once per arming
+pub fn arm(triggers: Triggers) -> Result<(), MouseError> {
+    SHAKE.store(triggers.shake, Ordering::SeqCst);
+    SCROLL.store(triggers.scroll, Ordering::SeqCst);
+    SWIPE.store(triggers.swipe, Ordering::SeqCst);
+    if !triggers.any() {
+        FAILED.store(false, Ordering::SeqCst);
+        uninstall_hook();
+        return Ok(());
+    }
+    let mut worker = WORKER.lock().unwrap_or_else(|e| e.into_inner());
+    if worker.is_some() || FAILED.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    shake::reset();
+    swipe::up();
+    // Wait for the install result so a failure is still reported here
+    let (ready_tx, ready) = mpsc::sync_channel(1);
+    let handle = std::thread::spawn(move || run(ready_tx));
+    match ready.recv() {
+        Ok(Ok(thread_id)) => {
+            *worker = Some((thread_id, handle));
+            Ok(())
+        }
+        _ => {
+            let _ = handle.join();
+            FAILED.store(true, Ordering::SeqCst);
+            Err(MouseError::HookInstall)
+        }
+    }
+}
+
+/// Remove the hook (stops its thread)
+pub fn uninstall_hook() {
+    let worker = WORKER.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some((thread_id, handle)) = worker {
+        unsafe {
+            let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+        let _ = handle.join();
+    }
+}
+
+/// Hook thread: install, pump messages (the hook runs inside GetMessageW)
+/// until WM_QUIT, remove
+fn run(ready: SyncSender<Result<u32, MouseError>>) {
+    let mut msg = MSG::default();
+    let hook = unsafe {
+        // Create the message queue before the thread id is handed out for WM_QUIT
+        let _ = PeekMessageW(&mut msg, None, 0, 0, PM_NOREMOVE);
+        GetModuleHandleW(None).and_then(|instance| {
+            SetWindowsHookExW(WH_MOUSE_LL, Some(hook_proc), Some(instance.into()), 0)
+        })
+    };
+    let Ok(hook) = hook else {
+        let _ = ready.send(Err(MouseError::HookInstall));
+        return;
+    };
+    let _ = ready.send(Ok(unsafe { GetCurrentThreadId() }));
+    unsafe {
+        while GetMessageW(&mut msg, None, 0, 0).0 > 0 {}
+        let _ = UnhookWindowsHookEx(hook);
+    }
+}
+
+/// Wheel delta from WM_EDGE_SCROLL's WPARAM
+pub fn scroll_delta(wparam: usize) -> i32 {
+    wparam as u16 as i16 as i32
+}
+
+/// Low-level mouse hook: feed moves / post wheel ticks, pass everything on
+unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) };
+        let touch = swipe::is_touch(info.dwExtraInfo);
+        match wparam.0 as u32 {
+            WM_MOUSEMOVE if touch => {
+                if SWIPE.load(Ordering::SeqCst)
+                    && let Some(swipe) = swipe::moved(info.pt, info.time)
+                {
+                    events::post(swipe::WM_EDGE_SWIPE, swipe.pack());
+                }
+            }
+            WM_MOUSEMOVE
+                if SHAKE.load(Ordering::SeqCst)
+                    && info.flags & LLMHF_INJECTED == 0
+                    && shake::feed(info.pt.x, info.time) =>
+            {
+                events::post(shake::WM_SHAKE_DETECTED, 0);
+            }
+            WM_LBUTTONDOWN if touch && SWIPE.load(Ordering::SeqCst) => {
+                swipe::down(info.pt, info.time);
+            }
+            WM_LBUTTONUP if touch => swipe::up(),
+            WM_MOUSEWHEEL if SCROLL.load(Ordering::SeqCst) => {
+                // High word of mouseData: signed wheel delta
+                events::post(WM_EDGE_SCROLL, (info.mouseData >> 16) as u16 as usize);
+            }
+            _ => {}
+        }
+    }
+    unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_delta_roundtrip() {
+        for delta in [120, -120, 240, -30] {
+            assert_eq!(scroll_delta(delta as i16 as u16 as usize), delta);
+        }
+    }
+}
@@ -1,14 +1,228 @@
 //! Desktop notification support
+//!
+//! Plain toasts go through notify-rust; toasts with buttons use the WinRT
+//! toast API directly and post the clicked action to the event loop as
+//! WM_TOAST_ACTION (wParam = ToastAction).
 
 use notify_rust::Notification;
+use tauri_winrt_notification::Toast;
+use windows::Win32::UI::WindowsAndMessaging::WM_USER;
 
-/// Show toast notification for tracked window
+use crate::backup;
+use crate::i18n::{Text, t, tf};
+
+/// Posted to the event loop when a toast button is clicked
+pub const WM_TOAST_ACTION: u32 = WM_USER + 4;
+
+const APP_NAME: &str = "Quake Modoki";
+
+/// Toast button actions routed back to the main loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastAction {
+    Untrack = 1,
+    Hide = 2,
+    Relaunch = 3,
+    Track = 4,
+}
+
+impl ToastAction {
+    /// Toast `arguments` value
+    fn as_arg(self) -> &'static str {
+        match self {
+            Self::Untrack => "untrack",
+            Self::Hide => "hide",
+            Self::Relaunch => "relaunch",
+            Self::Track => "track",
+        }
+    }
+
+    fn from_arg(arg: &str) -> Option<Self> {
+        match arg {
+            "untrack" => Some(Self::Untrack),
+            "hide" => Some(Self::Hide),
+            "relaunch" => Some(Self::Relaunch),
+            "track" => Some(Self::Track),
+            _ => None,
+        }
+    }
+
+    /// Decode WM_TOAST_ACTION wParam
+    pub fn from_wparam(value: usize) -> Option<Self> {
+        match value {
+            1 => Some(Self::Untrack),
+            2 => Some(Self::Hide),
+            3 => Some(Self::Relaunch),
+            4 => Some(Self::Track),
+            _ => None,
+        }
+    }
+}
+
+/// Show toast notification for tracked window (Untrack / Hide buttons)
 pub fn show_tracked(title: &str) {
-    if let Err(e) = Notification::new()
-        .summary("Quake Modoki")
-        .body(&format!("Tracking: {}", title))
-        .show()
-    {
+    show_actionable(
+        &tf(Text::Tracking, &[title]),
+        &[
+            (Text::Untrack, ToastAction::Untrack),
+            (Text::Hide, ToastAction::Hide),
+        ],
+    );
+}
+
+/// Show toast notification for tracked window closed (Relaunch button if exe known)
+pub fn show_closed(name: &str, can_relaunch: bool) {
+    let actions: &[(Text, ToastAction)] = if can_relaunch {
+        &[(Text::Relaunch, ToastAction::Relaunch)]
+    } else {
+        &[]
+    };
+    show_actionable(&tf(Text::WindowClosed, &[name]), actions);
+}
+
+/// Show toast notification for a toggle with nothing tracked (Track button)
+pub fn show_nothing_tracked(track: &str) {
+    show_actionable(
+        &tf(Text::NothingTracked, &[track]),
+        &[(Text::TrackForeground, ToastAction::Track)],
+    );
+}
+
+/// Show toast notification for a window refused by the blocklist
+pub fn show_cannot_track(reason: &str) {
+    show(&tf(Text::CannotTrack, &[reason]));
+}
+
+/// Show toast notification for hidden window requesting attention
+pub fn show_attention(title: &str) {
+    show(&tf(Text::NeedsAttention, &[title]));
+}
+
+/// Show toast notification for a toggle hotkey taken by another app
+pub fn show_hotkey_fallback(conflict: &str, active: &str) {
+    show(&tf(Text::HotkeyFallback, &[conflict, active]));
+}
+
+/// Show toast notification for no usable toggle hotkey
+pub fn show_hotkey_unavailable(tried: &str) {
+    show(&tf(Text::HotkeyUnavailable, &[tried]));
+}
+
+/// Show toast notification for another tool using one of our hotkeys
+pub fn show_hotkey_conflict(tool: &str, hotkey: &str, suggestions: &str) {
+    show(&tf(Text::HotkeyConflict, &[tool, hotkey, suggestions]));
+}
+
+/// Show toast notification for a toggle hotkey chosen via capture
+pub fn show_hotkey_changed(hotkey: &str) {
+    show(&tf(Text::HotkeyChanged, &[hotkey]));
+}
+
+pub fn show_track_hotkey_changed(hotkey: &str) {
+    show(&tf(Text::TrackHotkeyChanged, &[hotkey]));
+}
+
+/// Show toast notification for a rejected capture (previous binding kept)
+pub fn show_hotkey_change_failed(reason: &str) {
+    show(&tf(Text::HotkeyChangeFailed, &[reason]));
+}
+
+/// Show toast notification for a finished setup wizard
+pub fn show_setup_done(toggle: &str, track: &str) {
+    show(&tf(Text::SetupDone, &[toggle, track]));
+}
+
+/// Show toast notification for a written settings export
+pub fn show_settings_exported(path: &str) {
+    show(&tf(Text::SettingsExported, &[path]));
+}
+
+/// Show toast notification for an applied settings import
+pub fn show_settings_imported(imported: backup::Imported) {
+    let written = imported.written.to_string();
+    if imported.blocked == 0 {
+        show(&tf(Text::SettingsImported, &[&written]));
+    } else {
+        let blocked = imported.blocked.to_string();
+        show(&tf(Text::SettingsImportedBlocked, &[&written, &blocked]));
+    }
+}
+
+/// Show toast notification for a crash (window already restored)
+pub fn show_crash(location: &str) {
+    show(&tf(Text::Crashed, &[location]));
+}
+
+/// Show toast notification for a downloaded + verified update
+pub fn show_update_ready(tag: &str) {
+    show(&tf(Text::UpdateReady, &[tag]));
+}
+
+/// Show toast notification for a manual check with nothing new
+pub fn show_up_to_date() {
+    show(&tf(Text::UpToDate, &[env!("CARGO_PKG_VERSION")]));
+}
+
+/// Show toast notification for a failed manual update check
+pub fn show_update_failed(reason: &str) {
+    show(&tf(Text::UpdateFailed, &[reason]));
+}
+
+/// Show toast notification for a failed action (see `error::AppError::report`)
+pub fn show_error(message: &str) {
+    show(&tf(Text::ErrorOccurred, &[message]));
+}
+
+/// Toast with buttons; the clicked action is posted to the event loop
+fn show_actionable(body: &str, actions: &[(Text, ToastAction)]) {
+    let toast = actions
+        .iter()
+        .fold(
+            Toast::new(Toast::POWERSHELL_APP_ID)
+                .title(APP_NAME)
+                .text1(body),
+            |toast, (label, action)| toast.add_button(t(*label), action.as_arg()),
+        )
+        .on_activated(move |arg| {
+            if let Some(action) = arg.as_deref().and_then(ToastAction::from_arg) {
+                crate::events::post(WM_TOAST_ACTION, action as usize);
+            }
+            Ok(())
+        });
+    if let Err(e) = toast.show() {
+        tracing::warn!("Notification failed: {e:?}");
+    }
+}
+
+fn show(body: &str) {
+    if let Err(e) = Notification::new().summary(APP_NAME).body(body).show() {
         tracing::warn!("Notification failed: {e}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [ToastAction; 4] = [
+        ToastAction::Untrack,
+        ToastAction::Hide,
+        ToastAction::Relaunch,
+        ToastAction::Track,
+    ];
+
+    #[test]
+    fn test_toast_action_arg_roundtrip() {
+        for action in ALL {
+            assert_eq!(ToastAction::from_arg(action.as_arg()), Some(action));
+        }
+        assert_eq!(ToastAction::from_arg("unknown"), None);
+    }
+
+    #[test]
+    fn test_toast_action_wparam_roundtrip() {
+        for action in ALL {
+            assert_eq!(ToastAction::from_wparam(action as usize), Some(action));
+        }
+        assert_eq!(ToastAction::from_wparam(0), None);
+    }
+}
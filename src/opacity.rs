@@ -0,0 +1,189 @@
+//! Tracked-window opacity: layered-window alpha, remembered per app
+//!
+//! The percentage is stored per executable file name under the `Opacity`
+//! subkey (the app's profile) and applied when the window is tracked or the
+//! tray selection changes. Windows that are layered already (e.g. drawn with
+//! UpdateLayeredWindow) are left alone. 100% and untrack restore the original
+//! extended style, and so does "Transparency effects" being off in Windows.
+//! While the window is shown, the opacity hotkeys (Ctrl+Alt+PageUp / PageDown
+//! unless `OpacityUpHotkey` / `OpacityDownHotkey` are set) step it by 10%;
+//! the result is stored in the profile like a tray choice.
+
+use global_hotkey::GlobalHotKeyManager;
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use std::path::Path;
+use std::sync::Mutex;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_LAYERED;
+
+use crate::hotkey;
+use crate::settings::{self, SettingsError};
+use crate::sysprefs;
+
+/// Per-app percentages (DWORD per lowercase exe file name)
+const OPACITY_KEY: &str = "Opacity";
+
+/// Tray choices in percent
+pub const LEVELS: [u32; 4] = [100, 90, 80, 70];
+
+/// Lowest stored percentage honored (keeps the window findable)
+const MIN_PERCENT: u32 = 20;
+
+/// Percent per hotkey press
+const STEP_PERCENT: u32 = 10;
+
+/// Hotkey settings and defaults, brighter first
+const HOTKEYS: [(&str, Code, bool); 2] = [
+    ("OpacityUpHotkey", Code::PageUp, true),
+    ("OpacityDownHotkey", Code::PageDown, false),
+];
+
+/// Window made layered, its extended style before and the applied percentage
+/// (None = no window modified)
+static SAVED: Mutex<Option<(isize, isize, u32)>> = Mutex::new(None);
+
+/// Profile name of an exe path (lowercase file name)
+fn profile_name(exe: &str) -> String {
+    Path::new(exe)
+        .file_name()
+        .map_or_else(
+            || exe.to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+        .to_ascii_lowercase()
+}
+
+/// Opacity for `exe` in percent (default: 100)
+pub fn get(exe: &str) -> u32 {
+    settings::get_subkey_u32(OPACITY_KEY, &profile_name(exe), 100).clamp(MIN_PERCENT, 100)
+}
+
+/// Persist opacity for `exe`
+pub fn set(exe: &str, percent: u32) -> Result<(), SettingsError> {
+    settings::set_subkey_u32(
+        OPACITY_KEY,
+        &profile_name(exe),
+        percent.clamp(MIN_PERCENT, 100),
+    )
+}
+
+/// Layered-window alpha of a percentage
+fn alpha(percent: u32) -> u8 {
+    (percent.clamp(MIN_PERCENT, 100) * 255 / 100) as u8
+}
+
+/// Make `hwnd` translucent (100: restore opaque)
+pub fn apply(hwnd: HWND, percent: u32) {
+    if percent >= 100 || !sysprefs::transparency_enabled() {
+        restore(hwnd);
+        return;
+    }
+
+    let api = crate::win32::api();
+    let exstyle = api.ex_style(hwnd);
+    let mut saved = SAVED.lock().unwrap_or_else(|e| e.into_inner());
+    let key = hwnd.0 as isize;
+    let original = match *saved {
+        Some((saved_hwnd, original, _)) if saved_hwnd == key => original,
+        _ if exstyle & WS_EX_LAYERED.0 as isize != 0 => {
+            tracing::debug!(hwnd = ?hwnd, "Window already layered; opacity not applied");
+            return;
+        }
+        _ => exstyle,
+    };
+    *saved = Some((key, original, percent.clamp(MIN_PERCENT, 100)));
+
+    api.set_ex_style(hwnd, exstyle | WS_EX_LAYERED.0 as isize);
+    api.set_alpha(hwnd, alpha(percent));
+}
+
+/// Remove the layered style added by `apply` (no-op for other windows)
+pub fn restore(hwnd: HWND) {
+    let mut saved = SAVED.lock().unwrap_or_else(|e| e.into_inner());
+    let Some((saved_hwnd, exstyle, _)) = *saved else {
+        return;
+    };
+    if saved_hwnd != hwnd.0 as isize {
+        return;
+    }
+    *saved = None;
+
+    let api = crate::win32::api();
+    let layered = WS_EX_LAYERED.0 as isize;
+    api.set_ex_style(hwnd, (api.ex_style(hwnd) & !layered) | (exstyle & layered));
+}
+
+/// Opacity applied to `hwnd` in percent (100 when not modified)
+pub fn current(hwnd: HWND) -> u32 {
+    let saved = SAVED.lock().unwrap_or_else(|e| e.into_inner());
+    match *saved {
+        Some((saved_hwnd, _, percent)) if saved_hwnd == hwnd.0 as isize => percent,
+        _ => 100,
+    }
+}
+
+/// Percentage one step up / down from `percent`, snapped to the 10% grid
+pub fn step(percent: u32, up: bool) -> u32 {
+    let snapped = percent.div_ceil(STEP_PERCENT) * STEP_PERCENT;
+    let next = if up {
+        (percent / STEP_PERCENT + 1) * STEP_PERCENT
+    } else {
+        snapped.saturating_sub(STEP_PERCENT)
+    };
+    next.clamp(MIN_PERCENT, 100)
+}
+
+/// Opacity hotkeys with their direction (true: more opaque)
+fn hotkeys() -> [(bool, HotKey); 2] {
+    HOTKEYS.map(|(name, code, up)| {
+        let default = HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT), code);
+        (up, hotkey::configured(name).unwrap_or(default))
+    })
+}
+
+/// Register the opacity hotkeys; returns the registered (direction, hotkey)
+pub fn register(manager: &GlobalHotKeyManager) -> Vec<(bool, HotKey)> {
+    hotkeys()
+        .into_iter()
+        .filter(|(_, key)| {
+            manager
+                .register(*key)
+                .inspect_err(|e| {
+                    tracing::warn!(hotkey = %hotkey::label(key), "Opacity hotkey register failed: {e}")
+                })
+                .is_ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_name() {
+        assert_eq!(
+            profile_name(r"C:\Program Files\WindowsApps\WindowsTerminal.exe"),
+            "windowsterminal.exe"
+        );
+        assert_eq!(profile_name("wt.exe"), "wt.exe");
+    }
+
+    #[test]
+    fn test_alpha() {
+        assert_eq!(alpha(100), 255);
+        assert_eq!(alpha(70), 178);
+        assert_eq!(alpha(0), alpha(MIN_PERCENT));
+    }
+
+    #[test]
+    fn test_step() {
+        assert_eq!(step(100, false), 90);
+        assert_eq!(step(90, true), 100);
+        assert_eq!(step(100, true), 100);
+        assert_eq!(step(MIN_PERCENT, false), MIN_PERCENT);
+        // Off-grid values (per-app rules) snap to the next 10%
+        assert_eq!(step(75, true), 80);
+        assert_eq!(step(75, false), 70);
+    }
+}
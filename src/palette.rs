@@ -0,0 +1,404 @@
+//! Window palette: fuzzy-search open windows by title / exe and track one
+//!
+//! The palette hotkey opens a small topmost window: a search box over the
+//! trackable windows of other apps (Z-order). Typing filters them with a fuzzy
+//! match on "title — exe" (characters in order, case-insensitive; runs and
+//! word starts rank first). Up / Down select, Enter or a double click picks,
+//! Esc or clicking elsewhere closes. Like `prompt`, it runs its own message
+//! loop on a worker thread; a pick is posted as WM_PALETTE_PICKED and the
+//! event loop tracks the window (or toggles it when it is the tracked one).
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{COLOR_BTNFACE, DEFAULT_GUI_FONT, GetStockObject, HBRUSH};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    AdjustWindowRectEx, CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW,
+    EN_CHANGE, ES_AUTOHSCROLL, GetDlgItem, GetMessageW, GetWindowTextW, HMENU, IsDialogMessageW,
+    LB_ADDSTRING, LB_GETCOUNT, LB_GETCURSEL, LB_RESETCONTENT, LB_SETCURSEL, LBN_DBLCLK,
+    LBS_NOINTEGRALHEIGHT, LBS_NOTIFY, MSG, PostMessageW, PostQuitMessage, RegisterClassW,
+    SendMessageW, TranslateMessage, WA_INACTIVE, WINDOW_EX_STYLE, WINDOW_STYLE, WM_ACTIVATE,
+    WM_CLOSE, WM_COMMAND, WM_DESTROY, WM_KEYDOWN, WM_SETFONT, WM_USER, WNDCLASSW, WS_BORDER,
+    WS_CAPTION, WS_CHILD, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_POPUP, WS_TABSTOP, WS_VISIBLE,
+    WS_VSCROLL,
+};
+use windows::core::{HSTRING, PCWSTR, w};
+
+use crate::blocklist;
+use crate::events;
+use crate::i18n::{Text, t};
+use crate::tracking;
+use crate::win32;
+
+/// Window picked (WPARAM = HWND)
+pub const WM_PALETTE_PICKED: u32 = WM_USER + 18;
+
+const CLASS_NAME: PCWSTR = w!("QuakeModokiPalette");
+
+const EDIT_ID: i32 = 100;
+const LIST_ID: i32 = 101;
+
+// Virtual-key codes handled by the palette itself
+const VK_RETURN: usize = 0x0D;
+const VK_ESCAPE: usize = 0x1B;
+const VK_UP: usize = 0x26;
+const VK_DOWN: usize = 0x28;
+
+// Layout (client pixels)
+const MARGIN: i32 = 8;
+const CLIENT_WIDTH: i32 = 560;
+const EDIT_HEIGHT: i32 = 24;
+const LIST_HEIGHT: i32 = 320;
+
+/// Palette open (one at a time)
+static OPEN: AtomicBool = AtomicBool::new(false);
+
+/// Listed window
+#[derive(Debug, Clone)]
+struct Entry {
+    hwnd: isize,
+    /// "title — exe"
+    label: String,
+}
+
+thread_local! {
+    /// Windows listed when the palette opened
+    static ENTRIES: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+    /// Entry index of each list row
+    static SHOWN: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Fuzzy score of `query` in `text` (None: no match, higher: better)
+///
+/// Every query character must appear in order (case-insensitive). Each hit
+/// scores 1, plus 5 when it follows the previous hit and 3 at a word start.
+fn score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut total = 0;
+    let mut pos = 0;
+    let mut last: Option<usize> = None;
+    for q in query.chars().flat_map(char::to_lowercase) {
+        if q.is_whitespace() {
+            continue;
+        }
+        let found = pos + text[pos..].iter().position(|&c| c == q)?;
+        total += 1;
+        if last.is_some_and(|last| last + 1 == found) {
+            total += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            total += 3;
+        }
+        last = Some(found);
+        pos = found + 1;
+    }
+    Some(total)
+}
+
+/// Indexes of the labels matching `query`, best first (ties keep their order)
+fn rank(labels: &[&str], query: &str) -> Vec<usize> {
+    let mut scored: Vec<(usize, u32)> = labels
+        .iter()
+        .enumerate()
+        .filter_map(|(i, label)| score(query, label).map(|s| (i, s)))
+        .collect();
+    scored.sort_by_key(|&(_, s)| std::cmp::Reverse(s));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+/// Open the palette on a worker thread (no-op while one is open)
+pub fn open() {
+    if OPEN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| {
+        run();
+        OPEN.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Trackable windows of other apps, Z-order
+fn entries() -> Vec<Entry> {
+    let found = RefCell::new(Vec::new());
+    tracking::find_app_window(&|hwnd| {
+        if tracking::is_other_app_window(hwnd) && blocklist::check(hwnd).is_none() {
+            let exe = tracking::get_process_path(hwnd);
+            let exe = exe
+                .as_deref()
+                .and_then(|path| Path::new(path).file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            found.borrow_mut().push(Entry {
+                hwnd: hwnd.0 as isize,
+                label: format!("{} — {exe}", tracking::get_window_title(hwnd)),
+            });
+        }
+        false
+    });
+    found.into_inner()
+}
+
+/// Show the palette and run its message loop until it closes
+fn run() {
+    ENTRIES.set(entries());
+    let Some(hwnd) = create() else {
+        tracing::warn!("Window palette could not be created");
+        return;
+    };
+    refresh(hwnd);
+    let mut msg = MSG::default();
+    unsafe {
+        win32::api().set_foreground(hwnd);
+        while GetMessageW(&mut msg, None, 0, 0).0 > 0 {
+            if msg.message == WM_KEYDOWN && handle_key(hwnd, msg.wParam.0) {
+                continue;
+            }
+            if !IsDialogMessageW(hwnd, &msg).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+    }
+}
+
+/// Create the window with its controls, in the upper third of the cursor's monitor
+fn create() -> Option<HWND> {
+    let style = WS_POPUP | WS_CAPTION | WS_BORDER;
+    let ex_style = WS_EX_TOPMOST | WS_EX_TOOLWINDOW;
+    let mut frame = RECT {
+        left: 0,
+        top: 0,
+        right: CLIENT_WIDTH,
+        bottom: MARGIN * 3 + EDIT_HEIGHT + LIST_HEIGHT,
+    };
+    let api = win32::api();
+    let work_area = api.work_area_at(api.cursor_pos().unwrap_or_default())?;
+
+    unsafe {
+        let instance = GetModuleHandleW(None).ok()?;
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(wnd_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            hbrBackground: HBRUSH((COLOR_BTNFACE.0 + 1) as usize as *mut _),
+            ..Default::default()
+        };
+        RegisterClassW(&class);
+
+        let _ = AdjustWindowRectEx(&mut frame, style, false, ex_style);
+        let width = frame.right - frame.left;
+        let height = frame.bottom - frame.top;
+        let hwnd = CreateWindowExW(
+            ex_style,
+            CLASS_NAME,
+            &HSTRING::from(t(Text::WindowPalette)),
+            style | WS_VISIBLE,
+            work_area.left + (work_area.right - work_area.left - width) / 2,
+            work_area.top + (work_area.bottom - work_area.top - height) / 3,
+            width,
+            height,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )
+        .ok()?;
+
+        // bounds: (x, y, width, height)
+        let child = |class: PCWSTR, style: WINDOW_STYLE, id: i32, bounds: (i32, i32, i32, i32)| {
+            let control = CreateWindowExW(
+                WINDOW_EX_STYLE(0),
+                class,
+                None,
+                WS_CHILD | WS_VISIBLE | WS_TABSTOP | style,
+                bounds.0,
+                bounds.1,
+                bounds.2,
+                bounds.3,
+                Some(hwnd),
+                Some(HMENU(id as isize as *mut _)),
+                Some(instance.into()),
+                None,
+            );
+            if let Ok(control) = control {
+                let font = GetStockObject(DEFAULT_GUI_FONT);
+                SendMessageW(
+                    control,
+                    WM_SETFONT,
+                    Some(WPARAM(font.0 as usize)),
+                    Some(LPARAM(1)),
+                );
+            }
+        };
+
+        let inner_width = CLIENT_WIDTH - MARGIN * 2;
+        child(
+            w!("EDIT"),
+            WS_BORDER | WINDOW_STYLE(ES_AUTOHSCROLL as u32),
+            EDIT_ID,
+            (MARGIN, MARGIN, inner_width, EDIT_HEIGHT),
+        );
+        child(
+            w!("LISTBOX"),
+            WS_BORDER | WS_VSCROLL | WINDOW_STYLE((LBS_NOTIFY | LBS_NOINTEGRALHEIGHT) as u32),
+            LIST_ID,
+            (MARGIN, MARGIN * 2 + EDIT_HEIGHT, inner_width, LIST_HEIGHT),
+        );
+        Some(hwnd)
+    }
+}
+
+/// Search text as typed
+fn query(hwnd: HWND) -> String {
+    let mut buf = [0u16; 256];
+    let len =
+        unsafe { GetDlgItem(Some(hwnd), EDIT_ID).map_or(0, |edit| GetWindowTextW(edit, &mut buf)) };
+    String::from_utf16_lossy(&buf[..len.max(0) as usize])
+}
+
+/// Refill the list for the current search text, best match selected
+fn refresh(hwnd: HWND) {
+    let Ok(list) = (unsafe { GetDlgItem(Some(hwnd), LIST_ID) }) else {
+        return;
+    };
+    let query = query(hwnd);
+    ENTRIES.with_borrow(|entries| {
+        let labels: Vec<&str> = entries.iter().map(|e| e.label.as_str()).collect();
+        let shown = rank(&labels, &query);
+        unsafe {
+            SendMessageW(list, LB_RESETCONTENT, None, None);
+            for &i in &shown {
+                let label = HSTRING::from(entries[i].label.as_str());
+                SendMessageW(
+                    list,
+                    LB_ADDSTRING,
+                    None,
+                    Some(LPARAM(label.as_ptr() as isize)),
+                );
+            }
+            SendMessageW(list, LB_SETCURSEL, Some(WPARAM(0)), None);
+        }
+        SHOWN.set(shown);
+    });
+}
+
+/// Move the list selection by `delta` rows (clamped)
+fn move_selection(hwnd: HWND, delta: isize) {
+    let Ok(list) = (unsafe { GetDlgItem(Some(hwnd), LIST_ID) }) else {
+        return;
+    };
+    unsafe {
+        let count = SendMessageW(list, LB_GETCOUNT, None, None).0;
+        if count <= 0 {
+            return;
+        }
+        let current = SendMessageW(list, LB_GETCURSEL, None, None).0.max(0);
+        let next = (current + delta).clamp(0, count - 1);
+        SendMessageW(list, LB_SETCURSEL, Some(WPARAM(next as usize)), None);
+    }
+}
+
+/// Post the selected window to the event loop and close
+fn pick(hwnd: HWND) {
+    let selected = unsafe {
+        GetDlgItem(Some(hwnd), LIST_ID)
+            .map_or(-1, |list| SendMessageW(list, LB_GETCURSEL, None, None).0)
+    };
+    let target = usize::try_from(selected).ok().and_then(|row| {
+        let index = SHOWN.with_borrow(|shown| shown.get(row).copied())?;
+        ENTRIES.with_borrow(|entries| entries.get(index).map(|e| e.hwnd))
+    });
+    if let Some(target) = target {
+        events::post(WM_PALETTE_PICKED, target as usize);
+    }
+    unsafe {
+        let _ = DestroyWindow(hwnd);
+    }
+}
+
+/// Navigation keys, whichever control has focus (true: handled)
+fn handle_key(hwnd: HWND, vk: usize) -> bool {
+    match vk {
+        VK_UP => move_selection(hwnd, -1),
+        VK_DOWN => move_selection(hwnd, 1),
+        VK_RETURN => pick(hwnd),
+        VK_ESCAPE => unsafe {
+            let _ = DestroyWindow(hwnd);
+        },
+        _ => return false,
+    }
+    true
+}
+
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_COMMAND => {
+            let id = (wparam.0 & 0xFFFF) as i32;
+            let code = (wparam.0 >> 16) as u32 & 0xFFFF;
+            match (id, code) {
+                (EDIT_ID, EN_CHANGE) => refresh(hwnd),
+                (LIST_ID, LBN_DBLCLK) => pick(hwnd),
+                _ => {}
+            }
+            LRESULT(0)
+        }
+        // Clicking elsewhere closes (posted: deactivation also happens while destroying)
+        WM_ACTIVATE if (wparam.0 & 0xFFFF) as u32 == WA_INACTIVE => {
+            unsafe {
+                let _ = PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0));
+            }
+            LRESULT(0)
+        }
+        WM_CLOSE => {
+            unsafe {
+                let _ = DestroyWindow(hwnd);
+            }
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            unsafe { PostQuitMessage(0) };
+            LRESULT(0)
+        }
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        assert_eq!(score("", "Windows Terminal — wt.exe"), Some(0));
+        assert_eq!(score("xyz", "Windows Terminal — wt.exe"), None);
+        // Out of order does not match
+        assert_eq!(score("tw", "wt"), None);
+        assert_eq!(score("mt", "Terminal"), None);
+        // Case-insensitive; runs and word starts score higher
+        assert!(score("TERM", "Windows Terminal") > score("wtrm", "Windows Terminal"));
+        assert!(score("wt", "Windows Terminal") > score("wt", "wxt"));
+    }
+
+    #[test]
+    fn test_rank() {
+        let labels = [
+            "Inbox — outlook.exe",
+            "build — cmd.exe",
+            "notes.txt - Notepad — notepad.exe",
+            "Windows Terminal — wt.exe",
+        ];
+        // Contiguous "note" beats scattered matches; non-matches are dropped
+        assert_eq!(rank(&labels, "note"), vec![2, 3, 0]);
+        assert_eq!(rank(&labels, "term"), vec![3]);
+        // Ties keep Z-order
+        assert_eq!(rank(&labels, ""), vec![0, 1, 2, 3]);
+        assert_eq!(rank(&labels, "exe").len(), 4);
+    }
+}
@@ -0,0 +1,287 @@
+//! Peek: live DWM thumbnail of the tracked window in a small flyout
+//!
+//! Tray "Peek" shows the thumbnail near the cursor for PEEK_DURATION_MS (a
+//! click closes it sooner) without summoning the window, e.g. to see whether a
+//! build finished. DWM only renders shown windows, so a hidden window is shown
+//! far off every monitor for the peek, without activation, and hidden again
+//! when the flyout closes (a suspended process is resumed first). Toggling,
+//! tracking and untracking close the flyout.
+
+use std::sync::Mutex;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, RECT, WPARAM};
+use windows::Win32::Graphics::Dwm::{
+    DWM_THUMBNAIL_PROPERTIES, DWM_TNP_OPACITY, DWM_TNP_RECTDESTINATION, DWM_TNP_VISIBLE,
+    DwmRegisterThumbnail, DwmUnregisterThumbnail, DwmUpdateThumbnailProperties,
+};
+use windows::Win32::Graphics::Gdi::{COLOR_WINDOWFRAME, HBRUSH};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, GetClientRect, HWND_BOTTOM, MA_NOACTIVATE,
+    RegisterClassW, SWP_HIDEWINDOW, SWP_NOACTIVATE, SWP_NOSIZE, SWP_NOZORDER, SWP_SHOWWINDOW,
+    SetTimer, WM_LBUTTONUP, WM_MOUSEACTIVATE, WM_RBUTTONUP, WM_TIMER, WNDCLASSW, WS_BORDER,
+    WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_POPUP,
+};
+use windows::core::{PCWSTR, w};
+
+use crate::error::PeekError;
+use crate::tracking::WindowBounds;
+use crate::win32::{self, Move};
+
+const CLASS_NAME: PCWSTR = w!("QuakeModokiPeek");
+
+/// Flyout lifetime
+const PEEK_DURATION_MS: u32 = 4000;
+const TIMER_CLOSE: usize = 1;
+
+/// Largest thumbnail (aspect ratio kept)
+const MAX_WIDTH: i32 = 480;
+const MAX_HEIGHT: i32 = 320;
+
+/// Gap between the cursor and the flyout
+const CURSOR_GAP: i32 = 16;
+
+/// Where a hidden window is shown during a peek (off every monitor)
+const PARK_POS: i32 = -20000;
+
+/// Open flyout (handles stored as isize: the state lives in a static)
+struct Peek {
+    popup: isize,
+    thumbnail: isize,
+    source: isize,
+    /// Bounds of a hidden window shown for the peek (None: it was shown already)
+    parked: Option<WindowBounds>,
+}
+
+static PEEK: Mutex<Option<Peek>> = Mutex::new(None);
+
+/// `width` x `height` scaled down to fit MAX_WIDTH x MAX_HEIGHT (never up)
+fn fit_size(width: i32, height: i32) -> (i32, i32) {
+    let (width, height) = (width.max(1), height.max(1));
+    let scale = (MAX_WIDTH as f64 / width as f64)
+        .min(MAX_HEIGHT as f64 / height as f64)
+        .min(1.0);
+    (
+        ((width as f64 * scale).round() as i32).max(1),
+        ((height as f64 * scale).round() as i32).max(1),
+    )
+}
+
+/// Flyout position: centered above `cursor` (below when there is no room),
+/// kept inside `work_area`
+fn place_near(cursor: POINT, size: (i32, i32), work_area: &RECT) -> (i32, i32) {
+    let (width, height) = size;
+    let above = cursor.y - CURSOR_GAP - height;
+    let y = if above >= work_area.top {
+        above
+    } else {
+        cursor.y + CURSOR_GAP
+    };
+    let x = (cursor.x - width / 2).clamp(
+        work_area.left,
+        (work_area.right - width).max(work_area.left),
+    );
+    let y = y.clamp(
+        work_area.top,
+        (work_area.bottom - height).max(work_area.top),
+    );
+    (x, y)
+}
+
+/// Open the flyout for `source` (`visible`: the window is shown right now)
+pub fn show(source: HWND, visible: bool) -> Result<(), PeekError> {
+    close();
+    let api = win32::api();
+    let bounds = api.window_rect(source).ok_or(PeekError::NoWindow)?;
+    let cursor = api.cursor_pos().unwrap_or_default();
+    let work_area = api.work_area_at(cursor).ok_or(PeekError::NoWindow)?;
+    let size = fit_size(bounds.width, bounds.height);
+    let (x, y) = place_near(cursor, size, &work_area);
+
+    // DWM has nothing to draw for a hidden window: show it out of sight
+    let parked = (!visible).then(|| {
+        crate::process::restore(source);
+        let park = Move {
+            hwnd: source,
+            x: PARK_POS,
+            y: PARK_POS,
+            width: 0,
+            height: 0,
+            flags: SWP_NOSIZE | SWP_NOACTIVATE | SWP_SHOWWINDOW,
+        };
+        api.move_window(park, Some(HWND_BOTTOM));
+        bounds
+    });
+
+    let popup = match create_popup(x, y, size) {
+        Ok(popup) => popup,
+        Err(e) => {
+            rehide(source, parked);
+            return Err(e);
+        }
+    };
+    let thumbnail = match register_thumbnail(popup, source) {
+        Ok(thumbnail) => thumbnail,
+        Err(e) => {
+            unsafe {
+                let _ = DestroyWindow(popup);
+            }
+            rehide(source, parked);
+            return Err(e);
+        }
+    };
+    api.show_inactive(popup);
+    unsafe {
+        SetTimer(Some(popup), TIMER_CLOSE, PEEK_DURATION_MS, None);
+    }
+
+    *PEEK.lock().unwrap_or_else(|e| e.into_inner()) = Some(Peek {
+        popup: popup.0 as isize,
+        thumbnail,
+        source: source.0 as isize,
+        parked,
+    });
+    tracing::debug!(hwnd = ?source, parked = parked.is_some(), "Peek opened");
+    Ok(())
+}
+
+/// Close the flyout and hide a window shown for it (no-op when closed)
+pub fn close() {
+    // Take the state first: DestroyWindow re-enters the window procedure
+    let Some(peek) = PEEK.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return;
+    };
+    unsafe {
+        let _ = DwmUnregisterThumbnail(peek.thumbnail);
+        let _ = DestroyWindow(HWND(peek.popup as *mut _));
+    }
+    rehide(HWND(peek.source as *mut _), peek.parked);
+    tracing::debug!("Peek closed");
+}
+
+/// Hide a window shown for a peek again, back at its hidden position
+fn rehide(source: HWND, parked: Option<WindowBounds>) {
+    let Some(bounds) = parked else {
+        return;
+    };
+    let api = win32::api();
+    if !api.is_window(source) {
+        return;
+    }
+    let hide = Move {
+        hwnd: source,
+        x: bounds.x,
+        y: bounds.y,
+        width: 0,
+        height: 0,
+        flags: SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_HIDEWINDOW,
+    };
+    api.move_window(hide, None);
+}
+
+/// Topmost, never-activated flyout window (not shown yet)
+fn create_popup(x: i32, y: i32, size: (i32, i32)) -> Result<HWND, PeekError> {
+    unsafe {
+        let instance = GetModuleHandleW(None).map_err(|_| PeekError::WindowCreate)?;
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(wnd_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            hbrBackground: HBRUSH((COLOR_WINDOWFRAME.0 + 1) as usize as *mut _),
+            ..Default::default()
+        };
+        RegisterClassW(&class);
+
+        CreateWindowExW(
+            WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+            CLASS_NAME,
+            CLASS_NAME,
+            WS_POPUP | WS_BORDER,
+            x,
+            y,
+            size.0,
+            size.1,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )
+        .map_err(|_| PeekError::WindowCreate)
+    }
+}
+
+/// Live thumbnail of `source` filling `popup`'s client area
+fn register_thumbnail(popup: HWND, source: HWND) -> Result<isize, PeekError> {
+    unsafe {
+        let thumbnail =
+            DwmRegisterThumbnail(popup, source).map_err(|e| PeekError::Thumbnail(e.to_string()))?;
+        let mut client = RECT::default();
+        let _ = GetClientRect(popup, &mut client);
+        let properties = DWM_THUMBNAIL_PROPERTIES {
+            dwFlags: DWM_TNP_RECTDESTINATION | DWM_TNP_VISIBLE | DWM_TNP_OPACITY,
+            rcDestination: client,
+            opacity: u8::MAX,
+            fVisible: true.into(),
+            ..Default::default()
+        };
+        if let Err(e) = DwmUpdateThumbnailProperties(thumbnail, &properties) {
+            let _ = DwmUnregisterThumbnail(thumbnail);
+            return Err(PeekError::Thumbnail(e.to_string()));
+        }
+        Ok(thumbnail)
+    }
+}
+
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_MOUSEACTIVATE => LRESULT(MA_NOACTIVATE as isize),
+        WM_TIMER | WM_LBUTTONUP | WM_RBUTTONUP => {
+            close();
+            LRESULT(0)
+        }
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORK_AREA: RECT = RECT {
+        left: 0,
+        top: 0,
+        right: 1920,
+        bottom: 1040,
+    };
+
+    #[test]
+    fn test_fit_size() {
+        assert_eq!(fit_size(1920, 1080), (480, 270));
+        assert_eq!(fit_size(800, 1200), (213, 320));
+        // Small windows keep their size
+        assert_eq!(fit_size(300, 200), (300, 200));
+    }
+
+    #[test]
+    fn test_place_near() {
+        // Above the cursor, centered
+        assert_eq!(
+            place_near(POINT { x: 1000, y: 1000 }, (480, 270), &WORK_AREA),
+            (760, 714)
+        );
+        // Kept inside the work area near the right edge
+        assert_eq!(
+            place_near(POINT { x: 1900, y: 1000 }, (480, 270), &WORK_AREA),
+            (1440, 714)
+        );
+        // Below when there is no room above
+        assert_eq!(
+            place_near(POINT { x: 100, y: 10 }, (480, 270), &WORK_AREA),
+            (0, 26)
+        );
+    }
+}
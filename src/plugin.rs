@@ -0,0 +1,422 @@
+//! Native plugins: cdylib DLLs loaded from %LOCALAPPDATA%\quake-modoki\plugins at startup
+//!
+//! Opt-in: nothing is loaded unless `EnablePlugins` (DWORD, default 0) is on,
+//! so a DLL dropped into the folder does not run by itself; a policy value of
+//! 0 keeps plugins off. Every DLL is logged before it is loaded.
+//!
+//! A plugin exports `extern "C" fn quake_modoki_plugin() -> *const PluginVTable`
+//! pointing to a static vtable whose `abi_version` equals PLUGIN_ABI_VERSION.
+//! Every callback is optional (null = not provided):
+//! - `poll_trigger`: polled every 50 ms while a window is tracked, returns a TRIGGER_* code
+//! - `on_hide` / `on_show`: hide strategy, return true to replace the slide animation
+//! - `menu_item_count` / `menu_item_label` / `on_menu_item`: items in the tray "Plugins" submenu
+//!
+//! Callbacks run on the main thread; strings are NUL-terminated UTF-8 owned by the plugin.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, c_char};
+use std::path::{Path, PathBuf};
+use windows::Win32::Foundation::{FreeLibrary, HMODULE, HWND};
+use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+use windows::core::{HSTRING, s};
+
+use crate::error::PluginError;
+use crate::settings;
+
+/// Bumped on any incompatible vtable / host API change
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// `poll_trigger` result codes
+pub const TRIGGER_NONE: u32 = 0;
+pub const TRIGGER_TOGGLE: u32 = 1;
+pub const TRIGGER_SHOW: u32 = 2;
+pub const TRIGGER_HIDE: u32 = 3;
+
+/// Host services passed to `init`
+#[repr(C)]
+pub struct HostApi {
+    pub abi_version: u32,
+    /// level: 0 error, 1 warn, 2 info, 3 debug, other trace
+    pub log: unsafe extern "C" fn(level: u32, message: *const c_char),
+}
+
+/// Plugin entry table (returned by `quake_modoki_plugin`)
+#[repr(C)]
+pub struct PluginVTable {
+    pub abi_version: u32,
+    pub name: *const c_char,
+    /// Called once after load; false rejects the plugin
+    pub init: Option<unsafe extern "C" fn(host: *const HostApi) -> bool>,
+    pub shutdown: Option<unsafe extern "C" fn()>,
+    pub poll_trigger: Option<unsafe extern "C" fn() -> u32>,
+    pub on_hide: Option<unsafe extern "C" fn(hwnd: isize) -> bool>,
+    pub on_show: Option<unsafe extern "C" fn(hwnd: isize) -> bool>,
+    pub menu_item_count: Option<unsafe extern "C" fn() -> u32>,
+    pub menu_item_label: Option<unsafe extern "C" fn(index: u32) -> *const c_char>,
+    pub on_menu_item: Option<unsafe extern "C" fn(index: u32)>,
+}
+
+// Vtables are immutable statics; `name` points to a static string
+unsafe impl Sync for PluginVTable {}
+
+type EntryPoint = unsafe extern "C" fn() -> *const PluginVTable;
+
+const PLUGIN_DIR: &str = "plugins";
+
+const ENABLE_PLUGINS: &str = "EnablePlugins";
+
+/// Action requested by a plugin trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerAction {
+    Toggle,
+    Show,
+    Hide,
+}
+
+fn trigger_from_code(code: u32) -> Option<TriggerAction> {
+    match code {
+        TRIGGER_TOGGLE => Some(TriggerAction::Toggle),
+        TRIGGER_SHOW => Some(TriggerAction::Show),
+        TRIGGER_HIDE => Some(TriggerAction::Hide),
+        _ => None,
+    }
+}
+
+struct Plugin {
+    name: String,
+    /// None for statically linked vtables (tests)
+    module: Option<HMODULE>,
+    vtable: &'static PluginVTable,
+}
+
+/// Tray item contributed by a plugin
+struct MenuEntry {
+    plugin: usize,
+    index: u32,
+    label: String,
+}
+
+#[derive(Default)]
+struct Registry {
+    plugins: Vec<Plugin>,
+    menu: Vec<MenuEntry>,
+}
+
+thread_local! {
+    /// Loaded plugins (main thread only)
+    static PLUGINS: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+/// Check if plugin loading is enabled in registry (default: off)
+pub fn is_enabled() -> bool {
+    settings::get_bool(ENABLE_PLUGINS, false)
+}
+
+/// Plugin directory: %LOCALAPPDATA%\quake-modoki\plugins
+pub fn plugin_dir() -> Option<PathBuf> {
+    std::env::var_os("LOCALAPPDATA")
+        .map(|base| PathBuf::from(base).join("quake-modoki").join(PLUGIN_DIR))
+}
+
+unsafe extern "C" fn host_log(level: u32, message: *const c_char) {
+    let message = unsafe { c_string(message) }.unwrap_or_default();
+    match level {
+        0 => tracing::error!(target: "plugin", "{message}"),
+        1 => tracing::warn!(target: "plugin", "{message}"),
+        2 => tracing::info!(target: "plugin", "{message}"),
+        3 => tracing::debug!(target: "plugin", "{message}"),
+        _ => tracing::trace!(target: "plugin", "{message}"),
+    }
+}
+
+static HOST_API: HostApi = HostApi {
+    abi_version: PLUGIN_ABI_VERSION,
+    log: host_log,
+};
+
+/// Copy a plugin-owned C string (None if null)
+unsafe fn c_string(ptr: *const c_char) -> Option<String> {
+    (!ptr.is_null()).then(|| {
+        unsafe { CStr::from_ptr(ptr) }
+            .to_string_lossy()
+            .into_owned()
+    })
+}
+
+/// Validate vtable version and name
+fn check_vtable(vtable: &PluginVTable) -> Result<String, PluginError> {
+    if vtable.abi_version != PLUGIN_ABI_VERSION {
+        return Err(PluginError::AbiMismatch {
+            found: vtable.abi_version,
+            expected: PLUGIN_ABI_VERSION,
+        });
+    }
+    unsafe { c_string(vtable.name) }
+        .filter(|n| !n.is_empty())
+        .ok_or(PluginError::MissingName)
+}
+
+/// Load one DLL (kept loaded until `unload_all`)
+fn load_file(path: &Path) -> Result<Plugin, PluginError> {
+    tracing::info!(path = ?path, "Loading plugin DLL");
+    let module = unsafe { LoadLibraryW(&HSTRING::from(path)) }
+        .map_err(|e| PluginError::Load(e.to_string()))?;
+    let result = (|| {
+        let entry = unsafe { GetProcAddress(module, s!("quake_modoki_plugin")) }
+            .ok_or(PluginError::MissingEntryPoint)?;
+        let entry: EntryPoint = unsafe { std::mem::transmute(entry) };
+        let vtable = unsafe { entry().as_ref() }.ok_or(PluginError::MissingEntryPoint)?;
+        init(vtable, Some(module))
+    })();
+    if result.is_err() {
+        let _ = unsafe { FreeLibrary(module) };
+    }
+    result
+}
+
+/// Validate + call `init`
+fn init(vtable: &'static PluginVTable, module: Option<HMODULE>) -> Result<Plugin, PluginError> {
+    let name = check_vtable(vtable)?;
+    if let Some(init) = vtable.init
+        && !unsafe { init(&HOST_API) }
+    {
+        return Err(PluginError::InitFailed(name));
+    }
+    Ok(Plugin {
+        name,
+        module,
+        vtable,
+    })
+}
+
+/// Labels of a plugin's tray items
+fn menu_labels(vtable: &PluginVTable) -> Vec<(u32, String)> {
+    let (Some(count), Some(label)) = (vtable.menu_item_count, vtable.menu_item_label) else {
+        return Vec::new();
+    };
+    (0..unsafe { count() })
+        .filter_map(|i| unsafe { c_string(label(i)) }.map(|l| (i, l)))
+        .collect()
+}
+
+fn register(plugin: Plugin) {
+    PLUGINS.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let index = registry.plugins.len();
+        for (item, label) in menu_labels(plugin.vtable) {
+            registry.menu.push(MenuEntry {
+                plugin: index,
+                index: item,
+                label,
+            });
+        }
+        registry.plugins.push(plugin);
+    });
+}
+
+/// Load every *.dll in the plugin directory, returns names of loaded plugins
+/// (none unless plugins are enabled)
+pub fn load_all() -> Vec<String> {
+    let Some(dir) = plugin_dir() else {
+        return Vec::new();
+    };
+    if !is_enabled() {
+        tracing::debug!(dir = ?dir, "Plugins disabled (EnablePlugins off)");
+        return Vec::new();
+    }
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut loaded = Vec::new();
+    for path in entries.filter_map(Result::ok).map(|e| e.path()) {
+        let is_dll = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("dll"));
+        if !is_dll {
+            continue;
+        }
+        match load_file(&path) {
+            Ok(plugin) => {
+                tracing::info!(name = %plugin.name, path = ?path, "Plugin loaded");
+                loaded.push(plugin.name.clone());
+                register(plugin);
+            }
+            Err(e) => tracing::warn!(path = ?path, "Plugin skipped: {e}"),
+        }
+    }
+    loaded
+}
+
+/// Tray labels of all plugin items, in menu order
+pub fn menu_items() -> Vec<String> {
+    PLUGINS.with(|registry| {
+        registry
+            .borrow()
+            .menu
+            .iter()
+            .map(|entry| entry.label.clone())
+            .collect()
+    })
+}
+
+/// Dispatch a click on plugin tray item `position` (index into `menu_items`)
+pub fn on_menu_item(position: usize) {
+    PLUGINS.with(|registry| {
+        let registry = registry.borrow();
+        let Some(entry) = registry.menu.get(position) else {
+            return;
+        };
+        if let Some(callback) = registry.plugins[entry.plugin].vtable.on_menu_item {
+            unsafe { callback(entry.index) };
+        }
+    });
+}
+
+/// Check if any loaded plugin polls for triggers (housekeeping timer needed)
+pub fn has_triggers() -> bool {
+    PLUGINS.with(|registry| {
+        registry
+            .borrow()
+            .plugins
+            .iter()
+            .any(|p| p.vtable.poll_trigger.is_some())
+    })
+}
+
+/// First trigger request from any plugin this tick
+pub fn poll_trigger() -> Option<TriggerAction> {
+    PLUGINS.with(|registry| {
+        registry
+            .borrow()
+            .plugins
+            .iter()
+            .filter_map(|p| p.vtable.poll_trigger)
+            .map(|poll| unsafe { poll() })
+            .find(|code| *code != TRIGGER_NONE)
+            .and_then(trigger_from_code)
+    })
+}
+
+/// Offer hide to plugins; true if one handled it (skip the slide animation)
+pub fn hide(hwnd: HWND) -> bool {
+    PLUGINS.with(|registry| {
+        registry
+            .borrow()
+            .plugins
+            .iter()
+            .filter_map(|p| p.vtable.on_hide)
+            .any(|on_hide| unsafe { on_hide(hwnd.0 as isize) })
+    })
+}
+
+/// Offer show to plugins; true if one handled it (skip the slide animation)
+pub fn show(hwnd: HWND) -> bool {
+    PLUGINS.with(|registry| {
+        registry
+            .borrow()
+            .plugins
+            .iter()
+            .filter_map(|p| p.vtable.on_show)
+            .any(|on_show| unsafe { on_show(hwnd.0 as isize) })
+    })
+}
+
+/// Call `shutdown` and unload all plugins
+pub fn unload_all() {
+    PLUGINS.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        registry.menu.clear();
+        for plugin in registry.plugins.drain(..) {
+            if let Some(shutdown) = plugin.vtable.shutdown {
+                unsafe { shutdown() };
+            }
+            if let Some(module) = plugin.module {
+                let _ = unsafe { FreeLibrary(module) };
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn test_count() -> u32 {
+        2
+    }
+
+    unsafe extern "C" fn test_label(index: u32) -> *const c_char {
+        match index {
+            0 => c"First".as_ptr(),
+            _ => std::ptr::null(),
+        }
+    }
+
+    unsafe extern "C" fn test_poll() -> u32 {
+        TRIGGER_SHOW
+    }
+
+    static TEST_VTABLE: PluginVTable = PluginVTable {
+        abi_version: PLUGIN_ABI_VERSION,
+        name: c"test".as_ptr(),
+        init: None,
+        shutdown: None,
+        poll_trigger: Some(test_poll),
+        on_hide: None,
+        on_show: None,
+        menu_item_count: Some(test_count),
+        menu_item_label: Some(test_label),
+        on_menu_item: None,
+    };
+
+    static OLD_VTABLE: PluginVTable = PluginVTable {
+        abi_version: 0,
+        name: c"old".as_ptr(),
+        init: None,
+        shutdown: None,
+        poll_trigger: None,
+        on_hide: None,
+        on_show: None,
+        menu_item_count: None,
+        menu_item_label: None,
+        on_menu_item: None,
+    };
+
+    #[test]
+    fn test_trigger_from_code() {
+        assert_eq!(trigger_from_code(TRIGGER_NONE), None);
+        assert_eq!(
+            trigger_from_code(TRIGGER_TOGGLE),
+            Some(TriggerAction::Toggle)
+        );
+        assert_eq!(trigger_from_code(TRIGGER_HIDE), Some(TriggerAction::Hide));
+        assert_eq!(trigger_from_code(99), None);
+    }
+
+    #[test]
+    fn test_check_vtable() {
+        assert_eq!(check_vtable(&TEST_VTABLE).unwrap(), "test");
+        assert!(matches!(
+            check_vtable(&OLD_VTABLE),
+            Err(PluginError::AbiMismatch { found: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_menu_labels_skip_null() {
+        assert_eq!(menu_labels(&TEST_VTABLE), vec![(0, "First".to_string())]);
+        assert!(menu_labels(&OLD_VTABLE).is_empty());
+    }
+
+    #[test]
+    fn test_registered_plugin_dispatch() {
+        register(init(&TEST_VTABLE, None).unwrap());
+        assert_eq!(menu_items(), vec!["First"]);
+        assert_eq!(poll_trigger(), Some(TriggerAction::Show));
+        assert!(!hide(HWND::default()));
+        unload_all();
+        assert!(menu_items().is_empty());
+        assert_eq!(poll_trigger(), None);
+    }
+}
@@ -0,0 +1,148 @@
+//! Built-in target presets for common drop-down apps
+//!
+//! Applying a preset writes ordinary settings: the startup target
+//! (`DefaultTarget`, plus `DefaultTargetExe` when the command hands off to
+//! another process), the default dock and a per-app rule
+//! (`Rules\Preset <name>`) with the app's exe, slide side and size. Everything
+//! can be edited afterwards like hand-written settings.
+
+use crate::animation::Direction;
+use crate::rules;
+use crate::session;
+use crate::settings::{self, SettingsError};
+use crate::snap;
+use crate::terminal;
+
+/// Common drop-down target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    WindowsTerminal,
+    PowerShell,
+    Cmd,
+    Obsidian,
+    Spotify,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 5] = [
+        Preset::WindowsTerminal,
+        Preset::PowerShell,
+        Preset::Cmd,
+        Preset::Obsidian,
+        Preset::Spotify,
+    ];
+
+    /// Display name (product name, not translated)
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::WindowsTerminal => "Windows Terminal",
+            Preset::PowerShell => "PowerShell",
+            Preset::Cmd => "Command Prompt",
+            Preset::Obsidian => "Obsidian",
+            Preset::Spotify => "Spotify",
+        }
+    }
+
+    /// Launch command (`DefaultTarget`)
+    pub fn command(self) -> String {
+        match self {
+            Preset::WindowsTerminal => format!("wt.exe -w {}", terminal::WINDOW_NAME),
+            Preset::PowerShell => "powershell.exe -NoLogo".to_string(),
+            Preset::Cmd => "cmd.exe".to_string(),
+            // Installed per user; the URI handler finds it
+            Preset::Obsidian => "obsidian://open".to_string(),
+            Preset::Spotify => "spotify:".to_string(),
+        }
+    }
+
+    /// Exe owning the app's window
+    pub fn exe(self) -> &'static str {
+        match self {
+            Preset::WindowsTerminal => "WindowsTerminal.exe",
+            Preset::PowerShell => "powershell.exe",
+            Preset::Cmd => "cmd.exe",
+            Preset::Obsidian => "Obsidian.exe",
+            Preset::Spotify => "Spotify.exe",
+        }
+    }
+
+    /// Check if the launched process is not the one owning the window
+    fn hands_off(self) -> bool {
+        matches!(
+            self,
+            Preset::WindowsTerminal | Preset::Obsidian | Preset::Spotify
+        )
+    }
+
+    /// Slide side
+    pub fn dock(self) -> Direction {
+        match self {
+            Preset::WindowsTerminal | Preset::PowerShell | Preset::Cmd => Direction::Top,
+            Preset::Obsidian | Preset::Spotify => Direction::Right,
+        }
+    }
+
+    /// Visible size in percent of the work area (width, height)
+    pub fn size(self) -> (u32, u32) {
+        match self {
+            Preset::WindowsTerminal | Preset::PowerShell | Preset::Cmd => (100, 40),
+            Preset::Obsidian => (40, 100),
+            Preset::Spotify => (30, 100),
+        }
+    }
+
+    /// Subkey of the preset's rule under `Rules`
+    fn rule_name(self) -> String {
+        format!("Preset {}", self.name())
+    }
+}
+
+/// Write the preset's startup target, default dock and rule
+pub fn apply(preset: Preset) -> Result<(), SettingsError> {
+    let exe = preset.hands_off().then(|| preset.exe());
+    session::set_default_target(&preset.command(), exe)?;
+    snap::set_default_dock(preset.dock())?;
+
+    let rule = format!(r"{}\{}", rules::RULES, preset.rule_name());
+    let (width, height) = preset.size();
+    settings::set_subkey_string(&rule, "Exe", preset.exe())?;
+    settings::set_subkey_string(&rule, "Direction", snap::dock_name(preset.dock()))?;
+    settings::set_subkey_string(&rule, "WidthPct", &width.to_string())?;
+    settings::set_subkey_string(&rule, "HeightPct", &height.to_string())?;
+    tracing::info!(preset = preset.name(), "Preset applied");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launch::Target;
+
+    #[test]
+    fn test_commands_parse() {
+        for preset in Preset::ALL {
+            let target = Target::parse(&preset.command()).expect("target");
+            assert!(!target.program.is_empty(), "{}", preset.name());
+        }
+    }
+
+    #[test]
+    fn test_process_launch_matches_exe() {
+        // Presets that do not hand off must be found by the launched program
+        for preset in Preset::ALL.into_iter().filter(|p| !p.hands_off()) {
+            let target = Target::parse(&preset.command()).expect("target");
+            assert!(target.program.eq_ignore_ascii_case(preset.exe()));
+        }
+    }
+
+    #[test]
+    fn test_sizes_docked_full_length() {
+        for preset in Preset::ALL {
+            let (width, height) = preset.size();
+            match preset.dock() {
+                Direction::Top | Direction::Bottom => assert_eq!(width, 100),
+                Direction::Left | Direction::Right => assert_eq!(height, 100),
+            }
+        }
+    }
+}
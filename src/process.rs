@@ -0,0 +1,250 @@
+//! Background process mode: lower priority or suspend the tracked process while hidden
+//!
+//! Suspended threads cannot answer window messages, so `restore` must run before
+//! any SetWindowPos / ShowWindow on the tracked window.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use windows::Win32::Foundation::{CloseHandle, HWND};
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW, TH32CS_SNAPPROCESS,
+    TH32CS_SNAPTHREAD, THREADENTRY32, Thread32First, Thread32Next,
+};
+use windows::Win32::System::Threading::{
+    BELOW_NORMAL_PRIORITY_CLASS, GetPriorityClass, OpenProcess, OpenThread, PROCESS_CREATION_FLAGS,
+    PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SET_INFORMATION, ResumeThread, SetPriorityClass,
+    SuspendThread, THREAD_SUSPEND_RESUME,
+};
+
+use crate::settings::{self, SettingsError};
+
+const HIDDEN_PROCESS_MODE: &str = "HiddenProcessMode";
+
+/// What happens to the tracked process while hidden
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HiddenMode {
+    #[default]
+    Normal,
+    BelowNormal,
+    Suspend,
+}
+
+impl HiddenMode {
+    /// Decode registry value (unknown → Normal)
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => HiddenMode::BelowNormal,
+            2 => HiddenMode::Suspend,
+            _ => HiddenMode::Normal,
+        }
+    }
+
+    /// Encode as registry value
+    pub fn as_u32(self) -> u32 {
+        match self {
+            HiddenMode::Normal => 0,
+            HiddenMode::BelowNormal => 1,
+            HiddenMode::Suspend => 2,
+        }
+    }
+}
+
+/// Changes applied to the process, undone by `restore`
+#[derive(Debug)]
+enum Applied {
+    Priority { pid: u32, previous: u32 },
+    Suspended { threads: Vec<u32> },
+}
+
+/// Changes by hidden window (each slot's window is throttled on its own)
+static APPLIED: Mutex<BTreeMap<isize, Applied>> = Mutex::new(BTreeMap::new());
+
+/// Get configured hidden mode from registry (default: Normal)
+pub fn get_mode() -> HiddenMode {
+    HiddenMode::from_u32(settings::get_u32(HIDDEN_PROCESS_MODE, 0))
+}
+
+/// Check if the hidden mode is fixed by policy
+pub fn is_locked() -> bool {
+    settings::is_locked(HIDDEN_PROCESS_MODE)
+}
+
+/// Persist hidden mode
+pub fn set_mode(mode: HiddenMode) -> Result<(), SettingsError> {
+    settings::set_u32(HIDDEN_PROCESS_MODE, mode.as_u32())
+}
+
+/// Apply `mode` after slide-out (configured one unless a rule overrides it)
+pub fn apply(hwnd: HWND, mode: HiddenMode) {
+    if mode == HiddenMode::Normal {
+        return;
+    }
+
+    let pid = crate::tracking::get_process_id(hwnd);
+    // Never throttle ourselves
+    if pid == 0 || pid == std::process::id() {
+        return;
+    }
+
+    let mut applied = APPLIED.lock().unwrap_or_else(|e| e.into_inner());
+    let key = hwnd.0 as isize;
+    if applied.contains_key(&key) {
+        return;
+    }
+
+    let state = match mode {
+        HiddenMode::Normal => None,
+        HiddenMode::BelowNormal => lower_priority(pid),
+        HiddenMode::Suspend => suspend_threads(pid),
+    };
+    if let Some(state) = state {
+        tracing::debug!(pid, state = ?state, "Hidden process mode applied");
+        applied.insert(key, state);
+    }
+}
+
+/// Undo priority change / resume threads of `hwnd`'s process (before show,
+/// untrack, exit)
+pub fn restore(hwnd: HWND) {
+    let Some(applied) = APPLIED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&(hwnd.0 as isize))
+    else {
+        return;
+    };
+
+    match applied {
+        Applied::Priority { pid, previous } => unsafe {
+            if let Ok(process) = OpenProcess(PROCESS_SET_INFORMATION, false, pid) {
+                let _ = SetPriorityClass(process, PROCESS_CREATION_FLAGS(previous));
+                let _ = CloseHandle(process);
+            }
+        },
+        Applied::Suspended { threads } => {
+            for tid in threads {
+                unsafe {
+                    if let Ok(thread) = OpenThread(THREAD_SUSPEND_RESUME, false, tid) {
+                        ResumeThread(thread);
+                        let _ = CloseHandle(thread);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Drop process to BELOW_NORMAL, remembering previous class
+fn lower_priority(pid: u32) -> Option<Applied> {
+    unsafe {
+        let process = OpenProcess(
+            PROCESS_SET_INFORMATION | PROCESS_QUERY_LIMITED_INFORMATION,
+            false,
+            pid,
+        )
+        .ok()?;
+        let previous = GetPriorityClass(process);
+        let result = if previous != 0 {
+            SetPriorityClass(process, BELOW_NORMAL_PRIORITY_CLASS).ok()
+        } else {
+            None
+        };
+        let _ = CloseHandle(process);
+        result.map(|_| Applied::Priority { pid, previous })
+    }
+}
+
+/// Suspend every thread of pid, returning the ones actually suspended
+fn suspend_threads(pid: u32) -> Option<Applied> {
+    let threads: Vec<u32> = process_threads(pid)
+        .into_iter()
+        .filter(|&tid| unsafe {
+            let Ok(thread) = OpenThread(THREAD_SUSPEND_RESUME, false, tid) else {
+                return false;
+            };
+            let suspended = SuspendThread(thread) != u32::MAX;
+            let _ = CloseHandle(thread);
+            suspended
+        })
+        .collect();
+
+    (!threads.is_empty()).then_some(Applied::Suspended { threads })
+}
+
+/// Enumerate thread ids owned by pid (Toolhelp snapshot)
+fn process_threads(pid: u32) -> Vec<u32> {
+    let mut threads = Vec::new();
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0) else {
+            return threads;
+        };
+
+        let mut entry = THREADENTRY32 {
+            dwSize: std::mem::size_of::<THREADENTRY32>() as u32,
+            ..Default::default()
+        };
+        let mut ok = Thread32First(snapshot, &mut entry).is_ok();
+        while ok {
+            if entry.th32OwnerProcessID == pid {
+                threads.push(entry.th32ThreadID);
+            }
+            ok = Thread32Next(snapshot, &mut entry).is_ok();
+        }
+        let _ = CloseHandle(snapshot);
+    }
+    threads
+}
+
+/// Executable names of all running processes (Toolhelp snapshot)
+pub fn running_process_names() -> Vec<String> {
+    let mut names = Vec::new();
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            return names;
+        };
+
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut ok = Process32FirstW(snapshot, &mut entry).is_ok();
+        while ok {
+            let len = entry
+                .szExeFile
+                .iter()
+                .position(|&c| c == 0)
+                .unwrap_or(entry.szExeFile.len());
+            names.push(String::from_utf16_lossy(&entry.szExeFile[..len]));
+            ok = Process32NextW(snapshot, &mut entry).is_ok();
+        }
+        let _ = CloseHandle(snapshot);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hidden_mode_roundtrip() {
+        for mode in [
+            HiddenMode::Normal,
+            HiddenMode::BelowNormal,
+            HiddenMode::Suspend,
+        ] {
+            assert_eq!(HiddenMode::from_u32(mode.as_u32()), mode);
+        }
+    }
+
+    #[test]
+    fn test_hidden_mode_unknown_is_normal() {
+        assert_eq!(HiddenMode::from_u32(99), HiddenMode::Normal);
+    }
+
+    #[test]
+    fn test_restore_without_apply_is_noop() {
+        restore(HWND::default());
+        assert!(APPLIED.lock().unwrap().is_empty());
+    }
+}
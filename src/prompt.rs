@@ -0,0 +1,270 @@
+//! Number prompt: a small window of labelled number fields with OK / Cancel
+//!
+//! Built from plain Win32 controls (no dialog resources). `numbers` runs its
+//! own message loop and blocks the calling thread until the window closes, so
+//! callers run it on a worker thread to keep the event loop responsive.
+//! Tab moves between fields, Enter confirms, Esc cancels.
+
+use std::cell::RefCell;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{COLOR_BTNFACE, DEFAULT_GUI_FONT, GetStockObject, HBRUSH};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    AdjustWindowRectEx, BS_DEFPUSHBUTTON, BS_PUSHBUTTON, CreateWindowExW, DefWindowProcW,
+    DestroyWindow, DispatchMessageW, ES_NUMBER, GetDlgItem, GetMessageW, GetSystemMetrics,
+    GetWindowTextW, HMENU, IDCANCEL, IDOK, IsDialogMessageW, MSG, PostQuitMessage, RegisterClassW,
+    SM_CXSCREEN, SM_CYSCREEN, SendMessageW, TranslateMessage, WINDOW_EX_STYLE, WINDOW_STYLE,
+    WM_CLOSE, WM_COMMAND, WM_DESTROY, WM_SETFONT, WNDCLASSW, WS_BORDER, WS_CAPTION, WS_CHILD,
+    WS_EX_DLGMODALFRAME, WS_EX_TOPMOST, WS_SYSMENU, WS_TABSTOP, WS_VISIBLE,
+};
+use windows::core::{HSTRING, PCWSTR, w};
+
+use crate::i18n::{Text, t};
+
+const CLASS_NAME: PCWSTR = w!("QuakeModokiPrompt");
+
+/// Control id of the first field (fields are consecutive)
+const FIELD_ID: i32 = 100;
+
+// Layout (client pixels)
+const MARGIN: i32 = 12;
+const ROW_HEIGHT: i32 = 32;
+const LABEL_WIDTH: i32 = 200;
+const EDIT_WIDTH: i32 = 96;
+const EDIT_HEIGHT: i32 = 24;
+const BUTTON_WIDTH: i32 = 88;
+const BUTTON_HEIGHT: i32 = 28;
+const CLIENT_WIDTH: i32 = MARGIN * 3 + LABEL_WIDTH + EDIT_WIDTH;
+
+/// Labelled number field
+#[derive(Debug, Clone, Copy)]
+pub struct Field<'a> {
+    pub label: &'a str,
+    /// Initial value (also used if the entry is not a number)
+    pub value: u32,
+    /// Entries above are clamped
+    pub max: u32,
+}
+
+thread_local! {
+    /// Fields of the open prompt (fallback, max)
+    static FIELDS: RefCell<Vec<(u32, u32)>> = const { RefCell::new(Vec::new()) };
+    /// Values read on OK
+    static RESULT: RefCell<Option<Vec<u32>>> = const { RefCell::new(None) };
+}
+
+/// Show the prompt and wait (None: cancelled or the window could not be created)
+pub fn numbers(title: &str, fields: &[Field]) -> Option<Vec<u32>> {
+    FIELDS.with_borrow_mut(|stored| {
+        *stored = fields.iter().map(|f| (f.value, f.max)).collect();
+    });
+    RESULT.with_borrow_mut(|result| *result = None);
+
+    let hwnd = create(title, fields)?;
+    let mut msg = MSG::default();
+    unsafe {
+        crate::win32::api().set_foreground(hwnd);
+        while GetMessageW(&mut msg, None, 0, 0).0 > 0 {
+            if !IsDialogMessageW(hwnd, &msg).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+    }
+    RESULT.with_borrow_mut(Option::take)
+}
+
+/// Parse an entry: fallback if not a number, clamped to max
+fn parse_value(text: &str, fallback: u32, max: u32) -> u32 {
+    text.trim().parse::<u32>().unwrap_or(fallback).min(max)
+}
+
+/// Create the window with its controls, centered on the primary monitor
+fn create(title: &str, fields: &[Field]) -> Option<HWND> {
+    let style = WS_CAPTION | WS_SYSMENU;
+    let ex_style = WS_EX_DLGMODALFRAME | WS_EX_TOPMOST;
+    let rows = fields.len() as i32;
+    let client_height = MARGIN * 2 + rows * ROW_HEIGHT + BUTTON_HEIGHT;
+    let mut frame = RECT {
+        left: 0,
+        top: 0,
+        right: CLIENT_WIDTH,
+        bottom: client_height,
+    };
+
+    unsafe {
+        let instance = GetModuleHandleW(None).ok()?;
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(wnd_proc),
+            hInstance: instance.into(),
+            lpszClassName: CLASS_NAME,
+            hbrBackground: HBRUSH((COLOR_BTNFACE.0 + 1) as usize as *mut _),
+            ..Default::default()
+        };
+        RegisterClassW(&class);
+
+        let _ = AdjustWindowRectEx(&mut frame, style, false, ex_style);
+        let width = frame.right - frame.left;
+        let height = frame.bottom - frame.top;
+        let hwnd = CreateWindowExW(
+            ex_style,
+            CLASS_NAME,
+            &HSTRING::from(title),
+            style | WS_VISIBLE,
+            (GetSystemMetrics(SM_CXSCREEN) - width) / 2,
+            (GetSystemMetrics(SM_CYSCREEN) - height) / 2,
+            width,
+            height,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        )
+        .ok()?;
+
+        // bounds: (x, y, width, height)
+        let child = |class: PCWSTR,
+                     text: &str,
+                     style: WINDOW_STYLE,
+                     id: i32,
+                     bounds: (i32, i32, i32, i32)| {
+            let control = CreateWindowExW(
+                WINDOW_EX_STYLE(0),
+                class,
+                &HSTRING::from(text),
+                WS_CHILD | WS_VISIBLE | style,
+                bounds.0,
+                bounds.1,
+                bounds.2,
+                bounds.3,
+                Some(hwnd),
+                Some(HMENU(id as isize as *mut _)),
+                Some(instance.into()),
+                None,
+            );
+            if let Ok(control) = control {
+                let font = GetStockObject(DEFAULT_GUI_FONT);
+                SendMessageW(
+                    control,
+                    WM_SETFONT,
+                    Some(WPARAM(font.0 as usize)),
+                    Some(LPARAM(1)),
+                );
+            }
+        };
+
+        for (i, field) in fields.iter().enumerate() {
+            let top = MARGIN + i as i32 * ROW_HEIGHT;
+            child(
+                w!("STATIC"),
+                field.label,
+                WINDOW_STYLE(0),
+                -1,
+                (MARGIN, top + 4, LABEL_WIDTH, EDIT_HEIGHT),
+            );
+            child(
+                w!("EDIT"),
+                &field.value.to_string(),
+                WS_BORDER | WS_TABSTOP | WINDOW_STYLE(ES_NUMBER as u32),
+                FIELD_ID + i as i32,
+                (MARGIN * 2 + LABEL_WIDTH, top, EDIT_WIDTH, EDIT_HEIGHT),
+            );
+        }
+
+        let buttons_top = MARGIN + rows * ROW_HEIGHT;
+        child(
+            w!("BUTTON"),
+            "OK",
+            WS_TABSTOP | WINDOW_STYLE(BS_DEFPUSHBUTTON as u32),
+            IDOK.0,
+            (
+                CLIENT_WIDTH - MARGIN * 2 - BUTTON_WIDTH * 2,
+                buttons_top,
+                BUTTON_WIDTH,
+                BUTTON_HEIGHT,
+            ),
+        );
+        child(
+            w!("BUTTON"),
+            t(Text::Cancel),
+            WS_TABSTOP | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+            IDCANCEL.0,
+            (
+                CLIENT_WIDTH - MARGIN - BUTTON_WIDTH,
+                buttons_top,
+                BUTTON_WIDTH,
+                BUTTON_HEIGHT,
+            ),
+        );
+        Some(hwnd)
+    }
+}
+
+/// Read every field (on OK)
+fn read_fields(hwnd: HWND) -> Vec<u32> {
+    FIELDS.with_borrow(|fields| {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, &(fallback, max))| {
+                let mut buf = [0u16; 16];
+                let len = unsafe {
+                    GetDlgItem(Some(hwnd), FIELD_ID + i as i32)
+                        .map_or(0, |edit| GetWindowTextW(edit, &mut buf))
+                };
+                parse_value(
+                    &String::from_utf16_lossy(&buf[..len as usize]),
+                    fallback,
+                    max,
+                )
+            })
+            .collect()
+    })
+}
+
+unsafe extern "system" fn wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_COMMAND => {
+            let id = (wparam.0 & 0xFFFF) as i32;
+            if id == IDOK.0 {
+                let values = read_fields(hwnd);
+                RESULT.with_borrow_mut(|result| *result = Some(values));
+            }
+            if id == IDOK.0 || id == IDCANCEL.0 {
+                unsafe {
+                    let _ = DestroyWindow(hwnd);
+                }
+            }
+            LRESULT(0)
+        }
+        WM_CLOSE => {
+            unsafe {
+                let _ = DestroyWindow(hwnd);
+            }
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            unsafe { PostQuitMessage(0) };
+            LRESULT(0)
+        }
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value(" 250 ", 100, 5000), 250);
+        assert_eq!(parse_value("", 100, 5000), 100);
+        assert_eq!(parse_value("abc", 100, 5000), 100);
+        assert_eq!(parse_value("99999", 100, 5000), 5000);
+    }
+}
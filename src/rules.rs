@@ -0,0 +1,307 @@
+//! Per-app rules: overrides applied to matching windows when they are tracked
+//!
+//! Each rule is a subkey of HKCU\Software\QuakeModoki\Rules holding string
+//! values. Conditions: `Exe` (file name), `Class` (window class) and `Title`
+//! (regex); all given ones must match. Overrides: `Direction` (top / bottom /
+//! left / right), `WidthPct` / `HeightPct` (visible size, a missing one is
+//! 100), `Opacity` (percent), `HiddenMode` (normal / below-normal / suspend),
+//! `Animation` (slide / slidefade / zoom) and `HideOnFocusLoss` (0 / 1).
+//! With `Track` = 1 a matching running window is tracked at startup when
+//! nothing else was. Rules are tried in subkey name order (e.g. "10 Terminal",
+//! "20 Editor"); the first match wins.
+
+use regex::Regex;
+use windows::Win32::Foundation::HWND;
+
+use crate::animation::{self, AnimStyle, Direction};
+use crate::context;
+use crate::error::RuleError;
+use crate::find;
+use crate::process::{self, HiddenMode};
+use crate::settings;
+use crate::snap;
+
+pub const RULES: &str = "Rules";
+
+/// Settings a rule replaces for the tracked window (None: global setting)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Overrides {
+    pub direction: Option<Direction>,
+    /// Visible size in percent of the work area (width, height)
+    pub size: Option<(u32, u32)>,
+    pub opacity: Option<u32>,
+    pub hidden_mode: Option<HiddenMode>,
+    pub animation: Option<AnimStyle>,
+    pub hide_on_focus_loss: Option<bool>,
+}
+
+impl Overrides {
+    /// Hidden process mode: the rule's, else the configured one
+    pub fn hidden_mode(&self) -> HiddenMode {
+        self.hidden_mode.unwrap_or_else(process::get_mode)
+    }
+
+    /// Animation style: the rule's, else the configured one
+    pub fn anim_style(&self) -> AnimStyle {
+        self.animation.unwrap_or_else(animation::style)
+    }
+
+    /// Check if losing focus hides the window (default: yes)
+    pub fn hides_on_focus_loss(&self) -> bool {
+        self.hide_on_focus_loss.unwrap_or(true)
+    }
+}
+
+/// Window properties rules are matched against
+#[derive(Debug, Clone, Copy)]
+pub struct WindowInfo<'a> {
+    /// Full executable path
+    pub exe: Option<&'a str>,
+    pub class: &'a str,
+    pub title: &'a str,
+}
+
+/// Conditions + overrides of one rule
+#[derive(Debug, Clone)]
+pub struct Rule {
+    /// Subkey name
+    pub name: String,
+    /// Lowercase exe file name
+    exe: Option<String>,
+    class: Option<String>,
+    title: Option<Regex>,
+    /// Track a matching window at startup
+    pub track: bool,
+    pub overrides: Overrides,
+}
+
+impl Rule {
+    fn matches(&self, window: &WindowInfo) -> bool {
+        let exe = window.exe.map(context::exe_name);
+        self.exe.as_ref().is_none_or(|e| exe.as_ref() == Some(e))
+            && self
+                .class
+                .as_ref()
+                .is_none_or(|c| c.eq_ignore_ascii_case(window.class))
+            && self.title.as_ref().is_none_or(|t| t.is_match(window.title))
+    }
+}
+
+/// Parse a hidden process mode (case-insensitive)
+fn parse_hidden_mode(value: &str) -> Option<HiddenMode> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "normal" => Some(HiddenMode::Normal),
+        "below-normal" | "belownormal" => Some(HiddenMode::BelowNormal),
+        "suspend" => Some(HiddenMode::Suspend),
+        _ => None,
+    }
+}
+
+/// Parse a rule subkey's values (unknown names ignored)
+fn parse_rule(name: &str, values: &[(String, String)]) -> Result<Rule, RuleError> {
+    let get = |key: &str| {
+        values
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.trim())
+            .filter(|v| !v.is_empty())
+    };
+    let invalid = |key: &str, value: &str| RuleError::Value {
+        name: key.to_string(),
+        value: value.to_string(),
+    };
+    let number = |key: &str| {
+        get(key)
+            .map(|v| v.parse::<u32>().map_err(|_| invalid(key, v)))
+            .transpose()
+    };
+
+    let exe = get("Exe").map(str::to_ascii_lowercase);
+    let class = get("Class").map(str::to_string);
+    let title = get("Title").map(Regex::new).transpose()?;
+    if exe.is_none() && class.is_none() && title.is_none() {
+        return Err(RuleError::NoCondition);
+    }
+
+    let direction = get("Direction")
+        .map(|v| snap::parse_dock(v).ok_or_else(|| invalid("Direction", v)))
+        .transpose()?;
+    let (width, height) = (number("WidthPct")?, number("HeightPct")?);
+    let size = (width.is_some() || height.is_some())
+        .then(|| (width.unwrap_or(100), height.unwrap_or(100)));
+    let hidden_mode = get("HiddenMode")
+        .map(|v| parse_hidden_mode(v).ok_or_else(|| invalid("HiddenMode", v)))
+        .transpose()?;
+    let animation = get("Animation")
+        .map(|v| AnimStyle::from_name(v).ok_or_else(|| invalid("Animation", v)))
+        .transpose()?;
+
+    Ok(Rule {
+        name: name.to_string(),
+        exe,
+        class,
+        title,
+        track: number("Track")?.is_some_and(|v| v != 0),
+        overrides: Overrides {
+            direction,
+            size,
+            opacity: number("Opacity")?,
+            hidden_mode,
+            animation,
+            hide_on_focus_loss: number("HideOnFocusLoss")?.map(|v| v != 0),
+        },
+    })
+}
+
+/// Load rules from registry in name order (invalid rules skipped with a warning)
+pub fn load() -> Vec<Rule> {
+    settings::get_subkey_names(RULES)
+        .into_iter()
+        .filter_map(|name| {
+            let values = settings::get_string_values(&format!(r"{RULES}\{name}"));
+            parse_rule(&name, &values)
+                .inspect_err(|e| tracing::warn!(rule = %name, "Invalid rule: {e}"))
+                .ok()
+        })
+        .collect()
+}
+
+/// First rule matching `window`
+pub fn find<'a>(rules: &'a [Rule], window: &WindowInfo) -> Option<&'a Rule> {
+    rules.iter().find(|rule| rule.matches(window))
+}
+
+/// Running window of the first `Track` rule that has one
+pub fn find_tracked_window(rules: &[Rule]) -> Option<(&Rule, HWND)> {
+    rules
+        .iter()
+        .filter(|rule| rule.track)
+        .find_map(|rule| find::find_window(&|window| rule.matches(window)).map(|hwnd| (rule, hwnd)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TERMINAL: WindowInfo = WindowInfo {
+        exe: Some(r"C:\Program Files\WindowsApps\WindowsTerminal.exe"),
+        class: "CASCADIA_HOSTING_WINDOW_CLASS",
+        title: "PowerShell - ~/src",
+    };
+
+    #[test]
+    fn test_parse_rule_overrides() {
+        let rule = parse_rule(
+            "10 Terminal",
+            &settings::string_values(&[
+                ("Exe", "WindowsTerminal.exe"),
+                ("direction", "Left"),
+                ("WidthPct", "40"),
+                ("Opacity", "90"),
+                ("HiddenMode", "suspend"),
+                ("Animation", "zoom"),
+                ("HideOnFocusLoss", "0"),
+                ("Track", "1"),
+            ]),
+        )
+        .expect("rule");
+        assert!(rule.track);
+        assert_eq!(
+            rule.overrides,
+            Overrides {
+                direction: Some(Direction::Left),
+                size: Some((40, 100)),
+                opacity: Some(90),
+                hidden_mode: Some(HiddenMode::Suspend),
+                animation: Some(AnimStyle::Zoom),
+                hide_on_focus_loss: Some(false),
+            }
+        );
+        assert!(rule.matches(&TERMINAL));
+    }
+
+    #[test]
+    fn test_parse_rule_errors() {
+        assert!(matches!(
+            parse_rule("r", &settings::string_values(&[("Opacity", "80")])),
+            Err(RuleError::NoCondition)
+        ));
+        assert!(matches!(
+            parse_rule("r", &settings::string_values(&[("Title", "(")])),
+            Err(RuleError::Title(_))
+        ));
+        assert!(matches!(
+            parse_rule(
+                "r",
+                &settings::string_values(&[("Exe", "a.exe"), ("Direction", "up")])
+            ),
+            Err(RuleError::Value { .. })
+        ));
+        assert!(matches!(
+            parse_rule(
+                "r",
+                &settings::string_values(&[("Exe", "a.exe"), ("Animation", "spin")])
+            ),
+            Err(RuleError::Value { .. })
+        ));
+        assert!(matches!(
+            parse_rule(
+                "r",
+                &settings::string_values(&[("Exe", "a.exe"), ("WidthPct", "wide")])
+            ),
+            Err(RuleError::Value { .. })
+        ));
+    }
+
+    #[test]
+    fn test_all_conditions_must_match() {
+        let rule = parse_rule(
+            "r",
+            &settings::string_values(&[
+                ("Class", "cascadia_hosting_window_class"),
+                ("Title", "^PowerShell"),
+            ]),
+        )
+        .expect("rule");
+        assert!(!rule.track);
+        assert!(rule.matches(&TERMINAL));
+        assert!(!rule.matches(&WindowInfo {
+            title: "cmd",
+            ..TERMINAL
+        }));
+        assert!(!rule.matches(&WindowInfo {
+            class: "Notepad",
+            ..TERMINAL
+        }));
+    }
+
+    #[test]
+    fn test_find_first_match() {
+        let rules = [
+            parse_rule("10", &settings::string_values(&[("Exe", "notepad.exe")])).expect("rule"),
+            parse_rule("20", &settings::string_values(&[("Title", "PowerShell")])).expect("rule"),
+            parse_rule(
+                "30",
+                &settings::string_values(&[("Exe", "windowsterminal.exe")]),
+            )
+            .expect("rule"),
+        ];
+        assert_eq!(find(&rules, &TERMINAL).map(|r| r.name.as_str()), Some("20"));
+        let unknown = WindowInfo {
+            exe: None,
+            class: "",
+            title: "",
+        };
+        assert!(find(&rules, &unknown).is_none());
+    }
+
+    #[test]
+    fn test_overrides_defaults() {
+        assert!(Overrides::default().hides_on_focus_loss());
+        let overrides = Overrides {
+            hidden_mode: Some(HiddenMode::BelowNormal),
+            ..Default::default()
+        };
+        assert_eq!(overrides.hidden_mode(), HiddenMode::BelowNormal);
+    }
+}
@@ -0,0 +1,196 @@
+//! Lifecycle hooks: user rhai script run on show / hide / track / untrack
+//!
+//! Script: %LOCALAPPDATA%\quake-modoki\hooks.rhai (loaded at startup).
+//! Each hook is an optional function taking the tracked window as a map:
+//!
+//! ```rhai
+//! fn on_show(window) {
+//!     if window.exe.ends_with("WindowsTerminal.exe") {
+//!         run("nircmd", ["setdefaultsounddevice", "Headphones"]);
+//!     }
+//! }
+//! ```
+//!
+//! Built-ins: `log(text)`, `run(program, args)` (spawned, not awaited).
+//! Hooks run on the main thread with an operation limit.
+
+use rhai::{AST, Array, Dynamic, Engine, EvalAltResult, Map, Scope};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::error::ScriptError;
+
+const SCRIPT_FILE: &str = "hooks.rhai";
+
+/// Runaway-script guard (rhai operations per hook)
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Lifecycle event passed to the script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Show,
+    Hide,
+    Track,
+    Untrack,
+}
+
+impl Event {
+    /// Script function name
+    pub fn function(self) -> &'static str {
+        match self {
+            Self::Show => "on_show",
+            Self::Hide => "on_hide",
+            Self::Track => "on_track",
+            Self::Untrack => "on_untrack",
+        }
+    }
+}
+
+/// Tracked window as seen by scripts
+#[derive(Debug, Clone, Default)]
+pub struct WindowInfo {
+    pub title: String,
+    pub exe: String,
+    pub hwnd: usize,
+}
+
+impl WindowInfo {
+    fn to_map(&self) -> Map {
+        let mut map = Map::new();
+        map.insert("title".into(), self.title.clone().into());
+        map.insert("exe".into(), self.exe.clone().into());
+        map.insert("hwnd".into(), (self.hwnd as i64).into());
+        map
+    }
+}
+
+struct Hooks {
+    engine: Engine,
+    ast: AST,
+}
+
+thread_local! {
+    /// Compiled script (main thread; rhai Engine is not Send)
+    static HOOKS: RefCell<Option<Hooks>> = const { RefCell::new(None) };
+}
+
+/// Script path: %LOCALAPPDATA%\quake-modoki\hooks.rhai
+pub fn script_path() -> Option<PathBuf> {
+    std::env::var_os("LOCALAPPDATA")
+        .map(|base| PathBuf::from(base).join("quake-modoki").join(SCRIPT_FILE))
+}
+
+fn new_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.register_fn(
+        "log",
+        |text: &str| tracing::info!(target: "script", "{text}"),
+    );
+    engine.register_fn("run", run_program);
+    engine
+}
+
+/// `run(program, args)`: spawn detached, true if started
+fn run_program(program: &str, args: Array) -> bool {
+    let args: Vec<String> = args.into_iter().map(|a| a.to_string()).collect();
+    Command::new(program)
+        .args(&args)
+        .spawn()
+        .inspect_err(|e| tracing::warn!(program, "Script run failed: {e}"))
+        .is_ok()
+}
+
+/// Compile the script, true if one was found
+pub fn load() -> Result<bool, ScriptError> {
+    let Some(path) = script_path().filter(|p| p.exists()) else {
+        return Ok(false);
+    };
+    let source = std::fs::read_to_string(&path)?;
+    let engine = new_engine();
+    let ast = engine
+        .compile(source)
+        .map_err(|e| ScriptError::Compile(e.to_string()))?;
+    HOOKS.with(|hooks| *hooks.borrow_mut() = Some(Hooks { engine, ast }));
+    Ok(true)
+}
+
+/// Check if the script defines a hook for `event`
+fn has_hook(ast: &AST, event: Event) -> bool {
+    ast.iter_functions()
+        .any(|f| f.name == event.function() && f.params.len() == 1)
+}
+
+/// Run the hook for `event` (no-op without script or hook; errors are logged)
+pub fn fire(event: Event, window: &WindowInfo) {
+    HOOKS.with(|hooks| {
+        let hooks = hooks.borrow();
+        let Some(hooks) = hooks.as_ref() else {
+            return;
+        };
+        if !has_hook(&hooks.ast, event) {
+            return;
+        }
+        let result: Result<Dynamic, Box<EvalAltResult>> = hooks.engine.call_fn(
+            &mut Scope::new(),
+            &hooks.ast,
+            event.function(),
+            (window.to_map(),),
+        );
+        if let Err(e) = result {
+            tracing::warn!(hook = event.function(), "Script error: {e}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_function_names() {
+        assert_eq!(Event::Show.function(), "on_show");
+        assert_eq!(Event::Untrack.function(), "on_untrack");
+    }
+
+    #[test]
+    fn test_has_hook() {
+        let engine = new_engine();
+        let ast = engine
+            .compile("fn on_show(window) { log(window.title); }")
+            .unwrap();
+        assert!(has_hook(&ast, Event::Show));
+        assert!(!has_hook(&ast, Event::Hide));
+    }
+
+    #[test]
+    fn test_hook_sees_window_fields() {
+        let engine = new_engine();
+        let ast = engine
+            .compile("fn on_track(window) { window.title + \"|\" + window.exe }")
+            .unwrap();
+        let window = WindowInfo {
+            title: "vim".into(),
+            exe: "wt.exe".into(),
+            hwnd: 1,
+        };
+        let result: String = engine
+            .call_fn(&mut Scope::new(), &ast, "on_track", (window.to_map(),))
+            .unwrap();
+        assert_eq!(result, "vim|wt.exe");
+    }
+
+    #[test]
+    fn test_operation_limit() {
+        let engine = new_engine();
+        let ast = engine.compile("fn on_show(window) { loop {} }").unwrap();
+        let result: Result<Dynamic, _> = engine.call_fn(
+            &mut Scope::new(),
+            &ast,
+            "on_show",
+            (WindowInfo::default().to_map(),),
+        );
+        assert!(result.is_err());
+    }
+}
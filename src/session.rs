@@ -0,0 +1,123 @@
+//! Tracking session: how tracking starts and persists across restarts
+//!
+//! With `HideOnTrack` a newly registered window slides out right away, so it
+//! stays tucked away until summoned. `DefaultTarget` is a launch command
+//! tracked (found running or launched) and slid out on startup when no
+//! session was resumed. `DefaultTargetExe` names the exe owning its window when
+//! the command hands off to another process (e.g. `wt.exe`).
+//!
+//! On exit the tracked window's executable and visibility are saved; the next
+//! launch tracks that app's window again and slides it out if it was hidden
+//! (or always, with `ResumeHidden`). Untracking or closing the window clears
+//! the session so the app is not picked up again.
+
+use crate::launch::Target;
+use crate::settings::{self, SettingsError};
+
+/// Resume the last tracked app on startup (default on)
+const RESUME_TRACKING: &str = "ResumeTracking";
+
+/// Always start a resumed window hidden (default off)
+const RESUME_HIDDEN: &str = "ResumeHidden";
+
+/// Slide a window out as soon as it is registered (default off)
+const HIDE_ON_TRACK: &str = "HideOnTrack";
+
+/// Startup target: exe path / name or launch command (registry string)
+const DEFAULT_TARGET: &str = "DefaultTarget";
+/// Exe of the startup target's window when not the launched program (registry string)
+const DEFAULT_TARGET_EXE: &str = "DefaultTargetExe";
+
+const LAST_TRACKED_EXE: &str = "LastTrackedExe";
+const LAST_TRACKED_VISIBLE: &str = "LastTrackedVisible";
+
+/// Tracked app at the last exit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub exe: String,
+    pub visible: bool,
+}
+
+impl Session {
+    /// Check if the resumed window should start hidden
+    pub fn start_hidden(&self) -> bool {
+        !self.visible || settings::get_bool(RESUME_HIDDEN, false)
+    }
+}
+
+/// Check if newly tracked windows start hidden (default: off)
+pub fn is_hide_on_track() -> bool {
+    settings::get_bool(HIDE_ON_TRACK, false)
+}
+
+/// Toggle hide when tracked, returns new state
+pub fn toggle_hide_on_track() -> Result<bool, SettingsError> {
+    settings::toggle_bool(HIDE_ON_TRACK, false)
+}
+
+/// Configured startup target (None if unset)
+pub fn default_target() -> Option<Target> {
+    settings::get_string(DEFAULT_TARGET).and_then(|command| Target::parse(&command))
+}
+
+/// Exe owning the startup target's window, if not the launched program
+pub fn default_target_exe() -> Option<String> {
+    settings::get_string(DEFAULT_TARGET_EXE).filter(|exe| !exe.trim().is_empty())
+}
+
+/// Persist the startup target command and the exe owning its window (None: the program)
+pub fn set_default_target(command: &str, exe: Option<&str>) -> Result<(), SettingsError> {
+    settings::set_string(DEFAULT_TARGET, command)?;
+    settings::set_string(DEFAULT_TARGET_EXE, exe.unwrap_or(""))
+}
+
+/// Save the tracked app at exit
+pub fn save(exe: &str, visible: bool) -> Result<(), SettingsError> {
+    settings::set_string(LAST_TRACKED_EXE, exe)?;
+    settings::set_bool(LAST_TRACKED_VISIBLE, visible)
+}
+
+/// Forget the session (explicit untrack / window closed)
+pub fn clear() -> Result<(), SettingsError> {
+    settings::set_string(LAST_TRACKED_EXE, "")
+}
+
+/// Session to resume (None if disabled or nothing was tracked)
+pub fn load() -> Option<Session> {
+    if !settings::get_bool(RESUME_TRACKING, true) {
+        return None;
+    }
+    let exe = settings::get_string(LAST_TRACKED_EXE).filter(|exe| !exe.is_empty())?;
+    Some(Session {
+        exe,
+        visible: settings::get_bool(LAST_TRACKED_VISIBLE, true),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_save_load_clear() {
+        let previous = settings::get_string(LAST_TRACKED_EXE);
+        let exe = r"C:\Program Files\WindowsApps\WindowsTerminal.exe";
+
+        save(exe, false).expect("save failed");
+        if settings::get_bool(RESUME_TRACKING, true) {
+            let session = load().expect("session");
+            assert_eq!(session.exe, exe);
+            assert!(!session.visible);
+            assert!(session.start_hidden());
+        }
+
+        clear().expect("clear failed");
+        assert_eq!(load(), None);
+
+        if let Some(previous) = previous {
+            let _ = settings::set_string(LAST_TRACKED_EXE, &previous);
+        }
+    }
+}
@@ -0,0 +1,408 @@
+//! Settings persistence via Windows Registry (HKCU\Software\QuakeModoki)
+//!
+//! Values under HKLM\Software\Policies\QuakeModoki (deployed by IT) take
+//! precedence and are locked: reads return the policy value, writes fail.
+//! Test builds use scratch keys instead (`Software\QuakeModokiTests`, no
+//! policy), so tests never see or change the user's settings; `--simulate`
+//! switches to its own scratch keys (`Software\QuakeModokiSimulate`) at start.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use thiserror::Error;
+use winreg::RegKey;
+use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ};
+use winreg::types::FromRegValue;
+
+#[cfg(not(test))]
+const SETTINGS_KEY: &str = r"Software\QuakeModoki";
+#[cfg(test)]
+const SETTINGS_KEY: &str = r"Software\QuakeModokiTests";
+
+/// Machine policy key (read-only for the app)
+#[cfg(not(test))]
+const POLICY_KEY: &str = r"Software\Policies\QuakeModoki";
+#[cfg(test)]
+const POLICY_KEY: &str = r"Software\Policies\QuakeModokiTests";
+
+/// Scratch keys of `--simulate`
+const SIMULATE_KEY: &str = r"Software\QuakeModokiSimulate";
+const SIMULATE_POLICY_KEY: &str = r"Software\Policies\QuakeModokiSimulate";
+
+/// Scratch keys in use (`--simulate`)
+static SCRATCH: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("Registry access failed: {0}")]
+    Registry(#[from] std::io::Error),
+
+    #[error("{0} is set by policy")]
+    Locked(String),
+}
+
+/// Settings key under HKCU (the scratch one under `--simulate`)
+pub fn settings_key() -> &'static str {
+    if SCRATCH.load(Ordering::Relaxed) {
+        SIMULATE_KEY
+    } else {
+        SETTINGS_KEY
+    }
+}
+
+/// Policy key path under HKLM (the scratch one under `--simulate`)
+fn policy_path() -> &'static str {
+    if SCRATCH.load(Ordering::Relaxed) {
+        SIMULATE_POLICY_KEY
+    } else {
+        POLICY_KEY
+    }
+}
+
+/// Path of a settings subkey under HKCU
+fn subkey_path(subkey: &str) -> String {
+    format!(r"{}\{subkey}", settings_key())
+}
+
+/// Switch to empty scratch keys for the rest of the process (`--simulate`):
+/// the user's settings are neither read nor changed
+pub fn use_scratch_keys() {
+    SCRATCH.store(true, Ordering::Relaxed);
+    discard_scratch_keys();
+}
+
+/// Delete the `--simulate` scratch key (no-op unless scratch keys are in use)
+pub fn discard_scratch_keys() {
+    if SCRATCH.load(Ordering::Relaxed) {
+        let _ = RegKey::predef(HKEY_CURRENT_USER).delete_subkey_all(SIMULATE_KEY);
+    }
+}
+
+/// Policy key, None if no policy deployed
+fn policy_key() -> Option<RegKey> {
+    RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey_with_flags(policy_path(), KEY_READ)
+        .ok()
+}
+
+/// Check if a value is enforced by policy (user changes rejected)
+pub fn is_locked(name: &str) -> bool {
+    policy_key().is_some_and(|key| key.get_raw_value(name).is_ok())
+}
+
+/// Err(Locked) if a policy enforces `name`
+fn ensure_unlocked(name: &str) -> Result<(), SettingsError> {
+    if is_locked(name) {
+        return Err(SettingsError::Locked(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Policy subkey of the same name, None if not deployed
+fn policy_subkey(subkey: &str) -> Option<RegKey> {
+    RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey_with_flags(format!(r"{}\{subkey}", policy_path()), KEY_READ)
+        .ok()
+}
+
+/// Check if a value of a settings subkey is enforced by policy
+fn is_subkey_locked(subkey: &str, name: &str) -> bool {
+    policy_subkey(subkey).is_some_and(|key| key.get_raw_value(name).is_ok())
+}
+
+/// Err(Locked) if a policy enforces `name` of `subkey`
+fn ensure_subkey_unlocked(subkey: &str, name: &str) -> Result<(), SettingsError> {
+    if is_subkey_locked(subkey, name) {
+        return Err(SettingsError::Locked(format!(r"{subkey}\{name}")));
+    }
+    Ok(())
+}
+
+/// Check if the settings key exists (false on the very first run)
+pub fn exists() -> bool {
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey_with_flags(settings_key(), KEY_READ)
+        .is_ok()
+}
+
+/// Read boolean flag (stored as DWORD), `default` if missing
+pub fn get_bool(name: &str, default: bool) -> bool {
+    get_u32(name, default as u32) != 0
+}
+
+/// Write boolean flag (stored as DWORD)
+pub fn set_bool(name: &str, value: bool) -> Result<(), SettingsError> {
+    set_u32(name, value as u32)
+}
+
+/// Toggle boolean flag, returns new state
+pub fn toggle_bool(name: &str, default: bool) -> Result<bool, SettingsError> {
+    let new_state = !get_bool(name, default);
+    set_bool(name, new_state)?;
+    Ok(new_state)
+}
+
+/// Read DWORD value, `default` if missing
+pub fn get_u32(name: &str, default: u32) -> u32 {
+    if let Some(value) = policy_key().and_then(|key| key.get_value::<u32, _>(name).ok()) {
+        return value;
+    }
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    hkcu.open_subkey_with_flags(settings_key(), KEY_READ)
+        .ok()
+        .and_then(|key| key.get_value::<u32, _>(name).ok())
+        .unwrap_or(default)
+}
+
+/// Write DWORD value
+pub fn set_u32(name: &str, value: u32) -> Result<(), SettingsError> {
+    ensure_unlocked(name)?;
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (key, _) = hkcu.create_subkey(settings_key())?;
+    key.set_value(name, &value)?;
+    Ok(())
+}
+
+/// Read DWORD value of a settings subkey, `default` if missing
+pub fn get_subkey_u32(subkey: &str, name: &str, default: u32) -> u32 {
+    if let Some(value) = policy_subkey(subkey).and_then(|key| key.get_value::<u32, _>(name).ok()) {
+        return value;
+    }
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey_with_flags(subkey_path(subkey), KEY_READ)
+        .ok()
+        .and_then(|key| key.get_value::<u32, _>(name).ok())
+        .unwrap_or(default)
+}
+
+/// Write DWORD value of a settings subkey (created if missing)
+pub fn set_subkey_u32(subkey: &str, name: &str, value: u32) -> Result<(), SettingsError> {
+    ensure_subkey_unlocked(subkey, name)?;
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (key, _) = hkcu.create_subkey(subkey_path(subkey))?;
+    key.set_value(name, &value)?;
+    Ok(())
+}
+
+/// Write string value of a settings subkey (created if missing)
+pub fn set_subkey_string(subkey: &str, name: &str, value: &str) -> Result<(), SettingsError> {
+    ensure_subkey_unlocked(subkey, name)?;
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (key, _) = hkcu.create_subkey(subkey_path(subkey))?;
+    key.set_value(name, &value)?;
+    Ok(())
+}
+
+/// Read QWORD value, `default` if missing
+pub fn get_u64(name: &str, default: u64) -> u64 {
+    if let Some(value) = policy_key().and_then(|key| key.get_value::<u64, _>(name).ok()) {
+        return value;
+    }
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    hkcu.open_subkey_with_flags(settings_key(), KEY_READ)
+        .ok()
+        .and_then(|key| key.get_value::<u64, _>(name).ok())
+        .unwrap_or(default)
+}
+
+/// Write QWORD value
+pub fn set_u64(name: &str, value: u64) -> Result<(), SettingsError> {
+    ensure_unlocked(name)?;
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (key, _) = hkcu.create_subkey(settings_key())?;
+    key.set_value(name, &value)?;
+    Ok(())
+}
+
+/// Read string value, None if missing
+pub fn get_string(name: &str) -> Option<String> {
+    if let Some(value) = policy_key().and_then(|key| key.get_value::<String, _>(name).ok()) {
+        return Some(value);
+    }
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    hkcu.open_subkey_with_flags(settings_key(), KEY_READ)
+        .ok()
+        .and_then(|key| key.get_value::<String, _>(name).ok())
+}
+
+/// Write string value
+pub fn set_string(name: &str, value: &str) -> Result<(), SettingsError> {
+    ensure_unlocked(name)?;
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (key, _) = hkcu.create_subkey(settings_key())?;
+    key.set_value(name, &value)?;
+    Ok(())
+}
+
+/// Value of a settings subkey: a string, a DWORD, or another type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingValue {
+    Text(String),
+    Dword(u32),
+    Other,
+}
+
+/// Read all values of a settings subkey as (name, value), empty if missing
+/// A policy subkey of the same name replaces the user's one entirely
+pub fn get_values(subkey: &str) -> Vec<(String, SettingValue)> {
+    policy_subkey(subkey)
+        .or_else(|| {
+            RegKey::predef(HKEY_CURRENT_USER)
+                .open_subkey_with_flags(subkey_path(subkey), KEY_READ)
+                .ok()
+        })
+        .map(|key| {
+            key.enum_values()
+                .filter_map(Result::ok)
+                .map(|(name, raw)| {
+                    let value = u32::from_reg_value(&raw)
+                        .map(SettingValue::Dword)
+                        .or_else(|_| String::from_reg_value(&raw).map(SettingValue::Text))
+                        .unwrap_or(SettingValue::Other);
+                    (name, value)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Read all string values of a settings subkey as (name, data), empty if missing
+/// A policy subkey of the same name replaces the user's one entirely
+pub fn get_string_values(subkey: &str) -> Vec<(String, String)> {
+    get_values(subkey)
+        .into_iter()
+        .filter_map(|(name, value)| match value {
+            SettingValue::Text(text) => Some((name, text)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Delete the scratch settings key: the next reads return defaults
+/// (callers are `#[serial]` like every test touching settings)
+#[cfg(test)]
+pub fn clear() {
+    let _ = RegKey::predef(HKEY_CURRENT_USER).delete_subkey_all(SETTINGS_KEY);
+}
+
+/// Owned `(name, data)` pairs in the shape `get_string_values` returns
+#[cfg(test)]
+pub fn string_values(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(name, data)| (name.to_string(), data.to_string()))
+        .collect()
+}
+
+/// Names of the child keys of a settings subkey, sorted, empty if missing
+/// A policy subkey of the same name replaces the user's one entirely
+pub fn get_subkey_names(subkey: &str) -> Vec<String> {
+    let mut names: Vec<String> = policy_subkey(subkey)
+        .or_else(|| {
+            RegKey::predef(HKEY_CURRENT_USER)
+                .open_subkey_with_flags(subkey_path(subkey), KEY_READ)
+                .ok()
+        })
+        .map(|key| key.enum_keys().filter_map(Result::ok).collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    const TEST_FLAG: &str = "TestFlag";
+
+    fn delete_test_flag() {
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        if let Ok((key, _)) = hkcu.create_subkey(SETTINGS_KEY) {
+            let _ = key.delete_value(TEST_FLAG);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_get_bool_missing_returns_default() {
+        delete_test_flag();
+        assert!(get_bool(TEST_FLAG, true));
+        assert!(!get_bool(TEST_FLAG, false));
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_bool_roundtrip() {
+        set_bool(TEST_FLAG, true).expect("set failed");
+        assert!(get_bool(TEST_FLAG, false));
+
+        set_bool(TEST_FLAG, false).expect("set failed");
+        assert!(!get_bool(TEST_FLAG, true));
+
+        delete_test_flag();
+    }
+
+    #[test]
+    #[serial]
+    fn test_toggle_bool() {
+        delete_test_flag();
+
+        assert!(toggle_bool(TEST_FLAG, false).expect("toggle failed"));
+        assert!(!toggle_bool(TEST_FLAG, false).expect("toggle failed"));
+
+        delete_test_flag();
+    }
+
+    #[test]
+    #[serial]
+    fn test_u32_roundtrip_and_default() {
+        delete_test_flag();
+        assert_eq!(get_u32(TEST_FLAG, 42), 42);
+
+        set_u32(TEST_FLAG, 7).expect("set failed");
+        assert_eq!(get_u32(TEST_FLAG, 42), 7);
+
+        delete_test_flag();
+    }
+
+    #[test]
+    #[serial]
+    fn test_u64_roundtrip() {
+        delete_test_flag();
+        assert_eq!(get_u64(TEST_FLAG, 1), 1);
+
+        set_u64(TEST_FLAG, u64::MAX - 1).expect("set failed");
+        assert_eq!(get_u64(TEST_FLAG, 1), u64::MAX - 1);
+
+        delete_test_flag();
+    }
+
+    #[test]
+    #[serial]
+    fn test_string_roundtrip() {
+        delete_test_flag();
+        assert!(get_string(TEST_FLAG).is_none());
+
+        set_string(TEST_FLAG, "debug").expect("set failed");
+        assert_eq!(get_string(TEST_FLAG).as_deref(), Some("debug"));
+
+        delete_test_flag();
+    }
+
+    #[test]
+    fn test_unset_policy_not_locked() {
+        assert!(!is_locked("NoSuchPolicyForTests"));
+        assert!(ensure_unlocked("NoSuchPolicyForTests").is_ok());
+        assert!(!is_subkey_locked("NoSuchSubkeyForTests", "NoSuchPolicy"));
+        assert!(ensure_subkey_unlocked("NoSuchSubkeyForTests", "NoSuchPolicy").is_ok());
+    }
+
+    #[test]
+    fn test_string_values_missing_subkey() {
+        assert!(get_string_values("NoSuchSubkeyForTests").is_empty());
+    }
+
+    #[test]
+    fn test_subkey_names_missing_subkey() {
+        assert!(get_subkey_names("NoSuchSubkeyForTests").is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+//! Shake trigger: shaking the mouse left and right toggles the tracked window
+//!
+//! Opt-in (`ShakeEnabled`, default off). The mouse hook (see `mouse`) feeds
+//! the cursor's x coordinate: a stroke that turns back by at least
+//! `MIN_STROKE_PX` is a reversal, and `REVERSALS` reversals within `WINDOW_MS`
+//! post WM_SHAKE_DETECTED to the event loop. Injected moves (remote tools,
+//! simulated input) are ignored.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use windows::Win32::UI::WindowsAndMessaging::WM_USER;
+
+use crate::settings::{self, SettingsError};
+
+/// Posted to the event loop when a shake is detected
+pub const WM_SHAKE_DETECTED: u32 = WM_USER + 15;
+
+const SHAKE_ENABLED: &str = "ShakeEnabled";
+
+/// Travel before turning back that counts as a reversal
+const MIN_STROKE_PX: i32 = 40;
+
+/// Reversals needed within `WINDOW_MS`
+const REVERSALS: usize = 4;
+
+const WINDOW_MS: u32 = 700;
+
+static DETECTOR: Mutex<Detector> = Mutex::new(Detector::new());
+
+/// Reversal counting along the x axis
+#[derive(Debug)]
+pub struct Detector {
+    /// Current stroke: -1 left, 1 right, 0 none yet
+    direction: i32,
+    /// Farthest x of the current stroke (start point before the first stroke)
+    extreme: Option<i32>,
+    /// Times of recent reversals (ms, tick count)
+    reversals: VecDeque<u32>,
+}
+
+impl Detector {
+    pub const fn new() -> Self {
+        Self {
+            direction: 0,
+            extreme: None,
+            reversals: VecDeque::new(),
+        }
+    }
+
+    /// Feed a cursor move; true once the moves form a shake (state resets)
+    pub fn feed(&mut self, x: i32, time: u32) -> bool {
+        let Some(extreme) = self.extreme else {
+            self.extreme = Some(x);
+            return false;
+        };
+        let travel = x - extreme;
+        if self.direction == 0 {
+            if travel.abs() >= MIN_STROKE_PX {
+                self.direction = travel.signum();
+                self.extreme = Some(x);
+            }
+        } else if travel * self.direction > 0 {
+            self.extreme = Some(x);
+        } else if -travel * self.direction >= MIN_STROKE_PX {
+            self.direction = -self.direction;
+            self.extreme = Some(x);
+            self.reversals.push_back(time);
+        }
+
+        while self
+            .reversals
+            .front()
+            .is_some_and(|&t| time.wrapping_sub(t) > WINDOW_MS)
+        {
+            self.reversals.pop_front();
+        }
+        if self.reversals.len() >= REVERSALS {
+            self.reset();
+            return true;
+        }
+        false
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Check if the shake trigger is on (default: off)
+pub fn is_enabled() -> bool {
+    settings::get_bool(SHAKE_ENABLED, false)
+}
+
+/// Check if the shake trigger is fixed by policy
+pub fn is_locked() -> bool {
+    settings::is_locked(SHAKE_ENABLED)
+}
+
+/// Toggle the shake trigger, returns new state
+pub fn toggle() -> Result<bool, SettingsError> {
+    settings::toggle_bool(SHAKE_ENABLED, false)
+}
+
+/// Feed a cursor move from the mouse hook; true once it completes a shake
+pub fn feed(x: i32, time: u32) -> bool {
+    DETECTOR
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .feed(x, time)
+}
+
+/// Forget earlier strokes (hook just installed)
+pub fn reset() {
+    DETECTOR.lock().unwrap_or_else(|e| e.into_inner()).reset();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed cursor x positions `step_ms` apart; index of the detecting move
+    fn shake(detector: &mut Detector, points: &[i32], step_ms: u32) -> Option<usize> {
+        points
+            .iter()
+            .enumerate()
+            .position(|(i, &x)| detector.feed(x, 1000 + i as u32 * step_ms))
+    }
+
+    #[test]
+    fn test_fast_shake_detected() {
+        let mut detector = Detector::new();
+        let points = [500, 560, 620, 560, 500, 560, 620, 560, 500, 560, 620];
+        // Turns at indexes 3, 5, 7, 9
+        assert_eq!(shake(&mut detector, &points, 20), Some(9));
+    }
+
+    #[test]
+    fn test_slow_moves_ignored() {
+        let mut detector = Detector::new();
+        let points = [500, 560, 620, 560, 500, 560, 620, 560, 500, 560, 620];
+        assert_eq!(shake(&mut detector, &points, 200), None);
+    }
+
+    #[test]
+    fn test_jitter_ignored() {
+        let mut detector = Detector::new();
+        let points: Vec<i32> = (0..40).map(|i| 500 + (i % 2) * 10).collect();
+        assert_eq!(shake(&mut detector, &points, 10), None);
+    }
+
+    #[test]
+    fn test_resets_after_detection() {
+        let mut detector = Detector::new();
+        let points = [500, 560, 620, 560, 500, 560, 620, 560, 500, 560];
+        assert!(shake(&mut detector, &points, 20).is_some());
+        assert!(!detector.feed(500, 2000));
+        assert!(detector.reversals.is_empty());
+    }
+}
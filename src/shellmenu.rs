@@ -0,0 +1,87 @@
+//! Explorer context menu: "Launch and track with Quake Modoki" on .exe and shortcuts
+//!
+//! Per-user verbs under HKCU\Software\Classes\{exefile,lnkfile}\shell\QuakeModoki
+//! run `quake-modoki.exe --launch "%1"`.
+
+use std::env;
+use thiserror::Error;
+use winreg::RegKey;
+use winreg::enums::{HKEY_CURRENT_USER, KEY_READ};
+
+use crate::i18n::{Text, t};
+
+const VERB_KEYS: [&str; 2] = [
+    r"Software\Classes\exefile\shell\QuakeModoki",
+    r"Software\Classes\lnkfile\shell\QuakeModoki",
+];
+
+#[derive(Debug, Error)]
+pub enum ShellMenuError {
+    #[error("Registry access failed: {0}")]
+    Registry(#[from] std::io::Error),
+
+    #[error("Executable path not found")]
+    ExePath,
+}
+
+/// Check if the context menu entry is registered
+pub fn is_enabled() -> bool {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    VERB_KEYS
+        .iter()
+        .all(|key| hkcu.open_subkey_with_flags(key, KEY_READ).is_ok())
+}
+
+/// Register the verb for .exe and .lnk
+pub fn enable() -> Result<(), ShellMenuError> {
+    let exe_path = env::current_exe().map_err(|_| ShellMenuError::ExePath)?;
+    let exe = exe_path.display();
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    for verb in VERB_KEYS {
+        let (key, _) = hkcu.create_subkey(verb)?;
+        key.set_value("MUIVerb", &t(Text::LaunchAndTrack))?;
+        key.set_value("Icon", &format!("\"{exe}\""))?;
+        let (command, _) = key.create_subkey("command")?;
+        command.set_value("", &format!("\"{exe}\" --launch \"%1\""))?;
+    }
+    Ok(())
+}
+
+/// Remove the verb (missing keys ignored)
+pub fn disable() -> Result<(), ShellMenuError> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    for verb in VERB_KEYS {
+        let _ = hkcu.delete_subkey_all(verb);
+    }
+    Ok(())
+}
+
+/// Toggle the context menu entry, returns new state
+pub fn toggle() -> Result<bool, ShellMenuError> {
+    if is_enabled() {
+        disable()?;
+        Ok(false)
+    } else {
+        enable()?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_enable_disable_cycle() {
+        let _ = disable();
+        assert!(!is_enabled());
+
+        enable().expect("enable failed");
+        assert!(is_enabled());
+
+        disable().expect("disable failed");
+        assert!(!is_enabled());
+    }
+}
@@ -0,0 +1,133 @@
+//! Headless simulation (`--simulate`): run the pipeline against `win32::Recorder`
+//!
+//! Tracks a fake window on an in-memory 1920x1040 desktop and drives it through
+//! hotkey toggles, focus loss and the edge trigger with the real handlers.
+//! Window moves, hides, style and focus changes are recorded instead of
+//! performed and printed per step, so the sequence can be checked in CI
+//! without a desktop (the CI workflow runs it). The fake windows belong to no
+//! process: muting, suspending and trimming are skipped. Settings go to a
+//! scratch key (see `settings`), emptied before and after the run.
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use windows::Win32::Foundation::{HWND, POINT, RECT};
+
+use crate::edge::EdgeAction;
+use crate::settings;
+use crate::tracking::WindowBounds;
+use crate::win32::{self, Recorder, WindowApi};
+
+/// Simulated desktop (single monitor, taskbar at the bottom)
+const WORK_AREA: RECT = RECT {
+    left: 0,
+    top: 0,
+    right: 1920,
+    bottom: 1040,
+};
+
+/// Tracked window (left half) and the app focused when it hides
+const TERMINAL: isize = 0x5151;
+const OTHER_APP: isize = 0x7070;
+const TERMINAL_BOUNDS: WindowBounds = WindowBounds {
+    x: 0,
+    y: 0,
+    width: 960,
+    height: 1040,
+};
+
+/// Longest wait for an edge transition (show / hide delays are 100 / 300 ms)
+const EDGE_TIMEOUT: Duration = Duration::from_secs(2);
+const EDGE_POLL: Duration = Duration::from_millis(16);
+
+static RECORDER: OnceLock<Recorder> = OnceLock::new();
+
+#[derive(Debug, Error)]
+pub enum SimulateError {
+    #[error("Window backend already in use")]
+    BackendInUse,
+
+    #[error("Edge trigger did not fire: {0}")]
+    EdgeTimeout(&'static str),
+}
+
+/// Run the scripted scenario, returns the recorded operations per step
+pub fn run() -> Result<String, SimulateError> {
+    let recorder = RECORDER.get_or_init(|| Recorder::new(WORK_AREA));
+    if !win32::install(recorder) {
+        return Err(SimulateError::BackendInUse);
+    }
+    settings::use_scratch_keys();
+    let result = run_scenario(recorder);
+    settings::discard_scratch_keys();
+    result
+}
+
+/// Drive the fake window through the scripted steps
+fn run_scenario(recorder: &Recorder) -> Result<String, SimulateError> {
+    let terminal = HWND(TERMINAL as *mut _);
+    let other = HWND(OTHER_APP as *mut _);
+    recorder.add_window(other, TERMINAL_BOUNDS);
+    recorder.add_window(terminal, TERMINAL_BOUNDS);
+
+    let mut app = crate::AppState::default();
+    let mut out = Vec::new();
+    let mut step = |name: &str| {
+        out.push(format!("== {name}"));
+        out.extend(recorder.take_log().iter().map(|op| format!("  {op}")));
+    };
+
+    // The focus hook is a placeholder here; the scenario drives focus itself
+    if let Err(e) = app.window.track(terminal) {
+        tracing::warn!("Simulated track: {e}");
+    }
+    step("track");
+
+    crate::toggle_window(&mut app.window);
+    step("hotkey: hide");
+
+    recorder.set_foreground_window(other);
+    crate::toggle_window(&mut app.window);
+    step("hotkey: show");
+
+    recorder.set_foreground_window(other);
+    crate::handle_focus_lost(&mut app.window, other);
+    step("focus lost: hide");
+
+    recorder.set_cursor(POINT { x: 0, y: 500 });
+    wait_for_edge(&mut app, EdgeAction::Show)?;
+    crate::toggle_window(&mut app.window);
+    step("edge: show");
+
+    recorder.set_cursor(POINT { x: 1500, y: 500 });
+    wait_for_edge(&mut app, EdgeAction::Hide)?;
+    crate::toggle_window(&mut app.window);
+    step("edge: hide");
+
+    if let Err(e) = app.window.release() {
+        tracing::warn!("Simulated release: {e}");
+    }
+    step("untrack");
+
+    out.push(format!(
+        "final: visible={} bounds={:?}",
+        app.window.is_visible(),
+        recorder.window_rect(terminal)
+    ));
+    Ok(out.join("\n"))
+}
+
+/// Poll the edge state machine like the edge timer until `wanted` fires
+fn wait_for_edge(app: &mut crate::AppState, wanted: EdgeAction) -> Result<(), SimulateError> {
+    let start = Instant::now();
+    while start.elapsed() < EDGE_TIMEOUT {
+        if crate::check_edge_trigger(app, crate::edge::EdgeInput::Dwell) == Some(wanted) {
+            return Ok(());
+        }
+        std::thread::sleep(EDGE_POLL);
+    }
+    Err(SimulateError::EdgeTimeout(match wanted {
+        EdgeAction::Show => "show",
+        EdgeAction::Hide => "hide",
+    }))
+}
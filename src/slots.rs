@@ -0,0 +1,178 @@
+//! Window slots: up to four windows, each on its own hotkey
+//!
+//! Slot 1 is the usual tracked window on the toggle hotkey; slots 2-4 use
+//! `Slot2Hotkey`..`Slot4Hotkey` (global-hotkey string form, default F9 / F10 /
+//! F11) while `WindowSlots` is on (default off). Only the active slot's window
+//! is under the controller (animation, edge trigger, focus loss); the others
+//! are parked with their own bounds and visibility and taken back when their
+//! hotkey, tray entry or chord digit is pressed. An empty slot's hotkey tracks
+//! the focused window into it.
+
+use global_hotkey::GlobalHotKeyManager;
+use global_hotkey::hotkey::{Code, HotKey};
+use windows::Win32::Foundation::HWND;
+
+use crate::hotkey;
+use crate::settings::{self, SettingsError};
+use crate::tracking::WindowBounds;
+
+/// Number of slots (slot 0 is the toggle hotkey's)
+pub const COUNT: usize = 4;
+
+const ENABLED: &str = "WindowSlots";
+
+/// Default hotkeys of slots 2-4
+const DEFAULT_KEYS: [Code; COUNT - 1] = [Code::F9, Code::F10, Code::F11];
+
+/// Window of an inactive slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parked {
+    pub hwnd: HWND,
+    /// Slide-in target
+    pub bounds: WindowBounds,
+    pub visible: bool,
+}
+
+/// Active slot and the parked windows of the others
+#[derive(Debug, Default)]
+pub struct Slots {
+    active: usize,
+    parked: [Option<Parked>; COUNT],
+}
+
+impl Slots {
+    /// Slot under the controller (0-based)
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    pub fn set_active(&mut self, slot: usize) {
+        self.active = slot.min(COUNT - 1);
+    }
+
+    /// Park the window of `slot` (the window leaves any other slot)
+    pub fn park(&mut self, slot: usize, parked: Parked) {
+        self.remove(parked.hwnd);
+        if let Some(entry) = self.parked.get_mut(slot) {
+            *entry = Some(parked);
+        }
+    }
+
+    /// Parked window of `slot`
+    pub fn parked(&self, slot: usize) -> Option<Parked> {
+        *self.parked.get(slot)?
+    }
+
+    /// Take the parked window of `slot`, leaving it empty
+    pub fn take(&mut self, slot: usize) -> Option<Parked> {
+        self.parked.get_mut(slot)?.take()
+    }
+
+    /// Drop `hwnd` from whichever slot parks it
+    pub fn remove(&mut self, hwnd: HWND) {
+        for entry in &mut self.parked {
+            if entry.is_some_and(|p| p.hwnd == hwnd) {
+                *entry = None;
+            }
+        }
+    }
+
+    /// Parked windows (their original state stays stored while parked)
+    pub fn parked_windows(&self) -> Vec<HWND> {
+        self.parked.iter().flatten().map(|p| p.hwnd).collect()
+    }
+}
+
+/// Check if slot hotkeys are on (default: off)
+pub fn is_enabled() -> bool {
+    settings::get_bool(ENABLED, false)
+}
+
+/// Toggle slot hotkeys, returns new state
+pub fn toggle_enabled() -> Result<bool, SettingsError> {
+    settings::toggle_bool(ENABLED, false)
+}
+
+/// Hotkey of `slot` 1-3 (0-based; slot 0 uses the toggle hotkey)
+pub fn hotkey(slot: usize) -> Option<HotKey> {
+    let default = *DEFAULT_KEYS.get(slot.checked_sub(1)?)?;
+    Some(
+        hotkey::configured(&format!("Slot{}Hotkey", slot + 1))
+            .unwrap_or_else(|| HotKey::new(None, default)),
+    )
+}
+
+/// Register the hotkeys of slots 2-4; returns the registered (slot, hotkey)
+pub fn register(manager: &GlobalHotKeyManager) -> Vec<(usize, HotKey)> {
+    (1..COUNT)
+        .filter_map(|slot| Some((slot, hotkey(slot)?)))
+        .filter(|(slot, key)| {
+            manager
+                .register(*key)
+                .inspect_err(|e| {
+                    tracing::warn!(slot, hotkey = %hotkey::label(key), "Slot hotkey register failed: {e}")
+                })
+                .is_ok()
+        })
+        .collect()
+}
+
+/// Unregister hotkeys returned by `register`
+pub fn unregister(manager: &GlobalHotKeyManager, registered: &[(usize, HotKey)]) {
+    for (_, key) in registered {
+        let _ = manager.unregister(*key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parked(hwnd: isize) -> Parked {
+        Parked {
+            hwnd: HWND(hwnd as *mut _),
+            bounds: WindowBounds {
+                x: 0,
+                y: 0,
+                width: 800,
+                height: 600,
+            },
+            visible: false,
+        }
+    }
+
+    #[test]
+    fn test_park_and_take() {
+        let mut slots = Slots::default();
+        assert_eq!(slots.active(), 0);
+        slots.park(0, parked(0x10));
+        slots.park(2, parked(0x20));
+        assert_eq!(slots.parked_windows().len(), 2);
+
+        assert_eq!(slots.take(2), Some(parked(0x20)));
+        assert_eq!(slots.take(2), None);
+        assert_eq!(slots.take(COUNT), None);
+    }
+
+    #[test]
+    fn test_window_in_one_slot_only() {
+        let mut slots = Slots::default();
+        slots.park(0, parked(0x10));
+        slots.park(1, parked(0x10));
+        assert_eq!(slots.take(0), None);
+        assert_eq!(slots.take(1), Some(parked(0x10)));
+    }
+
+    #[test]
+    fn test_set_active_clamped() {
+        let mut slots = Slots::default();
+        slots.set_active(COUNT + 3);
+        assert_eq!(slots.active(), COUNT - 1);
+    }
+
+    #[test]
+    fn test_slot_hotkey_defaults() {
+        assert!(hotkey(0).is_none());
+        assert!(hotkey(COUNT).is_none());
+    }
+}
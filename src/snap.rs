@@ -0,0 +1,260 @@
+//! Snap presets: fixed visible bounds for the tracked window
+//!
+//! Snapping replaces the stored bounds (the slide-in target) with a share of
+//! the monitor's work area; the slide direction then follows from the new
+//! bounds like for any other position. `default_bounds` is the configured
+//! default position used by "Reset Position", `default_size` the configured
+//! share of a docked side used by "Apply Layout Now".
+
+use windows::Win32::Foundation::RECT;
+
+use crate::animation::Direction;
+use crate::settings::{self, SettingsError};
+use crate::tracking::WindowBounds;
+
+/// Default position: docked side (registry string top / bottom / left / right)
+const DEFAULT_DOCK: &str = "DefaultDock";
+/// Default size in percent of the work area (registry DWORDs)
+const DEFAULT_WIDTH_PCT: &str = "DefaultWidthPct";
+const DEFAULT_HEIGHT_PCT: &str = "DefaultHeightPct";
+const MIN_PCT: u32 = 10;
+const MAX_PCT: u32 = 100;
+
+/// Snap preset (share of the work area)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Snap {
+    /// Left 40%, full height
+    Left,
+    /// Right 40%, full height
+    Right,
+    /// Top 50%, full width
+    Top,
+    /// Bottom 30%, full width
+    Bottom,
+    /// Centered, 60% of width and height
+    Center,
+}
+
+impl Snap {
+    pub const ALL: [Snap; 5] = [
+        Snap::Left,
+        Snap::Right,
+        Snap::Top,
+        Snap::Bottom,
+        Snap::Center,
+    ];
+
+    /// Bounds of the preset in `work_area`
+    pub fn bounds(self, work_area: &RECT) -> WindowBounds {
+        let width = work_area.right - work_area.left;
+        let height = work_area.bottom - work_area.top;
+        let share = |length: i32, percent: i32| length * percent / 100;
+        match self {
+            Snap::Left => WindowBounds {
+                x: work_area.left,
+                y: work_area.top,
+                width: share(width, 40),
+                height,
+            },
+            Snap::Right => WindowBounds {
+                x: work_area.right - share(width, 40),
+                y: work_area.top,
+                width: share(width, 40),
+                height,
+            },
+            Snap::Top => WindowBounds {
+                x: work_area.left,
+                y: work_area.top,
+                width,
+                height: share(height, 50),
+            },
+            Snap::Bottom => WindowBounds {
+                x: work_area.left,
+                y: work_area.bottom - share(height, 30),
+                width,
+                height: share(height, 30),
+            },
+            Snap::Center => WindowBounds {
+                x: work_area.left + share(width, 20),
+                y: work_area.top + share(height, 20),
+                width: share(width, 60),
+                height: share(height, 60),
+            },
+        }
+    }
+}
+
+/// Parse a dock side (case-insensitive)
+pub fn parse_dock(value: &str) -> Option<Direction> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "top" => Some(Direction::Top),
+        "bottom" => Some(Direction::Bottom),
+        "left" => Some(Direction::Left),
+        "right" => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+/// Registry name of a dock side
+pub fn dock_name(dock: Direction) -> &'static str {
+    match dock {
+        Direction::Top => "top",
+        Direction::Bottom => "bottom",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
+
+/// Persist the default dock side
+pub fn set_default_dock(dock: Direction) -> Result<(), SettingsError> {
+    settings::set_string(DEFAULT_DOCK, dock_name(dock))
+}
+
+/// Bounds docked to `dock`, centered along it, sized by percent of `work_area`
+pub fn docked_bounds(
+    dock: Direction,
+    width_pct: u32,
+    height_pct: u32,
+    work_area: &RECT,
+) -> WindowBounds {
+    let area_width = work_area.right - work_area.left;
+    let area_height = work_area.bottom - work_area.top;
+    let width = area_width * width_pct.clamp(MIN_PCT, MAX_PCT) as i32 / 100;
+    let height = area_height * height_pct.clamp(MIN_PCT, MAX_PCT) as i32 / 100;
+    let center_x = work_area.left + (area_width - width) / 2;
+    let center_y = work_area.top + (area_height - height) / 2;
+    let (x, y) = match dock {
+        Direction::Top => (center_x, work_area.top),
+        Direction::Bottom => (center_x, work_area.bottom - height),
+        Direction::Left => (work_area.left, center_y),
+        Direction::Right => (work_area.right - width, center_y),
+    };
+    WindowBounds {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Configured default position (default: top, full width, half height)
+pub fn default_bounds(work_area: &RECT) -> WindowBounds {
+    let dock = settings::get_string(DEFAULT_DOCK)
+        .and_then(|value| parse_dock(&value))
+        .unwrap_or(Direction::Top);
+    let (width_pct, height_pct) = default_size(dock);
+    docked_bounds(dock, width_pct, height_pct, work_area)
+}
+
+/// Configured size in percent (width, height) of a window docked to `dock`
+pub fn default_size(dock: Direction) -> (u32, u32) {
+    let (width_pct, height_pct) = match dock {
+        Direction::Top | Direction::Bottom => (100, 50),
+        Direction::Left | Direction::Right => (40, 100),
+    };
+    (
+        settings::get_u32(DEFAULT_WIDTH_PCT, width_pct),
+        settings::get_u32(DEFAULT_HEIGHT_PCT, height_pct),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracking::calc_direction;
+
+    const WORK_AREA: RECT = RECT {
+        left: 0,
+        top: 0,
+        right: 1920,
+        bottom: 1040,
+    };
+
+    #[test]
+    fn test_snap_bounds() {
+        assert_eq!(
+            Snap::Left.bounds(&WORK_AREA),
+            WindowBounds {
+                x: 0,
+                y: 0,
+                width: 768,
+                height: 1040
+            }
+        );
+        assert_eq!(Snap::Right.bounds(&WORK_AREA).x, 1152);
+        assert_eq!(Snap::Top.bounds(&WORK_AREA).height, 520);
+        let bottom = Snap::Bottom.bounds(&WORK_AREA);
+        assert_eq!(bottom.y + bottom.height, 1040);
+        assert_eq!(bottom.height, 312);
+        assert_eq!(
+            Snap::Center.bounds(&WORK_AREA),
+            WindowBounds {
+                x: 384,
+                y: 208,
+                width: 1152,
+                height: 624
+            }
+        );
+    }
+
+    #[test]
+    fn test_snap_offset_work_area() {
+        let work_area = RECT {
+            left: 1920,
+            top: 40,
+            right: 3840,
+            bottom: 1080,
+        };
+        let right = Snap::Right.bounds(&work_area);
+        assert_eq!(right.x + right.width, 3840);
+        assert_eq!(right.y, 40);
+    }
+
+    #[test]
+    fn test_parse_dock() {
+        assert_eq!(parse_dock("Top"), Some(Direction::Top));
+        assert_eq!(parse_dock(" right "), Some(Direction::Right));
+        assert_eq!(parse_dock("middle"), None);
+        for dock in [
+            Direction::Top,
+            Direction::Bottom,
+            Direction::Left,
+            Direction::Right,
+        ] {
+            assert_eq!(parse_dock(dock_name(dock)), Some(dock));
+        }
+    }
+
+    #[test]
+    fn test_docked_bounds() {
+        assert_eq!(
+            docked_bounds(Direction::Top, 100, 50, &WORK_AREA),
+            WindowBounds {
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 520
+            }
+        );
+        let right = docked_bounds(Direction::Right, 40, 80, &WORK_AREA);
+        assert_eq!(right.x + right.width, 1920);
+        assert_eq!(right.y, 104);
+        let bottom = docked_bounds(Direction::Bottom, 60, 30, &WORK_AREA);
+        assert_eq!((bottom.x, bottom.y + bottom.height), (384, 1040));
+    }
+
+    #[test]
+    fn test_docked_bounds_clamps_percent() {
+        let bounds = docked_bounds(Direction::Left, 0, 500, &WORK_AREA);
+        assert_eq!((bounds.width, bounds.height), (192, 1040));
+    }
+
+    #[test]
+    fn test_snap_direction_follows_bounds() {
+        let direction = |snap: Snap| calc_direction(&snap.bounds(&WORK_AREA), &WORK_AREA);
+        assert_eq!(direction(Snap::Left), Direction::Left);
+        assert_eq!(direction(Snap::Right), Direction::Right);
+        assert_eq!(direction(Snap::Top), Direction::Top);
+        assert_eq!(direction(Snap::Bottom), Direction::Bottom);
+    }
+}
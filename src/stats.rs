@@ -0,0 +1,210 @@
+//! Usage statistics: toggle counts, trigger sources, visible duration
+//!
+//! Persisted in the settings registry key; summarized in the tray menu.
+
+use std::sync::Mutex;
+use std::time::Instant;
+use windows::Win32::System::SystemInformation::GetLocalTime;
+
+use crate::settings;
+
+const STATS_DAY: &str = "StatsDay";
+const STATS_TOGGLES_TODAY: &str = "StatsTogglesToday";
+const STATS_HOTKEY: &str = "StatsHotkeyActivations";
+const STATS_EDGE: &str = "StatsEdgeActivations";
+const STATS_VISIBLE_COUNT: &str = "StatsVisibleCount";
+const STATS_VISIBLE_TOTAL_MS: &str = "StatsVisibleTotalMs";
+
+/// What caused a toggle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    Hotkey,
+    Edge,
+    /// Plugin trigger, mouse shake, jump list, command line (counted in toggles only)
+    Other,
+}
+
+/// Persistent counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageStats {
+    /// Local date of `toggles_today` as yyyymmdd
+    pub day: u32,
+    pub toggles_today: u32,
+    pub hotkey_activations: u32,
+    pub edge_activations: u32,
+    pub visible_count: u32,
+    pub visible_total_ms: u64,
+}
+
+impl UsageStats {
+    /// Count a toggle, resetting the daily counter on date change
+    pub fn record_toggle(&mut self, day: u32, trigger: Trigger) {
+        if self.day != day {
+            self.day = day;
+            self.toggles_today = 0;
+        }
+        self.toggles_today = self.toggles_today.saturating_add(1);
+        match trigger {
+            Trigger::Hotkey => self.hotkey_activations = self.hotkey_activations.saturating_add(1),
+            Trigger::Edge => self.edge_activations = self.edge_activations.saturating_add(1),
+            Trigger::Other => {}
+        }
+    }
+
+    /// Add one completed visible period
+    pub fn record_visible(&mut self, ms: u64) {
+        self.visible_count = self.visible_count.saturating_add(1);
+        self.visible_total_ms = self.visible_total_ms.saturating_add(ms);
+    }
+
+    /// Average visible duration in ms (None before the first hide)
+    pub fn avg_visible_ms(&self) -> Option<u64> {
+        (self.visible_count > 0).then(|| self.visible_total_ms / self.visible_count as u64)
+    }
+
+    /// One-line summary for the tray (toggles shown as 0 on a new day)
+    pub fn summary(&self, today: u32) -> String {
+        let toggles = if self.day == today {
+            self.toggles_today
+        } else {
+            0
+        };
+        let avg = self
+            .avg_visible_ms()
+            .map(|ms| format!("{:.1}s", ms as f64 / 1000.0))
+            .unwrap_or_else(|| "-".to_string());
+        format!(
+            "Today: {} toggles · Avg visible {} · Hotkey {} / Edge {}",
+            toggles, avg, self.hotkey_activations, self.edge_activations
+        )
+    }
+
+    fn load() -> Self {
+        Self {
+            day: settings::get_u32(STATS_DAY, 0),
+            toggles_today: settings::get_u32(STATS_TOGGLES_TODAY, 0),
+            hotkey_activations: settings::get_u32(STATS_HOTKEY, 0),
+            edge_activations: settings::get_u32(STATS_EDGE, 0),
+            visible_count: settings::get_u32(STATS_VISIBLE_COUNT, 0),
+            visible_total_ms: settings::get_u64(STATS_VISIBLE_TOTAL_MS, 0),
+        }
+    }
+
+    fn save(&self) {
+        let result = settings::set_u32(STATS_DAY, self.day)
+            .and_then(|_| settings::set_u32(STATS_TOGGLES_TODAY, self.toggles_today))
+            .and_then(|_| settings::set_u32(STATS_HOTKEY, self.hotkey_activations))
+            .and_then(|_| settings::set_u32(STATS_EDGE, self.edge_activations))
+            .and_then(|_| settings::set_u32(STATS_VISIBLE_COUNT, self.visible_count))
+            .and_then(|_| settings::set_u64(STATS_VISIBLE_TOTAL_MS, self.visible_total_ms));
+        if let Err(e) = result {
+            tracing::warn!("Stats save failed: {e}");
+        }
+    }
+}
+
+/// Cached stats (loaded on first use) + start of current visible period
+struct StatsState {
+    stats: Option<UsageStats>,
+    shown_at: Option<Instant>,
+}
+
+static STATE: Mutex<StatsState> = Mutex::new(StatsState {
+    stats: None,
+    shown_at: None,
+});
+
+/// Today's local date as yyyymmdd
+fn today() -> u32 {
+    let now = unsafe { GetLocalTime() };
+    now.wYear as u32 * 10_000 + now.wMonth as u32 * 100 + now.wDay as u32
+}
+
+/// Run f on cached stats, then persist
+fn update(f: impl FnOnce(&mut UsageStats, &mut Option<Instant>)) {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let StatsState { stats, shown_at } = &mut *state;
+    let stats = stats.get_or_insert_with(UsageStats::load);
+    f(stats, shown_at);
+    stats.save();
+}
+
+/// Record a toggle triggered by hotkey / edge
+pub fn record_toggle(trigger: Trigger) {
+    let day = today();
+    update(|stats, _| stats.record_toggle(day, trigger));
+}
+
+/// Window became visible: start timing
+pub fn mark_shown() {
+    STATE.lock().unwrap_or_else(|e| e.into_inner()).shown_at = Some(Instant::now());
+}
+
+/// Window hidden: add elapsed visible time
+pub fn mark_hidden() {
+    update(|stats, shown_at| {
+        if let Some(since) = shown_at.take() {
+            stats.record_visible(since.elapsed().as_millis() as u64);
+        }
+    });
+}
+
+/// Summary line for the tray
+pub fn summary() -> String {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state
+        .stats
+        .get_or_insert_with(UsageStats::load)
+        .summary(today())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_toggle_counts_by_trigger() {
+        let mut stats = UsageStats::default();
+        stats.record_toggle(20260101, Trigger::Hotkey);
+        stats.record_toggle(20260101, Trigger::Hotkey);
+        stats.record_toggle(20260101, Trigger::Edge);
+
+        assert_eq!(stats.toggles_today, 3);
+        assert_eq!(stats.hotkey_activations, 2);
+        assert_eq!(stats.edge_activations, 1);
+    }
+
+    #[test]
+    fn test_record_toggle_resets_on_new_day() {
+        let mut stats = UsageStats::default();
+        stats.record_toggle(20260101, Trigger::Hotkey);
+        stats.record_toggle(20260102, Trigger::Hotkey);
+
+        assert_eq!(stats.day, 20260102);
+        assert_eq!(stats.toggles_today, 1);
+        assert_eq!(stats.hotkey_activations, 2); // lifetime counter
+    }
+
+    #[test]
+    fn test_avg_visible_ms() {
+        let mut stats = UsageStats::default();
+        assert_eq!(stats.avg_visible_ms(), None);
+
+        stats.record_visible(1000);
+        stats.record_visible(3000);
+        assert_eq!(stats.avg_visible_ms(), Some(2000));
+    }
+
+    #[test]
+    fn test_summary_stale_day_shows_zero() {
+        let mut stats = UsageStats::default();
+        stats.record_toggle(20260101, Trigger::Edge);
+        stats.record_visible(1500);
+
+        assert_eq!(
+            stats.summary(20260101),
+            "Today: 1 toggles · Avg visible 1.5s · Hotkey 0 / Edge 1"
+        );
+        assert!(stats.summary(20260102).starts_with("Today: 0 toggles"));
+    }
+}
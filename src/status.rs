@@ -0,0 +1,208 @@
+//! About / Status panel: snapshot of version, hotkeys, tracked window, edge, hooks
+//!
+//! Shown as a message box on its own thread so the event loop keeps running.
+
+use windows::Win32::UI::WindowsAndMessaging::{
+    MB_ICONINFORMATION, MB_OK, MB_SETFOREGROUND, MessageBoxW,
+};
+use windows::core::HSTRING;
+
+use crate::controller::WindowController;
+use crate::edge::{self, EdgeConfig};
+use crate::flash;
+use crate::i18n::{Text, t, tf};
+use crate::tracking::{self, WindowBounds};
+
+/// Tracked window details
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackedInfo {
+    pub title: String,
+    pub exe: Option<String>,
+    pub hwnd: isize,
+    pub bounds: Option<WindowBounds>,
+    pub visible: bool,
+}
+
+/// Everything shown in the panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusInfo {
+    pub version: &'static str,
+    pub hotkeys: Vec<(String, String)>,
+    pub tracked: Option<TrackedInfo>,
+    pub edge_enabled: bool,
+    pub edge: EdgeConfig,
+    pub focus_hook: bool,
+    pub flash_hook: bool,
+}
+
+/// Collect current state
+/// hotkeys: (binding, action) pairs as registered
+pub fn collect(
+    hotkeys: &[(&str, &str)],
+    edge_config: &EdgeConfig,
+    window: &WindowController,
+) -> StatusInfo {
+    let tracked = window.is_valid().then(|| {
+        let hwnd = window.hwnd();
+        TrackedInfo {
+            title: tracking::get_window_title(hwnd),
+            exe: tracking::get_process_path(hwnd),
+            hwnd: hwnd.0 as isize,
+            bounds: window.bounds(),
+            visible: window.is_visible(),
+        }
+    });
+
+    StatusInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        hotkeys: hotkeys
+            .iter()
+            .map(|(key, action)| (key.to_string(), action.to_string()))
+            .collect(),
+        tracked,
+        edge_enabled: edge::is_enabled(),
+        edge: *edge_config,
+        focus_hook: window.has_focus_hook(),
+        flash_hook: flash::is_hook_installed(),
+    }
+}
+
+/// Plain-text report (also suitable for pasting into bug reports)
+pub fn format_report(info: &StatusInfo) -> String {
+    let mut lines = vec![format!("Quake Modoki v{}", info.version), String::new()];
+
+    lines.push(t(Text::StatusHotkeys).to_string());
+    for (key, action) in &info.hotkeys {
+        lines.push(format!("  {key}  {action}"));
+    }
+    lines.push(String::new());
+
+    match &info.tracked {
+        Some(w) => {
+            let exe = w.exe.as_deref().unwrap_or(t(Text::StatusUnavailable));
+            lines.push(t(Text::StatusTracked).to_string());
+            lines.push(format!("  {}", tf(Text::StatusWindowTitle, &[&w.title])));
+            lines.push(format!("  {}", tf(Text::StatusExe, &[exe])));
+            lines.push(format!("  HWND: 0x{:X}", w.hwnd));
+            lines.push(match w.bounds {
+                Some(b) => format!(
+                    "  {}",
+                    tf(
+                        Text::StatusBounds,
+                        &[
+                            &b.width.to_string(),
+                            &b.height.to_string(),
+                            &b.x.to_string(),
+                            &b.y.to_string(),
+                        ],
+                    )
+                ),
+                None => format!("  {}", t(Text::StatusBoundsNotSaved)),
+            });
+            lines.push(format!(
+                "  {}",
+                t(if w.visible {
+                    Text::StatusVisible
+                } else {
+                    Text::StatusHidden
+                })
+            ));
+        }
+        None => lines.push(t(Text::StatusUntracked).to_string()),
+    }
+    lines.push(String::new());
+
+    lines.push(tf(
+        Text::StatusEdge,
+        &[
+            on_off(info.edge_enabled),
+            &info.edge.threshold_px.to_string(),
+            &info.edge.touchpad_threshold_px.to_string(),
+            &info.edge.touch_threshold_px.to_string(),
+            &info.edge.show_delay_ms.to_string(),
+            &info.edge.hide_delay_ms.to_string(),
+        ],
+    ));
+    lines.push(tf(
+        Text::StatusHooks,
+        &[on_off(info.focus_hook), on_off(info.flash_hook)],
+    ));
+
+    lines.join("\n")
+}
+
+fn on_off(value: bool) -> &'static str {
+    t(if value {
+        Text::StatusOn
+    } else {
+        Text::StatusOff
+    })
+}
+
+/// Show report in a message box (non-blocking)
+pub fn show(report: String) {
+    std::thread::spawn(move || unsafe {
+        MessageBoxW(
+            None,
+            &HSTRING::from(report),
+            &HSTRING::from(t(Text::StatusTitle)),
+            MB_OK | MB_ICONINFORMATION | MB_SETFOREGROUND,
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(tracked: Option<TrackedInfo>) -> StatusInfo {
+        StatusInfo {
+            version: "1.2.3",
+            hotkeys: vec![("F8".to_string(), "Toggle".to_string())],
+            tracked,
+            edge_enabled: true,
+            edge: EdgeConfig::default(),
+            focus_hook: true,
+            flash_hook: false,
+        }
+    }
+
+    #[test]
+    fn test_format_report_untracked() {
+        let report = format_report(&info(None));
+        assert!(report.starts_with("Quake Modoki v1.2.3"));
+        assert!(report.contains("  F8  Toggle"));
+        assert!(report.contains(t(Text::StatusUntracked)));
+        assert!(report.contains(&tf(
+            Text::StatusEdge,
+            &[t(Text::StatusOn), "1", "4", "24", "100", "300"]
+        )));
+        assert!(report.contains(&tf(
+            Text::StatusHooks,
+            &[t(Text::StatusOn), t(Text::StatusOff)]
+        )));
+    }
+
+    #[test]
+    fn test_format_report_tracked() {
+        let report = format_report(&info(Some(TrackedInfo {
+            title: "Terminal".to_string(),
+            exe: None,
+            hwnd: 0x1234,
+            bounds: Some(WindowBounds {
+                x: 10,
+                y: 20,
+                width: 800,
+                height: 600,
+            }),
+            visible: false,
+        })));
+        let exe = tf(Text::StatusExe, &[t(Text::StatusUnavailable)]);
+        let bounds = tf(Text::StatusBounds, &["800", "600", "10", "20"]);
+        assert!(report.contains(&format!("  {}", tf(Text::StatusWindowTitle, &["Terminal"]))));
+        assert!(report.contains(&format!("  {exe}")));
+        assert!(report.contains("  HWND: 0x1234"));
+        assert!(report.contains(&format!("  {bounds}")));
+        assert!(report.contains(&format!("  {}", t(Text::StatusHidden))));
+    }
+}
@@ -0,0 +1,181 @@
+//! Touch swipe in from the trigger edge (tablets)
+//!
+//! Touch input reaches the mouse hook as promoted mouse messages carrying the
+//! pen / touch signature. A touch contact that travels `MIN_TRAVEL_PX` within
+//! `MAX_DURATION_MS` of touching down is reported once as WM_EDGE_SWIPE with
+//! its start and current point; the event loop checks that it started at the
+//! trigger edge and moved inward (see `edge::check_swipe`).
+
+use std::sync::Mutex;
+use windows::Win32::Foundation::POINT;
+use windows::Win32::UI::WindowsAndMessaging::WM_USER;
+
+/// Posted for a quick touch drag (WPARAM: `pack`ed start and end point)
+pub const WM_EDGE_SWIPE: u32 = WM_USER + 17;
+
+/// `dwExtraInfo` of mouse messages promoted from pen / touch input
+const PEN_TOUCH_SIGNATURE: usize = 0xFF51_5700;
+const SIGNATURE_MASK: usize = 0xFFFF_FF00;
+/// Set for touch (clear for pen)
+const TOUCH_FLAG: usize = 0x80;
+
+/// Distance that makes a drag a swipe
+pub const MIN_TRAVEL_PX: i32 = 60;
+
+const MAX_DURATION_MS: u32 = 600;
+
+static TRACKER: Mutex<Tracker> = Mutex::new(Tracker::new());
+
+/// Start and end of a swipe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Swipe {
+    pub from: POINT,
+    pub to: POINT,
+}
+
+impl Swipe {
+    /// Pack into a WPARAM (16 bits per coordinate)
+    pub fn pack(self) -> usize {
+        [self.from.x, self.from.y, self.to.x, self.to.y]
+            .iter()
+            .enumerate()
+            .fold(0u64, |packed, (i, &c)| {
+                packed | (c as i16 as u16 as u64) << (i * 16)
+            }) as usize
+    }
+
+    pub fn unpack(wparam: usize) -> Self {
+        let c = |i: usize| ((wparam as u64) >> (i * 16)) as u16 as i16 as i32;
+        Self {
+            from: POINT { x: c(0), y: c(1) },
+            to: POINT { x: c(2), y: c(3) },
+        }
+    }
+}
+
+/// Check if a hooked mouse message was promoted from touch
+pub fn is_touch(extra_info: usize) -> bool {
+    extra_info & SIGNATURE_MASK == PEN_TOUCH_SIGNATURE && extra_info & TOUCH_FLAG != 0
+}
+
+/// Current touch contact
+#[derive(Debug)]
+pub struct Tracker {
+    /// Touch-down point and time (None: no contact, or already reported)
+    down: Option<(POINT, u32)>,
+}
+
+impl Tracker {
+    pub const fn new() -> Self {
+        Self { down: None }
+    }
+
+    pub fn down(&mut self, pt: POINT, time: u32) {
+        self.down = Some((pt, time));
+    }
+
+    /// Contact moved; the swipe once it travelled far enough in time
+    pub fn moved(&mut self, pt: POINT, time: u32) -> Option<Swipe> {
+        let (from, since) = self.down?;
+        if time.wrapping_sub(since) > MAX_DURATION_MS {
+            self.down = None;
+            return None;
+        }
+        let travel = (pt.x - from.x).abs().max((pt.y - from.y).abs());
+        if travel < MIN_TRAVEL_PX {
+            return None;
+        }
+        self.down = None;
+        Some(Swipe { from, to: pt })
+    }
+
+    pub fn up(&mut self) {
+        self.down = None;
+    }
+}
+
+impl Default for Tracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Touch down from the mouse hook
+pub fn down(pt: POINT, time: u32) {
+    TRACKER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .down(pt, time);
+}
+
+/// Touch move from the mouse hook; the swipe to report, if any
+pub fn moved(pt: POINT, time: u32) -> Option<Swipe> {
+    TRACKER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .moved(pt, time)
+}
+
+/// Touch up from the mouse hook
+pub fn up() {
+    TRACKER.lock().unwrap_or_else(|e| e.into_inner()).up();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: i32, y: i32) -> POINT {
+        POINT { x, y }
+    }
+
+    #[test]
+    fn test_pack_roundtrip() {
+        let swipe = Swipe {
+            from: point(-1920, 0),
+            to: point(3839, 2159),
+        };
+        assert_eq!(Swipe::unpack(swipe.pack()), swipe);
+    }
+
+    #[test]
+    fn test_is_touch() {
+        assert!(is_touch(0xFF51_5780));
+        assert!(!is_touch(0xFF51_5700)); // Pen
+        assert!(!is_touch(0));
+    }
+
+    #[test]
+    fn test_quick_drag_reported_once() {
+        let mut tracker = Tracker::new();
+        tracker.down(point(500, 0), 1000);
+        assert_eq!(tracker.moved(point(500, 30), 1050), None);
+        assert_eq!(
+            tracker.moved(point(505, 90), 1100),
+            Some(Swipe {
+                from: point(500, 0),
+                to: point(505, 90),
+            })
+        );
+        assert_eq!(tracker.moved(point(505, 200), 1150), None);
+    }
+
+    #[test]
+    fn test_slow_drag_ignored() {
+        let mut tracker = Tracker::new();
+        tracker.down(point(500, 0), 1000);
+        assert_eq!(
+            tracker.moved(point(500, 200), 1000 + MAX_DURATION_MS + 1),
+            None
+        );
+    }
+
+    #[test]
+    fn test_no_contact_no_swipe() {
+        let mut tracker = Tracker::new();
+        assert_eq!(tracker.moved(point(500, 200), 1000), None);
+        tracker.down(point(500, 0), 1000);
+        tracker.up();
+        assert_eq!(tracker.moved(point(500, 200), 1010), None);
+    }
+}
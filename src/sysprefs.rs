@@ -0,0 +1,172 @@
+//! System visual-effect preferences (Settings > Accessibility > Visual effects)
+//!
+//! With "Animation effects" off (SPI_GETCLIENTAREAANIMATION, or window
+//! animation via SPI_GETANIMATION) toggles happen instantly; with
+//! "Transparency effects" off (`Themes\Personalize\EnableTransparency` = 0)
+//! opacity and fades are skipped. In a remote (RDP) session or while the
+//! secure desktop (lock screen, UAC prompt) has the input, every frame would
+//! be remoted or wasted: toggles are instant and skip WS_EX_COMPOSITED.
+//! With battery saver on (and not on AC power) slides are shortened and edge
+//! polling slows down, back to full fidelity once it turns off.
+//! `FollowSystemEffects` = 0 ignores all of these.
+
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+use windows::Win32::System::StationsAndDesktops::{
+    CloseDesktop, DESKTOP_CONTROL_FLAGS, DESKTOP_READOBJECTS, GetUserObjectInformationW,
+    OpenInputDesktop, UOI_NAME,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    ANIMATIONINFO, GetSystemMetrics, SM_REMOTESESSION, SPI_GETANIMATION,
+    SPI_GETCLIENTAREAANIMATION, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, SystemParametersInfoW,
+};
+use winreg::RegKey;
+use winreg::enums::{HKEY_CURRENT_USER, KEY_READ};
+
+use crate::settings;
+
+const FOLLOW_SYSTEM: &str = "FollowSystemEffects";
+
+const PERSONALIZE_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
+const ENABLE_TRANSPARENCY: &str = "EnableTransparency";
+
+/// Check if system effect preferences are honored (default: yes)
+fn follows_system() -> bool {
+    settings::get_bool(FOLLOW_SYSTEM, true)
+}
+
+/// Check if the OS allows animations (true when not followed or unknown)
+pub fn animations_enabled() -> bool {
+    !follows_system() || (client_area_animation() && window_animation())
+}
+
+/// Check if the OS allows transparency effects (true when not followed or unknown)
+pub fn transparency_enabled() -> bool {
+    !follows_system() || system_transparency()
+}
+
+/// Check if the session makes animation frames costly or pointless
+/// (remote session or secure desktop; false when not followed)
+pub fn is_constrained_session() -> bool {
+    follows_system() && (is_remote_session() || is_secure_desktop())
+}
+
+/// Check if battery saver is on while running on battery (false when not followed)
+pub fn is_battery_saver() -> bool {
+    if !follows_system() {
+        return false;
+    }
+    let mut status = SYSTEM_POWER_STATUS::default();
+    unsafe { GetSystemPowerStatus(&mut status) }.is_ok() && saver_on_battery(&status)
+}
+
+/// Battery saver flag set and not plugged in (ACLineStatus 1 = online)
+fn saver_on_battery(status: &SYSTEM_POWER_STATUS) -> bool {
+    status.SystemStatusFlag == 1 && status.ACLineStatus != 1
+}
+
+/// Running in a Remote Desktop session
+fn is_remote_session() -> bool {
+    unsafe { GetSystemMetrics(SM_REMOTESESSION) != 0 }
+}
+
+/// Input desktop is not the user's "Default" one (Winlogon while locked or
+/// during UAC; opening it is denied then)
+fn is_secure_desktop() -> bool {
+    let Ok(desktop) =
+        (unsafe { OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), false, DESKTOP_READOBJECTS) })
+    else {
+        return true;
+    };
+    let mut name = [0u16; 64];
+    let named = unsafe {
+        GetUserObjectInformationW(
+            HANDLE(desktop.0),
+            UOI_NAME,
+            Some(name.as_mut_ptr().cast()),
+            std::mem::size_of_val(&name) as u32,
+            None,
+        )
+    }
+    .is_ok();
+    let _ = unsafe { CloseDesktop(desktop) };
+    named && !is_default_desktop(&name)
+}
+
+/// NUL-terminated desktop name is "Default" (case-insensitive)
+fn is_default_desktop(name: &[u16]) -> bool {
+    let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+    String::from_utf16_lossy(&name[..len]).eq_ignore_ascii_case("Default")
+}
+
+/// "Animation effects" (Windows 8+; true if the query fails)
+fn client_area_animation() -> bool {
+    let mut enabled = windows::core::BOOL(1);
+    let ok = unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            Some(&mut enabled as *mut _ as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    }
+    .is_ok();
+    !ok || enabled.as_bool()
+}
+
+/// "Animate windows when minimizing and maximizing" (true if the query fails)
+fn window_animation() -> bool {
+    let mut info = ANIMATIONINFO {
+        cbSize: std::mem::size_of::<ANIMATIONINFO>() as u32,
+        iMinAnimate: 1,
+    };
+    let ok = unsafe {
+        SystemParametersInfoW(
+            SPI_GETANIMATION,
+            info.cbSize,
+            Some(&mut info as *mut _ as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    }
+    .is_ok();
+    !ok || info.iMinAnimate != 0
+}
+
+/// "Transparency effects" (true if the value is missing)
+fn system_transparency() -> bool {
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey_with_flags(PERSONALIZE_KEY, KEY_READ)
+        .and_then(|key| key.get_value::<u32, _>(ENABLE_TRANSPARENCY))
+        .map_or(true, |value| value != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain([0, 0x41]).collect()
+    }
+
+    #[test]
+    fn test_saver_on_battery() {
+        let status = |saver: u8, ac: u8| SYSTEM_POWER_STATUS {
+            SystemStatusFlag: saver,
+            ACLineStatus: ac,
+            ..Default::default()
+        };
+        assert!(saver_on_battery(&status(1, 0)));
+        // Plugged in, or AC status unknown (255) on battery
+        assert!(!saver_on_battery(&status(1, 1)));
+        assert!(saver_on_battery(&status(1, 255)));
+        assert!(!saver_on_battery(&status(0, 0)));
+    }
+
+    #[test]
+    fn test_is_default_desktop() {
+        assert!(is_default_desktop(&wide("Default")));
+        assert!(is_default_desktop(&wide("default")));
+        assert!(!is_default_desktop(&wide("Winlogon")));
+        assert!(!is_default_desktop(&wide("")));
+    }
+}
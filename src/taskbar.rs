@@ -0,0 +1,142 @@
+//! Taskbar / Alt-Tab presence: keep the hidden tracked window out of the shell
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::{WS_EX_APPWINDOW, WS_EX_TOOLWINDOW};
+
+use crate::settings::{self, SettingsError};
+
+const HIDE_FROM_TASKBAR: &str = "HideFromTaskbar";
+const HIDE_FROM_ALT_TAB: &str = "HideFromAltTab";
+
+/// Extended style bits touched while hidden
+const MANAGED_BITS: isize = WS_EX_TOOLWINDOW.0 as isize | WS_EX_APPWINDOW.0 as isize;
+
+/// Extended style captured before hiding, by window (missing = not modified)
+static SAVED_EXSTYLE: Mutex<BTreeMap<isize, isize>> = Mutex::new(BTreeMap::new());
+
+/// Check if hide-from-taskbar enabled in registry (default: off)
+pub fn is_enabled() -> bool {
+    settings::get_bool(HIDE_FROM_TASKBAR, false)
+}
+
+/// Check if hide-from-taskbar is fixed by policy
+pub fn is_locked() -> bool {
+    settings::is_locked(HIDE_FROM_TASKBAR)
+}
+
+/// Toggle hide-from-taskbar, returns new state
+pub fn toggle() -> Result<bool, SettingsError> {
+    settings::toggle_bool(HIDE_FROM_TASKBAR, false)
+}
+
+/// Check if hide-from-Alt-Tab enabled in registry (default: off)
+pub fn is_alt_tab_enabled() -> bool {
+    settings::get_bool(HIDE_FROM_ALT_TAB, false)
+}
+
+/// Check if hide-from-Alt-Tab is fixed by policy
+pub fn is_alt_tab_locked() -> bool {
+    settings::is_locked(HIDE_FROM_ALT_TAB)
+}
+
+/// Toggle hide-from-Alt-Tab, returns new state
+pub fn toggle_alt_tab() -> Result<bool, SettingsError> {
+    settings::toggle_bool(HIDE_FROM_ALT_TAB, false)
+}
+
+/// Extended style for hidden state
+/// Tool windows without WS_EX_APPWINDOW get no taskbar button and are skipped
+/// by Alt-Tab (WS_EX_APPWINDOW wins over WS_EX_TOOLWINDOW for both)
+fn hidden_exstyle(exstyle: isize, taskbar: bool, alt_tab: bool) -> isize {
+    if !taskbar && !alt_tab {
+        return exstyle;
+    }
+    (exstyle | WS_EX_TOOLWINDOW.0 as isize) & !(WS_EX_APPWINDOW.0 as isize)
+}
+
+/// Restore managed bits from saved style, keep everything else current
+fn restored_exstyle(current: isize, saved: isize) -> isize {
+    (current & !MANAGED_BITS) | (saved & MANAGED_BITS)
+}
+
+/// Remove taskbar button / Alt-Tab entry after slide-out (no-op when both disabled)
+pub fn hide(hwnd: HWND) {
+    let (taskbar, alt_tab) = (is_enabled(), is_alt_tab_enabled());
+    if !taskbar && !alt_tab {
+        return;
+    }
+
+    let api = crate::win32::api();
+    let exstyle = api.ex_style(hwnd);
+    let mut saved = SAVED_EXSTYLE.lock().unwrap_or_else(|e| e.into_inner());
+    // Keep the first capture if hide is called twice
+    saved.entry(hwnd.0 as isize).or_insert(exstyle);
+
+    api.set_ex_style(hwnd, hidden_exstyle(exstyle, taskbar, alt_tab));
+}
+
+/// Restore taskbar button / Alt-Tab entry before slide-in / on untrack
+pub fn show(hwnd: HWND) {
+    let Some(saved) = SAVED_EXSTYLE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&(hwnd.0 as isize))
+    else {
+        return;
+    };
+
+    let api = crate::win32::api();
+    api.set_ex_style(hwnd, restored_exstyle(api.ex_style(hwnd), saved));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WS_EX_TOPMOST: isize = 0x0000_0008;
+
+    #[test]
+    fn test_hidden_exstyle_sets_toolwindow() {
+        let style = hidden_exstyle(0, true, false);
+        assert_ne!(style & WS_EX_TOOLWINDOW.0 as isize, 0);
+    }
+
+    #[test]
+    fn test_hidden_exstyle_clears_appwindow() {
+        let style = hidden_exstyle(WS_EX_APPWINDOW.0 as isize | WS_EX_TOPMOST, true, false);
+        assert_eq!(style & WS_EX_APPWINDOW.0 as isize, 0);
+        assert_ne!(style & WS_EX_TOPMOST, 0); // unrelated bits untouched
+    }
+
+    #[test]
+    fn test_hidden_exstyle_alt_tab_clears_appwindow() {
+        // Alt-Tab only: WS_EX_APPWINDOW would keep the window in the switcher
+        let style = hidden_exstyle(WS_EX_APPWINDOW.0 as isize | WS_EX_TOPMOST, false, true);
+        assert_ne!(style & WS_EX_TOOLWINDOW.0 as isize, 0);
+        assert_eq!(style & WS_EX_APPWINDOW.0 as isize, 0);
+        assert_ne!(style & WS_EX_TOPMOST, 0);
+    }
+
+    #[test]
+    fn test_hidden_exstyle_both_disabled_unchanged() {
+        let original = WS_EX_APPWINDOW.0 as isize | WS_EX_TOPMOST;
+        assert_eq!(hidden_exstyle(original, false, false), original);
+    }
+
+    #[test]
+    fn test_restored_exstyle_roundtrip() {
+        let original = WS_EX_APPWINDOW.0 as isize;
+        let hidden = hidden_exstyle(original, true, true);
+        assert_eq!(restored_exstyle(hidden, original), original);
+    }
+
+    #[test]
+    fn test_restored_exstyle_keeps_current_bits() {
+        let original = WS_EX_APPWINDOW.0 as isize;
+        let current = hidden_exstyle(original, true, false) | WS_EX_TOPMOST;
+        let restored = restored_exstyle(current, original);
+        assert_eq!(restored, original | WS_EX_TOPMOST);
+    }
+}
@@ -0,0 +1,100 @@
+//! Windows Terminal preset: a dedicated terminal window as the quake console
+//!
+//! Launches `wt.exe -w quake-modoki` (a named window, so it never takes over
+//! the user's other terminal windows), optionally with `TerminalProfile`
+//! (profile name, registry string; names with `"`, `;` or control characters
+//! are ignored, they would break out of the command line). wt.exe hands off
+//! to WindowsTerminal.exe, so the new window is picked by class. Once tracked
+//! it is docked to the top, full width at `TerminalHeightPct` (DWORD,
+//! default 40) of the work area.
+//! While that window is open the preset tracks and shows it instead of
+//! launching again (wt would add a tab to it).
+
+use std::sync::atomic::{AtomicIsize, Ordering};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+
+use crate::launch::Target;
+use crate::settings;
+
+/// Window class of Windows Terminal's top-level windows
+pub const CLASS: &str = "CASCADIA_HOSTING_WINDOW_CLASS";
+
+/// `wt -w` window name of the preset's window
+pub const WINDOW_NAME: &str = "quake-modoki";
+
+const PROFILE: &str = "TerminalProfile";
+const HEIGHT_PCT: &str = "TerminalHeightPct";
+const DEFAULT_HEIGHT_PCT: u32 = 40;
+
+/// Preset's window while open (0: none)
+static WINDOW: AtomicIsize = AtomicIsize::new(0);
+
+/// Check if a profile name can be quoted on wt's command line as is
+/// (`"` ends the quote, `;` separates wt subcommands)
+fn is_safe_profile(profile: &str) -> bool {
+    !profile
+        .chars()
+        .any(|c| c == '"' || c == ';' || c.is_control())
+}
+
+/// `wt.exe` arguments for the preset window (default profile if unsafe)
+fn args(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) if is_safe_profile(profile) => {
+            format!("-w {WINDOW_NAME} new-tab -p \"{profile}\"")
+        }
+        Some(profile) => {
+            tracing::warn!(profile, "Ignored terminal profile: unsafe characters");
+            format!("-w {WINDOW_NAME}")
+        }
+        None => format!("-w {WINDOW_NAME}"),
+    }
+}
+
+/// Launch command of the preset (configured profile, else the default one)
+pub fn target() -> Target {
+    let profile = settings::get_string(PROFILE).filter(|p| !p.trim().is_empty());
+    Target {
+        program: "wt.exe".to_string(),
+        args: Some(args(profile.as_deref().map(str::trim))),
+    }
+}
+
+/// Visible height in percent of the work area (docked top, full width)
+pub fn height_pct() -> u32 {
+    settings::get_u32(HEIGHT_PCT, DEFAULT_HEIGHT_PCT)
+}
+
+/// Remember the preset's window once it appeared
+pub fn remember(hwnd: HWND) {
+    WINDOW.store(hwnd.0 as isize, Ordering::SeqCst);
+}
+
+/// Preset's window if still open
+pub fn window() -> Option<HWND> {
+    let hwnd = HWND(WINDOW.load(Ordering::SeqCst) as *mut _);
+    (!hwnd.is_invalid() && unsafe { IsWindow(Some(hwnd)) }.as_bool()).then_some(hwnd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        assert_eq!(args(None), "-w quake-modoki");
+        assert_eq!(
+            args(Some("PowerShell 7")),
+            "-w quake-modoki new-tab -p \"PowerShell 7\""
+        );
+    }
+
+    #[test]
+    fn test_args_rejects_unsafe_profile() {
+        assert_eq!(args(Some("x\" ; calc ; \"")), "-w quake-modoki");
+        assert_eq!(args(Some("Ubuntu; calc")), "-w quake-modoki");
+        assert_eq!(args(Some("Ubuntu\ncalc")), "-w quake-modoki");
+        assert_eq!(args(Some("Ubuntu\tcalc")), "-w quake-modoki");
+    }
+}
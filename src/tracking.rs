@@ -1,25 +1,37 @@
-//! Window tracking module: register foreground window for toggle control
-
-use std::ffi::c_void;
-use std::ptr::null_mut;
-use std::sync::atomic::{AtomicPtr, Ordering};
-use windows::Win32::Foundation::{HWND, RECT};
+//! Window tracking helpers: bounds, original state, direction, process info
+//!
+//! The tracked window itself is owned by `controller::WindowController`; only
+//! the original state stays global (restored from console / panic handlers).
+//! Windows are topmost while shown; with `TopmostWhileVisible` (default on)
+//! a hidden window drops back to its own z-order (topmost only if it was).
+//! "Always on Top" (`AlwaysOnTop`) keeps tracked windows topmost while tracked,
+//! shown or hidden; untracking restores the original z-order.
+//! Untracking also puts back the style, extended style, placement (maximized /
+//! restore rect), layered alpha and shadow captured when tracking started.
+
+use std::sync::Mutex;
+use windows::Win32::Foundation::{CloseHandle, HWND, LPARAM, RECT};
+use windows::Win32::Graphics::Dwm::{DWMWA_CLOAKED, DwmGetWindowAttribute};
+use windows::Win32::System::Threading::{
+    GetCurrentProcessId, OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    QueryFullProcessImageNameW,
+};
 use windows::Win32::UI::WindowsAndMessaging::{
-    GWL_EXSTYLE, GetWindowLongPtrW, GetWindowRect, GetWindowTextLengthW, GetWindowTextW,
-    HWND_NOTOPMOST, HWND_TOPMOST, IsWindow, IsWindowVisible, SET_WINDOW_POS_FLAGS, SW_HIDE,
-    SW_SHOW, SetWindowPos, ShowWindow,
+    EnumWindows, GA_ROOT, GW_OWNER, GWL_EXSTYLE, GetAncestor, GetClassNameW, GetWindow,
+    GetWindowLongPtrW, GetWindowTextLengthW, GetWindowTextW, IsWindowVisible, WINDOWPLACEMENT,
+    WS_EX_LAYERED, WS_EX_TOOLWINDOW, WS_MAXIMIZE, WS_MINIMIZE, WS_VISIBLE, WindowFromPoint,
 };
+use windows::core::BOOL;
 
 use crate::animation::Direction;
+use crate::settings::{self, SettingsError};
+use crate::win32::Shadow;
 
-/// Registered window handle for toggle control
-static TRACKED_HWND: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
-
-/// Stored window bounds for animation
-static STORED_BOUNDS: AtomicPtr<WindowBounds> = AtomicPtr::new(null_mut());
+const TOPMOST_WHILE_VISIBLE: &str = "TopmostWhileVisible";
+const ALWAYS_ON_TOP: &str = "AlwaysOnTop";
 
-/// Stored original window state for restoration
-static ORIGINAL_STATE: AtomicPtr<OriginalState> = AtomicPtr::new(null_mut());
+/// Original state of every window taken under control (restored on exit / crash)
+static ORIGINAL_STATES: Mutex<Vec<OriginalState>> = Mutex::new(Vec::new());
 
 /// WS_EX_TOPMOST extended style flag
 const WS_EX_TOPMOST: isize = 0x0000_0008;
@@ -52,22 +64,68 @@ pub struct OriginalState {
     pub bounds: WindowBounds,
     pub was_visible: bool,
     pub was_topmost: bool,
+    pub styles: WindowStyles,
 }
 
-/// Register window for toggle control
-pub fn set_tracked(hwnd: HWND) {
-    TRACKED_HWND.store(hwnd.0 as *mut _, Ordering::SeqCst);
+/// Styles and attributes that toggling may change
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowStyles {
+    pub style: isize,
+    pub exstyle: isize,
+    /// Show state and restore rect (None if unreadable)
+    pub placement: Option<WINDOWPLACEMENT>,
+    /// Layered alpha (None: not layered with an alpha)
+    pub alpha: Option<u8>,
+    pub shadow: Shadow,
 }
 
-/// Get registered window handle
-pub fn get_tracked() -> HWND {
-    HWND(TRACKED_HWND.load(Ordering::SeqCst) as *mut _)
+/// Style bits owned by visibility / placement, never restored from a capture
+const STATE_STYLE_BITS: isize = (WS_VISIBLE.0 | WS_MINIMIZE.0 | WS_MAXIMIZE.0) as isize;
+
+/// Saved style bits with the bits in `keep` taken from `current`
+fn merge_style(saved: isize, current: isize, keep: isize) -> isize {
+    (saved & !keep) | (current & keep)
 }
 
-/// Check if tracked window is valid
-pub fn is_tracked_valid() -> bool {
-    let hwnd = get_tracked();
-    hwnd != HWND::default() && unsafe { IsWindow(Some(hwnd)) }.as_bool()
+// Window handles are plain process-wide identifiers; restoring from the
+// console handler / panic hook thread is fine
+unsafe impl Send for OriginalState {}
+
+/// Bounds per window (slide-in targets); entries are replaced, never leaked
+#[derive(Debug, Clone, Default)]
+pub struct BoundsStore {
+    entries: Vec<(isize, WindowBounds)>,
+}
+
+impl BoundsStore {
+    /// Store `bounds` for `hwnd`, replacing any previous entry
+    pub fn save(&mut self, hwnd: HWND, bounds: WindowBounds) {
+        let key = hwnd.0 as isize;
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some(entry) => entry.1 = bounds,
+            None => self.entries.push((key, bounds)),
+        }
+    }
+
+    /// Bounds stored for `hwnd`
+    pub fn get(&self, hwnd: HWND) -> Option<WindowBounds> {
+        let key = hwnd.0 as isize;
+        self.entries
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, bounds)| *bounds)
+    }
+
+    /// Drop the entry for `hwnd`
+    pub fn remove(&mut self, hwnd: HWND) {
+        let key = hwnd.0 as isize;
+        self.entries.retain(|(k, _)| *k != key);
+    }
+}
+
+/// Lock the original-state list (a poisoned lock still holds valid data)
+fn original_states() -> std::sync::MutexGuard<'static, Vec<OriginalState>> {
+    ORIGINAL_STATES.lock().unwrap_or_else(|e| e.into_inner())
 }
 
 /// Save original window state before tracking
@@ -77,124 +135,202 @@ pub fn save_original(hwnd: HWND) -> Option<OriginalState> {
         return None;
     }
 
+    let api = crate::win32::api();
+
     // Capture visibility
-    let was_visible = unsafe { IsWindowVisible(hwnd) }.as_bool();
+    let was_visible = api.is_visible(hwnd);
 
     // Capture bounds
-    let mut rect = RECT::default();
-    if unsafe { GetWindowRect(hwnd, &mut rect) }.is_err() {
-        return None;
-    }
-    let bounds = WindowBounds::from_rect(&rect);
+    let bounds = api.window_rect(hwnd)?;
 
     // Capture topmost state
-    let exstyle = unsafe { GetWindowLongPtrW(hwnd, GWL_EXSTYLE) };
+    let exstyle = api.ex_style(hwnd);
     let was_topmost = (exstyle & WS_EX_TOPMOST) != 0;
 
+    // Capture styles, placement and layered / shadow attributes
+    let styles = WindowStyles {
+        style: api.style(hwnd),
+        exstyle,
+        placement: api.placement(hwnd),
+        alpha: api.alpha(hwnd),
+        shadow: api.shadow(hwnd),
+    };
+
     let state = OriginalState {
         hwnd,
         bounds,
         was_visible,
         was_topmost,
+        styles,
     };
 
-    // Store (drop previous if exists)
-    let boxed = Box::new(state.clone());
-    let old = ORIGINAL_STATE.swap(Box::into_raw(boxed), Ordering::SeqCst);
-    if !old.is_null() {
-        drop(unsafe { Box::from_raw(old) });
-    }
+    // Store (replace an earlier capture of the same window)
+    let mut states = original_states();
+    states.retain(|s| s.hwnd != hwnd);
+    states.push(state.clone());
 
     Some(state)
 }
 
-/// Restore original window state
-/// Returns Some(()) on success, None if no state stored or window destroyed
-pub fn restore_original() -> Option<()> {
-    let ptr = ORIGINAL_STATE.swap(null_mut(), Ordering::SeqCst);
-    if ptr.is_null() {
-        return None;
-    }
+/// Check if hidden windows leave the topmost band (default: yes)
+pub fn is_topmost_while_visible() -> bool {
+    settings::get_bool(TOPMOST_WHILE_VISIBLE, true)
+}
 
-    let state = unsafe { Box::from_raw(ptr) };
+/// Check if tracked windows stay topmost while tracked (default: no)
+pub fn is_always_on_top() -> bool {
+    settings::get_bool(ALWAYS_ON_TOP, false)
+}
 
-    // Skip if window destroyed
-    if !unsafe { IsWindow(Some(state.hwnd)) }.as_bool() {
-        return None;
+/// Check if always on top is fixed by policy
+pub fn is_always_on_top_locked() -> bool {
+    settings::is_locked(ALWAYS_ON_TOP)
+}
+
+/// Toggle always on top, returns new state
+pub fn toggle_always_on_top() -> Result<bool, SettingsError> {
+    settings::toggle_bool(ALWAYS_ON_TOP, false)
+}
+
+/// Pin windows topmost when always on top is on (no-op otherwise)
+pub fn apply_always_on_top(hwnds: &[HWND]) {
+    if !is_always_on_top() {
+        return;
     }
+    let api = crate::win32::api();
+    for &hwnd in hwnds {
+        api.set_topmost(hwnd, true);
+    }
+}
 
-    // Restore position and z-order
-    let z_order = if state.was_topmost {
-        HWND_TOPMOST
-    } else {
-        HWND_NOTOPMOST
-    };
+/// Drop windows out of the topmost band after always on top is turned off,
+/// unless they were topmost before or stay topmost while visible
+pub fn release_always_on_top(hwnds: &[HWND], visible: bool) {
+    if is_always_on_top() || (visible && is_topmost_while_visible()) {
+        return;
+    }
+    let api = crate::win32::api();
+    for &hwnd in hwnds {
+        if !was_topmost(hwnd) {
+            api.set_topmost(hwnd, false);
+        }
+    }
+}
 
-    unsafe {
-        let _ = SetWindowPos(
-            state.hwnd,
-            Some(z_order),
-            state.bounds.x,
-            state.bounds.y,
-            state.bounds.width,
-            state.bounds.height,
-            SET_WINDOW_POS_FLAGS(0),
-        );
+/// Check if `hwnd` was topmost before it was taken under control
+fn was_topmost(hwnd: HWND) -> bool {
+    original_states()
+        .iter()
+        .any(|s| s.hwnd == hwnd && s.was_topmost)
+}
 
-        // Restore visibility
-        let cmd = if state.was_visible { SW_SHOW } else { SW_HIDE };
-        let _ = ShowWindow(state.hwnd, cmd);
+/// Give hidden windows back their own z-order (no-op when the option is off
+/// or they are always on top)
+pub fn restore_z_order(hwnds: &[HWND]) {
+    if !is_topmost_while_visible() || is_always_on_top() {
+        return;
     }
+    let api = crate::win32::api();
+    for &hwnd in hwnds {
+        if !was_topmost(hwnd) {
+            api.set_topmost(hwnd, false);
+        }
+    }
+}
 
-    Some(())
+/// Restore original state of all stored windows (the list is emptied)
+/// Returns Some(()) if at least one window was restored, None if none stored or all destroyed
+pub fn restore_original() -> Option<()> {
+    restore_original_except(&[])
 }
 
-/// Save current window bounds before slide-out
-/// Returns captured bounds, or None if GetWindowRect fails
-pub fn save_bounds(hwnd: HWND) -> Option<WindowBounds> {
-    let mut rect = RECT::default();
-    if unsafe { GetWindowRect(hwnd, &mut rect) }.is_err() {
+/// Restore original state of stored windows other than `keep` (those stay stored)
+pub fn restore_original_except(keep: &[HWND]) -> Option<()> {
+    // Take the list first: never hold the lock across window calls
+    let states = {
+        let mut stored = original_states();
+        let (kept, states) = std::mem::take(&mut *stored)
+            .into_iter()
+            .partition(|s| keep.contains(&s.hwnd));
+        *stored = kept;
+        states
+    };
+    let mut restored = None;
+    for state in states {
+        if restore_state(&state).is_some() {
+            restored = Some(());
+        }
+    }
+    restored
+}
+
+/// Restore one window's original state; None if destroyed
+fn restore_state(state: &OriginalState) -> Option<()> {
+    // Skip if window destroyed
+    let api = crate::win32::api();
+    if !api.is_window(state.hwnd) {
         return None;
     }
 
-    let bounds = WindowBounds::from_rect(&rect);
-    let boxed = Box::new(bounds);
-    let ptr = Box::into_raw(boxed);
+    // Undo hidden-state side effects before the window can reappear
+    // (resume first: a suspended window would block SetWindowPos)
+    crate::process::restore(state.hwnd);
+    crate::taskbar::show(state.hwnd);
+    crate::opacity::restore(state.hwnd);
+    crate::audio::unmute(state.hwnd);
+    crate::memory::clear(state.hwnd);
+
+    // Restore styles (visibility / placement bits and topmost follow below)
+    let styles = &state.styles;
+    let style = api.style(state.hwnd);
+    api.set_style(
+        state.hwnd,
+        merge_style(styles.style, style, STATE_STYLE_BITS),
+    );
+    let exstyle = api.ex_style(state.hwnd);
+    api.set_ex_style(
+        state.hwnd,
+        merge_style(styles.exstyle, exstyle, WS_EX_TOPMOST),
+    );
+    if styles.exstyle & WS_EX_LAYERED.0 as isize != 0
+        && let Some(alpha) = styles.alpha
+    {
+        api.set_alpha(state.hwnd, alpha);
+    }
+    if api.shadow(state.hwnd) != styles.shadow {
+        api.set_shadow(state.hwnd, styles.shadow);
+    }
+
+    // Restore position and z-order
+    api.restore_frame(state.hwnd, state.bounds, state.was_topmost);
+
+    // Show state and restore rect (maximized windows come back maximized)
+    if state.was_visible
+        && let Some(placement) = styles.placement
+    {
+        api.set_placement(state.hwnd, &placement);
+    }
 
-    // Swap old pointer, leak previous allocation (acceptable for single-window app)
-    STORED_BOUNDS.store(ptr, Ordering::SeqCst);
+    // Restore visibility
+    api.show(state.hwnd, state.was_visible);
 
-    Some(bounds)
+    Some(())
 }
 
-/// Load stored bounds
-pub fn load_bounds() -> Option<WindowBounds> {
-    let ptr = STORED_BOUNDS.load(Ordering::SeqCst);
-    if ptr.is_null() {
-        None
-    } else {
-        // Safety: ptr was created by Box::into_raw and is valid
-        Some(unsafe { *ptr })
-    }
+/// Current window bounds, or None if GetWindowRect fails
+pub fn window_bounds(hwnd: HWND) -> Option<WindowBounds> {
+    crate::win32::api().window_rect(hwnd)
 }
 
-/// Clear stored bounds (test-only)
-#[cfg(test)]
-fn clear_bounds() {
-    let ptr = STORED_BOUNDS.swap(null_mut(), Ordering::SeqCst);
-    if !ptr.is_null() {
-        // Safety: ptr was created by Box::into_raw
-        drop(unsafe { Box::from_raw(ptr) });
-    }
+/// Work area of the monitor showing a window (primary if none)
+pub fn get_work_area(hwnd: HWND) -> Option<RECT> {
+    crate::win32::api().work_area(hwnd)
 }
 
 /// Clear original state (test-only)
 #[cfg(test)]
 fn clear_original() {
-    let ptr = ORIGINAL_STATE.swap(null_mut(), Ordering::SeqCst);
-    if !ptr.is_null() {
-        drop(unsafe { Box::from_raw(ptr) });
-    }
+    original_states().clear();
 }
 
 /// Calculate overlap ratio between bounds and region
@@ -219,6 +355,18 @@ fn overlap_ratio(bounds: &WindowBounds, region: &RECT) -> f64 {
     intersection as f64 / window_area as f64
 }
 
+/// Fit `bounds` into `work_area`: shrink if larger, then shift inside
+pub fn clamp_to_work_area(bounds: &WindowBounds, work_area: &RECT) -> WindowBounds {
+    let width = bounds.width.min(work_area.right - work_area.left);
+    let height = bounds.height.min(work_area.bottom - work_area.top);
+    WindowBounds {
+        x: bounds.x.clamp(work_area.left, work_area.right - width),
+        y: bounds.y.clamp(work_area.top, work_area.bottom - height),
+        width,
+        height,
+    }
+}
+
 /// Calculate optimal slide direction based on overlap with screen halves
 /// Returns direction with maximum overlap ratio
 pub fn calc_direction(bounds: &WindowBounds, work_area: &RECT) -> Direction {
@@ -294,6 +442,119 @@ pub fn get_window_title(hwnd: HWND) -> String {
     }
 }
 
+/// Window class name (empty if unavailable)
+pub fn get_window_class(hwnd: HWND) -> String {
+    if hwnd == HWND::default() {
+        return String::new();
+    }
+    // Class names are limited to 256 characters
+    let mut buf = [0u16; 257];
+    let len = unsafe { GetClassNameW(hwnd, &mut buf) };
+    String::from_utf16_lossy(&buf[..len.max(0) as usize])
+}
+
+/// First visible, unowned, titled top-level window accepted by `matches` (Z-order, top first)
+pub fn find_app_window(matches: &dyn Fn(HWND) -> bool) -> Option<HWND> {
+    struct Search<'a> {
+        matches: &'a dyn Fn(HWND) -> bool,
+        found: Option<HWND>,
+    }
+
+    unsafe extern "system" fn enum_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = unsafe { &mut *(lparam.0 as *mut Search) };
+        let candidate = unsafe {
+            IsWindowVisible(hwnd).as_bool()
+                && GetWindow(hwnd, GW_OWNER).is_err()
+                && GetWindowTextLengthW(hwnd) > 0
+        };
+        if candidate && (search.matches)(hwnd) {
+            search.found = Some(hwnd);
+            return BOOL(0);
+        }
+        BOOL(1)
+    }
+
+    let mut search = Search {
+        matches,
+        found: None,
+    };
+    unsafe {
+        let _ = EnumWindows(
+            Some(enum_callback),
+            LPARAM(&mut search as *mut Search as isize),
+        );
+    }
+    search.found
+}
+
+/// Topmost app window of another process (the foreground before our tray menu opened)
+/// Tool windows (taskbar, palettes) and cloaked windows (suspended UWP apps) are skipped
+pub fn topmost_app_window() -> Option<HWND> {
+    find_app_window(&is_other_app_window)
+}
+
+/// App window of another process: not a tool window, not cloaked
+pub fn is_other_app_window(hwnd: HWND) -> bool {
+    let ex_style = unsafe { GetWindowLongPtrW(hwnd, GWL_EXSTYLE) };
+    get_process_id(hwnd) != unsafe { GetCurrentProcessId() }
+        && ex_style & WS_EX_TOOLWINDOW.0 as isize == 0
+        && !is_cloaked(hwnd)
+}
+
+/// Top-level window under the mouse cursor (None over nothing)
+pub fn window_under_cursor() -> Option<HWND> {
+    let point = crate::win32::api().cursor_pos()?;
+    let hwnd = unsafe { GetAncestor(WindowFromPoint(point), GA_ROOT) };
+    (hwnd != HWND::default()).then_some(hwnd)
+}
+
+/// Check if DWM hides the window (other virtual desktop, suspended UWP app)
+pub fn is_cloaked(hwnd: HWND) -> bool {
+    let mut cloaked = 0u32;
+    unsafe {
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_CLOAKED,
+            &mut cloaked as *mut u32 as *mut _,
+            size_of::<u32>() as u32,
+        )
+    }
+    .is_ok()
+        && cloaked != 0
+}
+
+/// Get owning process id (0 if window invalid)
+pub fn get_process_id(hwnd: HWND) -> u32 {
+    if hwnd == HWND::default() {
+        return 0;
+    }
+
+    crate::win32::api().process_id(hwnd)
+}
+
+/// Get full executable path of owning process (None if inaccessible)
+pub fn get_process_path(hwnd: HWND) -> Option<String> {
+    let pid = get_process_id(hwnd);
+    if pid == 0 {
+        return None;
+    }
+
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; 1024];
+        let mut len = buf.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            windows::core::PWSTR(buf.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+        Some(String::from_utf16_lossy(&buf[..len as usize]))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -307,42 +568,32 @@ mod tests {
         }
     }
 
-    // ========== HWND Tracking Tests ==========
+    // ========== Window Info Tests ==========
 
     #[test]
-    fn test_tracked_initially_null() {
-        // Reset state for test isolation
-        TRACKED_HWND.store(null_mut(), Ordering::SeqCst);
-
-        let hwnd = get_tracked();
-        assert_eq!(hwnd, HWND::default());
+    fn test_get_window_title_null_hwnd() {
+        let title = get_window_title(HWND::default());
+        assert!(title.is_empty());
     }
 
     #[test]
-    fn test_set_get_tracked_roundtrip() {
-        // Create fake HWND for testing (non-null pointer)
-        let fake_ptr = 0x12345678 as *mut c_void;
-        let fake_hwnd = HWND(fake_ptr);
-
-        set_tracked(fake_hwnd);
-        let retrieved = get_tracked();
-
-        assert_eq!(retrieved, fake_hwnd);
+    fn test_get_window_class_null_hwnd() {
+        assert!(get_window_class(HWND::default()).is_empty());
+    }
 
-        // Cleanup
-        TRACKED_HWND.store(null_mut(), Ordering::SeqCst);
+    #[test]
+    fn test_get_process_id_null_hwnd() {
+        assert_eq!(get_process_id(HWND::default()), 0);
     }
 
     #[test]
-    fn test_get_window_title_null_hwnd() {
-        let title = get_window_title(HWND::default());
-        assert!(title.is_empty());
+    fn test_get_process_path_null_hwnd() {
+        assert_eq!(get_process_path(HWND::default()), None);
     }
 
     #[test]
-    fn test_is_tracked_valid_null() {
-        TRACKED_HWND.store(null_mut(), Ordering::SeqCst);
-        assert!(!is_tracked_valid());
+    fn test_window_bounds_null_hwnd() {
+        assert!(window_bounds(HWND::default()).is_none());
     }
 
     // ========== WindowBounds Tests ==========
@@ -358,12 +609,6 @@ mod tests {
         assert_eq!(bounds.height, 400);
     }
 
-    #[test]
-    fn test_load_bounds_initially_none() {
-        clear_bounds();
-        assert!(load_bounds().is_none());
-    }
-
     // ========== Overlap Ratio Tests ==========
 
     #[test]
@@ -416,6 +661,39 @@ mod tests {
 
     // ========== Direction Calculation Tests ==========
 
+    #[test]
+    fn test_clamp_to_work_area() {
+        let work_area = make_rect(0, 0, 1920, 1000);
+        let inside = WindowBounds {
+            x: 100,
+            y: 100,
+            width: 800,
+            height: 400,
+        };
+        assert_eq!(clamp_to_work_area(&inside, &work_area), inside);
+
+        // Taskbar grew: bottom edge pulled up
+        let low = WindowBounds { y: 700, ..inside };
+        assert_eq!(clamp_to_work_area(&low, &work_area).y, 600);
+
+        // Larger than the work area: shrunk to fit
+        let huge = WindowBounds {
+            x: -50,
+            y: -50,
+            width: 3000,
+            height: 1200,
+        };
+        assert_eq!(
+            clamp_to_work_area(&huge, &work_area),
+            WindowBounds {
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1000
+            }
+        );
+    }
+
     #[test]
     fn test_calc_direction_left_half() {
         // Window in left half of screen
@@ -499,9 +777,9 @@ mod tests {
             },
             was_visible: true,
             was_topmost: false,
+            styles: WindowStyles::default(),
         };
-        let boxed = Box::new(state);
-        ORIGINAL_STATE.store(Box::into_raw(boxed), Ordering::SeqCst);
+        original_states().push(state);
 
         // Clear should drop
         clear_original();
@@ -509,4 +787,100 @@ mod tests {
         // Should be empty now
         assert!(restore_original().is_none());
     }
+
+    #[test]
+    fn test_restore_original_skips_destroyed_and_empties() {
+        clear_original();
+        let state = OriginalState {
+            hwnd: HWND(0x12345678 as *mut _),
+            bounds: WindowBounds {
+                x: 0,
+                y: 0,
+                width: 100,
+                height: 100,
+            },
+            was_visible: true,
+            was_topmost: false,
+            styles: WindowStyles::default(),
+        };
+        original_states().push(state.clone());
+        original_states().push(OriginalState {
+            hwnd: HWND(0x87654321 as *mut _),
+            ..state
+        });
+
+        // Fake handles are not windows: nothing restored, nothing kept
+        assert!(restore_original().is_none());
+        assert!(original_states().is_empty());
+    }
+
+    #[test]
+    fn test_restore_original_except_keeps_parked() {
+        clear_original();
+        let kept = HWND(0x12345678 as *mut _);
+        original_states().push(OriginalState {
+            hwnd: kept,
+            bounds: WindowBounds {
+                x: 0,
+                y: 0,
+                width: 100,
+                height: 100,
+            },
+            was_visible: true,
+            was_topmost: false,
+            styles: WindowStyles::default(),
+        });
+
+        assert!(restore_original_except(&[kept]).is_none());
+        assert_eq!(original_states().len(), 1);
+        clear_original();
+    }
+
+    #[test]
+    fn test_merge_style_keeps_state_bits() {
+        let caption = 0x00C0_0000;
+        let saved = caption | WS_MAXIMIZE.0 as isize;
+        let current = WS_VISIBLE.0 as isize;
+        // Caption comes back; visibility / maximize stay as they are now
+        assert_eq!(
+            merge_style(saved, current, STATE_STYLE_BITS),
+            caption | WS_VISIBLE.0 as isize
+        );
+        assert_eq!(merge_style(0, WS_EX_TOPMOST, WS_EX_TOPMOST), WS_EX_TOPMOST);
+    }
+
+    // ========== BoundsStore Tests ==========
+
+    #[test]
+    fn test_bounds_store_per_window() {
+        let a = HWND(0x1000 as *mut _);
+        let b = HWND(0x2000 as *mut _);
+        let small = WindowBounds {
+            x: 0,
+            y: 0,
+            width: 100,
+            height: 100,
+        };
+        let large = WindowBounds {
+            width: 800,
+            height: 600,
+            ..small
+        };
+
+        let mut store = BoundsStore::default();
+        assert!(store.entries.is_empty());
+        store.save(a, small);
+        store.save(b, large);
+        assert_eq!(store.get(a), Some(small));
+        assert_eq!(store.get(b), Some(large));
+
+        // Saving again replaces instead of accumulating
+        store.save(a, large);
+        assert_eq!(store.get(a), Some(large));
+        assert_eq!(store.entries.len(), 2);
+
+        store.remove(a);
+        assert_eq!(store.get(a), None);
+        assert_eq!(store.entries.len(), 1);
+    }
 }
@@ -1,8 +1,36 @@
 //! System tray integration using tray-icon crate
 
-use muda::{CheckMenuItem, Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem};
+use std::cell::Cell;
+use std::time::Duration;
+
+use muda::{CheckMenuItem, IconMenuItem, Menu, MenuId, MenuItem, PredefinedMenuItem, Submenu};
 use thiserror::Error;
 use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+use windows::Win32::Graphics::Gdi::{
+    BI_RGB, BITMAPINFO, BITMAPINFOHEADER, CreateCompatibleDC, DIB_RGB_COLORS, DeleteDC,
+    DeleteObject, GetDIBits,
+};
+use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Shell::{SHFILEINFOW, SHGFI_ICON, SHGFI_SMALLICON, SHGetFileInfoW};
+use windows::Win32::UI::WindowsAndMessaging::{
+    DestroyIcon, GetIconInfo, GetSystemMetrics, HICON, ICONINFO, IMAGE_ICON, LR_DEFAULTCOLOR,
+    LoadImageW, SM_CXSMICON,
+};
+use windows::core::{HSTRING, PCWSTR};
+
+use crate::animation::{AnimStyle, Easing};
+use crate::chord;
+use crate::edge::EdgePreset;
+use crate::focus::FocusLossAction;
+use crate::hotkey;
+use crate::i18n::{Text, t, tf};
+use crate::layout::Layout;
+use crate::opacity;
+use crate::presets::Preset;
+use crate::process::HiddenMode;
+use crate::slots;
+use crate::snap::Snap;
 
 #[derive(Debug, Error)]
 pub enum TrayError {
@@ -13,50 +41,565 @@ pub enum TrayError {
     Menu(String),
 }
 
+/// Tray items whose setting is enforced by machine policy (shown disabled)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PolicyLocks {
+    pub edge_trigger: bool,
+    pub edge_scroll: bool,
+    pub edge_swipe: bool,
+    pub shake: bool,
+    pub always_on_top: bool,
+    pub announce: bool,
+    pub auto_update: bool,
+    pub hide_taskbar: bool,
+    pub hide_alt_tab: bool,
+    pub suppress_flash: bool,
+    pub mute_hidden: bool,
+    pub trim_memory: bool,
+    pub process_mode: bool,
+}
+
+/// Attention flash of the tray icon (e.g. toggle with nothing tracked)
+pub const FLASH_DURATION: Duration = Duration::from_millis(1800);
+const FLASH_PERIOD_MS: u128 = 300;
+
+/// Size of the badged icon (rendered from the resource icon)
+const BADGE_ICON_SIZE: u32 = 32;
+
+/// Menu position of the "Last error" submenu (below status + stats)
+const ERROR_MENU_POSITION: usize = 2;
+
 /// System tray state and menu IDs
 pub struct TrayState {
-    _icon: TrayIcon,
+    icon: TrayIcon,
+    menu: Menu,
+    menu_clear_error: MenuId,
+    error_menu: Submenu,
+    error_detail_item: MenuItem,
+    /// "Last error" submenu inserted and icon badged
+    error_shown: Cell<bool>,
+    /// Attention flash showing its alternate icon
+    flash_on: Cell<bool>,
+    menu_toggle: MenuId,
+    menu_track: MenuId,
     menu_untrack: MenuId,
+    menu_retrack: MenuId,
+    menu_reset_position: MenuId,
+    menu_relayout: MenuId,
+    menu_peek: MenuId,
+    menu_terminal: MenuId,
     menu_autolaunch: MenuId,
+    menu_autolaunch_task: MenuId,
+    menu_shell_menu: MenuId,
     menu_edge_trigger: MenuId,
+    menu_edge_scroll: MenuId,
+    menu_edge_swipe: MenuId,
+    menu_focus_loss: MenuId,
+    menu_edge_custom: MenuId,
+    menu_hide_on_track: MenuId,
+    menu_shake: MenuId,
+    menu_always_on_top: MenuId,
+    menu_announce: MenuId,
+    menu_change_hotkey: MenuId,
+    menu_change_track_hotkey: MenuId,
+    menu_export_settings: MenuId,
+    menu_import_settings: MenuId,
+    menu_debug_logging: MenuId,
+    menu_check_update: MenuId,
+    menu_auto_update: MenuId,
+    menu_restart_update: MenuId,
+    menu_about: MenuId,
+    menu_plugin_items: Vec<MenuId>,
+    menu_hide_taskbar: MenuId,
+    menu_hide_alt_tab: MenuId,
+    menu_suppress_flash: MenuId,
+    menu_mute_hidden: MenuId,
+    menu_trim_memory: MenuId,
     menu_exit: MenuId,
-    status_item: MenuItem,
+    status_item: IconMenuItem,
+    stats_item: MenuItem,
+    toggle_item: MenuItem,
+    track_item: MenuItem,
     autolaunch_item: CheckMenuItem,
+    autolaunch_task_item: CheckMenuItem,
+    shell_menu_item: CheckMenuItem,
     edge_trigger_item: CheckMenuItem,
+    edge_scroll_item: CheckMenuItem,
+    edge_swipe_item: CheckMenuItem,
+    focus_loss_item: CheckMenuItem,
+    edge_preset_items: [(EdgePreset, CheckMenuItem); 3],
+    edge_custom_item: CheckMenuItem,
+    hide_on_track_item: CheckMenuItem,
+    shake_item: CheckMenuItem,
+    always_on_top_item: CheckMenuItem,
+    announce_item: CheckMenuItem,
+    debug_logging_item: CheckMenuItem,
+    auto_update_item: CheckMenuItem,
+    restart_update_item: MenuItem,
+    hide_taskbar_item: CheckMenuItem,
+    hide_alt_tab_item: CheckMenuItem,
+    suppress_flash_item: CheckMenuItem,
+    mute_hidden_item: CheckMenuItem,
+    trim_memory_item: CheckMenuItem,
+    process_mode_items: [(HiddenMode, CheckMenuItem); 3],
+    layout_items: [(Layout, CheckMenuItem); 3],
+    snap_items: Vec<(Snap, MenuId)>,
+    preset_items: Vec<(Preset, MenuId)>,
+    slots_item: CheckMenuItem,
+    menu_slots: MenuId,
+    slot_items: Vec<(usize, MenuId)>,
+    opacity_items: Vec<(u32, CheckMenuItem)>,
+    focus_loss_items: Vec<(FocusLossAction, CheckMenuItem)>,
+    anim_style_items: Vec<(AnimStyle, CheckMenuItem)>,
+    show_easing_items: Vec<(Easing, CheckMenuItem)>,
+    hide_easing_items: Vec<(Easing, CheckMenuItem)>,
 }
 
 impl TrayState {
     /// Create tray icon with menu
-    pub fn new() -> Result<Self, TrayError> {
+    /// trim_delay_min: shown in the "Trim Memory" label
+    /// plugin_items: labels for the "Plugins" submenu (omitted when empty)
+    pub fn new(trim_delay_min: u32, plugin_items: &[String]) -> Result<Self, TrayError> {
         // Create menu items
-        let status_item = MenuItem::with_id("status", "No window tracked", false, None);
-        let untrack_item = MenuItem::with_id("untrack", "Untrack", true, None);
+        let status_item =
+            IconMenuItem::with_id("status", t(Text::NoWindowTracked), false, None, None);
+        let stats_item = MenuItem::with_id("stats", "", false, None);
+        // Shortcut hints (follow rebinding, see set_toggle_hotkey / set_track_hotkey)
+        let toggle_item = MenuItem::with_id("toggle", t(Text::Toggle), true, None);
+        let track_item = MenuItem::with_id(
+            "track",
+            with_hint(
+                t(Text::TrackForeground),
+                Some(&hotkey::label(&hotkey::track_hotkey())),
+            ),
+            true,
+            None,
+        );
+        let untrack_hint = format!("{}, U", hotkey::label(&chord::leader_hotkey()));
+        let untrack_item = MenuItem::with_id(
+            "untrack",
+            with_hint(t(Text::Untrack), Some(&untrack_hint)),
+            true,
+            None,
+        );
+        let retrack_item = MenuItem::with_id(
+            "retrack",
+            with_hint(
+                t(Text::RetrackLast),
+                Some(&hotkey::label(&hotkey::retrack_hotkey())),
+            ),
+            true,
+            None,
+        );
+        let reset_position_item =
+            MenuItem::with_id("reset_position", t(Text::ResetPosition), true, None);
+        let relayout_item = MenuItem::with_id("relayout", t(Text::ApplyLayoutNow), true, None);
+        let peek_item = MenuItem::with_id("peek", t(Text::Peek), true, None);
+        let terminal_item = MenuItem::with_id("terminal", t(Text::WindowsTerminal), true, None);
         let autolaunch_item =
-            CheckMenuItem::with_id("autolaunch", "Start with Windows", true, false, None);
+            CheckMenuItem::with_id("autolaunch", t(Text::StartWithWindows), true, false, None);
+        let autolaunch_task_item =
+            CheckMenuItem::with_id("autolaunch_task", t(Text::StartElevated), true, false, None);
+        let shell_menu_item = CheckMenuItem::with_id(
+            "shell_menu",
+            t(Text::ExplorerContextMenu),
+            true,
+            false,
+            None,
+        );
         let edge_trigger_item =
-            CheckMenuItem::with_id("edge_trigger", "Edge Trigger", true, false, None);
-        let exit_item = MenuItem::with_id("exit", "Exit", true, None);
+            CheckMenuItem::with_id("edge_trigger", t(Text::Enabled), true, false, None);
+        let edge_scroll_item =
+            CheckMenuItem::with_id("edge_scroll", t(Text::ScrollAtEdge), true, false, None);
+        let edge_swipe_item =
+            CheckMenuItem::with_id("edge_swipe", t(Text::SwipeFromEdge), true, false, None);
+        let edge_preset_items = [
+            (
+                EdgePreset::Instant,
+                CheckMenuItem::with_id("edge_instant", t(Text::EdgeInstant), true, false, None),
+            ),
+            (
+                EdgePreset::Fast,
+                CheckMenuItem::with_id("edge_fast", t(Text::EdgeFast), true, false, None),
+            ),
+            (
+                EdgePreset::Relaxed,
+                CheckMenuItem::with_id("edge_relaxed", t(Text::EdgeRelaxed), true, false, None),
+            ),
+        ];
+        let edge_custom_item =
+            CheckMenuItem::with_id("edge_custom", t(Text::CustomEllipsis), true, false, None);
+        let hide_on_track_item =
+            CheckMenuItem::with_id("hide_on_track", t(Text::HideWhenTracked), true, false, None);
+        let shake_item = CheckMenuItem::with_id("shake", t(Text::ShakeToToggle), true, false, None);
+        let always_on_top_item =
+            CheckMenuItem::with_id("always_on_top", t(Text::AlwaysOnTop), true, false, None);
+        let announce_item =
+            CheckMenuItem::with_id("announce", t(Text::AnnounceStateChanges), true, false, None);
+        let debug_logging_item =
+            CheckMenuItem::with_id("debug_logging", t(Text::DebugLogging), true, false, None);
+        let change_hotkey_item =
+            MenuItem::with_id("change_hotkey", t(Text::ChangeToggleHotkey), true, None);
+        let change_track_hotkey_item = MenuItem::with_id(
+            "change_track_hotkey",
+            t(Text::ChangeTrackHotkey),
+            true,
+            None,
+        );
+        let export_settings_item =
+            MenuItem::with_id("export_settings", t(Text::ExportSettings), true, None);
+        let import_settings_item =
+            MenuItem::with_id("import_settings", t(Text::ImportSettings), true, None);
+        let check_update_item =
+            MenuItem::with_id("check_update", t(Text::CheckForUpdates), true, None);
+        let auto_update_item = CheckMenuItem::with_id(
+            "auto_update",
+            t(Text::CheckForUpdatesAtStartup),
+            true,
+            false,
+            None,
+        );
+        let restart_update_item =
+            MenuItem::with_id("restart_update", t(Text::RestartToUpdate), false, None);
+        let hide_taskbar_item =
+            CheckMenuItem::with_id("hide_taskbar", t(Text::HideFromTaskbar), true, false, None);
+        let hide_alt_tab_item =
+            CheckMenuItem::with_id("hide_alt_tab", t(Text::HideFromAltTab), true, false, None);
+        let suppress_flash_item = CheckMenuItem::with_id(
+            "suppress_flash",
+            t(Text::SuppressFlashing),
+            true,
+            false,
+            None,
+        );
+        let mute_hidden_item =
+            CheckMenuItem::with_id("mute_hidden", t(Text::MuteAudio), true, false, None);
+        let trim_memory_item = CheckMenuItem::with_id(
+            "trim_memory",
+            tf(Text::TrimMemoryAfter, &[&trim_delay_min.to_string()]),
+            true,
+            false,
+            None,
+        );
+        let process_mode_items = [
+            (
+                HiddenMode::Normal,
+                CheckMenuItem::with_id(
+                    "process_normal",
+                    t(Text::NormalPriority),
+                    true,
+                    false,
+                    None,
+                ),
+            ),
+            (
+                HiddenMode::BelowNormal,
+                CheckMenuItem::with_id("process_below", t(Text::LowerPriority), true, false, None),
+            ),
+            (
+                HiddenMode::Suspend,
+                CheckMenuItem::with_id(
+                    "process_suspend",
+                    t(Text::SuspendProcess),
+                    true,
+                    false,
+                    None,
+                ),
+            ),
+        ];
+        let error_detail_item = MenuItem::with_id("error_detail", "", false, None);
+        let clear_error_item = MenuItem::with_id("clear_error", t(Text::ClearError), true, None);
+        let error_menu = Submenu::with_id("last_error", "", true);
+        error_menu
+            .append(&error_detail_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        error_menu
+            .append(&clear_error_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        let layout_items = [
+            (
+                Layout::Free,
+                CheckMenuItem::with_id("layout_free", t(Text::KeepPositions), true, false, None),
+            ),
+            (
+                Layout::TopSplit,
+                CheckMenuItem::with_id(
+                    "layout_top_split",
+                    t(Text::TopHalfSideBySide),
+                    true,
+                    false,
+                    None,
+                ),
+            ),
+            (
+                Layout::LeftStack,
+                CheckMenuItem::with_id(
+                    "layout_left_stack",
+                    t(Text::LeftColumnStack),
+                    true,
+                    false,
+                    None,
+                ),
+            ),
+        ];
+        let preset_items: Vec<(Preset, MenuItem)> = Preset::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, &preset)| {
+                let item = MenuItem::with_id(format!("preset_{i}"), preset.name(), true, None);
+                (preset, item)
+            })
+            .collect();
+        let slots_item =
+            CheckMenuItem::with_id("slots_enabled", t(Text::SlotHotkeys), true, false, None);
+        let slot_items: Vec<(usize, MenuItem)> = (0..slots::COUNT)
+            .map(|slot| {
+                let hint = match slots::hotkey(slot) {
+                    Some(key) => hotkey::label(&key),
+                    None => t(Text::SlotToggleHotkey).to_string(),
+                };
+                let label = with_hint(&tf(Text::Slot, &[&(slot + 1).to_string()]), Some(&hint));
+                (
+                    slot,
+                    MenuItem::with_id(format!("slot_{slot}"), label, true, None),
+                )
+            })
+            .collect();
+        let snap_items: Vec<(Snap, MenuItem)> = Snap::ALL
+            .iter()
+            .map(|&snap| {
+                let (id, label) = match snap {
+                    Snap::Left => ("snap_left", Text::SnapLeft),
+                    Snap::Right => ("snap_right", Text::SnapRight),
+                    Snap::Top => ("snap_top", Text::SnapTop),
+                    Snap::Bottom => ("snap_bottom", Text::SnapBottom),
+                    Snap::Center => ("snap_center", Text::SnapCenter),
+                };
+                (snap, MenuItem::with_id(id, t(label), true, None))
+            })
+            .collect();
+        let focus_loss_item = CheckMenuItem::with_id(
+            "focus_loss_enabled",
+            t(Text::HideOnFocusLoss),
+            true,
+            true,
+            None,
+        );
+        let focus_loss_items: Vec<(FocusLossAction, CheckMenuItem)> = FocusLossAction::ALL
+            .iter()
+            .map(|&action| {
+                let (id, label) = match action {
+                    FocusLossAction::Slide => ("focus_loss_slide", Text::SlideOut),
+                    FocusLossAction::Instant => ("focus_loss_instant", Text::HideInstantly),
+                };
+                (
+                    action,
+                    CheckMenuItem::with_id(id, t(label), true, false, None),
+                )
+            })
+            .collect();
+        let anim_style_items: Vec<(AnimStyle, CheckMenuItem)> = AnimStyle::ALL
+            .iter()
+            .map(|&style| {
+                let (id, label) = match style {
+                    AnimStyle::Slide => ("anim_style_slide", Text::AnimSlide),
+                    AnimStyle::SlideFade => ("anim_style_slide_fade", Text::AnimSlideFade),
+                    AnimStyle::Zoom => ("anim_style_zoom", Text::AnimZoom),
+                };
+                (
+                    style,
+                    CheckMenuItem::with_id(id, t(label), true, style == AnimStyle::default(), None),
+                )
+            })
+            .collect();
+        let easing_items = |prefix: &str| -> Vec<(Easing, CheckMenuItem)> {
+            Easing::ALL
+                .iter()
+                .map(|&easing| {
+                    let item = CheckMenuItem::with_id(
+                        format!("{prefix}_easing_{}", easing.name().to_lowercase()),
+                        easing.name(),
+                        true,
+                        easing == Easing::default(),
+                        None,
+                    );
+                    (easing, item)
+                })
+                .collect()
+        };
+        let show_easing_items = easing_items("show");
+        let hide_easing_items = easing_items("hide");
+        let opacity_items: Vec<(u32, CheckMenuItem)> = opacity::LEVELS
+            .iter()
+            .map(|&percent| {
+                let item = CheckMenuItem::with_id(
+                    format!("opacity_{percent}"),
+                    format!("{percent}%"),
+                    true,
+                    percent == 100,
+                    None,
+                );
+                (percent, item)
+            })
+            .collect();
+        let about_item = MenuItem::with_id("about", t(Text::AboutStatus), true, None);
+        let exit_item = MenuItem::with_id("exit", t(Text::Exit), true, None);
 
         // Store IDs
+        let menu_toggle = toggle_item.id().clone();
+        let menu_track = track_item.id().clone();
         let menu_untrack = untrack_item.id().clone();
+        let menu_retrack = retrack_item.id().clone();
+        let menu_reset_position = reset_position_item.id().clone();
+        let menu_relayout = relayout_item.id().clone();
+        let menu_peek = peek_item.id().clone();
+        let menu_terminal = terminal_item.id().clone();
         let menu_autolaunch = autolaunch_item.id().clone();
+        let menu_autolaunch_task = autolaunch_task_item.id().clone();
+        let menu_shell_menu = shell_menu_item.id().clone();
         let menu_edge_trigger = edge_trigger_item.id().clone();
+        let menu_edge_scroll = edge_scroll_item.id().clone();
+        let menu_edge_swipe = edge_swipe_item.id().clone();
+        let menu_focus_loss = focus_loss_item.id().clone();
+        let menu_edge_custom = edge_custom_item.id().clone();
+        let menu_hide_on_track = hide_on_track_item.id().clone();
+        let menu_shake = shake_item.id().clone();
+        let menu_always_on_top = always_on_top_item.id().clone();
+        let menu_announce = announce_item.id().clone();
+        let menu_debug_logging = debug_logging_item.id().clone();
+        let menu_change_hotkey = change_hotkey_item.id().clone();
+        let menu_change_track_hotkey = change_track_hotkey_item.id().clone();
+        let menu_export_settings = export_settings_item.id().clone();
+        let menu_import_settings = import_settings_item.id().clone();
+        let menu_check_update = check_update_item.id().clone();
+        let menu_auto_update = auto_update_item.id().clone();
+        let menu_restart_update = restart_update_item.id().clone();
+        let menu_hide_taskbar = hide_taskbar_item.id().clone();
+        let menu_hide_alt_tab = hide_alt_tab_item.id().clone();
+        let menu_suppress_flash = suppress_flash_item.id().clone();
+        let menu_mute_hidden = mute_hidden_item.id().clone();
+        let menu_trim_memory = trim_memory_item.id().clone();
+        let menu_clear_error = clear_error_item.id().clone();
+        let menu_about = about_item.id().clone();
         let menu_exit = exit_item.id().clone();
 
         // Build menu
         let menu = Menu::new();
         menu.append(&status_item)
             .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&stats_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
         menu.append(&PredefinedMenuItem::separator())
             .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&toggle_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&track_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
         menu.append(&untrack_item)
             .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&retrack_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&peek_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&reset_position_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&relayout_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&terminal_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&preset_menu(&preset_items)?)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&slots_menu(&slots_item, &slot_items)?)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        let menu_slots = slots_item.id().clone();
+        let slot_items = slot_items
+            .iter()
+            .map(|(slot, item)| (*slot, item.id().clone()))
+            .collect();
+        let preset_items = preset_items
+            .iter()
+            .map(|(preset, item)| (*preset, item.id().clone()))
+            .collect();
         menu.append(&autolaunch_item)
             .map_err(|e| TrayError::Menu(e.to_string()))?;
-        menu.append(&edge_trigger_item)
+        menu.append(&autolaunch_task_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&shell_menu_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&edge_menu(
+            &edge_trigger_item,
+            &[&edge_scroll_item, &edge_swipe_item],
+            &edge_preset_items,
+            &edge_custom_item,
+        )?)
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&shake_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&hide_on_track_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&always_on_top_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&announce_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&hidden_menu(
+            &[
+                &hide_taskbar_item,
+                &hide_alt_tab_item,
+                &suppress_flash_item,
+                &mute_hidden_item,
+                &trim_memory_item,
+            ],
+            &process_mode_items,
+        )?)
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&focus_loss_menu(&focus_loss_item, &focus_loss_items)?)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&animation_menu(
+            &anim_style_items,
+            &show_easing_items,
+            &hide_easing_items,
+        )?)
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&layout_menu(&layout_items)?)
             .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&snap_menu(&snap_items)?)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&opacity_menu(&opacity_items)?)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        let snap_items = snap_items
+            .iter()
+            .map(|(snap, item)| (*snap, item.id().clone()))
+            .collect();
         menu.append(&PredefinedMenuItem::separator())
             .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&change_hotkey_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&change_track_hotkey_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&debug_logging_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&export_settings_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&import_settings_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&check_update_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&auto_update_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        menu.append(&restart_update_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+        let plugin_items: Vec<MenuItem> = plugin_items
+            .iter()
+            .enumerate()
+            .map(|(i, label)| MenuItem::with_id(format!("plugin_{i}"), label, true, None))
+            .collect();
+        let menu_plugin_items = plugin_items.iter().map(|item| item.id().clone()).collect();
+        if !plugin_items.is_empty() {
+            menu.append(&plugin_menu(&plugin_items)?)
+                .map_err(|e| TrayError::Menu(e.to_string()))?;
+        }
+        menu.append(&about_item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
         menu.append(&exit_item)
             .map_err(|e| TrayError::Menu(e.to_string()))?;
 
@@ -65,31 +608,196 @@ impl TrayState {
 
         // Build tray icon
         let tray = TrayIconBuilder::new()
-            .with_menu(Box::new(menu))
+            .with_menu(Box::new(menu.clone()))
             .with_tooltip("Quake Modoki")
             .with_icon(icon)
             .build()
             .map_err(|e| TrayError::Creation(e.to_string()))?;
 
         Ok(Self {
-            _icon: tray,
+            icon: tray,
+            menu,
+            menu_clear_error,
+            error_menu,
+            error_detail_item,
+            error_shown: Cell::new(false),
+            flash_on: Cell::new(false),
+            menu_toggle,
+            menu_track,
             menu_untrack,
+            menu_retrack,
+            menu_reset_position,
+            menu_relayout,
+            menu_peek,
+            menu_terminal,
             menu_autolaunch,
+            menu_autolaunch_task,
+            menu_shell_menu,
             menu_edge_trigger,
+            menu_edge_scroll,
+            menu_edge_swipe,
+            menu_focus_loss,
+            menu_edge_custom,
+            menu_hide_on_track,
+            menu_shake,
+            menu_always_on_top,
+            menu_announce,
+            menu_change_hotkey,
+            menu_change_track_hotkey,
+            menu_export_settings,
+            menu_import_settings,
+            menu_debug_logging,
+            menu_check_update,
+            menu_auto_update,
+            menu_restart_update,
+            menu_about,
+            menu_plugin_items,
+            menu_hide_taskbar,
+            menu_hide_alt_tab,
+            menu_suppress_flash,
+            menu_mute_hidden,
+            menu_trim_memory,
             menu_exit,
             status_item,
+            stats_item,
+            toggle_item,
+            track_item,
             autolaunch_item,
+            autolaunch_task_item,
+            shell_menu_item,
             edge_trigger_item,
+            edge_scroll_item,
+            edge_swipe_item,
+            focus_loss_item,
+            edge_preset_items,
+            edge_custom_item,
+            hide_on_track_item,
+            shake_item,
+            always_on_top_item,
+            announce_item,
+            debug_logging_item,
+            auto_update_item,
+            restart_update_item,
+            hide_taskbar_item,
+            hide_alt_tab_item,
+            suppress_flash_item,
+            mute_hidden_item,
+            trim_memory_item,
+            process_mode_items,
+            layout_items,
+            snap_items,
+            preset_items,
+            slots_item,
+            menu_slots,
+            slot_items,
+            opacity_items,
+            focus_loss_items,
+            anim_style_items,
+            show_easing_items,
+            hide_easing_items,
         })
     }
 
-    /// Update status display (tracked window title)
-    pub fn update_status(&self, title: Option<&str>) {
-        let text = match title {
-            Some(t) => format!("Tracking: {}", truncate_title(t, 30)),
-            None => "No window tracked".to_string(),
+    /// Update status display (tracked app's name, window title and icon)
+    pub fn update_status(&self, title: Option<&str>, exe: Option<&str>) {
+        let text = match (title, exe) {
+            (Some(title), Some(exe)) => tf(
+                Text::TrackingApp,
+                &[file_name(exe), &truncate_title(title, 30)],
+            ),
+            (Some(title), None) => tf(Text::Tracking, &[&truncate_title(title, 30)]),
+            (None, _) => t(Text::NoWindowTracked).to_string(),
         };
         self.status_item.set_text(&text);
+        self.status_item
+            .set_icon(exe.filter(|_| title.is_some()).and_then(app_icon));
+    }
+
+    /// Show active toggle hotkey in the tooltip (None: no hotkey registered)
+    pub fn set_toggle_hotkey(&self, label: Option<&str>) {
+        let tooltip = match label {
+            Some(label) => tf(Text::TooltipToggle, &[label]),
+            None => t(Text::TooltipNoHotkey).to_string(),
+        };
+        if let Err(e) = self.icon.set_tooltip(Some(tooltip)) {
+            tracing::warn!("Tray tooltip update failed: {e}");
+        }
+        self.toggle_item.set_text(with_hint(t(Text::Toggle), label));
+    }
+
+    /// Show the track hotkey on the "Track Foreground Window" entry
+    pub fn set_track_hotkey(&self, label: &str) {
+        self.track_item
+            .set_text(with_hint(t(Text::TrackForeground), Some(label)));
+    }
+
+    /// Show the last error (badge + "Last error" submenu), None clears both
+    pub fn set_last_error(&self, message: Option<&str>) {
+        match message {
+            Some(message) => {
+                self.error_menu
+                    .set_text(tf(Text::LastError, &[&truncate_title(message, 40)]));
+                self.error_detail_item
+                    .set_text(truncate_title(message, 120));
+                if !self.error_shown.get() {
+                    if let Err(e) = self.menu.insert(&self.error_menu, ERROR_MENU_POSITION) {
+                        tracing::warn!("Error menu insert failed: {e}");
+                    }
+                    match create_badge_icon() {
+                        Ok(icon) => self.set_icon(icon),
+                        Err(e) => tracing::warn!("Badge icon failed: {e}"),
+                    }
+                    self.error_shown.set(true);
+                }
+            }
+            None if self.error_shown.get() => {
+                if let Err(e) = self.menu.remove(&self.error_menu) {
+                    tracing::warn!("Error menu remove failed: {e}");
+                }
+                match create_default_icon() {
+                    Ok(icon) => self.set_icon(icon),
+                    Err(e) => tracing::warn!("Tray icon reset failed: {e}"),
+                }
+                self.error_shown.set(false);
+            }
+            None => {}
+        }
+    }
+
+    /// Show the attention flash `elapsed` into it; false once it is over (the
+    /// resting icon is back). It alternates with the badge, or with the plain
+    /// icon while an error badge is shown.
+    pub fn flash_frame(&self, elapsed: Duration) -> bool {
+        let lit = flash_lit(elapsed);
+        let on = lit.unwrap_or(false);
+        if on != self.flash_on.get() {
+            self.flash_on.set(on);
+            let icon = if on != self.error_shown.get() {
+                create_badge_icon()
+            } else {
+                create_default_icon()
+            };
+            match icon {
+                Ok(icon) => self.set_icon(icon),
+                Err(e) => tracing::warn!("Tray flash icon failed: {e}"),
+            }
+        }
+        lit.is_some()
+    }
+
+    fn set_icon(&self, icon: Icon) {
+        if let Err(e) = self.icon.set_icon(Some(icon)) {
+            tracing::warn!("Tray icon update failed: {e}");
+        }
+    }
+
+    pub fn is_clear_error(&self, id: &MenuId) -> bool {
+        id == &self.menu_clear_error
+    }
+
+    /// Update usage statistics line
+    pub fn update_stats(&self, summary: &str) {
+        self.stats_item.set_text(summary);
     }
 
     /// Set autolaunch checkbox state
@@ -97,16 +805,75 @@ impl TrayState {
         self.autolaunch_item.set_checked(checked);
     }
 
+    /// Set "start elevated" (Task Scheduler backend) checkbox state
+    pub fn set_autolaunch_task_checked(&self, checked: bool) {
+        self.autolaunch_task_item.set_checked(checked);
+    }
+
+    /// Set Explorer context menu checkbox state
+    pub fn set_shell_menu_checked(&self, checked: bool) {
+        self.shell_menu_item.set_checked(checked);
+    }
+
+    /// Check if event matches toggle menu
+    pub fn is_toggle(&self, id: &MenuId) -> bool {
+        *id == self.menu_toggle
+    }
+
+    /// Check if event matches track foreground menu
+    pub fn is_track(&self, id: &MenuId) -> bool {
+        *id == self.menu_track
+    }
+
     /// Check if event matches untrack menu
     pub fn is_untrack(&self, id: &MenuId) -> bool {
         *id == self.menu_untrack
     }
 
+    /// Check if event matches re-track menu
+    pub fn is_retrack(&self, id: &MenuId) -> bool {
+        *id == self.menu_retrack
+    }
+
     /// Check if event matches autolaunch menu
+    pub fn is_reset_position(&self, id: &MenuId) -> bool {
+        id == &self.menu_reset_position
+    }
+
+    /// Check if event matches "Peek"
+    pub fn is_peek(&self, id: &MenuId) -> bool {
+        id == &self.menu_peek
+    }
+
+    /// Check if event matches "Apply Layout Now"
+    pub fn is_relayout(&self, id: &MenuId) -> bool {
+        id == &self.menu_relayout
+    }
+
+    /// Check if event matches the Windows Terminal preset
+    pub fn is_terminal(&self, id: &MenuId) -> bool {
+        id == &self.menu_terminal
+    }
+
     pub fn is_autolaunch(&self, id: &MenuId) -> bool {
         *id == self.menu_autolaunch
     }
 
+    /// Check if event matches "start elevated" menu
+    pub fn is_autolaunch_task(&self, id: &MenuId) -> bool {
+        *id == self.menu_autolaunch_task
+    }
+
+    /// Check if event matches Explorer context menu item
+    pub fn is_shell_menu(&self, id: &MenuId) -> bool {
+        *id == self.menu_shell_menu
+    }
+
+    /// Check if event matches about / status menu
+    pub fn is_about(&self, id: &MenuId) -> bool {
+        *id == self.menu_about
+    }
+
     /// Check if event matches exit menu
     pub fn is_exit(&self, id: &MenuId) -> bool {
         *id == self.menu_exit
@@ -121,11 +888,545 @@ impl TrayState {
     pub fn set_edge_trigger_checked(&self, checked: bool) {
         self.edge_trigger_item.set_checked(checked);
     }
+
+    /// Check if event matches scroll at edge menu
+    pub fn is_edge_scroll(&self, id: &MenuId) -> bool {
+        *id == self.menu_edge_scroll
+    }
+
+    /// Set scroll at edge checkbox state
+    pub fn set_edge_scroll_checked(&self, checked: bool) {
+        self.edge_scroll_item.set_checked(checked);
+    }
+
+    /// Check if event matches swipe from edge menu
+    pub fn is_edge_swipe(&self, id: &MenuId) -> bool {
+        *id == self.menu_edge_swipe
+    }
+
+    /// Set swipe from edge checkbox state
+    pub fn set_edge_swipe_checked(&self, checked: bool) {
+        self.edge_swipe_item.set_checked(checked);
+    }
+
+    /// Check if event matches hide when tracked menu
+    pub fn is_hide_on_track(&self, id: &MenuId) -> bool {
+        *id == self.menu_hide_on_track
+    }
+
+    /// Set hide when tracked checkbox state
+    pub fn set_hide_on_track_checked(&self, checked: bool) {
+        self.hide_on_track_item.set_checked(checked);
+    }
+
+    /// Preset selected in the "Edge Trigger" submenu
+    pub fn edge_preset(&self, id: &MenuId) -> Option<EdgePreset> {
+        self.edge_preset_items
+            .iter()
+            .find(|(_, item)| item.id() == id)
+            .map(|(preset, _)| *preset)
+    }
+
+    /// Check if event matches edge trigger "Custom…" menu
+    pub fn is_edge_custom(&self, id: &MenuId) -> bool {
+        *id == self.menu_edge_custom
+    }
+
+    /// Check the active edge preset (radio behavior; None checks "Custom…")
+    pub fn set_edge_preset_checked(&self, preset: Option<EdgePreset>) {
+        for (item_preset, item) in &self.edge_preset_items {
+            item.set_checked(Some(*item_preset) == preset);
+        }
+        self.edge_custom_item.set_checked(preset.is_none());
+    }
+
+    /// Check if event matches always on top menu
+    pub fn is_always_on_top(&self, id: &MenuId) -> bool {
+        *id == self.menu_always_on_top
+    }
+
+    /// Set always on top checkbox state
+    pub fn set_always_on_top_checked(&self, checked: bool) {
+        self.always_on_top_item.set_checked(checked);
+    }
+
+    /// Check if event matches shake to toggle menu
+    pub fn is_shake(&self, id: &MenuId) -> bool {
+        *id == self.menu_shake
+    }
+
+    /// Set shake to toggle checkbox state
+    pub fn set_shake_checked(&self, checked: bool) {
+        self.shake_item.set_checked(checked);
+    }
+
+    /// Check if event matches screen reader announcements menu
+    pub fn is_announce(&self, id: &MenuId) -> bool {
+        *id == self.menu_announce
+    }
+
+    /// Set screen reader announcements checkbox state
+    pub fn set_announce_checked(&self, checked: bool) {
+        self.announce_item.set_checked(checked);
+    }
+
+    /// Check if event matches debug logging menu
+    pub fn is_debug_logging(&self, id: &MenuId) -> bool {
+        *id == self.menu_debug_logging
+    }
+
+    /// Get debug logging checkbox state
+    pub fn is_debug_logging_checked(&self) -> bool {
+        self.debug_logging_item.is_checked()
+    }
+
+    /// Set debug logging checkbox state
+    pub fn set_debug_logging_checked(&self, checked: bool) {
+        self.debug_logging_item.set_checked(checked);
+    }
+
+    /// Check if event matches change-toggle-hotkey menu
+    pub fn is_change_hotkey(&self, id: &MenuId) -> bool {
+        *id == self.menu_change_hotkey
+    }
+
+    pub fn is_change_track_hotkey(&self, id: &MenuId) -> bool {
+        *id == self.menu_change_track_hotkey
+    }
+
+    /// Check if event matches export settings menu
+    pub fn is_export_settings(&self, id: &MenuId) -> bool {
+        *id == self.menu_export_settings
+    }
+
+    /// Check if event matches import settings menu
+    pub fn is_import_settings(&self, id: &MenuId) -> bool {
+        *id == self.menu_import_settings
+    }
+
+    /// Position of a plugin tray item (index into `plugin::menu_items`)
+    pub fn plugin_item(&self, id: &MenuId) -> Option<usize> {
+        self.menu_plugin_items.iter().position(|item| item == id)
+    }
+
+    /// Check if event matches check-for-updates menu
+    pub fn is_check_update(&self, id: &MenuId) -> bool {
+        *id == self.menu_check_update
+    }
+
+    /// Check if event matches auto-update menu
+    pub fn is_auto_update(&self, id: &MenuId) -> bool {
+        *id == self.menu_auto_update
+    }
+
+    /// Set auto-update checkbox state
+    pub fn set_auto_update_checked(&self, checked: bool) {
+        self.auto_update_item.set_checked(checked);
+    }
+
+    /// Check if event matches restart-to-update menu
+    pub fn is_restart_update(&self, id: &MenuId) -> bool {
+        *id == self.menu_restart_update
+    }
+
+    /// Enable "Restart to Update" with the staged version
+    pub fn set_update_staged(&self, tag: &str) {
+        self.restart_update_item
+            .set_text(tf(Text::RestartToUpdateTag, &[tag]));
+        self.restart_update_item.set_enabled(true);
+    }
+
+    /// Check if event matches hide-from-taskbar menu
+    pub fn is_hide_taskbar(&self, id: &MenuId) -> bool {
+        *id == self.menu_hide_taskbar
+    }
+
+    /// Set hide-from-taskbar checkbox state
+    pub fn set_hide_taskbar_checked(&self, checked: bool) {
+        self.hide_taskbar_item.set_checked(checked);
+    }
+
+    /// Check if event matches hide-from-Alt-Tab menu
+    pub fn is_hide_alt_tab(&self, id: &MenuId) -> bool {
+        *id == self.menu_hide_alt_tab
+    }
+
+    /// Set hide-from-Alt-Tab checkbox state
+    pub fn set_hide_alt_tab_checked(&self, checked: bool) {
+        self.hide_alt_tab_item.set_checked(checked);
+    }
+
+    /// Check if event matches suppress-flashing menu
+    pub fn is_suppress_flash(&self, id: &MenuId) -> bool {
+        *id == self.menu_suppress_flash
+    }
+
+    /// Set suppress-flashing checkbox state
+    pub fn set_suppress_flash_checked(&self, checked: bool) {
+        self.suppress_flash_item.set_checked(checked);
+    }
+
+    /// Check if event matches mute-while-hidden menu
+    pub fn is_mute_hidden(&self, id: &MenuId) -> bool {
+        *id == self.menu_mute_hidden
+    }
+
+    /// Set mute-while-hidden checkbox state
+    pub fn set_mute_hidden_checked(&self, checked: bool) {
+        self.mute_hidden_item.set_checked(checked);
+    }
+
+    /// Check if event matches trim-memory menu
+    pub fn is_trim_memory(&self, id: &MenuId) -> bool {
+        *id == self.menu_trim_memory
+    }
+
+    /// Set trim-memory checkbox state
+    pub fn set_trim_memory_checked(&self, checked: bool) {
+        self.trim_memory_item.set_checked(checked);
+    }
+
+    /// Hidden process mode selected by event, if any
+    pub fn process_mode(&self, id: &MenuId) -> Option<HiddenMode> {
+        self.process_mode_items
+            .iter()
+            .find(|(_, item)| item.id() == id)
+            .map(|(mode, _)| *mode)
+    }
+
+    /// Check the active hidden process mode (radio behavior)
+    pub fn set_process_mode_checked(&self, mode: HiddenMode) {
+        for (item_mode, item) in &self.process_mode_items {
+            item.set_checked(*item_mode == mode);
+        }
+    }
+
+    pub fn is_hide_on_focus_loss(&self, id: &MenuId) -> bool {
+        *id == self.menu_focus_loss
+    }
+
+    pub fn set_hide_on_focus_loss_checked(&self, checked: bool) {
+        self.focus_loss_item.set_checked(checked);
+    }
+
+    /// Focus-loss action selected by an "On Focus Loss" item
+    pub fn focus_loss_action(&self, id: &MenuId) -> Option<FocusLossAction> {
+        self.focus_loss_items
+            .iter()
+            .find(|(_, item)| item.id() == id)
+            .map(|(action, _)| *action)
+    }
+
+    /// Check the active focus-loss action (radio behavior)
+    pub fn set_focus_loss_checked(&self, action: FocusLossAction) {
+        for (item_action, item) in &self.focus_loss_items {
+            item.set_checked(*item_action == action);
+        }
+    }
+
+    /// Style selected by an "Animation" item
+    pub fn anim_style(&self, id: &MenuId) -> Option<AnimStyle> {
+        self.anim_style_items
+            .iter()
+            .find(|(_, item)| item.id() == id)
+            .map(|(style, _)| *style)
+    }
+
+    /// Check the active animation style (radio behavior)
+    pub fn set_anim_style_checked(&self, style: AnimStyle) {
+        for (item_style, item) in &self.anim_style_items {
+            item.set_checked(*item_style == style);
+        }
+    }
+
+    /// Slide-in easing selected by an "Animation ▸ Slide In" item
+    pub fn show_easing(&self, id: &MenuId) -> Option<Easing> {
+        self.show_easing_items
+            .iter()
+            .find(|(_, item)| item.id() == id)
+            .map(|(easing, _)| *easing)
+    }
+
+    /// Slide-out easing selected by an "Animation ▸ Slide Out" item
+    pub fn hide_easing(&self, id: &MenuId) -> Option<Easing> {
+        self.hide_easing_items
+            .iter()
+            .find(|(_, item)| item.id() == id)
+            .map(|(easing, _)| *easing)
+    }
+
+    /// Check the active slide-in easing (radio behavior)
+    pub fn set_show_easing_checked(&self, easing: Easing) {
+        for (item_easing, item) in &self.show_easing_items {
+            item.set_checked(*item_easing == easing);
+        }
+    }
+
+    /// Check the active slide-out easing (radio behavior)
+    pub fn set_hide_easing_checked(&self, easing: Easing) {
+        for (item_easing, item) in &self.hide_easing_items {
+            item.set_checked(*item_easing == easing);
+        }
+    }
+
+    /// Layout selected by a "Group Layout" item
+    pub fn layout(&self, id: &MenuId) -> Option<Layout> {
+        self.layout_items
+            .iter()
+            .find(|(_, item)| item.id() == id)
+            .map(|(layout, _)| *layout)
+    }
+
+    /// Percentage selected in the "Opacity" submenu
+    pub fn opacity(&self, id: &MenuId) -> Option<u32> {
+        self.opacity_items
+            .iter()
+            .find(|(_, item)| item.id() == id)
+            .map(|(percent, _)| *percent)
+    }
+
+    /// Check the active opacity (radio behavior; no item for other values)
+    pub fn set_opacity_checked(&self, percent: u32) {
+        for (item_percent, item) in &self.opacity_items {
+            item.set_checked(*item_percent == percent);
+        }
+    }
+
+    /// Preset selected by a "Snap To" item
+    pub fn snap(&self, id: &MenuId) -> Option<Snap> {
+        self.snap_items
+            .iter()
+            .find(|(_, item_id)| item_id == id)
+            .map(|(snap, _)| *snap)
+    }
+
+    /// Check if event matches the slot hotkeys check
+    pub fn is_slots_enabled(&self, id: &MenuId) -> bool {
+        *id == self.menu_slots
+    }
+
+    /// Set slot hotkeys checkbox state
+    pub fn set_slots_checked(&self, checked: bool) {
+        self.slots_item.set_checked(checked);
+    }
+
+    /// Slot (0-based) selected in the "Window Slots" submenu
+    pub fn slot(&self, id: &MenuId) -> Option<usize> {
+        self.slot_items
+            .iter()
+            .find(|(_, item_id)| item_id == id)
+            .map(|(slot, _)| *slot)
+    }
+
+    /// Preset selected in the "Presets" submenu
+    pub fn preset(&self, id: &MenuId) -> Option<Preset> {
+        self.preset_items
+            .iter()
+            .find(|(_, item_id)| item_id == id)
+            .map(|(preset, _)| *preset)
+    }
+
+    /// Check the active group layout (radio behavior)
+    pub fn set_layout_checked(&self, layout: Layout) {
+        for (item_layout, item) in &self.layout_items {
+            item.set_checked(*item_layout == layout);
+        }
+    }
+
+    /// Disable items locked by policy
+    pub fn apply_policy_locks(&self, locks: &PolicyLocks) {
+        self.edge_trigger_item.set_enabled(!locks.edge_trigger);
+        self.edge_scroll_item.set_enabled(!locks.edge_scroll);
+        self.edge_swipe_item.set_enabled(!locks.edge_swipe);
+        self.shake_item.set_enabled(!locks.shake);
+        self.always_on_top_item.set_enabled(!locks.always_on_top);
+        self.announce_item.set_enabled(!locks.announce);
+        self.auto_update_item.set_enabled(!locks.auto_update);
+        self.hide_taskbar_item.set_enabled(!locks.hide_taskbar);
+        self.hide_alt_tab_item.set_enabled(!locks.hide_alt_tab);
+        self.suppress_flash_item.set_enabled(!locks.suppress_flash);
+        self.mute_hidden_item.set_enabled(!locks.mute_hidden);
+        self.trim_memory_item.set_enabled(!locks.trim_memory);
+        for (_, item) in &self.process_mode_items {
+            item.set_enabled(!locks.process_mode);
+        }
+    }
 }
 
-/// Get menu event receiver
-pub fn menu_receiver() -> &'static muda::MenuEventReceiver {
-    MenuEvent::receiver()
+/// Build "While Hidden" submenu: toggles, separator, process mode choices
+fn hidden_menu(
+    toggles: &[&CheckMenuItem],
+    process_modes: &[(HiddenMode, CheckMenuItem)],
+) -> Result<Submenu, TrayError> {
+    let submenu = Submenu::with_id("while_hidden", t(Text::WhileHidden), true);
+    for item in toggles {
+        submenu
+            .append(*item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    submenu
+        .append(&PredefinedMenuItem::separator())
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+    for (_, item) in process_modes {
+        submenu
+            .append(item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    Ok(submenu)
+}
+
+/// Build "Edge Trigger" submenu: enabled check, extra input checks, separator, presets
+fn edge_menu(
+    enabled: &CheckMenuItem,
+    inputs: &[&CheckMenuItem],
+    presets: &[(EdgePreset, CheckMenuItem)],
+    custom: &CheckMenuItem,
+) -> Result<Submenu, TrayError> {
+    let submenu = Submenu::with_id("edge", t(Text::EdgeTrigger), true);
+    submenu
+        .append(enabled)
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+    for item in inputs {
+        submenu
+            .append(*item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    submenu
+        .append(&PredefinedMenuItem::separator())
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+    for (_, item) in presets {
+        submenu
+            .append(item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    submenu
+        .append(custom)
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+    Ok(submenu)
+}
+
+/// Build "On Focus Loss" submenu: enabled check, separator, hide actions
+fn focus_loss_menu(
+    enabled: &CheckMenuItem,
+    items: &[(FocusLossAction, CheckMenuItem)],
+) -> Result<Submenu, TrayError> {
+    let submenu = Submenu::with_id("focus_loss", t(Text::OnFocusLoss), true);
+    submenu
+        .append(enabled)
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+    submenu
+        .append(&PredefinedMenuItem::separator())
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+    for (_, item) in items {
+        submenu
+            .append(item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    Ok(submenu)
+}
+
+/// Build "Animation" submenu: styles, separator, per-direction easing curves
+fn animation_menu(
+    styles: &[(AnimStyle, CheckMenuItem)],
+    show: &[(Easing, CheckMenuItem)],
+    hide: &[(Easing, CheckMenuItem)],
+) -> Result<Submenu, TrayError> {
+    let submenu = Submenu::with_id("animation", t(Text::Animation), true);
+    for (_, item) in styles {
+        submenu
+            .append(item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    submenu
+        .append(&PredefinedMenuItem::separator())
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+    for (id, label, items) in [
+        ("animation_show", Text::SlideIn, show),
+        ("animation_hide", Text::SlideOut, hide),
+    ] {
+        let direction = Submenu::with_id(id, t(label), true);
+        for (_, item) in items {
+            direction
+                .append(item)
+                .map_err(|e| TrayError::Menu(e.to_string()))?;
+        }
+        submenu
+            .append(&direction)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    Ok(submenu)
+}
+
+/// Build "Opacity" submenu
+fn opacity_menu(items: &[(u32, CheckMenuItem)]) -> Result<Submenu, TrayError> {
+    let submenu = Submenu::with_id("opacity", t(Text::Opacity), true);
+    for (_, item) in items {
+        submenu
+            .append(item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    Ok(submenu)
+}
+
+/// Build "Group Layout" submenu
+fn layout_menu(items: &[(Layout, CheckMenuItem)]) -> Result<Submenu, TrayError> {
+    let submenu = Submenu::with_id("group_layout", t(Text::GroupLayout), true);
+    for (_, item) in items {
+        submenu
+            .append(item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    Ok(submenu)
+}
+
+/// Build "Snap To" submenu
+fn snap_menu(items: &[(Snap, MenuItem)]) -> Result<Submenu, TrayError> {
+    let submenu = Submenu::with_id("snap", t(Text::SnapTo), true);
+    for (_, item) in items {
+        submenu
+            .append(item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    Ok(submenu)
+}
+
+/// Build "Presets" submenu
+fn preset_menu(items: &[(Preset, MenuItem)]) -> Result<Submenu, TrayError> {
+    let submenu = Submenu::with_id("presets", t(Text::Presets), true);
+    for (_, item) in items {
+        submenu
+            .append(item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    Ok(submenu)
+}
+
+/// Build "Window Slots" submenu: hotkeys check, then one entry per slot
+fn slots_menu(enabled: &CheckMenuItem, items: &[(usize, MenuItem)]) -> Result<Submenu, TrayError> {
+    let submenu = Submenu::with_id("slots", t(Text::WindowSlots), true);
+    submenu
+        .append(enabled)
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+    submenu
+        .append(&PredefinedMenuItem::separator())
+        .map_err(|e| TrayError::Menu(e.to_string()))?;
+    for (_, item) in items {
+        submenu
+            .append(item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    Ok(submenu)
+}
+
+/// Build "Plugins" submenu
+fn plugin_menu(items: &[MenuItem]) -> Result<Submenu, TrayError> {
+    let submenu = Submenu::with_id("plugins", t(Text::Plugins), true);
+    for item in items {
+        submenu
+            .append(item)
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+    }
+    Ok(submenu)
 }
 
 /// Load icon from embedded Windows resource
@@ -134,6 +1435,152 @@ fn create_default_icon() -> Result<Icon, TrayError> {
     Icon::from_resource(1, None).map_err(|e| TrayError::Creation(e.to_string()))
 }
 
+/// Default icon with a red dot in the bottom-right corner
+fn create_badge_icon() -> Result<Icon, TrayError> {
+    let mut rgba = icon_pixels(BADGE_ICON_SIZE)
+        .ok_or_else(|| TrayError::Creation("Icon pixels unavailable".to_string()))?;
+    draw_badge(&mut rgba, BADGE_ICON_SIZE);
+    Icon::from_rgba(rgba, BADGE_ICON_SIZE, BADGE_ICON_SIZE)
+        .map_err(|e| TrayError::Creation(e.to_string()))
+}
+
+/// Small icon of an executable (as Explorer shows it), None if unavailable
+fn app_icon(exe: &str) -> Option<muda::Icon> {
+    let mut info = SHFILEINFOW::default();
+    let found = unsafe {
+        SHGetFileInfoW(
+            &HSTRING::from(exe),
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            Some(&mut info),
+            size_of::<SHFILEINFOW>() as u32,
+            SHGFI_ICON | SHGFI_SMALLICON,
+        )
+    };
+    if found == 0 || info.hIcon.is_invalid() {
+        return None;
+    }
+    let size = (unsafe { GetSystemMetrics(SM_CXSMICON) }).max(16) as u32;
+    let pixels = hicon_pixels(info.hIcon, size);
+    let _ = unsafe { DestroyIcon(info.hIcon) };
+    muda::Icon::from_rgba(pixels?, size, size)
+        .inspect_err(|e| tracing::debug!(exe, "App icon unusable: {e}"))
+        .ok()
+}
+
+/// Alternate icon shown at `elapsed` into a flash (None: flash over)
+fn flash_lit(elapsed: Duration) -> Option<bool> {
+    (elapsed < FLASH_DURATION).then(|| (elapsed.as_millis() / FLASH_PERIOD_MS).is_multiple_of(2))
+}
+
+/// File name of an exe path, as written
+fn file_name(exe: &str) -> &str {
+    exe.rsplit(['\\', '/']).next().unwrap_or(exe)
+}
+
+/// RGBA pixels of resource icon 1 rendered at `size` x `size`
+fn icon_pixels(size: u32) -> Option<Vec<u8>> {
+    let side = size as i32;
+    unsafe {
+        let instance = GetModuleHandleW(None).ok()?;
+        let handle = LoadImageW(
+            Some(instance.into()),
+            PCWSTR(1 as _),
+            IMAGE_ICON,
+            side,
+            side,
+            LR_DEFAULTCOLOR,
+        )
+        .ok()?;
+        let hicon = HICON(handle.0);
+        let pixels = hicon_pixels(hicon, size);
+        let _ = DestroyIcon(hicon);
+        pixels
+    }
+}
+
+/// RGBA pixels of `hicon` (`size` x `size` bitmap; the icon is not destroyed)
+fn hicon_pixels(hicon: HICON, size: u32) -> Option<Vec<u8>> {
+    let side = size as i32;
+    let mut pixels = vec![0u8; (size * size * 4) as usize];
+    let lines = unsafe {
+        let mut info = ICONINFO::default();
+        if GetIconInfo(hicon, &mut info).is_ok() {
+            let dc = CreateCompatibleDC(None);
+            let mut bmi = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: side,
+                    // Negative height: top-down rows
+                    biHeight: -side,
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let lines = GetDIBits(
+                dc,
+                info.hbmColor,
+                0,
+                size,
+                Some(pixels.as_mut_ptr().cast()),
+                &mut bmi,
+                DIB_RGB_COLORS,
+            );
+            let _ = DeleteDC(dc);
+            let _ = DeleteObject(info.hbmColor.into());
+            let _ = DeleteObject(info.hbmMask.into());
+            lines
+        } else {
+            0
+        }
+    };
+    if lines <= 0 {
+        return None;
+    }
+
+    // BGRA → RGBA; icons without an alpha channel are fully opaque
+    let has_alpha = pixels.chunks_exact(4).any(|px| px[3] != 0);
+    for px in pixels.chunks_exact_mut(4) {
+        px.swap(0, 2);
+        if !has_alpha {
+            px[3] = 0xFF;
+        }
+    }
+    Some(pixels)
+}
+
+/// Paint a red dot with a white ring over the bottom-right quarter of `rgba`
+fn draw_badge(rgba: &mut [u8], size: u32) {
+    let radius = size as f32 / 4.0;
+    let center = size as f32 - radius - 0.5;
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x as f32 - center;
+            let dy = y as f32 - center;
+            let distance = (dx * dx + dy * dy).sqrt();
+            let color = if distance <= radius - 1.5 {
+                [0xE0, 0x30, 0x30, 0xFF]
+            } else if distance <= radius {
+                [0xFF, 0xFF, 0xFF, 0xFF]
+            } else {
+                continue;
+            };
+            let index = ((y * size + x) * 4) as usize;
+            rgba[index..index + 4].copy_from_slice(&color);
+        }
+    }
+}
+
+/// Menu label with its shortcut right-aligned (tab-separated accelerator text)
+fn with_hint(text: &str, hint: Option<&str>) -> String {
+    match hint {
+        Some(hint) => format!("{text}\t{hint}"),
+        None => text.to_string(),
+    }
+}
+
 /// Truncate title with ellipsis if too long (char-based, UTF-8 safe)
 fn truncate_title(s: &str, max_chars: usize) -> String {
     if s.chars().count() <= max_chars {
@@ -152,6 +1599,43 @@ fn truncate_title(s: &str, max_chars: usize) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_with_hint() {
+        assert_eq!(with_hint("Toggle", Some("F8")), "Toggle\tF8");
+        assert_eq!(with_hint("Toggle", None), "Toggle");
+    }
+
+    fn pixel(rgba: &[u8], size: u32, x: u32, y: u32) -> &[u8] {
+        let index = ((y * size + x) * 4) as usize;
+        &rgba[index..index + 4]
+    }
+
+    #[test]
+    fn test_draw_badge_bottom_right() {
+        let size = BADGE_ICON_SIZE;
+        let mut rgba = vec![0u8; (size * size * 4) as usize];
+        draw_badge(&mut rgba, size);
+        let center = size - size / 4 - 1;
+        assert_eq!(pixel(&rgba, size, center, center), [0xE0, 0x30, 0x30, 0xFF]);
+        // Top-left untouched (original icon stays visible)
+        assert_eq!(pixel(&rgba, size, 2, 2), [0, 0, 0, 0]);
+        assert_eq!(pixel(&rgba, size, size / 2, 2), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_flash_lit() {
+        assert_eq!(flash_lit(Duration::ZERO), Some(true));
+        assert_eq!(flash_lit(Duration::from_millis(350)), Some(false));
+        assert_eq!(flash_lit(Duration::from_millis(650)), Some(true));
+        assert_eq!(flash_lit(FLASH_DURATION), None);
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name(r"C:\Windows\System32\cmd.exe"), "cmd.exe");
+        assert_eq!(file_name("WindowsTerminal.exe"), "WindowsTerminal.exe");
+    }
+
     #[test]
     fn test_truncate_title_short() {
         assert_eq!(truncate_title("Hello", 10), "Hello");
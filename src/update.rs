@@ -0,0 +1,516 @@
+//! Self-update: fetch latest GitHub release, verify it, swap exe on exit
+//!
+//! Release assets: `quake-modoki.exe` + `quake-modoki.exe.sha256` ("<hex>  <name>").
+//! The hash only catches a broken download (it comes from the same release);
+//! the binary must also carry a valid Authenticode signature of [`PUBLISHER`],
+//! so a tampered release is rejected. The new binary is staged next to the
+//! running exe and swapped in on exit (a running exe can be renamed but not
+//! overwritten). Settings live in the registry, so the restarted binary picks
+//! them up unchanged; it gets the same command-line arguments.
+
+use sha2::{Digest, Sha256};
+use std::ffi::c_void;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use thiserror::Error;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Networking::WinHttp::{
+    INTERNET_DEFAULT_HTTPS_PORT, WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY, WINHTTP_FLAG_SECURE,
+    WINHTTP_QUERY_FLAG_NUMBER, WINHTTP_QUERY_STATUS_CODE, WinHttpCloseHandle, WinHttpConnect,
+    WinHttpOpen, WinHttpOpenRequest, WinHttpQueryDataAvailable, WinHttpQueryHeaders,
+    WinHttpReadData, WinHttpReceiveResponse, WinHttpSendRequest,
+};
+use windows::Win32::Security::Cryptography::{CERT_NAME_SIMPLE_DISPLAY_TYPE, CertGetNameStringW};
+use windows::Win32::Security::WinTrust::{
+    WINTRUST_ACTION_GENERIC_VERIFY_V2, WINTRUST_DATA, WINTRUST_DATA_0, WINTRUST_FILE_INFO,
+    WTD_CHOICE_FILE, WTD_REVOKE_WHOLECHAIN, WTD_STATEACTION_CLOSE, WTD_STATEACTION_VERIFY,
+    WTD_UI_NONE, WTHelperGetProvSignerFromChain, WTHelperProvDataFromStateData, WinVerifyTrust,
+};
+use windows::Win32::UI::WindowsAndMessaging::WM_USER;
+use windows::core::{HSTRING, PCWSTR, w};
+
+use crate::settings::{self, SettingsError};
+
+const AUTO_UPDATE: &str = "AutoUpdate";
+const RELEASES_URL: &str = "https://api.github.com/repos/oshiteku/quake-modoki/releases/latest";
+const ASSET_NAME: &str = "quake-modoki.exe";
+const HASH_ASSET_NAME: &str = "quake-modoki.exe.sha256";
+
+/// Signer (certificate subject name) release binaries must be signed by
+const PUBLISHER: &str = "oshiteku";
+
+/// Posted to the event loop once an update is staged
+pub const WM_UPDATE_STAGED: u32 = WM_USER + 3;
+
+#[derive(Debug, Error)]
+pub enum UpdateError {
+    #[error("Network request failed: {0}")]
+    Network(#[from] windows::core::Error),
+
+    #[error("HTTP status {0}")]
+    Status(u32),
+
+    #[error("Unsupported URL: {0}")]
+    Url(String),
+
+    #[error("Release metadata invalid: {0}")]
+    Metadata(String),
+
+    #[error("SHA-256 mismatch (expected {expected}, got {actual})")]
+    HashMismatch { expected: String, actual: String },
+
+    #[error("Signature check failed: {0}")]
+    Signature(String),
+
+    #[error("File operation failed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Latest release as published on GitHub
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub tag: String,
+    pub version: (u32, u32, u32),
+    pub exe_url: String,
+    pub hash_url: String,
+}
+
+/// Verified binary waiting to be swapped in on exit
+static STAGED: Mutex<Option<(String, PathBuf)>> = Mutex::new(None);
+
+/// Check if automatic update check at startup enabled (default: off)
+pub fn is_enabled() -> bool {
+    settings::get_bool(AUTO_UPDATE, false)
+}
+
+/// Check if automatic update check is fixed by policy
+pub fn is_locked() -> bool {
+    settings::is_locked(AUTO_UPDATE)
+}
+
+/// Toggle automatic update check, returns new state
+pub fn toggle() -> Result<bool, SettingsError> {
+    settings::toggle_bool(AUTO_UPDATE, false)
+}
+
+/// Parse "v1.2.3" / "1.2.3" (pre-release suffix ignored)
+pub fn parse_version(tag: &str) -> Option<(u32, u32, u32)> {
+    let core = tag.trim_start_matches('v').split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u32>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Version of the running binary
+pub fn current_version() -> (u32, u32, u32) {
+    parse_version(env!("CARGO_PKG_VERSION")).unwrap_or_default()
+}
+
+/// Extract tag + asset URLs from GitHub "latest release" JSON
+fn parse_release(json: &str) -> Result<Release, UpdateError> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| UpdateError::Metadata(e.to_string()))?;
+
+    let tag = value["tag_name"]
+        .as_str()
+        .ok_or_else(|| UpdateError::Metadata("tag_name missing".into()))?
+        .to_string();
+    let version =
+        parse_version(&tag).ok_or_else(|| UpdateError::Metadata(format!("bad tag {tag}")))?;
+
+    let asset_url = |name: &str| {
+        value["assets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|a| a["name"].as_str() == Some(name))
+            .and_then(|a| a["browser_download_url"].as_str())
+            .map(str::to_string)
+            .ok_or_else(|| UpdateError::Metadata(format!("asset {name} missing")))
+    };
+
+    Ok(Release {
+        tag,
+        version,
+        exe_url: asset_url(ASSET_NAME)?,
+        hash_url: asset_url(HASH_ASSET_NAME)?,
+    })
+}
+
+/// First token of a `sha256sum`-style line, lowercased
+fn parse_sha256_file(text: &str) -> Option<String> {
+    let hex = text.split_whitespace().next()?.to_ascii_lowercase();
+    (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit())).then_some(hex)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Check if a signer name is the pinned publisher
+fn is_publisher(signer: &str) -> bool {
+    signer == PUBLISHER
+}
+
+/// Verify the Authenticode signature of `path` (trusted chain, not revoked)
+/// and that it was signed by [`PUBLISHER`]
+fn verify_signature(path: &Path) -> Result<(), UpdateError> {
+    let path = HSTRING::from(path.as_os_str());
+    let mut file = WINTRUST_FILE_INFO {
+        cbStruct: std::mem::size_of::<WINTRUST_FILE_INFO>() as u32,
+        pcwszFilePath: PCWSTR(path.as_ptr()),
+        ..Default::default()
+    };
+    let mut data = WINTRUST_DATA {
+        cbStruct: std::mem::size_of::<WINTRUST_DATA>() as u32,
+        dwUIChoice: WTD_UI_NONE,
+        fdwRevocationChecks: WTD_REVOKE_WHOLECHAIN,
+        dwUnionChoice: WTD_CHOICE_FILE,
+        Anonymous: WINTRUST_DATA_0 { pFile: &mut file },
+        dwStateAction: WTD_STATEACTION_VERIFY,
+        ..Default::default()
+    };
+    let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+
+    unsafe {
+        let status = WinVerifyTrust(
+            HWND::default(),
+            &mut action,
+            &mut data as *mut WINTRUST_DATA as *mut c_void,
+        );
+        let signer = (status == 0).then(|| signer_name(&data)).flatten();
+
+        // Release the state WinVerifyTrust kept for the signer lookup
+        data.dwStateAction = WTD_STATEACTION_CLOSE;
+        WinVerifyTrust(
+            HWND::default(),
+            &mut action,
+            &mut data as *mut WINTRUST_DATA as *mut c_void,
+        );
+
+        if status != 0 {
+            return Err(UpdateError::Signature(format!(
+                "not trusted (0x{:08x})",
+                status as u32
+            )));
+        }
+        match signer {
+            Some(signer) if is_publisher(&signer) => Ok(()),
+            Some(signer) => Err(UpdateError::Signature(format!(
+                "signed by {signer}, expected {PUBLISHER}"
+            ))),
+            None => Err(UpdateError::Signature("signer unknown".into())),
+        }
+    }
+}
+
+/// Subject name of the leaf certificate of a verified file's first signer
+unsafe fn signer_name(data: &WINTRUST_DATA) -> Option<String> {
+    unsafe {
+        let provider = WTHelperProvDataFromStateData(data.hWVTStateData);
+        if provider.is_null() {
+            return None;
+        }
+        let signer = WTHelperGetProvSignerFromChain(provider, 0, false, 0);
+        if signer.is_null() || (*signer).csCertChain == 0 || (*signer).pasCertChain.is_null() {
+            return None;
+        }
+        let cert = (*(*signer).pasCertChain).pCert;
+        if cert.is_null() {
+            return None;
+        }
+        let mut name = [0u16; 256];
+        let len = CertGetNameStringW(
+            cert,
+            CERT_NAME_SIMPLE_DISPLAY_TYPE,
+            0,
+            None,
+            Some(&mut name),
+        ) as usize;
+        // len counts the terminating null
+        (len > 1).then(|| String::from_utf16_lossy(&name[..len - 1]))
+    }
+}
+
+/// Owned WinHTTP handle
+struct Handle(*mut c_void);
+
+impl Handle {
+    fn new(raw: *mut c_void) -> Result<Self, UpdateError> {
+        if raw.is_null() {
+            Err(windows::core::Error::from_thread().into())
+        } else {
+            Ok(Self(raw))
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = WinHttpCloseHandle(self.0);
+        }
+    }
+}
+
+/// Blocking HTTPS GET via WinHTTP (redirects followed by WinHTTP)
+fn http_get(url: &str) -> Result<Vec<u8>, UpdateError> {
+    let rest = url
+        .strip_prefix("https://")
+        .ok_or_else(|| UpdateError::Url(url.to_string()))?;
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let agent = HSTRING::from(format!("quake-modoki/{}", env!("CARGO_PKG_VERSION")));
+
+    unsafe {
+        let session = Handle::new(WinHttpOpen(
+            &agent,
+            WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY,
+            PCWSTR::null(),
+            PCWSTR::null(),
+            0,
+        ))?;
+        let connect = Handle::new(WinHttpConnect(
+            session.0,
+            &HSTRING::from(host),
+            INTERNET_DEFAULT_HTTPS_PORT,
+            0,
+        ))?;
+        let request = Handle::new(WinHttpOpenRequest(
+            connect.0,
+            w!("GET"),
+            &HSTRING::from(format!("/{path}")),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            std::ptr::null(),
+            WINHTTP_FLAG_SECURE,
+        ))?;
+
+        WinHttpSendRequest(request.0, None, None, 0, 0, 0)?;
+        WinHttpReceiveResponse(request.0, std::ptr::null_mut())?;
+
+        let mut status = 0u32;
+        let mut len = std::mem::size_of::<u32>() as u32;
+        WinHttpQueryHeaders(
+            request.0,
+            WINHTTP_QUERY_STATUS_CODE | WINHTTP_QUERY_FLAG_NUMBER,
+            PCWSTR::null(),
+            Some(&mut status as *mut u32 as *mut c_void),
+            &mut len,
+            std::ptr::null_mut(),
+        )?;
+        if status != 200 {
+            return Err(UpdateError::Status(status));
+        }
+
+        let mut body = Vec::new();
+        loop {
+            let mut available = 0u32;
+            WinHttpQueryDataAvailable(request.0, &mut available)?;
+            if available == 0 {
+                break;
+            }
+            let start = body.len();
+            body.resize(start + available as usize, 0);
+            let mut read = 0u32;
+            WinHttpReadData(
+                request.0,
+                body[start..].as_mut_ptr() as *mut c_void,
+                available,
+                &mut read,
+            )?;
+            body.truncate(start + read as usize);
+        }
+        Ok(body)
+    }
+}
+
+/// Path the verified binary is staged at: "<exe>.new"
+fn staged_path(exe: &Path) -> PathBuf {
+    exe.with_extension("exe.new")
+}
+
+/// Path the replaced binary is moved to: "<exe>.old"
+fn old_path(exe: &Path) -> PathBuf {
+    exe.with_extension("exe.old")
+}
+
+/// Query latest release; download + verify + stage if newer
+/// Returns the staged tag, or None if already up to date
+pub fn check_and_stage() -> Result<Option<String>, UpdateError> {
+    let json = http_get(RELEASES_URL)?;
+    let release = parse_release(&String::from_utf8_lossy(&json))?;
+    if release.version <= current_version() {
+        return Ok(None);
+    }
+
+    let expected = parse_sha256_file(&String::from_utf8_lossy(&http_get(&release.hash_url)?))
+        .ok_or_else(|| UpdateError::Metadata("sha256 file malformed".into()))?;
+    let binary = http_get(&release.exe_url)?;
+    let actual = sha256_hex(&binary);
+    if actual != expected {
+        return Err(UpdateError::HashMismatch { expected, actual });
+    }
+
+    let exe = std::env::current_exe()?;
+    let staged = staged_path(&exe);
+    fs::write(&staged, &binary)?;
+    if let Err(e) = verify_signature(&staged) {
+        let _ = fs::remove_file(&staged);
+        return Err(e);
+    }
+    *STAGED.lock().unwrap_or_else(|e| e.into_inner()) = Some((release.tag.clone(), staged));
+    Ok(Some(release.tag))
+}
+
+/// Run `check_and_stage` on a worker thread, reporting via toast + log
+/// manual: also notify when already up to date / on failure
+pub fn spawn_check(manual: bool) {
+    std::thread::spawn(move || match check_and_stage() {
+        Ok(Some(tag)) => {
+            tracing::info!(tag = %tag, "Update staged");
+            crate::notification::show_update_ready(&tag);
+            crate::events::post(WM_UPDATE_STAGED, 0);
+        }
+        Ok(None) => {
+            tracing::info!("Already up to date");
+            if manual {
+                crate::notification::show_up_to_date();
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Update check failed: {e}");
+            if manual {
+                crate::notification::show_update_failed(&e.to_string());
+            }
+        }
+    });
+}
+
+/// Tag of the staged update, if any
+pub fn staged_tag() -> Option<String> {
+    STAGED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|(tag, _)| tag.clone())
+}
+
+/// Swap staged binary into place (call on exit); restart: relaunch afterwards
+pub fn apply_staged(restart: bool) -> Result<bool, UpdateError> {
+    let Some((tag, staged)) = STAGED.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return Ok(false);
+    };
+
+    let exe = std::env::current_exe()?;
+    let old = old_path(&exe);
+    let _ = fs::remove_file(&old);
+    fs::rename(&exe, &old)?;
+    if let Err(e) = fs::rename(&staged, &exe) {
+        // Put the running binary back so the next launch still works
+        let _ = fs::rename(&old, &exe);
+        return Err(e.into());
+    }
+    tracing::info!(tag = %tag, "Update applied");
+
+    if restart {
+        // Same flags as this run (--simulate, log level, ...)
+        Command::new(&exe)
+            .args(std::env::args_os().skip(1))
+            .spawn()?;
+    }
+    Ok(true)
+}
+
+/// Remove leftovers of a previous swap (startup)
+pub fn cleanup() {
+    if let Ok(exe) = std::env::current_exe() {
+        let _ = fs::remove_file(old_path(&exe));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v0.2.0"), Some((0, 2, 0)));
+        assert_eq!(parse_version("1.10.3"), Some((1, 10, 3)));
+        assert_eq!(parse_version("v1.2.3-beta.1"), Some((1, 2, 3)));
+    }
+
+    #[test]
+    fn test_parse_version_invalid() {
+        assert_eq!(parse_version("v1.2"), None);
+        assert_eq!(parse_version("v1.2.3.4"), None);
+        assert_eq!(parse_version("latest"), None);
+    }
+
+    #[test]
+    fn test_version_ordering() {
+        assert!(parse_version("v0.10.0") > parse_version("v0.9.9"));
+        assert!(parse_version("v1.0.0") > parse_version("v0.99.99"));
+    }
+
+    #[test]
+    fn test_parse_release() {
+        let json = r#"{
+            "tag_name": "v0.3.0",
+            "assets": [
+                {"name": "quake-modoki.exe", "browser_download_url": "https://x/exe"},
+                {"name": "quake-modoki.exe.sha256", "browser_download_url": "https://x/sha"}
+            ]
+        }"#;
+        let release = parse_release(json).expect("parse failed");
+        assert_eq!(release.version, (0, 3, 0));
+        assert_eq!(release.exe_url, "https://x/exe");
+        assert_eq!(release.hash_url, "https://x/sha");
+    }
+
+    #[test]
+    fn test_parse_release_missing_asset() {
+        let json = r#"{"tag_name": "v0.3.0", "assets": []}"#;
+        assert!(matches!(parse_release(json), Err(UpdateError::Metadata(_))));
+    }
+
+    #[test]
+    fn test_parse_sha256_file() {
+        let hex = "A".repeat(64);
+        let parsed = parse_sha256_file(&format!("{hex}  quake-modoki.exe\n"));
+        assert_eq!(parsed, Some("a".repeat(64)));
+        assert_eq!(parse_sha256_file("deadbeef  file"), None);
+    }
+
+    #[test]
+    fn test_sha256_hex_known_vector() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_is_publisher() {
+        assert!(is_publisher(PUBLISHER));
+        assert!(!is_publisher("Someone Else"));
+        assert!(!is_publisher(""));
+    }
+
+    #[test]
+    fn test_staged_and_old_paths() {
+        let exe = Path::new(r"C:\tools\quake-modoki.exe");
+        assert_eq!(
+            staged_path(exe),
+            PathBuf::from(r"C:\tools\quake-modoki.exe.new")
+        );
+        assert_eq!(
+            old_path(exe),
+            PathBuf::from(r"C:\tools\quake-modoki.exe.old")
+        );
+    }
+}
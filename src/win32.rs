@@ -0,0 +1,977 @@
+//! Window-system calls of the toggle / edge / focus pipeline behind a trait
+//!
+//! `Native` forwards to Win32. `Recorder` (headless `--simulate`) models one
+//! desktop in memory and logs every intended move, hide, style, alpha, flash
+//! and focus change instead of performing it; its windows belong to no
+//! process, so process-level effects (mute, suspend, trim) are skipped too.
+//! The active implementation is chosen once, before first use (`install`);
+//! everything else calls `api()`.
+
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use windows::Win32::Foundation::{COLORREF, HWND, POINT, RECT};
+use windows::Win32::Graphics::Dwm::{
+    DWMNCRP_DISABLED, DWMNCRP_USEWINDOWSTYLE, DWMWA_NCRENDERING_ENABLED, DWMWA_NCRENDERING_POLICY,
+    DwmGetWindowAttribute, DwmSetWindowAttribute,
+};
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, HMONITOR, InvalidateRect, MONITOR_DEFAULTTONEAREST, MONITOR_DEFAULTTONULL,
+    MONITOR_DEFAULTTOPRIMARY, MONITORINFO, MonitorFromPoint, MonitorFromRect, MonitorFromWindow,
+};
+use windows::Win32::UI::Accessibility::{
+    HWINEVENTHOOK, SetWinEventHook, UnhookWinEvent, WINEVENTPROC,
+};
+use windows::Win32::UI::HiDpi::{
+    DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, DPI_AWARENESS_PER_MONITOR_AWARE,
+    GetAwarenessFromDpiAwarenessContext, GetDpiForMonitor, GetDpiForWindow,
+    GetWindowDpiAwarenessContext, MDT_EFFECTIVE_DPI, SetThreadDpiAwarenessContext,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    BeginDeferWindowPos, CS_DROPSHADOW, DeferWindowPos, EndDeferWindowPos, FLASHW_STOP, FLASHWINFO,
+    FlashWindowEx, GCL_STYLE, GWL_EXSTYLE, GWL_STYLE, GetClassLongPtrW, GetCursorPos,
+    GetForegroundWindow, GetLayeredWindowAttributes, GetWindowLongPtrW, GetWindowPlacement,
+    GetWindowRect, GetWindowThreadProcessId, HWND_NOTOPMOST, HWND_TOPMOST, IsWindow,
+    IsWindowVisible, LAYERED_WINDOW_ATTRIBUTES_FLAGS, LWA_ALPHA, SET_WINDOW_POS_FLAGS, SW_HIDE,
+    SW_SHOW, SW_SHOWNOACTIVATE, SWP_FRAMECHANGED, SWP_HIDEWINDOW, SWP_NOACTIVATE, SWP_NOMOVE,
+    SWP_NOSIZE, SWP_SHOWWINDOW, SetClassLongPtrW, SetForegroundWindow, SetLayeredWindowAttributes,
+    SetWindowLongPtrW, SetWindowPlacement, SetWindowPos, ShowWindow, WINDOWPLACEMENT,
+};
+
+use crate::tracking::WindowBounds;
+
+/// Topmost move of one window (position, size, SWP_* flags)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub hwnd: HWND,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub flags: SET_WINDOW_POS_FLAGS,
+}
+
+/// Shadow sources of a window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Shadow {
+    /// DWM non-client rendering (frame and its shadow)
+    pub frame: bool,
+    /// CS_DROPSHADOW class style (popup shadow)
+    pub class: bool,
+}
+
+impl Shadow {
+    pub fn any(self) -> bool {
+        self.frame || self.class
+    }
+}
+
+/// Window-system operations used by the pipeline
+pub trait WindowApi: Send + Sync {
+    fn is_window(&self, hwnd: HWND) -> bool;
+    fn is_visible(&self, hwnd: HWND) -> bool;
+    /// Show (SW_SHOW) or hide (SW_HIDE)
+    fn show(&self, hwnd: HWND, visible: bool);
+    /// Owning process id (0 if none)
+    fn process_id(&self, hwnd: HWND) -> u32;
+    fn window_rect(&self, hwnd: HWND) -> Option<WindowBounds>;
+    /// Work area of the monitor showing `hwnd` (primary if none)
+    fn work_area(&self, hwnd: HWND) -> Option<RECT>;
+    /// Work area of the monitor nearest to `point`
+    fn work_area_at(&self, point: POINT) -> Option<RECT>;
+    /// Full rect of the monitor containing `point` (None when off every screen)
+    fn monitor_at(&self, point: POINT) -> Option<RECT>;
+    /// Effective DPI of the monitor nearest to `rect`
+    fn dpi_at(&self, rect: &RECT) -> Option<u32>;
+    /// DPI of `hwnd` if it follows its monitor's DPI (per-monitor aware)
+    fn window_dpi(&self, hwnd: HWND) -> Option<u32>;
+    fn cursor_pos(&self) -> Option<POINT>;
+    fn foreground(&self) -> HWND;
+    fn set_foreground(&self, hwnd: HWND);
+    fn style(&self, hwnd: HWND) -> isize;
+    fn set_style(&self, hwnd: HWND, style: isize);
+    fn ex_style(&self, hwnd: HWND) -> isize;
+    fn set_ex_style(&self, hwnd: HWND, style: isize);
+    fn placement(&self, hwnd: HWND) -> Option<WINDOWPLACEMENT>;
+    fn set_placement(&self, hwnd: HWND, placement: &WINDOWPLACEMENT);
+    /// Layered-window alpha (None: not layered with an alpha)
+    fn alpha(&self, hwnd: HWND) -> Option<u8>;
+    fn set_alpha(&self, hwnd: HWND, alpha: u8);
+    /// Repaint request (no-op when nothing is drawn)
+    fn invalidate(&self, hwnd: HWND);
+    /// Apply `moves` together (one composition when possible)
+    fn move_windows(&self, moves: &[Move]);
+    /// Move one window outside the topmost band: after `insert_after` in
+    /// Z-order, or where `m.flags` say (e.g. SWP_NOZORDER) when None
+    fn move_window(&self, m: Move, insert_after: Option<HWND>);
+    /// Show without activating (SW_SHOWNOACTIVATE)
+    fn show_inactive(&self, hwnd: HWND);
+    /// Stop a flashing taskbar button / caption
+    fn stop_flash(&self, hwnd: HWND);
+    /// Put `hwnd` in or out of the topmost band (no move, no activation)
+    fn set_topmost(&self, hwnd: HWND, topmost: bool);
+    /// Move to `bounds` in or out of the topmost band, re-reading frame styles
+    fn restore_frame(&self, hwnd: HWND, bounds: WindowBounds, topmost: bool);
+    fn shadow(&self, hwnd: HWND) -> Shadow;
+    /// Turn shadow sources on / off (the class style affects the whole class)
+    fn set_shadow(&self, hwnd: HWND, shadow: Shadow);
+    /// Out-of-context WinEvent hook for `event` (None if it failed)
+    fn hook_event(&self, event: u32, callback: WINEVENTPROC, flags: u32) -> Option<HWINEVENTHOOK>;
+    fn unhook_event(&self, hook: HWINEVENTHOOK) -> bool;
+}
+
+static API: OnceLock<&'static dyn WindowApi> = OnceLock::new();
+static NATIVE: Native = Native;
+
+/// Active implementation (Native unless `install`ed before first use)
+pub fn api() -> &'static dyn WindowApi {
+    *API.get_or_init(|| &NATIVE)
+}
+
+/// Select the implementation; false if `api()` was already used
+pub fn install(api: &'static dyn WindowApi) -> bool {
+    API.set(api).is_ok()
+}
+
+/// Real Win32 calls
+pub struct Native;
+
+impl Native {
+    fn monitor_info(monitor: HMONITOR) -> Option<MONITORINFO> {
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        unsafe { GetMonitorInfoW(monitor, &mut info) }
+            .as_bool()
+            .then_some(info)
+    }
+
+    fn monitor_work_area(monitor: HMONITOR) -> Option<RECT> {
+        Self::monitor_info(monitor).map(|info| info.rcWork)
+    }
+
+    /// All moves in one DeferWindowPos batch; false if the batch failed
+    fn defer_all(moves: &[Move]) -> bool {
+        unsafe {
+            let Ok(mut hdwp) = BeginDeferWindowPos(moves.len() as i32) else {
+                return false;
+            };
+            for m in moves {
+                // On failure the batch is already freed by the system
+                match DeferWindowPos(
+                    hdwp,
+                    m.hwnd,
+                    Some(HWND_TOPMOST),
+                    m.x,
+                    m.y,
+                    m.width,
+                    m.height,
+                    m.flags,
+                ) {
+                    Ok(next) => hdwp = next,
+                    Err(_) => return false,
+                }
+            }
+            EndDeferWindowPos(hdwp).is_ok()
+        }
+    }
+}
+
+impl WindowApi for Native {
+    fn is_window(&self, hwnd: HWND) -> bool {
+        unsafe { IsWindow(Some(hwnd)) }.as_bool()
+    }
+
+    fn is_visible(&self, hwnd: HWND) -> bool {
+        unsafe { IsWindowVisible(hwnd) }.as_bool()
+    }
+
+    fn show(&self, hwnd: HWND, visible: bool) {
+        let cmd = if visible { SW_SHOW } else { SW_HIDE };
+        let _ = unsafe { ShowWindow(hwnd, cmd) };
+    }
+
+    fn process_id(&self, hwnd: HWND) -> u32 {
+        let mut pid = 0u32;
+        unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+        pid
+    }
+
+    fn window_rect(&self, hwnd: HWND) -> Option<WindowBounds> {
+        let mut rect = RECT::default();
+        unsafe { GetWindowRect(hwnd, &mut rect) }.ok()?;
+        Some(WindowBounds::from_rect(&rect))
+    }
+
+    fn work_area(&self, hwnd: HWND) -> Option<RECT> {
+        Self::monitor_work_area(unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTOPRIMARY) })
+    }
+
+    fn work_area_at(&self, point: POINT) -> Option<RECT> {
+        Self::monitor_work_area(unsafe { MonitorFromPoint(point, MONITOR_DEFAULTTONEAREST) })
+    }
+
+    fn monitor_at(&self, point: POINT) -> Option<RECT> {
+        let monitor = unsafe { MonitorFromPoint(point, MONITOR_DEFAULTTONULL) };
+        if monitor.is_invalid() {
+            return None;
+        }
+        Self::monitor_info(monitor).map(|info| info.rcMonitor)
+    }
+
+    fn dpi_at(&self, rect: &RECT) -> Option<u32> {
+        let monitor = unsafe { MonitorFromRect(rect, MONITOR_DEFAULTTONEAREST) };
+        let (mut dpi_x, mut dpi_y) = (0, 0);
+        unsafe {
+            // Real DPI even though this process is not DPI aware itself
+            let previous = SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+            let result = GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+            SetThreadDpiAwarenessContext(previous);
+            result.ok()?;
+        }
+        (dpi_x > 0).then_some(dpi_x)
+    }
+
+    fn window_dpi(&self, hwnd: HWND) -> Option<u32> {
+        unsafe {
+            let awareness = GetAwarenessFromDpiAwarenessContext(GetWindowDpiAwarenessContext(hwnd));
+            if awareness != DPI_AWARENESS_PER_MONITOR_AWARE {
+                return None;
+            }
+            let dpi = GetDpiForWindow(hwnd);
+            (dpi > 0).then_some(dpi)
+        }
+    }
+
+    fn cursor_pos(&self) -> Option<POINT> {
+        let mut point = POINT::default();
+        unsafe { GetCursorPos(&mut point) }.ok()?;
+        Some(point)
+    }
+
+    fn foreground(&self) -> HWND {
+        unsafe { GetForegroundWindow() }
+    }
+
+    fn set_foreground(&self, hwnd: HWND) {
+        let _ = unsafe { SetForegroundWindow(hwnd) };
+    }
+
+    fn style(&self, hwnd: HWND) -> isize {
+        unsafe { GetWindowLongPtrW(hwnd, GWL_STYLE) }
+    }
+
+    fn set_style(&self, hwnd: HWND, style: isize) {
+        unsafe {
+            SetWindowLongPtrW(hwnd, GWL_STYLE, style);
+        }
+    }
+
+    fn ex_style(&self, hwnd: HWND) -> isize {
+        unsafe { GetWindowLongPtrW(hwnd, GWL_EXSTYLE) }
+    }
+
+    fn set_ex_style(&self, hwnd: HWND, style: isize) {
+        unsafe {
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style);
+        }
+    }
+
+    fn placement(&self, hwnd: HWND) -> Option<WINDOWPLACEMENT> {
+        let mut placement = WINDOWPLACEMENT {
+            length: std::mem::size_of::<WINDOWPLACEMENT>() as u32,
+            ..Default::default()
+        };
+        unsafe { GetWindowPlacement(hwnd, &mut placement) }
+            .is_ok()
+            .then_some(placement)
+    }
+
+    fn set_placement(&self, hwnd: HWND, placement: &WINDOWPLACEMENT) {
+        let _ = unsafe { SetWindowPlacement(hwnd, placement) };
+    }
+
+    fn alpha(&self, hwnd: HWND) -> Option<u8> {
+        let mut alpha = 0;
+        let mut flags = LAYERED_WINDOW_ATTRIBUTES_FLAGS::default();
+        unsafe { GetLayeredWindowAttributes(hwnd, None, Some(&mut alpha), Some(&mut flags)) }
+            .ok()?;
+        flags.contains(LWA_ALPHA).then_some(alpha)
+    }
+
+    fn set_alpha(&self, hwnd: HWND, alpha: u8) {
+        let _ = unsafe { SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA) };
+    }
+
+    fn invalidate(&self, hwnd: HWND) {
+        let _ = unsafe { InvalidateRect(Some(hwnd), None, true) };
+    }
+
+    fn move_windows(&self, moves: &[Move]) {
+        if moves.len() > 1 && Self::defer_all(moves) {
+            return;
+        }
+        for m in moves {
+            unsafe {
+                let _ = SetWindowPos(
+                    m.hwnd,
+                    Some(HWND_TOPMOST),
+                    m.x,
+                    m.y,
+                    m.width,
+                    m.height,
+                    m.flags,
+                );
+            }
+        }
+    }
+
+    fn move_window(&self, m: Move, insert_after: Option<HWND>) {
+        let _ = unsafe { SetWindowPos(m.hwnd, insert_after, m.x, m.y, m.width, m.height, m.flags) };
+    }
+
+    fn show_inactive(&self, hwnd: HWND) {
+        let _ = unsafe { ShowWindow(hwnd, SW_SHOWNOACTIVATE) };
+    }
+
+    fn stop_flash(&self, hwnd: HWND) {
+        let info = FLASHWINFO {
+            cbSize: std::mem::size_of::<FLASHWINFO>() as u32,
+            hwnd,
+            dwFlags: FLASHW_STOP,
+            uCount: 0,
+            dwTimeout: 0,
+        };
+        let _ = unsafe { FlashWindowEx(&info) };
+    }
+
+    fn set_topmost(&self, hwnd: HWND, topmost: bool) {
+        let z_order = if topmost {
+            HWND_TOPMOST
+        } else {
+            HWND_NOTOPMOST
+        };
+        let _ = unsafe {
+            SetWindowPos(
+                hwnd,
+                Some(z_order),
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            )
+        };
+    }
+
+    fn restore_frame(&self, hwnd: HWND, bounds: WindowBounds, topmost: bool) {
+        let z_order = if topmost {
+            HWND_TOPMOST
+        } else {
+            HWND_NOTOPMOST
+        };
+        let _ = unsafe {
+            SetWindowPos(
+                hwnd,
+                Some(z_order),
+                bounds.x,
+                bounds.y,
+                bounds.width,
+                bounds.height,
+                SWP_FRAMECHANGED,
+            )
+        };
+    }
+
+    fn shadow(&self, hwnd: HWND) -> Shadow {
+        let mut enabled = windows::core::BOOL(0);
+        let frame = unsafe {
+            DwmGetWindowAttribute(
+                hwnd,
+                DWMWA_NCRENDERING_ENABLED,
+                &mut enabled as *mut _ as *mut _,
+                std::mem::size_of_val(&enabled) as u32,
+            )
+        }
+        .is_ok()
+            && enabled.as_bool();
+        let class_style = unsafe { GetClassLongPtrW(hwnd, GCL_STYLE) };
+        Shadow {
+            frame,
+            class: class_style & CS_DROPSHADOW.0 as usize != 0,
+        }
+    }
+
+    fn set_shadow(&self, hwnd: HWND, shadow: Shadow) {
+        let policy = if shadow.frame {
+            DWMNCRP_USEWINDOWSTYLE
+        } else {
+            DWMNCRP_DISABLED
+        };
+        let _ = unsafe {
+            DwmSetWindowAttribute(
+                hwnd,
+                DWMWA_NCRENDERING_POLICY,
+                &policy as *const _ as *const _,
+                std::mem::size_of_val(&policy) as u32,
+            )
+        };
+        let class_style = unsafe { GetClassLongPtrW(hwnd, GCL_STYLE) };
+        let wanted = if shadow.class {
+            class_style | CS_DROPSHADOW.0 as usize
+        } else {
+            class_style & !(CS_DROPSHADOW.0 as usize)
+        };
+        if wanted != class_style {
+            unsafe { SetClassLongPtrW(hwnd, GCL_STYLE, wanted as isize) };
+        }
+    }
+
+    fn hook_event(&self, event: u32, callback: WINEVENTPROC, flags: u32) -> Option<HWINEVENTHOOK> {
+        let hook = unsafe { SetWinEventHook(event, event, None, callback, 0, 0, flags) };
+        (!hook.is_invalid()).then_some(hook)
+    }
+
+    fn unhook_event(&self, hook: HWINEVENTHOOK) -> bool {
+        unsafe { UnhookWinEvent(hook) }.as_bool()
+    }
+}
+
+/// Intended mutation logged by `Recorder`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    Move {
+        hwnd: isize,
+        x: i32,
+        y: i32,
+        show: bool,
+        hide: bool,
+    },
+    Foreground(isize),
+    Show(isize, bool),
+    Style(isize, isize),
+    ExStyle(isize, isize),
+    Placement(isize),
+    Frame {
+        hwnd: isize,
+        bounds: WindowBounds,
+        topmost: bool,
+    },
+    Alpha(isize, u8),
+    Topmost(isize, bool),
+    Shadow(isize, Shadow),
+    StopFlash(isize),
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Move {
+                hwnd,
+                x,
+                y,
+                show,
+                hide,
+            } => {
+                write!(f, "move {hwnd:#x} -> ({x}, {y})")?;
+                if *show {
+                    write!(f, " +show")?;
+                }
+                if *hide {
+                    write!(f, " +hide")?;
+                }
+                Ok(())
+            }
+            Op::Foreground(hwnd) => write!(f, "foreground {hwnd:#x}"),
+            Op::Show(hwnd, visible) => write!(f, "show {hwnd:#x} = {visible}"),
+            Op::Style(hwnd, style) => write!(f, "style {hwnd:#x} = {style:#x}"),
+            Op::Placement(hwnd) => write!(f, "placement {hwnd:#x}"),
+            Op::Frame {
+                hwnd,
+                bounds,
+                topmost,
+            } => write!(
+                f,
+                "frame {hwnd:#x} -> ({}, {}) {}x{}, topmost {topmost}",
+                bounds.x, bounds.y, bounds.width, bounds.height
+            ),
+            Op::ExStyle(hwnd, style) => write!(f, "exstyle {hwnd:#x} = {style:#x}"),
+            Op::Alpha(hwnd, alpha) => write!(f, "alpha {hwnd:#x} = {alpha}"),
+            Op::Topmost(hwnd, topmost) => write!(f, "topmost {hwnd:#x} = {topmost}"),
+            Op::Shadow(hwnd, shadow) => write!(
+                f,
+                "shadow {hwnd:#x} = frame {}, class {}",
+                shadow.frame, shadow.class
+            ),
+            Op::StopFlash(hwnd) => write!(f, "stop flash {hwnd:#x}"),
+        }
+    }
+}
+
+/// Simulated top-level window
+#[derive(Debug, Clone, Copy)]
+struct FakeWindow {
+    hwnd: isize,
+    bounds: WindowBounds,
+    visible: bool,
+    style: isize,
+    ex_style: isize,
+    /// Layered alpha (None until set)
+    alpha: Option<u8>,
+    shadow: Shadow,
+}
+
+#[derive(Debug, Default)]
+struct Desktop {
+    windows: Vec<FakeWindow>,
+    work_area: RECT,
+    cursor: POINT,
+    foreground: isize,
+    log: Vec<Op>,
+}
+
+impl Desktop {
+    fn window(&mut self, hwnd: HWND) -> Option<&mut FakeWindow> {
+        self.windows.iter_mut().find(|w| w.hwnd == hwnd.0 as isize)
+    }
+}
+
+/// In-memory desktop that records mutations (single monitor)
+#[derive(Debug, Default)]
+pub struct Recorder {
+    desktop: Mutex<Desktop>,
+}
+
+impl Recorder {
+    pub fn new(work_area: RECT) -> Self {
+        Self {
+            desktop: Mutex::new(Desktop {
+                work_area,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn desktop(&self) -> std::sync::MutexGuard<'_, Desktop> {
+        self.desktop.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Add a window (focused, like a freshly opened one)
+    pub fn add_window(&self, hwnd: HWND, bounds: WindowBounds) {
+        let mut desktop = self.desktop();
+        desktop.windows.push(FakeWindow {
+            hwnd: hwnd.0 as isize,
+            bounds,
+            visible: true,
+            style: 0,
+            ex_style: 0,
+            alpha: None,
+            shadow: Shadow {
+                frame: true,
+                class: false,
+            },
+        });
+        desktop.foreground = hwnd.0 as isize;
+    }
+
+    pub fn set_cursor(&self, point: POINT) {
+        self.desktop().cursor = point;
+    }
+
+    pub fn set_foreground_window(&self, hwnd: HWND) {
+        self.desktop().foreground = hwnd.0 as isize;
+    }
+
+    /// Take the recorded operations
+    pub fn take_log(&self) -> Vec<Op> {
+        std::mem::take(&mut self.desktop().log)
+    }
+}
+
+impl WindowApi for Recorder {
+    fn is_window(&self, hwnd: HWND) -> bool {
+        self.desktop().window(hwnd).is_some()
+    }
+
+    fn is_visible(&self, hwnd: HWND) -> bool {
+        self.desktop().window(hwnd).is_some_and(|w| w.visible)
+    }
+
+    fn show(&self, hwnd: HWND, visible: bool) {
+        let mut desktop = self.desktop();
+        if let Some(window) = desktop.window(hwnd) {
+            window.visible = visible;
+        }
+        desktop.log.push(Op::Show(hwnd.0 as isize, visible));
+    }
+
+    /// Simulated windows belong to no process
+    fn process_id(&self, _hwnd: HWND) -> u32 {
+        0
+    }
+
+    fn window_rect(&self, hwnd: HWND) -> Option<WindowBounds> {
+        self.desktop().window(hwnd).map(|w| w.bounds)
+    }
+
+    fn work_area(&self, _hwnd: HWND) -> Option<RECT> {
+        Some(self.desktop().work_area)
+    }
+
+    fn work_area_at(&self, _point: POINT) -> Option<RECT> {
+        Some(self.desktop().work_area)
+    }
+
+    fn monitor_at(&self, point: POINT) -> Option<RECT> {
+        let area = self.desktop().work_area;
+        let inside = point.x >= area.left
+            && point.x < area.right
+            && point.y >= area.top
+            && point.y < area.bottom;
+        inside.then_some(area)
+    }
+
+    /// Single monitor at 96 DPI
+    fn dpi_at(&self, _rect: &RECT) -> Option<u32> {
+        Some(96)
+    }
+
+    fn window_dpi(&self, _hwnd: HWND) -> Option<u32> {
+        None
+    }
+
+    fn cursor_pos(&self) -> Option<POINT> {
+        Some(self.desktop().cursor)
+    }
+
+    fn foreground(&self) -> HWND {
+        HWND(self.desktop().foreground as *mut _)
+    }
+
+    fn set_foreground(&self, hwnd: HWND) {
+        let mut desktop = self.desktop();
+        desktop.foreground = hwnd.0 as isize;
+        desktop.log.push(Op::Foreground(hwnd.0 as isize));
+    }
+
+    fn style(&self, hwnd: HWND) -> isize {
+        self.desktop().window(hwnd).map_or(0, |w| w.style)
+    }
+
+    fn set_style(&self, hwnd: HWND, style: isize) {
+        let mut desktop = self.desktop();
+        if let Some(window) = desktop.window(hwnd) {
+            window.style = style;
+        }
+        desktop.log.push(Op::Style(hwnd.0 as isize, style));
+    }
+
+    fn ex_style(&self, hwnd: HWND) -> isize {
+        self.desktop().window(hwnd).map_or(0, |w| w.ex_style)
+    }
+
+    fn set_ex_style(&self, hwnd: HWND, style: isize) {
+        let mut desktop = self.desktop();
+        if let Some(window) = desktop.window(hwnd) {
+            window.ex_style = style;
+        }
+        desktop.log.push(Op::ExStyle(hwnd.0 as isize, style));
+    }
+
+    fn placement(&self, _hwnd: HWND) -> Option<WINDOWPLACEMENT> {
+        None
+    }
+
+    fn set_placement(&self, hwnd: HWND, _placement: &WINDOWPLACEMENT) {
+        self.desktop().log.push(Op::Placement(hwnd.0 as isize));
+    }
+
+    fn alpha(&self, hwnd: HWND) -> Option<u8> {
+        self.desktop().window(hwnd).and_then(|w| w.alpha)
+    }
+
+    fn set_alpha(&self, hwnd: HWND, alpha: u8) {
+        let mut desktop = self.desktop();
+        if let Some(window) = desktop.window(hwnd) {
+            window.alpha = Some(alpha);
+        }
+        desktop.log.push(Op::Alpha(hwnd.0 as isize, alpha));
+    }
+
+    fn invalidate(&self, _hwnd: HWND) {}
+
+    fn move_windows(&self, moves: &[Move]) {
+        let mut desktop = self.desktop();
+        for m in moves {
+            if let Some(window) = desktop.window(m.hwnd) {
+                window.bounds = WindowBounds {
+                    x: m.x,
+                    y: m.y,
+                    width: m.width,
+                    height: m.height,
+                };
+                window.visible = (window.visible || m.flags.contains(SWP_SHOWWINDOW))
+                    && !m.flags.contains(SWP_HIDEWINDOW);
+            }
+            desktop.log.push(Op::Move {
+                hwnd: m.hwnd.0 as isize,
+                x: m.x,
+                y: m.y,
+                show: m.flags.contains(SWP_SHOWWINDOW),
+                hide: m.flags.contains(SWP_HIDEWINDOW),
+            });
+        }
+    }
+
+    /// Z-order is not modelled; SWP_NOMOVE / SWP_NOSIZE keep those parts
+    fn move_window(&self, m: Move, _insert_after: Option<HWND>) {
+        let mut desktop = self.desktop();
+        let mut logged = (m.x, m.y);
+        if let Some(window) = desktop.window(m.hwnd) {
+            if !m.flags.contains(SWP_NOMOVE) {
+                window.bounds.x = m.x;
+                window.bounds.y = m.y;
+            }
+            if !m.flags.contains(SWP_NOSIZE) {
+                window.bounds.width = m.width;
+                window.bounds.height = m.height;
+            }
+            window.visible = (window.visible || m.flags.contains(SWP_SHOWWINDOW))
+                && !m.flags.contains(SWP_HIDEWINDOW);
+            logged = (window.bounds.x, window.bounds.y);
+        }
+        desktop.log.push(Op::Move {
+            hwnd: m.hwnd.0 as isize,
+            x: logged.0,
+            y: logged.1,
+            show: m.flags.contains(SWP_SHOWWINDOW),
+            hide: m.flags.contains(SWP_HIDEWINDOW),
+        });
+    }
+
+    fn show_inactive(&self, hwnd: HWND) {
+        let mut desktop = self.desktop();
+        if let Some(window) = desktop.window(hwnd) {
+            window.visible = true;
+        }
+        desktop.log.push(Op::Show(hwnd.0 as isize, true));
+    }
+
+    fn stop_flash(&self, hwnd: HWND) {
+        self.desktop().log.push(Op::StopFlash(hwnd.0 as isize));
+    }
+
+    fn set_topmost(&self, hwnd: HWND, topmost: bool) {
+        self.desktop()
+            .log
+            .push(Op::Topmost(hwnd.0 as isize, topmost));
+    }
+
+    fn restore_frame(&self, hwnd: HWND, bounds: WindowBounds, topmost: bool) {
+        let mut desktop = self.desktop();
+        if let Some(window) = desktop.window(hwnd) {
+            window.bounds = bounds;
+        }
+        desktop.log.push(Op::Frame {
+            hwnd: hwnd.0 as isize,
+            bounds,
+            topmost,
+        });
+    }
+
+    fn shadow(&self, hwnd: HWND) -> Shadow {
+        self.desktop()
+            .window(hwnd)
+            .map_or_else(Shadow::default, |w| w.shadow)
+    }
+
+    fn set_shadow(&self, hwnd: HWND, shadow: Shadow) {
+        let mut desktop = self.desktop();
+        if let Some(window) = desktop.window(hwnd) {
+            window.shadow = shadow;
+        }
+        desktop.log.push(Op::Shadow(hwnd.0 as isize, shadow));
+    }
+
+    /// No system events on a simulated desktop: a placeholder handle
+    fn hook_event(
+        &self,
+        _event: u32,
+        _callback: WINEVENTPROC,
+        _flags: u32,
+    ) -> Option<HWINEVENTHOOK> {
+        Some(HWINEVENTHOOK(std::ptr::dangling_mut()))
+    }
+
+    fn unhook_event(&self, _hook: HWINEVENTHOOK) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
+
+    const WORK_AREA: RECT = RECT {
+        left: 0,
+        top: 0,
+        right: 1920,
+        bottom: 1040,
+    };
+
+    fn bounds() -> WindowBounds {
+        WindowBounds {
+            x: 100,
+            y: 100,
+            width: 800,
+            height: 600,
+        }
+    }
+
+    #[test]
+    fn test_recorder_models_window() {
+        let recorder = Recorder::new(WORK_AREA);
+        let hwnd = HWND(0x1000 as *mut _);
+        assert!(!recorder.is_window(hwnd));
+
+        recorder.add_window(hwnd, bounds());
+        assert!(recorder.is_window(hwnd));
+        assert_eq!(recorder.window_rect(hwnd), Some(bounds()));
+        assert_eq!(recorder.foreground(), hwnd);
+        assert_eq!(recorder.work_area(hwnd), Some(WORK_AREA));
+        assert_eq!(recorder.monitor_at(POINT { x: 10, y: 10 }), Some(WORK_AREA));
+        assert_eq!(recorder.monitor_at(POINT { x: -1, y: 10 }), None);
+    }
+
+    #[test]
+    fn test_recorder_logs_moves() {
+        let recorder = Recorder::new(WORK_AREA);
+        let hwnd = HWND(0x1000 as *mut _);
+        recorder.add_window(hwnd, bounds());
+
+        recorder.move_windows(&[Move {
+            hwnd,
+            x: -800,
+            y: 100,
+            width: 800,
+            height: 600,
+            flags: SWP_NOACTIVATE | SWP_HIDEWINDOW,
+        }]);
+        assert_eq!(recorder.window_rect(hwnd).map(|b| b.x), Some(-800));
+        assert_eq!(
+            recorder.take_log(),
+            vec![Op::Move {
+                hwnd: 0x1000,
+                x: -800,
+                y: 100,
+                show: false,
+                hide: true,
+            }]
+        );
+        assert!(recorder.take_log().is_empty());
+    }
+
+    #[test]
+    fn test_recorder_models_alpha() {
+        let recorder = Recorder::new(WORK_AREA);
+        let hwnd = HWND(0x1000 as *mut _);
+        recorder.add_window(hwnd, bounds());
+        assert_eq!(recorder.alpha(hwnd), None);
+
+        recorder.set_alpha(hwnd, 128);
+        assert_eq!(recorder.alpha(hwnd), Some(128));
+        assert_eq!(recorder.take_log(), vec![Op::Alpha(0x1000, 128)]);
+    }
+
+    #[test]
+    fn test_recorder_models_visibility_and_frame() {
+        let recorder = Recorder::new(WORK_AREA);
+        let hwnd = HWND(0x1000 as *mut _);
+        recorder.add_window(hwnd, bounds());
+        assert!(recorder.is_visible(hwnd));
+        assert_eq!(recorder.process_id(hwnd), 0);
+
+        recorder.show(hwnd, false);
+        assert!(!recorder.is_visible(hwnd));
+        let moved = WindowBounds { x: 0, ..bounds() };
+        recorder.restore_frame(hwnd, moved, false);
+        assert_eq!(recorder.window_rect(hwnd), Some(moved));
+        assert_eq!(
+            recorder.take_log(),
+            vec![
+                Op::Show(0x1000, false),
+                Op::Frame {
+                    hwnd: 0x1000,
+                    bounds: moved,
+                    topmost: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recorder_models_shadow() {
+        let recorder = Recorder::new(WORK_AREA);
+        let hwnd = HWND(0x1000 as *mut _);
+        recorder.add_window(hwnd, bounds());
+        assert!(recorder.shadow(hwnd).frame);
+
+        recorder.set_shadow(hwnd, Shadow::default());
+        assert!(!recorder.shadow(hwnd).any());
+        assert_eq!(
+            recorder.take_log(),
+            vec![Op::Shadow(0x1000, Shadow::default())]
+        );
+    }
+
+    #[test]
+    fn test_recorder_move_window_keeps_size() {
+        let recorder = Recorder::new(WORK_AREA);
+        let hwnd = HWND(0x1000 as *mut _);
+        recorder.add_window(hwnd, bounds());
+
+        recorder.move_window(
+            Move {
+                hwnd,
+                x: -20000,
+                y: -20000,
+                width: 0,
+                height: 0,
+                flags: SWP_NOSIZE | SWP_NOACTIVATE | SWP_HIDEWINDOW,
+            },
+            None,
+        );
+        recorder.stop_flash(hwnd);
+        let moved = recorder.window_rect(hwnd).expect("window");
+        assert_eq!((moved.x, moved.width), (-20000, 800));
+        assert!(!recorder.is_visible(hwnd));
+        assert_eq!(
+            recorder.take_log(),
+            vec![
+                Op::Move {
+                    hwnd: 0x1000,
+                    x: -20000,
+                    y: -20000,
+                    show: false,
+                    hide: true,
+                },
+                Op::StopFlash(0x1000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_op_display() {
+        let op = Op::Move {
+            hwnd: 0x10,
+            x: 1,
+            y: 2,
+            show: true,
+            hide: false,
+        };
+        assert_eq!(op.to_string(), "move 0x10 -> (1, 2) +show");
+        assert_eq!(Op::Foreground(0x10).to_string(), "foreground 0x10");
+        assert_eq!(Op::Topmost(0x10, false).to_string(), "topmost 0x10 = false");
+    }
+}
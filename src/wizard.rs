@@ -0,0 +1,201 @@
+//! First-run setup wizard: target app, toggle hotkey, slide edge
+//!
+//! Offered once, when the settings key does not exist yet. The steps are
+//! yes / no message boxes and a file picker on a worker thread, so the event
+//! loop keeps running; the answers are posted back as WM_WIZARD_DONE. The loop
+//! saves them (startup target, default dock), applies the hotkey and previews
+//! the result by tracking the target and sliding it out to the chosen edge.
+//! A built-in preset replaces the file picker and the edge question.
+
+use global_hotkey::hotkey::HotKey;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use windows::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx, CoTaskMemFree,
+};
+use windows::Win32::UI::Shell::Common::COMDLG_FILTERSPEC;
+use windows::Win32::UI::Shell::{FileOpenDialog, IFileOpenDialog, SIGDN_FILESYSPATH};
+use windows::Win32::UI::WindowsAndMessaging::{
+    IDYES, MB_ICONQUESTION, MB_SETFOREGROUND, MB_YESNO, MessageBoxW, WM_USER,
+};
+use windows::core::{HSTRING, w};
+
+use crate::animation::Direction;
+use crate::events;
+use crate::hotkey;
+use crate::i18n::{Text, t, tf};
+use crate::presets::{self, Preset};
+use crate::session;
+use crate::settings::{self, SettingsError};
+use crate::snap;
+
+/// Posted to the event loop once the wizard is closed (answers in `take`)
+pub const WM_WIZARD_DONE: u32 = WM_USER + 12;
+
+/// Written when the wizard is finished or skipped
+const SETUP_DONE: &str = "SetupDone";
+
+/// Slide edges offered, in order (all declined: top)
+const DOCKS: [(Direction, Text); 4] = [
+    (Direction::Top, Text::EdgeTop),
+    (Direction::Left, Text::EdgeLeft),
+    (Direction::Right, Text::EdgeRight),
+    (Direction::Bottom, Text::EdgeBottom),
+];
+
+/// How the toggle hotkey was chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyChoice {
+    /// One of the offered bindings
+    Use(HotKey),
+    /// All declined: press a custom combination after setup
+    Capture,
+}
+
+/// Answers of a completed wizard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setup {
+    /// Built-in preset (instead of a picked target)
+    pub preset: Option<Preset>,
+    /// App to track on startup (None: track windows with the hotkey)
+    pub target: Option<PathBuf>,
+    pub hotkey: HotkeyChoice,
+    pub dock: Direction,
+}
+
+/// Answers of the last wizard run (None: declined)
+static RESULT: Mutex<Option<Setup>> = Mutex::new(None);
+
+/// Check if this is the first run (no settings written yet)
+pub fn is_first_run() -> bool {
+    !settings::exists()
+}
+
+/// Run the wizard on its own thread
+/// hotkeys: toggle bindings to offer, active first
+pub fn start(hotkeys: Vec<HotKey>) {
+    std::thread::spawn(move || {
+        let setup = run(&hotkeys);
+        *RESULT.lock().unwrap_or_else(|e| e.into_inner()) = setup;
+        events::post(WM_WIZARD_DONE, 0);
+    });
+}
+
+/// Answers of the closed wizard (None if declined)
+pub fn take() -> Option<Setup> {
+    RESULT.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Persist the answers (preset, or startup target and default dock) and mark setup done
+pub fn save(setup: &Setup) -> Result<(), SettingsError> {
+    if let Some(preset) = setup.preset {
+        presets::apply(preset)?;
+        return mark_done();
+    }
+    if let Some(target) = &setup.target {
+        session::set_default_target(&target_command(target), None)?;
+    }
+    snap::set_default_dock(setup.dock)?;
+    mark_done()
+}
+
+/// Do not offer the wizard again
+pub fn mark_done() -> Result<(), SettingsError> {
+    settings::set_bool(SETUP_DONE, true)
+}
+
+/// Startup target command for a picked exe (quoted: paths may contain spaces)
+fn target_command(path: &Path) -> String {
+    format!("\"{}\"", path.display())
+}
+
+/// Wizard steps (None if declined on the welcome page)
+fn run(hotkeys: &[HotKey]) -> Option<Setup> {
+    if !ask(t(Text::SetupWelcome)) {
+        return None;
+    }
+    let preset = if ask(t(Text::SetupOfferPresets)) {
+        Preset::ALL
+            .into_iter()
+            .find(|preset| ask(&tf(Text::SetupUsePreset, &[preset.name()])))
+    } else {
+        None
+    };
+    let target = if preset.is_none() && ask(t(Text::SetupPickTarget)) {
+        pick_target()
+    } else {
+        None
+    };
+    let hotkey = hotkeys
+        .iter()
+        .find(|h| ask(&tf(Text::SetupUseHotkey, &[&hotkey::label(h)])))
+        .map_or(HotkeyChoice::Capture, |h| HotkeyChoice::Use(*h));
+    let dock = match preset {
+        Some(preset) => preset.dock(),
+        None => DOCKS
+            .iter()
+            .find(|(_, edge)| ask(&tf(Text::SetupSlideFrom, &[t(*edge)])))
+            .map_or(Direction::Top, |(dock, _)| *dock),
+    };
+    Some(Setup {
+        preset,
+        target,
+        hotkey,
+        dock,
+    })
+}
+
+/// Yes / No question, true on Yes
+fn ask(question: &str) -> bool {
+    let answer = unsafe {
+        MessageBoxW(
+            None,
+            &HSTRING::from(question),
+            &HSTRING::from(t(Text::SetupTitle)),
+            MB_YESNO | MB_ICONQUESTION | MB_SETFOREGROUND,
+        )
+    };
+    answer == IDYES
+}
+
+/// Common file dialog filtered to programs (shortcuts resolve to their target)
+fn pick_target() -> Option<PathBuf> {
+    let filter = [COMDLG_FILTERSPEC {
+        pszName: w!("Programs (*.exe; *.lnk)"),
+        pszSpec: w!("*.exe;*.lnk"),
+    }];
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let dialog: IFileOpenDialog =
+            CoCreateInstance(&FileOpenDialog, None, CLSCTX_INPROC_SERVER).ok()?;
+        dialog.SetFileTypes(&filter).ok()?;
+        // Cancel returns an error
+        dialog.Show(None).ok()?;
+        let item = dialog.GetResult().ok()?;
+        let raw = item.GetDisplayName(SIGDN_FILESYSPATH).ok()?;
+        let path = raw.to_string().ok().map(PathBuf::from);
+        CoTaskMemFree(Some(raw.0 as *const _));
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launch::Target;
+
+    #[test]
+    fn test_target_command_round_trip() {
+        let path = Path::new(r"C:\Program Files\Alacritty\alacritty.exe");
+        let target = Target::parse(&target_command(path)).expect("target");
+        assert_eq!(Path::new(&target.program), path);
+        assert_eq!(target.args, None);
+    }
+
+    #[test]
+    fn test_docks_distinct() {
+        for (i, (dock, _)) in DOCKS.iter().enumerate() {
+            assert!(DOCKS[i + 1..].iter().all(|(other, _)| other != dock));
+        }
+    }
+}